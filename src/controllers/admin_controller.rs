@@ -151,6 +151,8 @@ pub async fn admin_plans(
                 crews: limits.crews.unwrap_or(0),
                 members_per_crew: limits.members_per_crew.unwrap_or(0),
                 users: limits.users.unwrap_or(0),
+                storage_mb: limits.storage_mb.unwrap_or(0),
+                assets: limits.assets.unwrap_or(0),
                 expires_after_days: limits.expires_after_days.unwrap_or(0),
             },
             pro_limits: context! {
@@ -161,6 +163,8 @@ pub async fn admin_plans(
                 crews: pro_limits.crews.unwrap_or(0),
                 members_per_crew: pro_limits.members_per_crew.unwrap_or(0),
                 users: pro_limits.users.unwrap_or(0),
+                storage_mb: pro_limits.storage_mb.unwrap_or(0),
+                assets: pro_limits.assets.unwrap_or(0),
                 expires_after_days: pro_limits.expires_after_days.unwrap_or(180),
             },
             enterprise_limits: context! {
@@ -198,6 +202,8 @@ pub async fn admin_plans_update(
                     crews: form.crews,
                     members_per_crew: form.members_per_crew,
                     users: form.users,
+                    storage_mb: form.storage_mb,
+                    assets: form.assets,
                     expires_after_days: form.expires_after_days,
                 },
                 pro_limits: {
@@ -210,6 +216,8 @@ pub async fn admin_plans_update(
                         crews: pro_limits.crews.unwrap_or(0),
                         members_per_crew: pro_limits.members_per_crew.unwrap_or(0),
                         users: pro_limits.users.unwrap_or(0),
+                        storage_mb: pro_limits.storage_mb.unwrap_or(0),
+                        assets: pro_limits.assets.unwrap_or(0),
                         expires_after_days: pro_limits.expires_after_days.unwrap_or(180),
                     }
                 },
@@ -254,6 +262,8 @@ pub async fn admin_plans_update_pro(
                         crews: free_limits.crews.unwrap_or(0),
                         members_per_crew: free_limits.members_per_crew.unwrap_or(0),
                         users: free_limits.users.unwrap_or(0),
+                        storage_mb: free_limits.storage_mb.unwrap_or(0),
+                        assets: free_limits.assets.unwrap_or(0),
                         expires_after_days: free_limits.expires_after_days.unwrap_or(0),
                     }
                 },
@@ -265,6 +275,8 @@ pub async fn admin_plans_update_pro(
                     crews: form.crews,
                     members_per_crew: form.members_per_crew,
                     users: form.users,
+                    storage_mb: form.storage_mb,
+                    assets: form.assets,
                     expires_after_days: form.expires_after_days,
                 },
                 enterprise_limits: {
@@ -308,6 +320,8 @@ pub async fn admin_plans_update_enterprise(
                         crews: free_limits.crews.unwrap_or(0),
                         members_per_crew: free_limits.members_per_crew.unwrap_or(0),
                         users: free_limits.users.unwrap_or(0),
+                        storage_mb: free_limits.storage_mb.unwrap_or(0),
+                        assets: free_limits.assets.unwrap_or(0),
                         expires_after_days: free_limits.expires_after_days.unwrap_or(0),
                     }
                 },
@@ -321,6 +335,8 @@ pub async fn admin_plans_update_enterprise(
                         crews: pro_limits.crews.unwrap_or(0),
                         members_per_crew: pro_limits.members_per_crew.unwrap_or(0),
                         users: pro_limits.users.unwrap_or(0),
+                        storage_mb: pro_limits.storage_mb.unwrap_or(0),
+                        assets: pro_limits.assets.unwrap_or(0),
                         expires_after_days: pro_limits.expires_after_days.unwrap_or(180),
                     }
                 },
@@ -0,0 +1,794 @@
+use rocket::form::Form;
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::{
+    Asset,
+    AssetCheckinForm,
+    AssetCheckoutForm,
+    AssetForm,
+    AssetFormView,
+    CurrentUserView,
+    MaintenanceLogForm,
+    MaintenancePlanForm,
+    MaintenancePlanFormView,
+    PaginationView,
+    RequiredKitForm,
+};
+use crate::services::{
+    access_service,
+    asset_assignment_service,
+    asset_service,
+    auth_service,
+    gear_service,
+    maintenance_service,
+    workspace_service,
+};
+use crate::Db;
+
+const PER_PAGE: usize = 10;
+
+fn normalize_page(page: Option<usize>) -> usize {
+    page.unwrap_or(1).max(1)
+}
+
+fn pagination_view(
+    page: usize,
+    total_count: i64,
+    build_url: impl Fn(usize) -> String,
+) -> PaginationView {
+    let per_page = PER_PAGE as i64;
+    let total_pages = ((total_count + per_page - 1) / per_page).max(1) as usize;
+    let page = page.min(total_pages).max(1);
+    let has_prev = page > 1;
+    let has_next = page < total_pages;
+    let prev_url = if has_prev {
+        build_url(page - 1)
+    } else {
+        build_url(1)
+    };
+    let next_url = if has_next {
+        build_url(page + 1)
+    } else {
+        build_url(total_pages)
+    };
+
+    PaginationView {
+        page,
+        total_pages,
+        has_prev,
+        has_next,
+        prev_url,
+        next_url,
+    }
+}
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    page: usize,
+    error: Option<String>,
+) -> Template {
+    let current_user = CurrentUserView::from(user);
+    let stats = asset_service::asset_stats(db, tenant_id)
+        .await
+        .unwrap_or(crate::models::AssetStats {
+            total_assets: 0,
+            total_value: 0.0,
+            needs_attention: 0,
+        });
+    let (_plan_key, limits) = workspace_service::plan_limits_for_tenant(db, tenant_id).await;
+    let asset_limit = limits.assets.unwrap_or(0);
+    let asset_limit_reached = limits
+        .assets
+        .map(|limit| stats.total_assets >= limit)
+        .unwrap_or(false);
+    let offset = ((page - 1) * PER_PAGE) as i64;
+    let assets = asset_service::list_assets_paged(db, tenant_id, PER_PAGE as i64, offset)
+        .await
+        .unwrap_or_default();
+    let assets = asset_assignment_service::register_views(db, tenant_id, assets).await;
+    let pagination = pagination_view(page, stats.total_assets, |target_page| {
+        format!("/{}/assets?page={}", current_user.tenant_slug, target_page)
+    });
+
+    Template::render(
+        "assets/index",
+        context! {
+            title: "Assets",
+            current_user: Some(current_user),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            assets: assets,
+            stats: stats,
+            asset_limit: asset_limit,
+            asset_limit_reached: asset_limit_reached,
+            can_edit_assets: access_service::can_edit(db, user, "assets").await,
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
+            pagination: pagination,
+            error: error,
+        },
+    )
+}
+
+async fn render_form(
+    db: &Db,
+    user: &crate::models::User,
+    asset_id: Option<i64>,
+    form: AssetFormView,
+    error: Option<String>,
+) -> Template {
+    let (template, title) = match asset_id {
+        Some(_) => ("assets/edit", "Edit asset"),
+        None => ("assets/new", "New asset"),
+    };
+    Template::render(
+        template,
+        context! {
+            title: title,
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, user.tenant_id).await,
+            error: error,
+            asset_id: asset_id,
+            form: form,
+            type_options: asset_service::type_options(),
+            condition_options: asset_service::condition_options(),
+        },
+    )
+}
+
+async fn render_required_kit(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    kit: String,
+    error: Option<String>,
+) -> Template {
+    let can_edit_assets = access_service::can_edit(db, user, "assets").await
+        && !access_service::is_plan_expired(db, user).await;
+    Template::render(
+        "assets/required_kit",
+        context! {
+            title: "Required kit",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            kit: kit,
+            type_options: asset_service::type_options(),
+            can_edit_assets: can_edit_assets,
+            error: error,
+        },
+    )
+}
+
+async fn render_maintenance_plans(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    form: MaintenancePlanFormView,
+    error: Option<String>,
+) -> Template {
+    let plans = maintenance_service::list_plans(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    let can_edit_assets = access_service::can_edit(db, user, "assets").await
+        && !access_service::is_plan_expired(db, user).await;
+    Template::render(
+        "assets/maintenance_plans",
+        context! {
+            title: "Maintenance plans",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            plans: plans,
+            form: form,
+            type_options: asset_service::type_options(),
+            can_edit_assets: can_edit_assets,
+            error: error,
+        },
+    )
+}
+
+async fn render_show(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    asset: Asset,
+    error: Option<String>,
+) -> Template {
+    let history = asset_assignment_service::list_history(db, tenant_id, asset.id)
+        .await
+        .unwrap_or_default();
+    let (open_assignments, returned_assignments): (Vec<_>, Vec<_>) = history
+        .into_iter()
+        .partition(|assignment| assignment.checked_in_at.is_none());
+    let maintenance_statuses = maintenance_service::statuses_for_asset(db, tenant_id, &asset).await;
+    let maintenance_overdue = maintenance_statuses
+        .iter()
+        .any(|status| status.state == maintenance_service::STATE_OVERDUE);
+    let maintenance_logs = maintenance_service::list_logs(db, tenant_id, asset.id)
+        .await
+        .unwrap_or_default();
+    let maintenance_cost = maintenance_logs.iter().map(|log| log.cost).sum::<f64>();
+    let maintenance_plan_options = maintenance_service::plans_for_asset(db, tenant_id, &asset).await;
+    let can_edit_assets = access_service::can_edit(db, user, "assets").await
+        && !access_service::is_plan_expired(db, user).await;
+    Template::render(
+        "assets/show",
+        context! {
+            title: "Asset details",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            asset: asset,
+            open_assignments: open_assignments,
+            returned_assignments: returned_assignments,
+            crew_options: asset_assignment_service::crew_target_options(db, tenant_id).await,
+            deployment_options: asset_assignment_service::deployment_target_options(db, tenant_id).await,
+            condition_options: asset_service::condition_options(),
+            now: chrono::Local::now().format("%Y-%m-%dT%H:%M").to_string(),
+            maintenance_statuses: maintenance_statuses,
+            maintenance_overdue: maintenance_overdue,
+            maintenance_logs: maintenance_logs,
+            maintenance_cost: maintenance_cost,
+            maintenance_plan_options: maintenance_plan_options,
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
+            can_edit_assets: can_edit_assets,
+            can_delete_assets: access_service::can_delete(db, user, "assets").await,
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/assets?<page>")]
+pub async fn assets_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    page: Option<usize>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(assets_index(
+            slug = current_user.tenant_slug,
+            page = Option::<usize>::None
+        ))));
+    }
+    if !access_service::can_view(db, &user, "assets").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_index(
+        db,
+        tenant_id,
+        &user,
+        normalize_page(page),
+        None,
+    )
+    .await)
+}
+
+#[get("/<slug>/assets/new")]
+pub async fn asset_new_form(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (_, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(asset_new_form(slug = current_user.tenant_slug))));
+    }
+    if !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Err(Redirect::to(uri!(assets_index(
+            slug = current_user.tenant_slug,
+            page = Option::<usize>::None
+        ))));
+    }
+
+    Ok(render_form(db, &user, None, asset_service::empty_form_view(), None).await)
+}
+
+#[post("/<slug>/assets", data = "<form>")]
+pub async fn asset_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<AssetForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(assets_index(
+            slug = current_user.tenant_slug,
+            page = Option::<usize>::None
+        ))));
+    }
+
+    match asset_service::create_asset(db, tenant_id, form.into_inner()).await {
+        Ok(asset_id) => Ok(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = asset_id
+        )))),
+        Err(err) => Err(render_form(db, &user, None, err.form, Some(err.message)).await),
+    }
+}
+
+#[get("/<slug>/assets/<id>/profile")]
+pub async fn asset_show(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = id
+        ))));
+    }
+    if !access_service::can_view(db, &user, "assets").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let asset = match asset_service::find_asset_by_id(db, tenant_id, id).await {
+        Ok(Some(asset)) => asset,
+        _ => {
+            return Ok(render_index(
+                db,
+                tenant_id,
+                &user,
+                1,
+                Some("Asset not found.".to_string()),
+            )
+            .await)
+        }
+    };
+
+    Ok(render_show(db, tenant_id, &user, asset, None).await)
+}
+
+#[get("/<slug>/assets/<id>/edit")]
+pub async fn asset_edit_form(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(asset_edit_form(
+            slug = current_user.tenant_slug,
+            id = id
+        ))));
+    }
+    if !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Err(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = id
+        ))));
+    }
+
+    match asset_service::find_asset_by_id(db, tenant_id, id).await {
+        Ok(Some(asset)) => {
+            Ok(render_form(db, &user, Some(id), asset_service::form_view(&asset), None).await)
+        }
+        _ => Ok(render_index(
+            db,
+            tenant_id,
+            &user,
+            1,
+            Some("Asset not found.".to_string()),
+        )
+        .await),
+    }
+}
+
+#[post("/<slug>/assets/<id>", data = "<form>")]
+pub async fn asset_update(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<AssetForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(assets_index(
+            slug = current_user.tenant_slug,
+            page = Option::<usize>::None
+        ))));
+    }
+    if !matches!(asset_service::find_asset_by_id(db, tenant_id, id).await, Ok(Some(_))) {
+        return Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            1,
+            Some("Asset not found.".to_string()),
+        )
+        .await);
+    }
+
+    match asset_service::update_asset(db, tenant_id, id, form.into_inner()).await {
+        Ok(()) => Ok(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = id
+        )))),
+        Err(err) => Err(render_form(db, &user, Some(id), err.form, Some(err.message)).await),
+    }
+}
+
+#[post("/<slug>/assets/<id>/delete")]
+pub async fn asset_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_delete(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(assets_index(
+            slug = current_user.tenant_slug,
+            page = Option::<usize>::None
+        ))));
+    }
+
+    if let Err(message) = asset_service::delete_asset(db, tenant_id, id).await {
+        return Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            1,
+            Some(message),
+        )
+        .await);
+    }
+
+    Ok(Redirect::to(uri!(assets_index(
+        slug = current_user.tenant_slug,
+        page = Option::<usize>::None
+    ))))
+}
+
+#[post("/<slug>/assets/<id>/checkout", data = "<form>")]
+pub async fn asset_checkout(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<AssetCheckoutForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = id
+        ))));
+    }
+    let asset = match asset_service::find_asset_by_id(db, tenant_id, id).await {
+        Ok(Some(asset)) => asset,
+        _ => {
+            return Err(render_index(
+                db,
+                tenant_id,
+                &user,
+                1,
+                Some("Asset not found.".to_string()),
+            )
+            .await)
+        }
+    };
+
+    match asset_assignment_service::check_out(db, tenant_id, user.id, &asset, form.into_inner()).await {
+        Ok(()) => Ok(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = id
+        )))),
+        Err(message) => Err(render_show(db, tenant_id, &user, asset, Some(message)).await),
+    }
+}
+
+#[post("/<slug>/assets/<id>/assignments/<assignment_id>/checkin", data = "<form>")]
+pub async fn asset_checkin(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    assignment_id: i64,
+    form: Form<AssetCheckinForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = id
+        ))));
+    }
+
+    if let Err(message) = asset_assignment_service::check_in(
+        db,
+        tenant_id,
+        user.id,
+        id,
+        assignment_id,
+        form.into_inner(),
+    )
+    .await
+    {
+        return match asset_service::find_asset_by_id(db, tenant_id, id).await {
+            Ok(Some(asset)) => Err(render_show(db, tenant_id, &user, asset, Some(message)).await),
+            _ => Err(render_index(db, tenant_id, &user, 1, Some(message)).await),
+        };
+    }
+
+    Ok(Redirect::to(uri!(asset_show(
+        slug = current_user.tenant_slug,
+        id = id
+    ))))
+}
+
+#[get("/<slug>/required-kit")]
+pub async fn required_kit_form(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(required_kit_form(slug = current_user.tenant_slug))));
+    }
+    if !access_service::can_view(db, &user, "assets").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let kit = gear_service::list_required_kit(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    Ok(render_required_kit(db, tenant_id, &user, gear_service::format_required_kit(&kit), None).await)
+}
+
+#[post("/<slug>/required-kit", data = "<form>")]
+pub async fn required_kit_update(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<RequiredKitForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(required_kit_form(slug = current_user.tenant_slug))));
+    }
+
+    let form = form.into_inner();
+    match gear_service::update_required_kit(db, tenant_id, &form.kit).await {
+        Ok(()) => Ok(Redirect::to(uri!(required_kit_form(slug = current_user.tenant_slug)))),
+        Err(err) => Err(render_required_kit(db, tenant_id, &user, form.kit, Some(err)).await),
+    }
+}
+
+#[get("/<slug>/maintenance-plans")]
+pub async fn maintenance_plans_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(maintenance_plans_index(slug = current_user.tenant_slug))));
+    }
+    if !access_service::can_view(db, &user, "assets").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_maintenance_plans(
+        db,
+        tenant_id,
+        &user,
+        maintenance_service::empty_plan_form_view(),
+        None,
+    )
+    .await)
+}
+
+#[post("/<slug>/maintenance-plans", data = "<form>")]
+pub async fn maintenance_plan_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<MaintenancePlanForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(maintenance_plans_index(slug = current_user.tenant_slug))));
+    }
+
+    match maintenance_service::create_plan(db, tenant_id, form.into_inner()).await {
+        Ok(()) => Ok(Redirect::to(uri!(maintenance_plans_index(slug = current_user.tenant_slug)))),
+        Err(err) => Err(render_maintenance_plans(db, tenant_id, &user, err.form, Some(err.message)).await),
+    }
+}
+
+#[post("/<slug>/maintenance-plans/<id>/delete")]
+pub async fn maintenance_plan_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_delete(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(maintenance_plans_index(slug = current_user.tenant_slug))));
+    }
+
+    match maintenance_service::delete_plan(db, tenant_id, id).await {
+        Ok(()) => Ok(Redirect::to(uri!(maintenance_plans_index(slug = current_user.tenant_slug)))),
+        Err(message) => Err(render_maintenance_plans(
+            db,
+            tenant_id,
+            &user,
+            maintenance_service::empty_plan_form_view(),
+            Some(message),
+        )
+        .await),
+    }
+}
+
+#[post("/<slug>/assets/<id>/maintenance", data = "<form>")]
+pub async fn asset_maintenance_log(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<MaintenanceLogForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = id
+        ))));
+    }
+    let asset = match asset_service::find_asset_by_id(db, tenant_id, id).await {
+        Ok(Some(asset)) => asset,
+        _ => {
+            return Err(render_index(
+                db,
+                tenant_id,
+                &user,
+                1,
+                Some("Asset not found.".to_string()),
+            )
+            .await)
+        }
+    };
+
+    match maintenance_service::log_maintenance(db, tenant_id, user.id, &asset, form.into_inner()).await {
+        Ok(()) => Ok(Redirect::to(uri!(asset_show(
+            slug = current_user.tenant_slug,
+            id = id
+        )))),
+        Err(message) => Err(render_show(db, tenant_id, &user, asset, Some(message)).await),
+    }
+}
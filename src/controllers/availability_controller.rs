@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+
+use chrono::Duration;
+use rocket::form::Form;
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::{
+    CrewMember,
+    CurrentUserView,
+    LeaveRequest,
+    LeaveRequestForm,
+    LeaveRequestFormView,
+    LeaveReviewForm,
+    RecurringUnavailabilityForm,
+    User,
+    WorkspaceHolidayForm,
+};
+use crate::services::{
+    access_service,
+    auth_service,
+    availability_service,
+    crew_service,
+    workspace_service,
+};
+use crate::Db;
+
+async fn tenant_from_cookies(cookies: &CookieJar<'_>, db: &Db) -> Option<(i64, User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+async fn can_view_availability(db: &Db, user: &User) -> bool {
+    access_service::is_employee(&user.role) || access_service::can_view(db, user, "crew").await
+}
+
+fn can_review(user: &User) -> bool {
+    user.is_super_admin || access_service::can_review_leave(&user.role)
+}
+
+fn is_visible_member(user: &User, member: &CrewMember) -> bool {
+    !access_service::is_employee(&user.role) || availability_service::is_own_member(member, user)
+}
+
+fn can_cancel(user: &User, request: &LeaveRequest) -> bool {
+    can_review(user)
+        || request.requested_by_user_id == user.id
+        || request.member_user_id == Some(user.id)
+}
+
+fn calendar_redirect(slug: &str, month: Option<String>) -> Redirect {
+    Redirect::to(uri!(availability_index(
+        slug = slug,
+        month = month.filter(|value| !value.trim().is_empty()),
+        crew_id = Option::<i64>::None
+    )))
+}
+
+async fn find_member(db: &Db, tenant_id: i64, member_id: i64) -> Option<CrewMember> {
+    availability_service::list_members(db, tenant_id, None)
+        .await
+        .ok()?
+        .into_iter()
+        .find(|member| member.id == member_id)
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &User,
+    month: Option<&str>,
+    crew_id: Option<i64>,
+    form: LeaveRequestFormView,
+    error: Option<String>,
+) -> Template {
+    let (start, end) = availability_service::month_bounds(month);
+    let crews = crew_service::list_crews(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    let crew_names = crews
+        .iter()
+        .map(|crew| (crew.id, crew.name.clone()))
+        .collect::<HashMap<_, _>>();
+    let crew_id = crew_id.filter(|id| crew_names.contains_key(id));
+    let all_members = availability_service::list_members(db, tenant_id, None)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|member| is_visible_member(user, member))
+        .collect::<Vec<_>>();
+    let members = all_members
+        .iter()
+        .filter(|member| crew_id.is_none_or(|crew_id| member.crew_id == crew_id))
+        .cloned()
+        .collect::<Vec<_>>();
+    let member_ids = all_members.iter().map(|member| member.id).collect::<Vec<_>>();
+
+    let calendar = availability_service::load_calendar(db, tenant_id, start, end).await;
+    let days = availability_service::calendar_days(&calendar, start, end);
+    let rows = availability_service::calendar_rows(&calendar, &members, &crew_names, start, end);
+    let leave_requests = availability_service::list_leave_requests(db, tenant_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|request| {
+            !access_service::is_employee(&user.role) || member_ids.contains(&request.member_id)
+        })
+        .map(|request| {
+            let cancellable = can_cancel(user, &request);
+            context! {
+                request: request,
+                can_cancel: cancellable,
+            }
+        })
+        .collect::<Vec<_>>();
+    let holidays = availability_service::list_holidays(db, tenant_id, start, end)
+        .await
+        .unwrap_or_default();
+    let recurring = availability_service::list_recurring(db, tenant_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|rule| member_ids.contains(&rule.member_id))
+        .map(|rule| {
+            let weekday = availability_service::weekday_options()
+                .get(rule.weekday as usize)
+                .copied()
+                .unwrap_or("");
+            context! {
+                rule: rule,
+                weekday: weekday,
+            }
+        })
+        .collect::<Vec<_>>();
+    let member_options = all_members
+        .iter()
+        .filter(|member| can_review(user) || availability_service::is_own_member(member, user))
+        .map(|member| {
+            context! {
+                id: member.id,
+                label: format!(
+                    "{} ({})",
+                    member.name,
+                    crew_names.get(&member.crew_id).cloned().unwrap_or_default()
+                ),
+            }
+        })
+        .collect::<Vec<_>>();
+    let prev_month = (start - Duration::days(1)).format("%Y-%m").to_string();
+    let next_month = (end + Duration::days(1)).format("%Y-%m").to_string();
+    let weekdays = availability_service::weekday_options()
+        .iter()
+        .enumerate()
+        .map(|(index, label)| context! { value: index, label: *label })
+        .collect::<Vec<_>>();
+
+    Template::render(
+        "availability/index",
+        context! {
+            title: "Availability",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            month: start.format("%Y-%m").to_string(),
+            month_label: start.format("%B %Y").to_string(),
+            prev_month: prev_month,
+            next_month: next_month,
+            today_month: chrono::Local::now().date_naive().format("%Y-%m").to_string(),
+            crews: crews,
+            crew_id: crew_id.unwrap_or(0),
+            days: days,
+            rows: rows,
+            leave_requests: leave_requests,
+            holidays: holidays,
+            recurring: recurring,
+            member_options: member_options,
+            leave_types: availability_service::leave_type_options(),
+            weekdays: weekdays,
+            form: form,
+            error: error,
+            can_review: can_review(user),
+            can_edit: !access_service::is_plan_expired(db, user).await,
+        },
+    )
+}
+
+#[get("/<slug>/availability?<month>&<crew_id>")]
+pub async fn availability_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    month: Option<String>,
+    crew_id: Option<i64>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(availability_index(
+            slug = current_user.tenant_slug,
+            month = month,
+            crew_id = crew_id
+        ))));
+    }
+    if !can_view_availability(db, &user).await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_index(
+        db,
+        tenant_id,
+        &user,
+        month.as_deref(),
+        crew_id,
+        availability_service::empty_leave_form(),
+        None,
+    )
+    .await)
+}
+
+#[post("/<slug>/availability/leave", data = "<form>")]
+pub async fn leave_request_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<LeaveRequestForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let form = form.into_inner();
+    if current_user.tenant_slug != slug
+        || !can_view_availability(db, &user).await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(calendar_redirect(&current_user.tenant_slug, form.month));
+    }
+    let Some(member) = find_member(db, tenant_id, form.member_id)
+        .await
+        .filter(|member| can_review(&user) || availability_service::is_own_member(member, &user))
+    else {
+        let mut view = availability_service::empty_leave_form();
+        view.start_date = form.start_date.clone();
+        view.end_date = form.end_date.clone();
+        view.reason = form.reason.clone();
+        return Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            form.month.as_deref(),
+            None,
+            view,
+            Some("Choose a crew member you can file leave for.".to_string()),
+        )
+        .await);
+    };
+
+    match availability_service::request_leave(db, tenant_id, &user, &member, &form, can_review(&user))
+        .await
+    {
+        Ok(()) => Ok(calendar_redirect(&current_user.tenant_slug, form.month)),
+        Err(err) => Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            form.month.as_deref(),
+            None,
+            err.form,
+            Some(err.message),
+        )
+        .await),
+    }
+}
+
+async fn review_request(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: LeaveReviewForm,
+    approve: bool,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !can_review(&user)
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(calendar_redirect(&current_user.tenant_slug, form.month));
+    }
+    let Some(request) = availability_service::find_leave_request(db, tenant_id, id)
+        .await
+        .ok()
+        .flatten()
+    else {
+        return Ok(calendar_redirect(&current_user.tenant_slug, form.month));
+    };
+
+    match availability_service::review_leave(
+        db,
+        tenant_id,
+        &user,
+        &request,
+        approve,
+        form.review_note.as_deref(),
+    )
+    .await
+    {
+        Ok(()) => Ok(calendar_redirect(&current_user.tenant_slug, form.month)),
+        Err(message) => Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            form.month.as_deref(),
+            None,
+            availability_service::empty_leave_form(),
+            Some(message),
+        )
+        .await),
+    }
+}
+
+#[post("/<slug>/availability/leave/<id>/approve", data = "<form>")]
+pub async fn leave_request_approve(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<LeaveReviewForm>,
+) -> Result<Redirect, Template> {
+    review_request(cookies, db, slug, id, form.into_inner(), true).await
+}
+
+#[post("/<slug>/availability/leave/<id>/reject", data = "<form>")]
+pub async fn leave_request_reject(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<LeaveReviewForm>,
+) -> Result<Redirect, Template> {
+    review_request(cookies, db, slug, id, form.into_inner(), false).await
+}
+
+#[post("/<slug>/availability/leave/<id>/cancel", data = "<form>")]
+pub async fn leave_request_cancel(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<LeaveReviewForm>,
+) -> Redirect {
+    let form = form.into_inner();
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Redirect::to(uri!(crate::controllers::public_controller::login_form)),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug || access_service::is_plan_expired(db, &user).await {
+        return calendar_redirect(&current_user.tenant_slug, form.month);
+    }
+    if let Ok(Some(request)) = availability_service::find_leave_request(db, tenant_id, id).await
+        && can_cancel(&user, &request)
+    {
+        let _ = availability_service::cancel_leave(db, tenant_id, &request).await;
+    }
+    calendar_redirect(&current_user.tenant_slug, form.month)
+}
+
+#[post("/<slug>/availability/holidays", data = "<form>")]
+pub async fn holiday_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<WorkspaceHolidayForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let form = form.into_inner();
+    if current_user.tenant_slug != slug
+        || !can_review(&user)
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(calendar_redirect(&current_user.tenant_slug, form.month));
+    }
+
+    match availability_service::add_holiday(db, tenant_id, &form).await {
+        Ok(()) => Ok(calendar_redirect(&current_user.tenant_slug, form.month)),
+        Err(message) => Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            form.month.as_deref(),
+            None,
+            availability_service::empty_leave_form(),
+            Some(message),
+        )
+        .await),
+    }
+}
+
+#[post("/<slug>/availability/holidays/<id>/delete", data = "<form>")]
+pub async fn holiday_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<LeaveReviewForm>,
+) -> Redirect {
+    let form = form.into_inner();
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Redirect::to(uri!(crate::controllers::public_controller::login_form)),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug == slug
+        && can_review(&user)
+        && !access_service::is_plan_expired(db, &user).await
+    {
+        let _ = availability_service::delete_holiday(db, tenant_id, id).await;
+    }
+    calendar_redirect(&current_user.tenant_slug, form.month)
+}
+
+#[post("/<slug>/availability/recurring", data = "<form>")]
+pub async fn recurring_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<RecurringUnavailabilityForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let form = form.into_inner();
+    if current_user.tenant_slug != slug
+        || !can_review(&user)
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(calendar_redirect(&current_user.tenant_slug, form.month));
+    }
+    let result = match find_member(db, tenant_id, form.member_id).await {
+        Some(member) => availability_service::add_recurring(db, tenant_id, &member, &form).await,
+        None => Err("Choose a crew member.".to_string()),
+    };
+
+    match result {
+        Ok(()) => Ok(calendar_redirect(&current_user.tenant_slug, form.month)),
+        Err(message) => Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            form.month.as_deref(),
+            None,
+            availability_service::empty_leave_form(),
+            Some(message),
+        )
+        .await),
+    }
+}
+
+#[post("/<slug>/availability/recurring/<id>/delete", data = "<form>")]
+pub async fn recurring_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<LeaveReviewForm>,
+) -> Redirect {
+    let form = form.into_inner();
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Redirect::to(uri!(crate::controllers::public_controller::login_form)),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug == slug
+        && can_review(&user)
+        && !access_service::is_plan_expired(db, &user).await
+    {
+        let _ = availability_service::delete_recurring(db, tenant_id, id).await;
+    }
+    calendar_redirect(&current_user.tenant_slug, form.month)
+}
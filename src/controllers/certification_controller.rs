@@ -0,0 +1,396 @@
+use rocket::form::Form;
+use rocket::fs::NamedFile;
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::{
+    Crew,
+    CrewCertification,
+    CrewCertificationForm,
+    CrewCertificationFormView,
+    CrewMember,
+    CurrentUserView,
+    User,
+};
+use crate::services::{
+    access_service,
+    auth_service,
+    certification_service,
+    crew_service,
+    workspace_service,
+};
+use crate::Db;
+
+async fn tenant_from_cookies(cookies: &CookieJar<'_>, db: &Db) -> Option<(i64, User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+fn crew_profile(slug: &str, crew_id: i64) -> Redirect {
+    Redirect::to(uri!(crate::controllers::crew_controller::crew_show(
+        slug = slug,
+        id = crew_id,
+        members_page = Option::<usize>::None
+    )))
+}
+
+async fn crew_and_member(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    member_id: i64,
+) -> Option<(Crew, CrewMember)> {
+    let crew = crew_service::find_crew_by_id(db, tenant_id, crew_id)
+        .await
+        .ok()
+        .flatten()?;
+    let member = crew_service::find_member_by_id(db, tenant_id, crew_id, member_id)
+        .await
+        .ok()
+        .flatten()?;
+    Some((crew, member))
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &User,
+    crew: Crew,
+    member: CrewMember,
+    form: CrewCertificationFormView,
+    error: Option<String>,
+) -> Template {
+    let certifications =
+        certification_service::list_certifications_for_member(db, tenant_id, member.id)
+            .await
+            .unwrap_or_default();
+
+    Template::render(
+        "crew/certifications",
+        context! {
+            title: "Certifications",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            crew: crew,
+            member: member,
+            certifications: certifications,
+            form: form,
+            error: error,
+            can_edit_crew: access_service::can_edit(db, user, "crew").await,
+            can_delete_crew: access_service::can_delete(db, user, "crew").await,
+        },
+    )
+}
+
+async fn render_edit(
+    db: &Db,
+    tenant_id: i64,
+    user: &User,
+    crew: Crew,
+    member: CrewMember,
+    certification: CrewCertification,
+    form: CrewCertificationFormView,
+    error: Option<String>,
+) -> Template {
+    Template::render(
+        "crew/certification_edit",
+        context! {
+            title: "Edit certification",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            crew: crew,
+            member: member,
+            certification: certification,
+            form: form,
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/crew/<id>/members/<member_id>/certifications")]
+pub async fn member_certifications(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    member_id: i64,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(member_certifications(
+            slug = current_user.tenant_slug,
+            id = id,
+            member_id = member_id
+        ))));
+    }
+    if !access_service::can_view(db, &user, "crew").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+    let Some((crew, member)) = crew_and_member(db, tenant_id, id, member_id).await else {
+        return Err(crew_profile(&current_user.tenant_slug, id));
+    };
+
+    Ok(render_index(
+        db,
+        tenant_id,
+        &user,
+        crew,
+        member,
+        certification_service::empty_form_view(),
+        None,
+    )
+    .await)
+}
+
+#[post("/<slug>/crew/<id>/members/<member_id>/certifications", data = "<form>")]
+pub async fn member_certification_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    member_id: i64,
+    form: Form<CrewCertificationForm<'_>>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Ok(Redirect::to(uri!(member_certifications(
+            slug = current_user.tenant_slug,
+            id = id,
+            member_id = member_id
+        ))));
+    }
+    if !access_service::can_edit(db, &user, "crew").await {
+        return Ok(crew_profile(&current_user.tenant_slug, id));
+    }
+    let Some((crew, member)) = crew_and_member(db, tenant_id, id, member_id).await else {
+        return Ok(crew_profile(&current_user.tenant_slug, id));
+    };
+
+    match certification_service::create_certification(
+        db,
+        tenant_id,
+        crew.id,
+        member.id,
+        form.into_inner(),
+    )
+    .await
+    {
+        Ok(()) => Ok(Redirect::to(uri!(member_certifications(
+            slug = current_user.tenant_slug,
+            id = id,
+            member_id = member_id
+        )))),
+        Err(err) => Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            crew,
+            member,
+            err.form,
+            Some(err.message),
+        )
+        .await),
+    }
+}
+
+#[get("/<slug>/crew/<id>/members/<member_id>/certifications/<certification_id>/edit")]
+pub async fn member_certification_edit_form(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    member_id: i64,
+    certification_id: i64,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(member_certification_edit_form(
+            slug = current_user.tenant_slug,
+            id = id,
+            member_id = member_id,
+            certification_id = certification_id
+        ))));
+    }
+    if !access_service::can_edit(db, &user, "crew").await {
+        return Err(crew_profile(&current_user.tenant_slug, id));
+    }
+    let Some((crew, member)) = crew_and_member(db, tenant_id, id, member_id).await else {
+        return Err(crew_profile(&current_user.tenant_slug, id));
+    };
+    let Some(certification) =
+        certification_service::find_certification_by_id(db, tenant_id, member.id, certification_id)
+            .await
+            .ok()
+            .flatten()
+    else {
+        return Err(Redirect::to(uri!(member_certifications(
+            slug = current_user.tenant_slug,
+            id = id,
+            member_id = member_id
+        ))));
+    };
+
+    let form = certification_service::form_view(&certification);
+    Ok(render_edit(db, tenant_id, &user, crew, member, certification, form, None).await)
+}
+
+#[post(
+    "/<slug>/crew/<id>/members/<member_id>/certifications/<certification_id>",
+    data = "<form>"
+)]
+pub async fn member_certification_update(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    member_id: i64,
+    certification_id: i64,
+    form: Form<CrewCertificationForm<'_>>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Ok(Redirect::to(uri!(member_certifications(
+            slug = current_user.tenant_slug,
+            id = id,
+            member_id = member_id
+        ))));
+    }
+    if !access_service::can_edit(db, &user, "crew").await {
+        return Ok(crew_profile(&current_user.tenant_slug, id));
+    }
+    let Some((crew, member)) = crew_and_member(db, tenant_id, id, member_id).await else {
+        return Ok(crew_profile(&current_user.tenant_slug, id));
+    };
+    let Some(certification) =
+        certification_service::find_certification_by_id(db, tenant_id, member.id, certification_id)
+            .await
+            .ok()
+            .flatten()
+    else {
+        return Ok(Redirect::to(uri!(member_certifications(
+            slug = current_user.tenant_slug,
+            id = id,
+            member_id = member_id
+        ))));
+    };
+
+    match certification_service::update_certification(
+        db,
+        tenant_id,
+        &certification,
+        form.into_inner(),
+    )
+    .await
+    {
+        Ok(()) => Ok(Redirect::to(uri!(member_certifications(
+            slug = current_user.tenant_slug,
+            id = id,
+            member_id = member_id
+        )))),
+        Err(err) => Err(render_edit(
+            db,
+            tenant_id,
+            &user,
+            crew,
+            member,
+            certification,
+            err.form,
+            Some(err.message),
+        )
+        .await),
+    }
+}
+
+#[post("/<slug>/crew/<id>/members/<member_id>/certifications/<certification_id>/delete")]
+pub async fn member_certification_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    member_id: i64,
+    certification_id: i64,
+) -> Redirect {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Redirect::to(uri!(crate::controllers::public_controller::login_form)),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug || !access_service::can_delete(db, &user, "crew").await {
+        return crew_profile(&current_user.tenant_slug, id);
+    }
+    if crew_and_member(db, tenant_id, id, member_id).await.is_none() {
+        return crew_profile(&current_user.tenant_slug, id);
+    }
+    if let Ok(Some(certification)) =
+        certification_service::find_certification_by_id(db, tenant_id, member_id, certification_id)
+            .await
+    {
+        let _ = certification_service::delete_certification(db, tenant_id, &certification).await;
+    }
+    Redirect::to(uri!(member_certifications(
+        slug = current_user.tenant_slug,
+        id = id,
+        member_id = member_id
+    )))
+}
+
+#[get("/<slug>/crew/<id>/members/<member_id>/certifications/<certification_id>/document")]
+pub async fn member_certification_document(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    member_id: i64,
+    certification_id: i64,
+) -> Option<NamedFile> {
+    let (tenant_id, user) = tenant_from_cookies(cookies, db).await?;
+    if CurrentUserView::from(&user).tenant_slug != slug
+        || !access_service::can_view(db, &user, "crew").await
+    {
+        return None;
+    }
+    crew_and_member(db, tenant_id, id, member_id).await?;
+    let certification =
+        certification_service::find_certification_by_id(db, tenant_id, member_id, certification_id)
+            .await
+            .ok()
+            .flatten()?;
+    let path = certification_service::document_file_path(&certification)?;
+    NamedFile::open(path).await.ok()
+}
@@ -10,6 +10,7 @@ use crate::models::{
     ClientContactFormView,
     ClientForm,
     ClientFormView,
+    ClientGeofenceForm,
     CurrentUserView,
     DiscussionForm,
     DiscussionFormView,
@@ -18,7 +19,7 @@ use crate::models::{
     PaginationView,
 };
 use crate::repositories::user_repo;
-use crate::services::{access_service, appointment_service, auth_service, client_service, discussion_service, email_service, workspace_service};
+use crate::services::{access_service, appointment_service, auth_service, client_service, custom_field_service, discussion_service, email_service, workspace_service};
 use crate::Db;
 
 const PER_PAGE: usize = 10;
@@ -172,6 +173,9 @@ pub async fn clients_index(
     let pagination = pagination_view(page, total_clients, |target_page| {
         format!("/{}/clients?page={}", tenant_slug, target_page)
     });
+    let client_ids = clients.iter().map(|client| client.id).collect::<Vec<_>>();
+    let custom_columns =
+        custom_field_service::list_columns(db, tenant_id, custom_field_service::ENTITY_CLIENTS, &client_ids).await;
     let (_plan_key, limits) = workspace_service::plan_limits_for_tenant(db, tenant_id).await;
     let client_limit = limits.clients.unwrap_or(0);
     let client_limit_reached = limits
@@ -189,6 +193,7 @@ pub async fn clients_index(
             pagination: pagination,
             client_limit: client_limit,
             client_limit_reached: client_limit_reached,
+            custom_columns: custom_columns,
         },
     ))
 }
@@ -222,6 +227,7 @@ pub async fn client_new_form(
             workspace_brand: workspace_brand(db, user.tenant_id).await,
             error: Option::<String>::None,
             form: ClientFormView::new("", "", "", "", "", "", "Proposal", "USD"),
+            custom_inputs: custom_field_service::inputs_for_record(db, user.tenant_id, custom_field_service::ENTITY_CLIENTS, None).await,
             stage_options: client_service::client_stage_options(),
             currency_options: client_service::currency_options(),
         },
@@ -253,6 +259,7 @@ pub async fn client_create(
         ))));
     }
     let form = form.into_inner();
+    let submitted_custom = form.custom.clone();
     match client_service::create_client(db, tenant_id, form).await {
         Ok(_) => Ok(Redirect::to(uri!(clients_index(
             slug = current_user.tenant_slug,
@@ -266,6 +273,7 @@ pub async fn client_create(
             workspace_brand: workspace_brand(db, user.tenant_id).await,
                 error: err.message,
                 form: err.form,
+                custom_inputs: custom_field_service::inputs_from_form(db, tenant_id, custom_field_service::ENTITY_CLIENTS, &submitted_custom).await,
                 stage_options: client_service::client_stage_options(),
                 currency_options: client_service::currency_options(),
             },
@@ -273,50 +281,17 @@ pub async fn client_create(
     }
 }
 
-#[get("/<slug>/clients/<id>/profile?<contacts_page>&<appointments_page>")]
-pub async fn client_show(
-    cookies: &CookieJar<'_>,
+async fn render_client_show(
     db: &Db,
-    slug: &str,
-    id: i64,
+    tenant_id: i64,
+    user: &crate::models::User,
+    client: crate::models::Client,
     contacts_page: Option<usize>,
     appointments_page: Option<usize>,
-) -> Result<Template, Redirect> {
-    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
-        Some(data) => data,
-        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
-    };
-    let current_user = CurrentUserView::from(&user);
-    if current_user.tenant_slug != slug {
-        return Err(Redirect::to(uri!(client_show(
-            slug = current_user.tenant_slug,
-            id = id,
-            contacts_page = Option::<usize>::None,
-            appointments_page = Option::<usize>::None
-        ))));
-    }
-    if !access_service::can_view(db, &user, "clients").await {
-        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
-            slug = current_user.tenant_slug
-        ))));
-    }
-    let tenant_slug = current_user.tenant_slug.clone();
-
-    let client = match client_service::find_client_by_id(db, tenant_id, id).await {
-        Ok(Some(client)) => client,
-        _ => {
-            return Ok(Template::render(
-                "clients/index",
-                context! {
-                    title: "Clients",
-                    current_user: Some(current_user),
-            workspace_brand: workspace_brand(db, user.tenant_id).await,
-                    clients: Vec::<crate::models::Client>::new(),
-                    error: "Client not found.".to_string(),
-                },
-            ))
-        }
-    };
+    error: Option<String>,
+) -> Template {
+    let tenant_slug = CurrentUserView::from(user).tenant_slug;
+    let id = client.id;
     let portal_url = portal_link_for_client(db, tenant_id, &tenant_slug, &client).await;
 
     let contacts_page = normalize_page(contacts_page);
@@ -377,14 +352,19 @@ pub async fn client_show(
     let discussions = discussion_service::list_discussions_by_client(db, tenant_id, id)
         .await
         .unwrap_or_default();
-    let can_edit_clients = access_service::can_edit(db, &user, "clients").await;
-    let can_delete_clients = access_service::can_delete(db, &user, "clients").await;
-
-    Ok(Template::render(
+    let custom_values =
+        custom_field_service::values_for_record(db, tenant_id, custom_field_service::ENTITY_CLIENTS, id).await;
+    let contact_ids = contacts.iter().map(|contact| contact.id).collect::<Vec<_>>();
+    let contact_columns =
+        custom_field_service::list_columns(db, tenant_id, custom_field_service::ENTITY_CONTACTS, &contact_ids).await;
+    let can_edit_clients = access_service::can_edit(db, user, "clients").await;
+    let can_delete_clients = access_service::can_delete(db, user, "clients").await;
+
+    Template::render(
         "clients/show",
         context! {
             title: "Client details",
-            current_user: Some(current_user),
+            current_user: Some(CurrentUserView::from(user)),
             workspace_brand: workspace_brand(db, user.tenant_id).await,
             include_map: true,
             client: client,
@@ -403,8 +383,66 @@ pub async fn client_show(
             discussions: discussions,
             can_edit_clients: can_edit_clients,
             can_delete_clients: can_delete_clients,
+            custom_values: custom_values,
+            contact_columns: contact_columns,
+            geofence_mode_options: client_service::geofence_mode_options(),
+            error: error,
         },
-    ))
+    )
+}
+
+#[get("/<slug>/clients/<id>/profile?<contacts_page>&<appointments_page>")]
+pub async fn client_show(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    contacts_page: Option<usize>,
+    appointments_page: Option<usize>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(client_show(
+            slug = current_user.tenant_slug,
+            id = id,
+            contacts_page = Option::<usize>::None,
+            appointments_page = Option::<usize>::None
+        ))));
+    }
+    if !access_service::can_view(db, &user, "clients").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+    let client = match client_service::find_client_by_id(db, tenant_id, id).await {
+        Ok(Some(client)) => client,
+        _ => {
+            return Ok(Template::render(
+                "clients/index",
+                context! {
+                    title: "Clients",
+                    current_user: Some(current_user),
+            workspace_brand: workspace_brand(db, user.tenant_id).await,
+                    clients: Vec::<crate::models::Client>::new(),
+                    error: "Client not found.".to_string(),
+                },
+            ))
+        }
+    };
+    Ok(render_client_show(
+        db,
+        tenant_id,
+        &user,
+        client,
+        contacts_page,
+        appointments_page,
+        None,
+    )
+    .await)
 }
 
 #[get("/<slug>/clients/<id>/edit")]
@@ -468,6 +506,7 @@ pub async fn client_edit_form(
                 client.stage,
                 client.currency,
             ),
+            custom_inputs: custom_field_service::inputs_for_record(db, tenant_id, custom_field_service::ENTITY_CLIENTS, Some(client.id)).await,
             stage_options: client_service::client_stage_options(),
             currency_options: client_service::currency_options(),
         },
@@ -502,6 +541,7 @@ pub async fn client_update(
         ))));
     }
     let form = form.into_inner();
+    let submitted_custom = form.custom.clone();
     match client_service::update_client(db, tenant_id, id, form).await {
         Ok(_) => Ok(Redirect::to(uri!(clients_index(
             slug = current_user.tenant_slug,
@@ -516,6 +556,7 @@ pub async fn client_update(
                 error: err.message,
                 client_id: id,
                 form: err.form,
+                custom_inputs: custom_field_service::inputs_from_form(db, tenant_id, custom_field_service::ENTITY_CLIENTS, &submitted_custom).await,
                 stage_options: client_service::client_stage_options(),
                 currency_options: client_service::currency_options(),
             },
@@ -523,6 +564,52 @@ pub async fn client_update(
     }
 }
 
+#[post("/<slug>/clients/<id>/geofence", data = "<form>")]
+pub async fn client_geofence_update(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<ClientGeofenceForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let show_redirect = Redirect::to(uri!(client_show(
+        slug = current_user.tenant_slug.clone(),
+        id = id,
+        contacts_page = Option::<usize>::None,
+        appointments_page = Option::<usize>::None
+    )));
+    if current_user.tenant_slug != slug || !access_service::can_edit(db, &user, "clients").await {
+        return Ok(show_redirect);
+    }
+    let client = match client_service::find_client_by_id(db, tenant_id, id).await {
+        Ok(Some(client)) => client,
+        _ => {
+            return Ok(Redirect::to(uri!(clients_index(
+                slug = current_user.tenant_slug,
+                page = Option::<usize>::None
+            ))))
+        }
+    };
+    match client_service::update_geofence(db, tenant_id, &client, form.into_inner()).await {
+        Ok(_) => Ok(show_redirect),
+        Err(message) => Err(render_client_show(
+            db,
+            tenant_id,
+            &user,
+            client,
+            None,
+            None,
+            Some(message),
+        )
+        .await),
+    }
+}
+
 #[post("/<slug>/clients/<id>/delete")]
 pub async fn client_delete(
     cookies: &CookieJar<'_>,
@@ -619,6 +706,7 @@ pub async fn contact_new_form(
             error: Option::<String>::None,
             client: client,
             form: ClientContactFormView::new("", "", "", "", "", ""),
+            custom_inputs: custom_field_service::inputs_for_record(db, tenant_id, custom_field_service::ENTITY_CONTACTS, None).await,
         },
     ))
 }
@@ -669,6 +757,7 @@ pub async fn contact_create(
         }
     };
 
+    let submitted_custom = form.custom.clone();
     match client_service::create_contact(db, tenant_id, id, form).await {
         Ok(_) => Ok(Redirect::to(uri!(client_show(
             slug = current_user.tenant_slug,
@@ -685,6 +774,7 @@ pub async fn contact_create(
             error: err.message,
             client: client,
             form: err.form,
+            custom_inputs: custom_field_service::inputs_from_form(db, tenant_id, custom_field_service::ENTITY_CONTACTS, &submitted_custom).await,
         },
     )),
     }
@@ -780,6 +870,7 @@ pub async fn contact_edit_form(
                 contact.department,
                 contact.position,
             ),
+            custom_inputs: custom_field_service::inputs_for_record(db, tenant_id, custom_field_service::ENTITY_CONTACTS, Some(contact.id)).await,
         },
     ))
 }
@@ -831,6 +922,7 @@ pub async fn contact_update(
         }
     };
 
+    let submitted_custom = form.custom.clone();
     match client_service::update_contact(db, tenant_id, id, contact_id, form).await {
         Ok(_) => Ok(Redirect::to(uri!(client_show(
             slug = current_user.tenant_slug,
@@ -848,6 +940,7 @@ pub async fn contact_update(
             error: err.message,
             contact_id: contact_id,
             form: err.form,
+            custom_inputs: custom_field_service::inputs_from_form(db, tenant_id, custom_field_service::ENTITY_CONTACTS, &submitted_custom).await,
         },
     )),
     }
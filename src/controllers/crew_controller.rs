@@ -13,7 +13,16 @@ use crate::models::{
     DiscussionFormView,
     PaginationView,
 };
-use crate::services::{access_service, auth_service, crew_discussion_service, crew_service, workspace_service};
+use crate::services::{
+    access_service,
+    auth_service,
+    certification_service,
+    crew_discussion_service,
+    crew_service,
+    custom_field_service,
+    gear_service,
+    workspace_service,
+};
 use crate::repositories::user_repo;
 use crate::Db;
 
@@ -129,6 +138,9 @@ pub async fn crew_index(
     let pagination = pagination_view(page, total_crews, |target_page| {
         format!("/{}/crew?page={}", current_user.tenant_slug, target_page)
     });
+    let crew_ids = roster.iter().map(|crew| crew.id).collect::<Vec<_>>();
+    let custom_columns =
+        custom_field_service::list_columns(db, tenant_id, custom_field_service::ENTITY_CREWS, &crew_ids).await;
 
     Ok(Template::render(
         "crew/index",
@@ -141,6 +153,7 @@ pub async fn crew_index(
             crew_limit_reached: crew_limit_reached,
             crew_limit: crew_limit,
             pagination: pagination,
+            custom_columns: custom_columns,
         },
     ))
 }
@@ -196,6 +209,9 @@ pub async fn crew_show(
         .await
         .unwrap_or_default();
     let total_members = crew_service::count_members(db, tenant_id, id).await.unwrap_or(0);
+    let member_ids = members.iter().map(|member| member.id).collect::<Vec<_>>();
+    let member_columns =
+        custom_field_service::list_columns(db, tenant_id, custom_field_service::ENTITY_MEMBERS, &member_ids).await;
     let members_pagination = pagination_view(members_page, total_members, |target_page| {
         format!(
             "/{}/crew/{}/profile?members_page={}",
@@ -213,6 +229,20 @@ pub async fn crew_show(
         .unwrap_or_default();
     let can_edit_crew = access_service::can_edit(db, &user, "crew").await;
     let can_delete_crew = access_service::can_delete(db, &user, "crew").await;
+    let certifications = certification_service::list_certifications_for_crew(db, tenant_id, id)
+        .await
+        .unwrap_or_default();
+    let gear = gear_service::crew_gear_view(db, tenant_id, &crew).await;
+    let (certified_skills, lapsed_skills) = certification_service::certified_skills(&certifications)
+        .remove(&id)
+        .map(|skills| {
+            let mut valid = skills.valid.into_iter().collect::<Vec<_>>();
+            let mut lapsed = skills.lapsed.into_iter().collect::<Vec<_>>();
+            valid.sort();
+            lapsed.sort();
+            (valid, lapsed)
+        })
+        .unwrap_or_default();
 
     Ok(Template::render(
         "crew/show",
@@ -222,13 +252,20 @@ pub async fn crew_show(
             workspace_brand: workspace_brand(db, user.tenant_id).await,
             crew: crew,
             members: members,
+            member_columns: member_columns,
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
             members_count: total_members as usize,
             member_limit_reached: member_limit_reached,
             member_limit: member_limit,
             members_pagination: members_pagination,
             discussions: discussions,
+            certifications: certifications,
+            certified_skills: certified_skills,
+            lapsed_skills: lapsed_skills,
+            gear: gear,
             can_edit_crew: can_edit_crew,
             can_delete_crew: can_delete_crew,
+            custom_values: custom_field_service::values_for_record(db, tenant_id, custom_field_service::ENTITY_CREWS, id).await,
         },
     ))
 }
@@ -262,6 +299,7 @@ pub async fn crew_new_form(
             workspace_brand: workspace_brand(db, user.tenant_id).await,
             error: Option::<String>::None,
             form: CrewFormView::new("", "Active", 100, "", ""),
+            custom_inputs: custom_field_service::inputs_for_record(db, user.tenant_id, custom_field_service::ENTITY_CREWS, None).await,
             status_options: crew_service::status_options(),
         },
     ))
@@ -292,6 +330,7 @@ pub async fn crew_create(
         ))));
     }
     let form = form.into_inner();
+    let submitted_custom = form.custom.clone();
     match crew_service::create_crew(db, tenant_id, form).await {
         Ok(_) => Ok(Redirect::to(uri!(crew_index(
             slug = current_user.tenant_slug,
@@ -305,6 +344,7 @@ pub async fn crew_create(
             workspace_brand: workspace_brand(db, user.tenant_id).await,
                 error: err.message,
                 form: err.form,
+                custom_inputs: custom_field_service::inputs_from_form(db, tenant_id, custom_field_service::ENTITY_CREWS, &submitted_custom).await,
                 status_options: crew_service::status_options(),
             },
         )),
@@ -352,6 +392,8 @@ pub async fn crew_edit_form(
         }
     };
 
+    let gear_override = gear_service::find_override(db, tenant_id, crew.id).await;
+
     Ok(Template::render(
         "crew/edit",
         context! {
@@ -366,7 +408,9 @@ pub async fn crew_edit_form(
                 crew.gear_score,
                 crew.skill_tags,
                 crew.compatibility_tags,
-            ),
+            )
+            .with_gear_override(gear_override.0.is_some(), gear_override.1),
+            custom_inputs: custom_field_service::inputs_for_record(db, tenant_id, custom_field_service::ENTITY_CREWS, Some(crew.id)).await,
             status_options: crew_service::status_options(),
         },
     ))
@@ -427,9 +471,11 @@ pub async fn crew_member_new_form(
             workspace_brand: workspace_brand(db, user.tenant_id).await,
             error: Option::<String>::None,
             crew: crew,
-            form: CrewMemberFormView::new(0, "", "", "", "Available"),
+            form: CrewMemberFormView::new(0, "", "", "", "Available", None),
+            custom_inputs: custom_field_service::inputs_for_record(db, tenant_id, custom_field_service::ENTITY_MEMBERS, None).await,
             users: users,
             availability_options: crew_service::availability_options(),
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
         },
     ))
 }
@@ -462,6 +508,7 @@ pub async fn crew_member_create(
         ))));
     }
     let form = form.into_inner();
+    let submitted_custom = form.custom.clone();
     let crew = match crew_service::find_crew_by_id(db, tenant_id, id).await {
         Ok(Some(crew)) => crew,
         _ => {
@@ -498,8 +545,10 @@ pub async fn crew_member_create(
             error: err.message,
             crew: crew,
             form: err.form,
+            custom_inputs: custom_field_service::inputs_from_form(db, tenant_id, custom_field_service::ENTITY_MEMBERS, &submitted_custom).await,
             users: users,
             availability_options: crew_service::availability_options(),
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
         },
     )),
     }
@@ -601,9 +650,12 @@ pub async fn crew_member_edit_form(
                 member.phone,
                 member.position,
                 member.availability_status,
+                Some(member.hourly_cost),
             ),
+            custom_inputs: custom_field_service::inputs_for_record(db, tenant_id, custom_field_service::ENTITY_MEMBERS, Some(member.id)).await,
             users: users,
             availability_options: crew_service::availability_options(),
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
         },
     ))
 }
@@ -637,6 +689,7 @@ pub async fn crew_member_update(
         ))));
     }
     let form = form.into_inner();
+    let submitted_custom = form.custom.clone();
     let crew = match crew_service::find_crew_by_id(db, tenant_id, id).await {
         Ok(Some(crew)) => crew,
         _ => {
@@ -674,8 +727,10 @@ pub async fn crew_member_update(
             error: err.message,
             member_id: member_id,
             form: err.form,
+            custom_inputs: custom_field_service::inputs_from_form(db, tenant_id, custom_field_service::ENTITY_MEMBERS, &submitted_custom).await,
             users: users,
             availability_options: crew_service::availability_options(),
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
         },
     )),
     }
@@ -1121,6 +1176,7 @@ pub async fn crew_update(
         ))));
     }
     let form = form.into_inner();
+    let submitted_custom = form.custom.clone();
     match crew_service::update_crew(db, tenant_id, id, form).await {
         Ok(_) => Ok(Redirect::to(uri!(crew_index(
             slug = current_user.tenant_slug,
@@ -1135,6 +1191,7 @@ pub async fn crew_update(
                 error: err.message,
                 crew_id: id,
                 form: err.form,
+                custom_inputs: custom_field_service::inputs_from_form(db, tenant_id, custom_field_service::ENTITY_CREWS, &submitted_custom).await,
                 status_options: crew_service::status_options(),
             },
         )),
@@ -0,0 +1,207 @@
+use rocket::form::Form;
+use rocket::http::{CookieJar, Header, Status};
+use rocket::response::Redirect;
+use rocket::serde::json::Json;
+use rocket_dyn_templates::{context, Template};
+use serde_json::Value;
+
+use crate::models::{CurrentUserView, CustomFieldForm, CustomFieldFormView};
+use crate::services::{access_service, auth_service, custom_field_service, export_service, workspace_service};
+use crate::Db;
+
+#[derive(Responder)]
+#[response(content_type = "text/csv")]
+pub struct RecordsCsv {
+    body: String,
+    disposition: Header<'static>,
+}
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+async fn render_custom_fields(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    form: CustomFieldFormView,
+    error: Option<String>,
+) -> Template {
+    let definitions = custom_field_service::list_all_definitions(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    let can_edit_settings = access_service::can_edit(db, user, "settings").await
+        && !access_service::is_plan_expired(db, user).await;
+    Template::render(
+        "custom_fields/index",
+        context! {
+            title: "Custom fields",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            definitions: definitions,
+            form: form,
+            entity_options: custom_field_service::entity_options(),
+            type_options: custom_field_service::type_options(),
+            can_edit_settings: can_edit_settings,
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/custom-fields")]
+pub async fn custom_fields_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(custom_fields_index(slug = current_user.tenant_slug))));
+    }
+    if !access_service::can_view(db, &user, "settings").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_custom_fields(db, tenant_id, &user, custom_field_service::empty_form_view(), None).await)
+}
+
+#[post("/<slug>/custom-fields", data = "<form>")]
+pub async fn custom_field_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<CustomFieldForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "settings").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(custom_fields_index(slug = current_user.tenant_slug))));
+    }
+
+    match custom_field_service::create_definition(db, tenant_id, form.into_inner()).await {
+        Ok(()) => Ok(Redirect::to(uri!(custom_fields_index(slug = current_user.tenant_slug)))),
+        Err(err) => Err(render_custom_fields(db, tenant_id, &user, err.form, Some(err.message)).await),
+    }
+}
+
+#[post("/<slug>/custom-fields/<id>/delete")]
+pub async fn custom_field_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_delete(db, &user, "settings").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(custom_fields_index(slug = current_user.tenant_slug))));
+    }
+
+    match custom_field_service::delete_definition(db, tenant_id, id).await {
+        Ok(()) => Ok(Redirect::to(uri!(custom_fields_index(slug = current_user.tenant_slug)))),
+        Err(message) => Err(render_custom_fields(
+            db,
+            tenant_id,
+            &user,
+            custom_field_service::empty_form_view(),
+            Some(message),
+        )
+        .await),
+    }
+}
+
+#[get("/<slug>/exports/<entity>")]
+pub async fn records_export(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    entity: &str,
+) -> Result<RecordsCsv, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let dashboard = Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+        slug = current_user.tenant_slug.clone()
+    )));
+    let Some(resource) = export_service::resource_for(entity) else {
+        return Err(dashboard);
+    };
+    if current_user.tenant_slug != slug || !access_service::can_view(db, &user, resource).await {
+        return Err(dashboard);
+    }
+
+    let scope = export_service::scope_for(db, &user).await;
+    let body = export_service::export_csv(db, tenant_id, entity, &scope)
+        .await
+        .map_err(|_| dashboard)?;
+    Ok(RecordsCsv {
+        body,
+        disposition: Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"{slug}-{entity}.csv\""),
+        ),
+    })
+}
+
+#[get("/<slug>/api/<entity>")]
+pub async fn records_api(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    entity: &str,
+) -> Result<Json<Vec<Value>>, Status> {
+    let (tenant_id, user) = tenant_from_cookies(cookies, db)
+        .await
+        .ok_or(Status::Unauthorized)?;
+    let resource = export_service::resource_for(entity).ok_or(Status::NotFound)?;
+    if user.tenant_slug != slug || !access_service::can_view(db, &user, resource).await {
+        return Err(Status::Forbidden);
+    }
+
+    let scope = export_service::scope_for(db, &user).await;
+    export_service::export_json(db, tenant_id, entity, &scope)
+        .await
+        .map(Json)
+        .map_err(|_| Status::InternalServerError)
+}
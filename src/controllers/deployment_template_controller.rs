@@ -0,0 +1,356 @@
+use rocket::form::Form;
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::{CurrentUserView, DeploymentTemplateForm, DeploymentTemplateFormView};
+use crate::services::{
+    access_service,
+    auth_service,
+    deployment_service,
+    deployment_template_service,
+    workspace_service,
+};
+use crate::Db;
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    error: Option<String>,
+) -> Template {
+    let templates = deployment_template_service::list_template_usage(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    let can_edit_templates = access_service::can_edit(db, user, "deployments").await;
+    let can_delete_templates = access_service::can_delete(db, user, "deployments").await;
+    Template::render(
+        "deployment_templates/index",
+        context! {
+            title: "Deployment templates",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            templates: templates,
+            can_edit_templates: can_edit_templates,
+            can_delete_templates: can_delete_templates,
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/deployment-templates")]
+pub async fn deployment_templates_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(deployment_templates_index(
+            slug = current_user.tenant_slug
+        ))));
+    }
+    if !access_service::can_view(db, &user, "deployments").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_index(db, tenant_id, &user, None).await)
+}
+
+#[get("/<slug>/deployment-templates/report")]
+pub async fn deployment_templates_report(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(deployment_templates_report(
+            slug = current_user.tenant_slug
+        ))));
+    }
+    if !access_service::can_view(db, &user, "deployments").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let usage = deployment_template_service::list_template_usage(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    let total_usage: i64 = usage.iter().map(|item| item.usage_count).sum();
+    let total_hours = usage.iter().fold(0.0, |sum, item| sum + item.total_hours);
+    let unused_templates = usage.iter().filter(|item| item.usage_count == 0).count();
+    let rows = usage
+        .into_iter()
+        .map(|item| {
+            let share = if total_usage > 0 {
+                ((item.usage_count as f64 / total_usage as f64) * 100.0).round() as i64
+            } else {
+                0
+            };
+            let completion_rate = if item.usage_count > 0 {
+                ((item.completed_count as f64 / item.usage_count as f64) * 100.0).round() as i64
+            } else {
+                0
+            };
+            context! {
+                id: item.id,
+                name: item.name,
+                deployment_type: item.deployment_type,
+                usage_count: item.usage_count,
+                scheduled_count: item.scheduled_count,
+                active_count: item.active_count,
+                completed_count: item.completed_count,
+                cancelled_count: item.cancelled_count,
+                total_hours: (item.total_hours * 100.0).round() / 100.0,
+                last_used_at: item.last_used_at,
+                share: share,
+                completion_rate: completion_rate,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Template::render(
+        "deployment_templates/report",
+        context! {
+            title: "Template usage",
+            current_user: Some(current_user),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            rows: rows,
+            total_usage: total_usage,
+            total_hours: (total_hours * 100.0).round() / 100.0,
+            unused_templates: unused_templates,
+        },
+    ))
+}
+
+#[get("/<slug>/deployment-templates/new")]
+pub async fn deployment_template_new_form(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(deployment_template_new_form(
+            slug = current_user.tenant_slug
+        ))));
+    }
+    if !access_service::can_edit(db, &user, "deployments").await {
+        return Err(Redirect::to(uri!(deployment_templates_index(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(Template::render(
+        "deployment_templates/new",
+        context! {
+            title: "New deployment template",
+            current_user: Some(current_user),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            error: Option::<String>::None,
+            form: DeploymentTemplateFormView::new("", "Onsite", "", "", 0.0, 8.0, "", ""),
+            deployment_type_options: deployment_service::deployment_type_options(),
+        },
+    ))
+}
+
+#[post("/<slug>/deployment-templates", data = "<form>")]
+pub async fn deployment_template_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<DeploymentTemplateForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "deployments").await
+    {
+        return Ok(Redirect::to(uri!(deployment_templates_index(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    match deployment_template_service::create_template(db, tenant_id, form.into_inner()).await {
+        Ok(_) => Ok(Redirect::to(uri!(deployment_templates_index(
+            slug = current_user.tenant_slug
+        )))),
+        Err(err) => Err(Template::render(
+            "deployment_templates/new",
+            context! {
+                title: "New deployment template",
+                current_user: Some(current_user),
+                workspace_brand: workspace_brand(db, tenant_id).await,
+                error: err.message,
+                form: err.form,
+                deployment_type_options: deployment_service::deployment_type_options(),
+            },
+        )),
+    }
+}
+
+#[get("/<slug>/deployment-templates/<id>/edit")]
+pub async fn deployment_template_edit_form(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(deployment_template_edit_form(
+            slug = current_user.tenant_slug,
+            id = id
+        ))));
+    }
+    if !access_service::can_edit(db, &user, "deployments").await {
+        return Err(Redirect::to(uri!(deployment_templates_index(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let template = match deployment_template_service::find_template_by_id(db, tenant_id, id).await
+    {
+        Ok(Some(template)) => template,
+        _ => {
+            return Ok(render_index(
+                db,
+                tenant_id,
+                &user,
+                Some("Template not found.".to_string()),
+            )
+            .await)
+        }
+    };
+
+    Ok(Template::render(
+        "deployment_templates/edit",
+        context! {
+            title: "Edit deployment template",
+            current_user: Some(current_user),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            error: Option::<String>::None,
+            template_id: template.id,
+            form: deployment_template_service::template_form_view(&template),
+            deployment_type_options: deployment_service::deployment_type_options(),
+        },
+    ))
+}
+
+#[post("/<slug>/deployment-templates/<id>", data = "<form>")]
+pub async fn deployment_template_update(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<DeploymentTemplateForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "deployments").await
+    {
+        return Ok(Redirect::to(uri!(deployment_templates_index(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    match deployment_template_service::update_template(db, tenant_id, id, form.into_inner()).await
+    {
+        Ok(_) => Ok(Redirect::to(uri!(deployment_templates_index(
+            slug = current_user.tenant_slug
+        )))),
+        Err(err) => Err(Template::render(
+            "deployment_templates/edit",
+            context! {
+                title: "Edit deployment template",
+                current_user: Some(current_user),
+                workspace_brand: workspace_brand(db, tenant_id).await,
+                error: err.message,
+                template_id: id,
+                form: err.form,
+                deployment_type_options: deployment_service::deployment_type_options(),
+            },
+        )),
+    }
+}
+
+#[post("/<slug>/deployment-templates/<id>/delete")]
+pub async fn deployment_template_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_delete(db, &user, "deployments").await
+    {
+        return Ok(Redirect::to(uri!(deployment_templates_index(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    if let Err(message) = deployment_template_service::delete_template(db, tenant_id, id).await {
+        return Err(render_index(db, tenant_id, &user, Some(message)).await);
+    }
+
+    Ok(Redirect::to(uri!(deployment_templates_index(
+        slug = current_user.tenant_slug
+    ))))
+}
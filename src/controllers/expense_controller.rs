@@ -0,0 +1,246 @@
+use rocket::form::Form;
+use rocket::fs::NamedFile;
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::{CurrentUserView, ExpenseReviewForm};
+use crate::services::{access_service, auth_service, expense_service, workspace_service};
+use crate::Db;
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+fn can_view_expense_queue(user: &crate::models::User) -> bool {
+    user.is_super_admin
+        || access_service::can_review_expenses(&user.role)
+        || access_service::is_accounting(&user.role)
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    status: Option<String>,
+    error: Option<String>,
+) -> Template {
+    let status_filter = status.filter(|value| {
+        expense_service::status_options()
+            .iter()
+            .any(|option| option.eq(value))
+    });
+    let expenses = expense_service::list_expenses_by_status(db, tenant_id, status_filter.as_deref())
+        .await
+        .unwrap_or_default();
+    let pending_count = expense_service::count_pending_expenses(db, tenant_id)
+        .await
+        .unwrap_or(0);
+    Template::render(
+        "expenses/index",
+        context! {
+            title: "Expenses",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            expenses: expenses,
+            status_filter: status_filter,
+            status_options: expense_service::status_options(),
+            pending_count: pending_count,
+            can_review: user.is_super_admin || access_service::can_review_expenses(&user.role),
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/expenses?<status>")]
+pub async fn expenses_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    status: Option<String>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(expenses_index(
+            slug = current_user.tenant_slug,
+            status = Option::<String>::None
+        ))));
+    }
+    if !can_view_expense_queue(&user) {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let status = status.or_else(|| Some(expense_service::STATUS_SUBMITTED.to_string()));
+    Ok(render_index(db, tenant_id, &user, status, None).await)
+}
+
+async fn review(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    approve: bool,
+    form: ExpenseReviewForm,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !(user.is_super_admin || access_service::can_review_expenses(&user.role))
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(expenses_index(
+            slug = current_user.tenant_slug,
+            status = Option::<String>::None
+        ))));
+    }
+
+    let expense = match expense_service::find_expense_by_id(db, tenant_id, id).await {
+        Ok(Some(expense)) => expense,
+        _ => {
+            return Err(render_index(
+                db,
+                tenant_id,
+                &user,
+                None,
+                Some("Expense not found.".to_string()),
+            )
+            .await)
+        }
+    };
+    if let Err(message) = expense_service::review_expense(
+        db,
+        tenant_id,
+        &expense,
+        user.id,
+        approve,
+        form.review_note,
+    )
+    .await
+    {
+        return Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            Some(expense_service::STATUS_SUBMITTED.to_string()),
+            Some(message),
+        )
+        .await);
+    }
+
+    Ok(Redirect::to(uri!(expenses_index(
+        slug = current_user.tenant_slug,
+        status = Option::<String>::None
+    ))))
+}
+
+#[post("/<slug>/expenses/<id>/approve", data = "<form>")]
+pub async fn expense_approve(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<ExpenseReviewForm>,
+) -> Result<Redirect, Template> {
+    review(cookies, db, slug, id, true, form.into_inner()).await
+}
+
+#[post("/<slug>/expenses/<id>/reject", data = "<form>")]
+pub async fn expense_reject(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<ExpenseReviewForm>,
+) -> Result<Redirect, Template> {
+    review(cookies, db, slug, id, false, form.into_inner()).await
+}
+
+#[post("/<slug>/expenses/<id>/delete")]
+pub async fn expense_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Redirect {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Redirect::to(uri!(crate::controllers::public_controller::login_form)),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let expense = match expense_service::find_expense_by_id(db, tenant_id, id).await {
+        Ok(Some(expense)) if current_user.tenant_slug == slug => expense,
+        _ => {
+            return Redirect::to(uri!(crate::controllers::public_controller::tracking(
+                slug = current_user.tenant_slug,
+                deployment_id = Option::<i64>::None
+            )))
+        }
+    };
+    let is_own_pending = expense.submitted_by_user_id == user.id
+        && expense.status == expense_service::STATUS_SUBMITTED
+        && access_service::can_edit(db, &user, "tracking").await;
+    if is_own_pending || access_service::can_delete(db, &user, "tracking").await {
+        let _ = expense_service::delete_expense(db, tenant_id, &expense).await;
+    }
+
+    Redirect::to(uri!(crate::controllers::public_controller::tracking(
+        slug = current_user.tenant_slug,
+        deployment_id = Some(expense.deployment_id)
+    )))
+}
+
+#[get("/<slug>/expenses/<id>/receipt")]
+pub async fn expense_receipt(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Option<NamedFile> {
+    let (tenant_id, user) = tenant_from_cookies(cookies, db).await?;
+    if CurrentUserView::from(&user).tenant_slug != slug {
+        return None;
+    }
+    let expense = expense_service::find_expense_by_id(db, tenant_id, id)
+        .await
+        .ok()
+        .flatten()?;
+    let allowed = expense.submitted_by_user_id == user.id
+        || can_view_expense_queue(&user)
+        || (!access_service::is_employee(&user.role)
+            && access_service::can_view(db, &user, "tracking").await);
+    if !allowed {
+        return None;
+    }
+    let path = expense_service::receipt_file_path(&expense)?;
+    NamedFile::open(path).await.ok()
+}
@@ -0,0 +1,341 @@
+use rocket::form::Form;
+use rocket::http::{CookieJar, Header};
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+use serde_json::json;
+
+use crate::models::{CurrentUserView, DataImport, ImportMappingForm, ImportUploadForm, User};
+use crate::services::{access_service, auth_service, import_service, workspace_service};
+use crate::Db;
+
+#[derive(Responder)]
+#[response(content_type = "text/csv")]
+pub struct ImportErrorsCsv {
+    body: String,
+    disposition: Header<'static>,
+}
+
+async fn tenant_from_cookies(cookies: &CookieJar<'_>, db: &Db) -> Option<(i64, User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+async fn can_import(db: &Db, user: &User, entity: &str) -> bool {
+    match import_service::resource_for(entity) {
+        Some(resource) => {
+            access_service::can_edit(db, user, resource).await
+                && !access_service::is_plan_expired(db, user).await
+        }
+        None => false,
+    }
+}
+
+async fn importable_entities(db: &Db, user: &User) -> Vec<serde_json::Value> {
+    let mut entities = Vec::new();
+    for entity in import_service::entity_options() {
+        if can_import(db, user, entity).await {
+            entities.push(json!({ "key": entity, "label": import_service::entity_label(entity) }));
+        }
+    }
+    entities
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &User,
+    entities: Vec<serde_json::Value>,
+    selected: Option<String>,
+    error: Option<String>,
+) -> Template {
+    let imports = import_service::list_imports(db, tenant_id)
+        .await
+        .into_iter()
+        .map(|summary| {
+            json!({
+                "id": summary.id,
+                "entity_label": import_service::entity_label(&summary.entity),
+                "file_name": summary.file_name,
+                "row_count": summary.row_count,
+                "status": summary.status,
+                "imported_count": summary.imported_count,
+                "skipped_count": summary.skipped_count,
+                "created_at": summary.created_at,
+            })
+        })
+        .collect::<Vec<_>>();
+    Template::render(
+        "imports/index",
+        context! {
+            title: "Import data",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            entities: entities,
+            selected_entity: selected.unwrap_or_default(),
+            imports: imports,
+            error: error,
+        },
+    )
+}
+
+async fn render_import(
+    db: &Db,
+    tenant_id: i64,
+    user: &User,
+    import: &DataImport,
+    error: Option<String>,
+) -> Template {
+    let is_draft = import.status == import_service::STATUS_DRAFT;
+    let (preview, preview_error) = if is_draft {
+        match import_service::preview(db, tenant_id, import).await {
+            Ok(preview) => (Some(preview), None),
+            Err(message) => (None, Some(message)),
+        }
+    } else {
+        (None, None)
+    };
+    let headers = import
+        .headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| {
+            let sample = import
+                .rows
+                .iter()
+                .filter_map(|row| row.get(index))
+                .find(|value| !value.is_empty())
+                .cloned()
+                .unwrap_or_default();
+            json!({ "index": index, "name": header, "sample": sample })
+        })
+        .collect::<Vec<_>>();
+    Template::render(
+        "imports/show",
+        context! {
+            title: "Import data",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            import: context! {
+                id: import.id,
+                entity_label: import_service::entity_label(&import.entity),
+                file_name: &import.file_name,
+                status: &import.status,
+                imported_count: import.imported_count,
+                skipped_count: import.skipped_count,
+                has_error_report: !import.error_report.is_empty(),
+                created_at: &import.created_at,
+                completed_at: &import.completed_at,
+            },
+            is_draft: is_draft,
+            headers: headers,
+            columns: import_service::column_views(db, tenant_id, import).await,
+            missing_columns: import_service::missing_columns(db, tenant_id, import).await,
+            preview: preview,
+            error: error.or(preview_error),
+        },
+    )
+}
+
+#[get("/<slug>/imports?<entity>")]
+pub async fn imports_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    entity: Option<String>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(imports_index(
+            slug = current_user.tenant_slug,
+            entity = Option::<String>::None
+        ))));
+    }
+    let entities = importable_entities(db, &user).await;
+    if entities.is_empty() {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_index(db, tenant_id, &user, entities, entity, None).await)
+}
+
+#[post("/<slug>/imports", data = "<form>")]
+pub async fn import_upload(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<ImportUploadForm<'_>>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let form = form.into_inner();
+    if current_user.tenant_slug != slug || !can_import(db, &user, &form.entity).await {
+        return Ok(Redirect::to(uri!(imports_index(
+            slug = current_user.tenant_slug,
+            entity = Option::<String>::None
+        ))));
+    }
+
+    let entity = form.entity.clone();
+    match import_service::create_import(db, tenant_id, user.id, &form.entity, form.file).await {
+        Ok(id) => Ok(Redirect::to(uri!(import_show(slug = current_user.tenant_slug, id = id)))),
+        Err(message) => {
+            let entities = importable_entities(db, &user).await;
+            Err(render_index(db, tenant_id, &user, entities, Some(entity), Some(message)).await)
+        }
+    }
+}
+
+#[get("/<slug>/imports/<id>")]
+pub async fn import_show(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let index = Redirect::to(uri!(imports_index(
+        slug = current_user.tenant_slug.clone(),
+        entity = Option::<String>::None
+    )));
+    if current_user.tenant_slug != slug {
+        return Err(index);
+    }
+    let Some(import) = import_service::find_import(db, tenant_id, id).await else {
+        return Err(index);
+    };
+    if !can_import(db, &user, &import.entity).await {
+        return Err(index);
+    }
+
+    Ok(render_import(db, tenant_id, &user, &import, None).await)
+}
+
+#[post("/<slug>/imports/<id>/mapping", data = "<form>")]
+pub async fn import_mapping(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<ImportMappingForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let index = Redirect::to(uri!(imports_index(
+        slug = current_user.tenant_slug.clone(),
+        entity = Option::<String>::None
+    )));
+    if current_user.tenant_slug != slug {
+        return Ok(index);
+    }
+    let Some(import) = import_service::find_import(db, tenant_id, id).await else {
+        return Ok(index);
+    };
+    if !can_import(db, &user, &import.entity).await {
+        return Ok(index);
+    }
+
+    match import_service::save_mapping(db, tenant_id, &import, &form.mapping).await {
+        Ok(()) => Ok(Redirect::to(uri!(import_show(slug = current_user.tenant_slug, id = id)))),
+        Err(message) => Err(render_import(db, tenant_id, &user, &import, Some(message)).await),
+    }
+}
+
+#[post("/<slug>/imports/<id>/commit")]
+pub async fn import_commit(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let index = Redirect::to(uri!(imports_index(
+        slug = current_user.tenant_slug.clone(),
+        entity = Option::<String>::None
+    )));
+    if current_user.tenant_slug != slug {
+        return Ok(index);
+    }
+    let Some(import) = import_service::find_import(db, tenant_id, id).await else {
+        return Ok(index);
+    };
+    if !can_import(db, &user, &import.entity).await {
+        return Ok(index);
+    }
+
+    match import_service::commit_import(db, tenant_id, &import).await {
+        Ok(_) => Ok(Redirect::to(uri!(import_show(slug = current_user.tenant_slug, id = id)))),
+        Err(message) => Err(render_import(db, tenant_id, &user, &import, Some(message)).await),
+    }
+}
+
+#[get("/<slug>/imports/<id>/errors")]
+pub async fn import_errors(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<ImportErrorsCsv, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let index = Redirect::to(uri!(imports_index(
+        slug = current_user.tenant_slug.clone(),
+        entity = Option::<String>::None
+    )));
+    if current_user.tenant_slug != slug {
+        return Err(index);
+    }
+    let Some(import) = import_service::find_import(db, tenant_id, id).await else {
+        return Err(index);
+    };
+    if import.error_report.is_empty() || !can_import(db, &user, &import.entity).await {
+        return Err(index);
+    }
+
+    Ok(ImportErrorsCsv {
+        body: import.error_report,
+        disposition: Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"{slug}-import-{id}-errors.csv\""),
+        ),
+    })
+}
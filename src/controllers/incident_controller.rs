@@ -0,0 +1,247 @@
+use rocket::form::Form;
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::{CurrentUserView, DeploymentIncident, IncidentUpdateForm};
+use crate::repositories::user_repo;
+use crate::services::{access_service, auth_service, incident_service, workspace_service};
+use crate::Db;
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+async fn can_view_incident_log(db: &Db, user: &crate::models::User) -> bool {
+    !access_service::is_employee(&user.role) && access_service::can_view(db, user, "tracking").await
+}
+
+async fn can_manage_incident(
+    db: &Db,
+    user: &crate::models::User,
+    incident: &DeploymentIncident,
+) -> bool {
+    incident.assignee_user_id == Some(user.id)
+        || (!access_service::is_employee(&user.role)
+            && access_service::can_edit(db, user, "tracking").await)
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    status: Option<String>,
+    error: Option<String>,
+) -> Template {
+    let status_filter = status.filter(|value| {
+        incident_service::status_options()
+            .iter()
+            .any(|option| option.eq(value))
+    });
+    let incidents = incident_service::list_incidents(db, tenant_id, status_filter.as_deref())
+        .await
+        .unwrap_or_default();
+    let unresolved_count = incident_service::count_unresolved_incidents(db, tenant_id).await;
+    Template::render(
+        "incidents/index",
+        context! {
+            title: "Incidents",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            incidents: incidents,
+            status_filter: status_filter,
+            status_options: incident_service::status_options(),
+            unresolved_count: unresolved_count,
+            can_delete: access_service::can_delete(db, user, "tracking").await,
+            error: error,
+        },
+    )
+}
+
+async fn render_edit(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    incident: DeploymentIncident,
+    error: Option<String>,
+) -> Template {
+    let assignees = user_repo::list_users_by_tenant(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    Template::render(
+        "incidents/edit",
+        context! {
+            title: "Incident",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            incident: incident,
+            assignees: assignees,
+            severity_options: incident_service::severity_options(),
+            category_options: incident_service::category_options(),
+            status_options: incident_service::status_options(),
+            can_view_log: can_view_incident_log(db, user).await,
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/incidents?<status>")]
+pub async fn incidents_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    status: Option<String>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(incidents_index(
+            slug = current_user.tenant_slug,
+            status = Option::<String>::None
+        ))));
+    }
+    if !can_view_incident_log(db, &user).await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_index(db, tenant_id, &user, status, None).await)
+}
+
+#[get("/<slug>/incidents/<id>/edit")]
+pub async fn incident_edit_form(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let incident = match incident_service::find_incident_by_id(db, tenant_id, id).await {
+        Ok(Some(incident)) if current_user.tenant_slug == slug => incident,
+        _ => {
+            return Err(Redirect::to(uri!(crate::controllers::public_controller::tracking(
+                slug = current_user.tenant_slug,
+                deployment_id = Option::<i64>::None
+            ))))
+        }
+    };
+    if !can_manage_incident(db, &user, &incident).await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::tracking(
+            slug = current_user.tenant_slug,
+            deployment_id = Some(incident.deployment_id)
+        ))));
+    }
+
+    Ok(render_edit(db, tenant_id, &user, incident, None).await)
+}
+
+#[post("/<slug>/incidents/<id>", data = "<form>")]
+pub async fn incident_update(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    form: Form<IncidentUpdateForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let incident = match incident_service::find_incident_by_id(db, tenant_id, id).await {
+        Ok(Some(incident)) if current_user.tenant_slug == slug => incident,
+        _ => {
+            return Ok(Redirect::to(uri!(crate::controllers::public_controller::tracking(
+                slug = current_user.tenant_slug,
+                deployment_id = Option::<i64>::None
+            ))))
+        }
+    };
+    if !can_manage_incident(db, &user, &incident).await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(crate::controllers::public_controller::tracking(
+            slug = current_user.tenant_slug,
+            deployment_id = Some(incident.deployment_id)
+        ))));
+    }
+
+    if let Err(message) =
+        incident_service::update_incident(db, tenant_id, &incident, user.id, form.into_inner())
+            .await
+    {
+        return Err(render_edit(db, tenant_id, &user, incident, Some(message)).await);
+    }
+
+    Ok(Redirect::to(uri!(crate::controllers::public_controller::tracking(
+        slug = current_user.tenant_slug,
+        deployment_id = Some(incident.deployment_id)
+    ))))
+}
+
+#[post("/<slug>/incidents/<id>/delete")]
+pub async fn incident_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let incident = match incident_service::find_incident_by_id(db, tenant_id, id).await {
+        Ok(Some(incident)) if current_user.tenant_slug == slug => incident,
+        _ => {
+            return Ok(Redirect::to(uri!(crate::controllers::public_controller::tracking(
+                slug = current_user.tenant_slug,
+                deployment_id = Option::<i64>::None
+            ))))
+        }
+    };
+    if !access_service::can_delete(db, &user, "tracking").await {
+        return Ok(Redirect::to(uri!(crate::controllers::public_controller::tracking(
+            slug = current_user.tenant_slug,
+            deployment_id = Some(incident.deployment_id)
+        ))));
+    }
+
+    if let Err(message) = incident_service::delete_incident(db, tenant_id, incident.id).await {
+        return Err(render_index(db, tenant_id, &user, None, Some(message)).await);
+    }
+
+    Ok(Redirect::to(uri!(crate::controllers::public_controller::tracking(
+        slug = current_user.tenant_slug,
+        deployment_id = Some(incident.deployment_id)
+    ))))
+}
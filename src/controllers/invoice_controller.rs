@@ -1,24 +1,31 @@
 use rocket::form::Form;
+use rocket::fs::NamedFile;
 use rocket::http::CookieJar;
 use rocket::response::Redirect;
 use rocket_dyn_templates::{context, Template};
 
 use crate::models::{
     CurrentUserView,
+    DeploymentExpense,
     DeploymentUpdate,
     EmailForm,
     EmailFormView,
     InvoiceForm,
     InvoiceFormView,
+    UpdateAttachment,
 };
 use crate::services::{
     access_service,
+    attachment_service,
     auth_service,
     email_service,
+    expense_service,
     invoice_service,
+    timesheet_service,
     tracking_service,
     workspace_service,
 };
+use crate::services::utils::escape_html;
 use crate::Db;
 
 async fn tenant_from_cookies(
@@ -50,21 +57,14 @@ fn invoice_number(id: i64) -> String {
     format!("INV-{:05}", id)
 }
 
-fn escape_html(value: &str) -> String {
-    value
-        .replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&#39;")
-}
-
 fn build_invoice_email_body(
     invoice: &crate::models::InvoiceSummary,
     invoice_number: &str,
     total_hours: f64,
     total_amount: f64,
     updates: &[DeploymentUpdate],
+    expenses: &[DeploymentExpense],
+    attachments: &[UpdateAttachment],
 ) -> String {
     let client_name = escape_html(&invoice.client_name);
     let crew_name = escape_html(&invoice.crew_name);
@@ -104,6 +104,42 @@ fn build_invoice_email_body(
         }
         body.push_str("</tbody></table>");
     }
+    let billable = expenses
+        .iter()
+        .filter(|expense| expense.currency == invoice.client_currency)
+        .collect::<Vec<_>>();
+    if !billable.is_empty() {
+        body.push_str("<h3>Billable expenses</h3>");
+        body.push_str("<table cellpadding=\"6\" cellspacing=\"0\" border=\"1\" style=\"border-collapse: collapse;\">");
+        body.push_str("<thead><tr><th>Date</th><th>Category</th><th>Description</th><th>Amount</th></tr></thead><tbody>");
+        for expense in billable {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.2} {}</td></tr>",
+                escape_html(&expense.incurred_on),
+                escape_html(&expense.category),
+                escape_html(&expense.description),
+                expense.billable_amount,
+                currency
+            ));
+        }
+        body.push_str("</tbody></table>");
+    }
+    let shared = attachments
+        .iter()
+        .filter(|attachment| attachment.is_client_visible)
+        .collect::<Vec<_>>();
+    if !shared.is_empty() {
+        body.push_str("<h3>Appendix: photos and files</h3>");
+        body.push_str("<p>The following files from the approved updates are available in your client portal:</p><ul>");
+        for attachment in shared {
+            body.push_str(&format!(
+                "<li>{} ({})</li>",
+                escape_html(&attachment.original_name),
+                escape_html(&attachment.work_date)
+            ));
+        }
+        body.push_str("</ul>");
+    }
 
     body
 }
@@ -136,7 +172,8 @@ pub async fn invoices_index(
     let invoice_items = invoices
         .into_iter()
         .map(|invoice| {
-            let total_amount = invoice.total_hours * invoice.fee_per_hour;
+            let total_amount =
+                invoice.total_hours * invoice.fee_per_hour + invoice.expense_total;
             context! {
                 id: invoice.id,
                 deployment_id: invoice.deployment_id,
@@ -157,7 +194,8 @@ pub async fn invoices_index(
         .unwrap_or_default()
         .into_iter()
         .map(|candidate| {
-            let total_amount = candidate.total_hours * candidate.fee_per_hour;
+            let total_amount =
+                candidate.total_hours * candidate.fee_per_hour + candidate.expense_total;
             context! {
                 deployment_id: candidate.deployment_id,
                 client_name: candidate.client_name,
@@ -307,7 +345,8 @@ pub async fn invoice_show(
                 .unwrap_or_default()
                 .into_iter()
                 .map(|invoice| {
-                    let total_amount = invoice.total_hours * invoice.fee_per_hour;
+                    let total_amount =
+                        invoice.total_hours * invoice.fee_per_hour + invoice.expense_total;
                     context! {
                         id: invoice.id,
                         deployment_id: invoice.deployment_id,
@@ -336,11 +375,33 @@ pub async fn invoice_show(
         }
     };
 
-    let updates = tracking_service::list_updates(db, tenant_id, invoice.deployment_id)
+    let updates = tracking_service::list_approved_updates(db, tenant_id, invoice.deployment_id)
         .await
         .unwrap_or_default();
     let total_hours: f64 = updates.iter().map(|update| update.hours_worked).sum();
-    let total_amount = total_hours * invoice.fee_per_hour;
+    let pending_hours = tracking_service::list_updates(db, tenant_id, invoice.deployment_id)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|update| {
+            !update.is_placeholder && update.approval_status != timesheet_service::STATUS_APPROVED
+        })
+        .fold(0.0, |total, update| total + update.hours_worked);
+    let expenses = expense_service::list_billable_expenses_for_deployment(
+        db,
+        tenant_id,
+        invoice.deployment_id,
+    )
+    .await
+    .unwrap_or_default();
+    let total_amount = total_hours * invoice.fee_per_hour + invoice.expense_total;
+    let attachments = attachment_service::list_approved_attachments_for_deployment(
+        db,
+        tenant_id,
+        invoice.deployment_id,
+    )
+    .await
+    .unwrap_or_default();
 
     Ok(Template::render(
         "invoices/show",
@@ -351,13 +412,48 @@ pub async fn invoice_show(
             invoice: invoice,
             invoice_number: invoice_number(id),
             updates: updates,
+            expenses: expenses,
+            attachments: attachments,
             total_hours: total_hours,
+            pending_hours: (pending_hours * 100.0_f64).round() / 100.0,
             total_amount: total_amount,
             error: Option::<String>::None,
         },
     ))
 }
 
+#[get("/<slug>/invoices/<id>/attachments/<attachment_id>?<thumb>")]
+pub async fn invoice_attachment(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    attachment_id: i64,
+    thumb: Option<bool>,
+) -> Option<NamedFile> {
+    let (tenant_id, user) = tenant_from_cookies(cookies, db).await?;
+    if CurrentUserView::from(&user).tenant_slug != slug
+        || !access_service::can_view(db, &user, "invoices").await
+    {
+        return None;
+    }
+    let invoice = invoice_service::find_invoice_with_details(db, tenant_id, id)
+        .await
+        .ok()
+        .flatten()?;
+    let attachment = attachment_service::list_approved_attachments_for_deployment(
+        db,
+        tenant_id,
+        invoice.deployment_id,
+    )
+    .await
+    .ok()?
+    .into_iter()
+    .find(|attachment| attachment.id == attachment_id)?;
+    let path = attachment_service::attachment_file_path(&attachment, thumb.unwrap_or(false));
+    NamedFile::open(path).await.ok()
+}
+
 #[get("/<slug>/invoices/<id>/edit")]
 pub async fn invoice_edit_form(
     cookies: &CookieJar<'_>,
@@ -391,7 +487,8 @@ pub async fn invoice_edit_form(
                 .unwrap_or_default()
                 .into_iter()
                 .map(|invoice| {
-                    let total_amount = invoice.total_hours * invoice.fee_per_hour;
+                    let total_amount =
+                        invoice.total_hours * invoice.fee_per_hour + invoice.expense_total;
                     context! {
                         id: invoice.id,
                         deployment_id: invoice.deployment_id,
@@ -474,7 +571,8 @@ pub async fn invoice_update(
                 .unwrap_or_default()
                 .into_iter()
                 .map(|invoice| {
-                    let total_amount = invoice.total_hours * invoice.fee_per_hour;
+                    let total_amount =
+                        invoice.total_hours * invoice.fee_per_hour + invoice.expense_total;
                     context! {
                         id: invoice.id,
                         deployment_id: invoice.deployment_id,
@@ -554,7 +652,8 @@ pub async fn invoice_delete(
             .unwrap_or_default()
             .into_iter()
             .map(|invoice| {
-                let total_amount = invoice.total_hours * invoice.fee_per_hour;
+                let total_amount =
+                    invoice.total_hours * invoice.fee_per_hour + invoice.expense_total;
                 context! {
                     id: invoice.id,
                     deployment_id: invoice.deployment_id,
@@ -620,7 +719,8 @@ pub async fn invoice_email_form(
                 .unwrap_or_default()
                 .into_iter()
                 .map(|invoice| {
-                    let total_amount = invoice.total_hours * invoice.fee_per_hour;
+                    let total_amount =
+                        invoice.total_hours * invoice.fee_per_hour + invoice.expense_total;
                     context! {
                         id: invoice.id,
                         deployment_id: invoice.deployment_id,
@@ -649,14 +749,36 @@ pub async fn invoice_email_form(
         }
     };
 
-    let updates = tracking_service::list_updates(db, tenant_id, invoice.deployment_id)
+    let updates = tracking_service::list_approved_updates(db, tenant_id, invoice.deployment_id)
         .await
         .unwrap_or_default();
     let total_hours: f64 = updates.iter().map(|update| update.hours_worked).sum();
-    let total_amount = total_hours * invoice.fee_per_hour;
+    let expenses = expense_service::list_billable_expenses_for_deployment(
+        db,
+        tenant_id,
+        invoice.deployment_id,
+    )
+    .await
+    .unwrap_or_default();
+    let total_amount = total_hours * invoice.fee_per_hour + invoice.expense_total;
+    let attachments = attachment_service::list_approved_attachments_for_deployment(
+        db,
+        tenant_id,
+        invoice.deployment_id,
+    )
+    .await
+    .unwrap_or_default();
     let invoice_no = invoice_number(invoice.id);
     let subject = format!("Invoice {} for {}", invoice_no, &invoice.client_name);
-    let body = build_invoice_email_body(&invoice, &invoice_no, total_hours, total_amount, &updates);
+    let body = build_invoice_email_body(
+        &invoice,
+        &invoice_no,
+        total_hours,
+        total_amount,
+        &updates,
+        &expenses,
+        &attachments,
+    );
     let error = if invoice.client_email.trim().is_empty() {
         Some("Client email is required to send invoices.".to_string())
     } else {
@@ -0,0 +1,390 @@
+use std::net::IpAddr;
+
+use rocket::form::Form;
+use rocket::http::{CookieJar, Header};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+use serde::Deserialize;
+
+use crate::models::{Asset, AssetCheckinForm, AssetCheckoutForm, CurrentUserView};
+use crate::services::{
+    access_service,
+    asset_assignment_service,
+    asset_service,
+    auth_service,
+    label_service,
+    maintenance_service,
+    workspace_service,
+};
+use crate::Db;
+
+#[derive(Responder)]
+pub enum LabelSheet {
+    #[response(content_type = "image/svg+xml")]
+    Svg(String, Header<'static>),
+    #[response(content_type = "pdf")]
+    Pdf(Vec<u8>, Header<'static>),
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct OriginConfig {
+    pub public_url: String,
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+/// Scheme and host that QR codes and emailed links point at. The configured
+/// `public_url` wins; otherwise `X-Forwarded-Host`/`-Proto` are only honoured
+/// when the request arrives from one of `trusted_proxies`.
+pub struct RequestOrigin(pub String);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestOrigin {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let config = request.rocket().state::<OriginConfig>();
+        if let Some(public_url) = config
+            .map(|config| config.public_url.trim().trim_end_matches('/'))
+            .filter(|public_url| !public_url.is_empty())
+        {
+            return Outcome::Success(RequestOrigin(public_url.to_string()));
+        }
+
+        let headers = request.headers();
+        let behind_proxy = config
+            .zip(request.remote())
+            .is_some_and(|(config, remote)| config.trusted_proxies.contains(&remote.ip()));
+        let forwarded = |name: &str| {
+            behind_proxy
+                .then(|| headers.get_one(name))
+                .flatten()
+                .and_then(|value| value.split(',').next())
+                .map(str::trim)
+        };
+        let host = forwarded("X-Forwarded-Host")
+            .or_else(|| headers.get_one("Host"))
+            .filter(|host| is_valid_host(host))
+            .unwrap_or("localhost");
+        let scheme = match forwarded("X-Forwarded-Proto") {
+            Some("https") => "https",
+            _ => "http",
+        };
+        Outcome::Success(RequestOrigin(format!("{scheme}://{host}")))
+    }
+}
+
+fn is_valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && host
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | ':' | '[' | ']'))
+}
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+fn sheet(format: &str, name: &str, labels: &[crate::models::LabelItem]) -> LabelSheet {
+    if format == label_service::FORMAT_SVG {
+        LabelSheet::Svg(
+            label_service::render_svg(labels),
+            Header::new("Content-Disposition", format!("inline; filename=\"{name}.svg\"")),
+        )
+    } else {
+        LabelSheet::Pdf(
+            label_service::render_pdf(labels),
+            Header::new("Content-Disposition", format!("inline; filename=\"{name}.pdf\"")),
+        )
+    }
+}
+
+async fn render_scan(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    asset: Option<Asset>,
+    signature: &str,
+    error: Option<String>,
+) -> Template {
+    let (current_assignment, overdue_plans) = match &asset {
+        Some(asset) => (
+            asset_assignment_service::current_assignment(db, tenant_id, asset.id).await,
+            maintenance_service::overdue_plan_names(db, tenant_id, asset).await,
+        ),
+        None => (None, Vec::new()),
+    };
+    let can_edit_assets = access_service::can_edit(db, user, "assets").await
+        && !access_service::is_plan_expired(db, user).await;
+    Template::render(
+        "labels/scan",
+        context! {
+            title: "Scan",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            asset: asset,
+            signature: signature,
+            current_assignment: current_assignment,
+            overdue_plans: overdue_plans,
+            crew_options: asset_assignment_service::crew_target_options(db, tenant_id).await,
+            deployment_options: asset_assignment_service::deployment_target_options(db, tenant_id).await,
+            condition_options: asset_service::condition_options(),
+            can_edit_assets: can_edit_assets,
+            error: error,
+        },
+    )
+}
+
+async fn scanned_asset(
+    db: &Db,
+    tenant_id: i64,
+    id: i64,
+    signature: &str,
+) -> Result<Asset, String> {
+    if !label_service::verify_asset_signature(db, tenant_id, id, signature).await {
+        return Err("This label is not valid for this workspace. Reprint it from the asset page.".to_string());
+    }
+    match asset_service::find_asset_by_id(db, tenant_id, id).await {
+        Ok(Some(asset)) => Ok(asset),
+        _ => Err("This asset no longer exists.".to_string()),
+    }
+}
+
+#[get("/<slug>/labels")]
+pub async fn labels_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(labels_index(slug = current_user.tenant_slug))));
+    }
+    let can_view_assets = access_service::can_view(db, &user, "assets").await;
+    let can_view_deployments = access_service::can_view(db, &user, "deployments").await;
+    if !can_view_assets && !can_view_deployments {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let assets = if can_view_assets {
+        crate::repositories::asset_repo::list_assets(db, tenant_id)
+            .await
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let deployments = if can_view_deployments {
+        label_service::deployment_options(db, tenant_id).await
+    } else {
+        Vec::new()
+    };
+    Ok(Template::render(
+        "labels/index",
+        context! {
+            title: "QR labels",
+            current_user: Some(current_user),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            assets: assets,
+            deployments: deployments,
+            can_view_assets: can_view_assets,
+            can_view_deployments: can_view_deployments,
+            format_options: label_service::format_options(),
+        },
+    ))
+}
+
+#[get("/<slug>/labels/assets?<format>&<id>")]
+pub async fn asset_labels(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    origin: RequestOrigin,
+    slug: &str,
+    format: Option<&str>,
+    id: Vec<i64>,
+) -> Result<LabelSheet, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug || !access_service::can_view(db, &user, "assets").await {
+        return Err(Redirect::to(uri!(labels_index(slug = current_user.tenant_slug))));
+    }
+
+    let labels = label_service::asset_labels(db, tenant_id, &origin.0, slug, &id)
+        .await
+        .unwrap_or_default();
+    Ok(sheet(label_service::normalize_format(format), "asset-labels", &labels))
+}
+
+#[get("/<slug>/labels/deployments?<format>&<id>")]
+pub async fn deployment_labels(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    origin: RequestOrigin,
+    slug: &str,
+    format: Option<&str>,
+    id: Vec<i64>,
+) -> Result<LabelSheet, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug || !access_service::can_view(db, &user, "deployments").await {
+        return Err(Redirect::to(uri!(labels_index(slug = current_user.tenant_slug))));
+    }
+
+    let labels = label_service::deployment_labels(db, tenant_id, &origin.0, slug, &id)
+        .await
+        .unwrap_or_default();
+    Ok(sheet(label_service::normalize_format(format), "deployment-labels", &labels))
+}
+
+#[get("/<slug>/scan/assets/<id>/<signature>")]
+pub async fn asset_scan(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    signature: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug || !access_service::can_view(db, &user, "assets").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(match scanned_asset(db, tenant_id, id, signature).await {
+        Ok(asset) => render_scan(db, tenant_id, &user, Some(asset), signature, None).await,
+        Err(message) => render_scan(db, tenant_id, &user, None, signature, Some(message)).await,
+    })
+}
+
+#[post("/<slug>/scan/assets/<id>/<signature>/checkout", data = "<form>")]
+pub async fn asset_scan_checkout(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    signature: &str,
+    form: Form<AssetCheckoutForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(asset_scan(
+            slug = current_user.tenant_slug,
+            id = id,
+            signature = signature
+        ))));
+    }
+    let asset = match scanned_asset(db, tenant_id, id, signature).await {
+        Ok(asset) => asset,
+        Err(message) => {
+            return Err(render_scan(db, tenant_id, &user, None, signature, Some(message)).await)
+        }
+    };
+
+    match asset_assignment_service::check_out(db, tenant_id, user.id, &asset, form.into_inner()).await {
+        Ok(()) => Ok(Redirect::to(uri!(asset_scan(
+            slug = current_user.tenant_slug,
+            id = id,
+            signature = signature
+        )))),
+        Err(message) => Err(render_scan(db, tenant_id, &user, Some(asset), signature, Some(message)).await),
+    }
+}
+
+#[post(
+    "/<slug>/scan/assets/<id>/<signature>/assignments/<assignment_id>/checkin",
+    data = "<form>"
+)]
+pub async fn asset_scan_checkin(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    signature: &str,
+    assignment_id: i64,
+    form: Form<AssetCheckinForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "assets").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(asset_scan(
+            slug = current_user.tenant_slug,
+            id = id,
+            signature = signature
+        ))));
+    }
+    let asset = match scanned_asset(db, tenant_id, id, signature).await {
+        Ok(asset) => asset,
+        Err(message) => {
+            return Err(render_scan(db, tenant_id, &user, None, signature, Some(message)).await)
+        }
+    };
+
+    match asset_assignment_service::check_in(
+        db,
+        tenant_id,
+        user.id,
+        asset.id,
+        assignment_id,
+        form.into_inner(),
+    )
+    .await
+    {
+        Ok(()) => Ok(Redirect::to(uri!(asset_scan(
+            slug = current_user.tenant_slug,
+            id = id,
+            signature = signature
+        )))),
+        Err(message) => Err(render_scan(db, tenant_id, &user, Some(asset), signature, Some(message)).await),
+    }
+}
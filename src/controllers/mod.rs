@@ -1,5 +1,21 @@
 pub mod admin_controller;
+pub mod asset_controller;
+pub mod availability_controller;
+pub mod certification_controller;
 pub mod client_controller;
 pub mod crew_controller;
+pub mod custom_field_controller;
+pub mod deployment_template_controller;
+pub mod expense_controller;
+pub mod import_controller;
+pub mod incident_controller;
 pub mod invoice_controller;
+pub mod label_controller;
+pub mod payroll_controller;
+pub mod profitability_controller;
 pub mod public_controller;
+pub mod scheduler_controller;
+pub mod timesheet_controller;
+pub mod tracking_sync_controller;
+pub mod upload_limit_controller;
+pub mod workspace_export_controller;
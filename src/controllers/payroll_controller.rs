@@ -0,0 +1,240 @@
+use rocket::form::Form;
+use rocket::http::{CookieJar, Header};
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::{CurrentUserView, PayrollPolicy, PayrollPolicyForm};
+use crate::services::{
+    access_service,
+    auth_service,
+    payroll_service,
+    timesheet_service,
+    workspace_service,
+};
+use crate::Db;
+
+#[derive(Responder)]
+#[response(content_type = "text/csv")]
+pub struct PayrollCsv {
+    body: String,
+    disposition: Header<'static>,
+}
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+fn can_run_payroll(user: &crate::models::User) -> bool {
+    user.is_super_admin || access_service::can_view_costs(&user.role)
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    start: Option<&str>,
+    end: Option<&str>,
+    policy: Option<PayrollPolicy>,
+    error: Option<String>,
+) -> Template {
+    let (period, error) = match payroll_service::period_bounds(start, end) {
+        Ok(period) => (period, error),
+        Err(message) => (
+            timesheet_service::week_bounds(None),
+            error.or(Some(message)),
+        ),
+    };
+    let (period_start, period_end) = period;
+    let saved_policy = payroll_service::policy_for_tenant(db, tenant_id).await;
+    let lines = payroll_service::list_payroll(db, tenant_id, period_start, period_end, &saved_policy)
+        .await
+        .unwrap_or_default();
+    let totals = payroll_service::totals(&lines);
+    let unapproved_count =
+        payroll_service::count_unapproved_entries(db, tenant_id, period_start, period_end)
+            .await
+            .unwrap_or(0);
+    let can_edit = !access_service::is_plan_expired(db, user).await;
+
+    Template::render(
+        "payroll/index",
+        context! {
+            title: "Payroll",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            start: period_start.format("%Y-%m-%d").to_string(),
+            end: period_end.format("%Y-%m-%d").to_string(),
+            period_label: format!(
+                "{} – {}",
+                period_start.format("%d %b %Y"),
+                period_end.format("%d %b %Y")
+            ),
+            lines: lines,
+            regular_hours: totals.regular_hours,
+            overtime_hours: totals.overtime_hours,
+            total_hours: totals.total_hours,
+            unapproved_count: unapproved_count,
+            applied_policy: saved_policy.clone(),
+            policy: policy.unwrap_or(saved_policy),
+            can_edit: can_edit,
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/payroll?<start>&<end>")]
+pub async fn payroll_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    start: Option<String>,
+    end: Option<String>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(payroll_index(
+            slug = current_user.tenant_slug,
+            start = start,
+            end = end
+        ))));
+    }
+    if !can_run_payroll(&user) {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_index(
+        db,
+        tenant_id,
+        &user,
+        start.as_deref(),
+        end.as_deref(),
+        None,
+        None,
+    )
+    .await)
+}
+
+#[get("/<slug>/payroll/export.csv?<start>&<end>")]
+pub async fn payroll_export(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    start: Option<String>,
+    end: Option<String>,
+) -> Result<PayrollCsv, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(payroll_export(
+            slug = current_user.tenant_slug,
+            start = start,
+            end = end
+        ))));
+    }
+    if !can_run_payroll(&user) {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+    let Ok((period_start, period_end)) =
+        payroll_service::period_bounds(start.as_deref(), end.as_deref())
+    else {
+        return Err(Redirect::to(uri!(payroll_index(
+            slug = current_user.tenant_slug,
+            start = start,
+            end = end
+        ))));
+    };
+
+    let policy = payroll_service::policy_for_tenant(db, tenant_id).await;
+    let lines = payroll_service::list_payroll(db, tenant_id, period_start, period_end, &policy)
+        .await
+        .unwrap_or_default();
+    let filename = format!(
+        "payroll-{}-to-{}.csv",
+        period_start.format("%Y-%m-%d"),
+        period_end.format("%Y-%m-%d")
+    );
+    Ok(PayrollCsv {
+        body: payroll_service::export_csv(&lines, period_start, period_end),
+        disposition: Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"{filename}\""),
+        ),
+    })
+}
+
+#[post("/<slug>/payroll/settings", data = "<form>")]
+pub async fn payroll_settings(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<PayrollPolicyForm>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(payroll_index(
+            slug = current_user.tenant_slug,
+            start = Option::<String>::None,
+            end = Option::<String>::None
+        ))));
+    }
+    if !can_run_payroll(&user) || access_service::is_plan_expired(db, &user).await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let form = form.into_inner();
+    match payroll_service::update_policy(db, tenant_id, &form).await {
+        Ok(()) => Err(Redirect::to(uri!(payroll_index(
+            slug = current_user.tenant_slug,
+            start = Some(form.start),
+            end = Some(form.end)
+        )))),
+        Err(err) => Ok(render_index(
+            db,
+            tenant_id,
+            &user,
+            Some(&form.start),
+            Some(&form.end),
+            Some(err.form),
+            Some(err.message),
+        )
+        .await),
+    }
+}
@@ -0,0 +1,162 @@
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::CurrentUserView;
+use crate::services::{access_service, auth_service, profitability_service, workspace_service};
+use crate::Db;
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+fn can_view_profitability(user: &crate::models::User) -> bool {
+    user.is_super_admin || access_service::can_view_costs(&user.role)
+}
+
+fn parse_date(value: Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .and_then(|value| chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+#[get("/<slug>/reports/profitability?<group>&<from>&<to>")]
+pub async fn profitability_report(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    group: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(profitability_report(
+            slug = current_user.tenant_slug,
+            group = Option::<String>::None,
+            from = Option::<String>::None,
+            to = Option::<String>::None
+        ))));
+    }
+    if !can_view_profitability(&user) {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let group = profitability_service::normalize_group(group.as_deref());
+    let from = parse_date(from);
+    let to = parse_date(to);
+    let rows = profitability_service::list_deployment_profitability(
+        db,
+        tenant_id,
+        from.as_deref(),
+        to.as_deref(),
+    )
+    .await
+    .unwrap_or_default();
+    let lines = profitability_service::roll_up(&rows, group);
+    let totals = profitability_service::totals_by_currency(&rows);
+    let group_options = profitability_service::group_options()
+        .into_iter()
+        .map(|(key, label)| context! { key: key, label: label })
+        .collect::<Vec<_>>();
+
+    Ok(Template::render(
+        "profitability/index",
+        context! {
+            title: "Profitability",
+            current_user: Some(current_user),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            lines: lines,
+            totals: totals,
+            group: group,
+            group_options: group_options,
+            from: from.unwrap_or_default(),
+            to: to.unwrap_or_default(),
+        },
+    ))
+}
+
+#[get("/<slug>/deployments/<id>/profitability")]
+pub async fn deployment_profitability(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(deployment_profitability(
+            slug = current_user.tenant_slug,
+            id = id
+        ))));
+    }
+    if !can_view_profitability(&user) {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let (deployment, labour_lines) =
+        match profitability_service::find_deployment_profitability(db, tenant_id, id).await {
+            Ok(Some(data)) => data,
+            _ => {
+                return Err(Redirect::to(uri!(profitability_report(
+                    slug = current_user.tenant_slug,
+                    group = Option::<String>::None,
+                    from = Option::<String>::None,
+                    to = Option::<String>::None
+                ))))
+            }
+        };
+    let summary = profitability_service::roll_up(
+        std::slice::from_ref(&deployment),
+        profitability_service::GROUP_DEPLOYMENT,
+    )
+    .into_iter()
+    .next();
+
+    Ok(Template::render(
+        "profitability/show",
+        context! {
+            title: "Deployment P&L",
+            current_user: Some(current_user),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            deployment: deployment,
+            summary: summary,
+            labour_lines: labour_lines,
+        },
+    ))
+}
@@ -1,21 +1,30 @@
 use rocket::form::Form;
+use rocket::fs::NamedFile;
 use rocket::http::{Cookie, CookieJar};
-use rocket::response::Redirect;
+use rocket::request::FlashMessage;
+use rocket::response::{Flash, Redirect};
 use rocket_dyn_templates::{context, Template};
 
 use crate::models::{
     CurrentUserView,
     DeploymentForm,
+    DeploymentExpenseForm,
+    DeploymentIncidentForm,
     DeploymentFormView,
+    DeploymentTaskForm,
     DeploymentUpdateForm,
     DeploymentUpdateFormView,
     DiscussionForm,
     DiscussionFormView,
     WorkTimerForm,
+    WorkTimerPauseForm,
+    UpdateBreakForm,
     LoginForm,
     LoginView,
     RegisterForm,
     RegisterView,
+    RecommendationWeightsForm,
+    TimerPolicyForm,
     UserPermissionForm,
     WorkspaceThemeForm,
     WorkspaceRegisterForm,
@@ -34,17 +43,30 @@ use crate::repositories::{
 };
 use crate::services::{
     access_service,
+    asset_assignment_service,
     auth_service,
+    availability_service,
     appointment_service,
+    attachment_service,
+    certification_service,
     client_service,
     crew_service,
+    custom_field_service,
     deployment_discussion_service,
     deployment_service,
+    deployment_task_service,
+    deployment_template_service,
+    expense_service,
+    incident_service,
     invoice_service,
     email_service,
+    recommendation_service,
+    timesheet_service,
+    timer_policy_service,
     tracking_service,
     workspace_service,
 };
+use crate::services::recommendation_service::RecommendationRequest;
 use crate::Db;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -66,24 +88,24 @@ fn is_theme_locked(plan_key: &str) -> bool {
     plan_key.eq_ignore_ascii_case("free")
 }
 
-fn portal_progress(status: &str, update_count: i64) -> i64 {
-    let base = if status.eq_ignore_ascii_case("completed") {
-        100
-    } else if status.eq_ignore_ascii_case("active") {
+fn portal_progress(status: &str, update_count: i64, task_percent: Option<i64>) -> i64 {
+    if status.eq_ignore_ascii_case("cancelled") {
+        return 0;
+    }
+    if status.eq_ignore_ascii_case("completed") {
+        return 100;
+    }
+    if let Some(percent) = task_percent {
+        return percent.clamp(0, 100);
+    }
+    let base = if status.eq_ignore_ascii_case("active") {
         60
     } else if status.eq_ignore_ascii_case("scheduled") {
         20
-    } else if status.eq_ignore_ascii_case("cancelled") {
-        0
     } else {
         10
     };
-    let boost = if status.eq_ignore_ascii_case("completed") {
-        0
-    } else {
-        (update_count * 5).min(30)
-    };
-    (base + boost).min(100)
+    (base + (update_count * 5).min(30)).min(100)
 }
 
 fn portal_window(start_at: &str, end_at: &str) -> String {
@@ -113,15 +135,20 @@ fn is_truthy(value: Option<&str>) -> bool {
     matches!(value, Some("1" | "true" | "yes" | "on"))
 }
 
-fn recommended_crews_view(
+async fn recommended_crews_view(
+    db: &Db,
+    tenant_id: i64,
     crews: &[crate::models::Crew],
-    required_skills: &str,
-    compatibility_pref: &str,
+    request: RecommendationRequest<'_>,
 ) -> Vec<serde_json::Value> {
-    if required_skills.trim().is_empty() && compatibility_pref.trim().is_empty() {
+    if request.required_skills.trim().is_empty()
+        && request.compatibility_pref.trim().is_empty()
+        && request.client_id == 0
+    {
         return Vec::new();
     }
-    crew_service::recommend_crews(crews, required_skills, compatibility_pref)
+    recommendation_service::recommend_crews(db, tenant_id, crews, &request)
+        .await
         .into_iter()
         .filter(|rec| rec.score > 0)
         .take(3)
@@ -131,8 +158,7 @@ fn recommended_crews_view(
                 "name": rec.name,
                 "status": rec.status,
                 "score": rec.score,
-                "skill_matches": rec.skill_matches,
-                "compatibility_matches": rec.compatibility_matches
+                "factors": rec.factors
             })
         })
         .collect()
@@ -377,6 +403,7 @@ pub async fn client_portal(
     slug: &str,
     token: &str,
     hide_completed: Option<String>,
+    flash: Option<FlashMessage<'_>>,
 ) -> Template {
     let hide_completed_flag = is_truthy(hide_completed.as_deref());
     let tenant_id = match tenant_repo::find_tenant_id_by_slug(db, slug).await {
@@ -460,6 +487,23 @@ pub async fn client_portal(
         })
         .collect::<HashMap<i64, (String, String, String)>>();
 
+    let task_map =
+        deployment_task_service::task_progress_map(db, tenant_id, &deployment_ids).await;
+    let mut attachment_map: HashMap<i64, Vec<crate::models::UpdateAttachment>> = HashMap::new();
+    for attachment in attachment_service::list_client_visible_attachments_for_deployments(
+        db,
+        tenant_id,
+        &deployment_ids,
+    )
+    .await
+    .unwrap_or_default()
+    {
+        attachment_map
+            .entry(attachment.deployment_id)
+            .or_default()
+            .push(attachment);
+    }
+
     let mut progress_total = 0i64;
     let mut deployments_view = Vec::new();
     for deployment in deployments.iter() {
@@ -467,7 +511,12 @@ pub async fn client_portal(
             continue;
         }
         let update_count = *update_map.get(&deployment.id).unwrap_or(&0);
-        let progress = portal_progress(&deployment.status, update_count);
+        let tasks = task_map.get(&deployment.id).copied().unwrap_or_default();
+        let progress = portal_progress(
+            &deployment.status,
+            update_count,
+            deployment_task_service::completion_percent(&tasks),
+        );
         progress_total += progress;
         let latest_update = match latest_map.get(&deployment.id) {
             Some((work_date, start_time, notes)) => format!(
@@ -487,8 +536,12 @@ pub async fn client_portal(
             info: deployment.info.clone(),
             expected_window: expected_window,
             progress: progress,
+            tasks_total: tasks.total,
+            tasks_completed: tasks.completed,
+            tasks_required_open: tasks.required_open,
             updates_total: update_count,
             latest_update: latest_update,
+            attachments: attachment_map.remove(&deployment.id).unwrap_or_default(),
         });
     }
     let active_deployments = deployments
@@ -512,7 +565,7 @@ pub async fn client_portal(
             current_user: Option::<CurrentUserView>::None,
             workspace_brand: workspace_brand,
             client_name: client.company_name.clone(),
-            portal_error: "".to_string(),
+            portal_error: flash.map(|flash| flash.message().to_string()).unwrap_or_default(),
             portal_slug: slug,
             portal_token: token,
             is_portal: true,
@@ -526,6 +579,35 @@ pub async fn client_portal(
     )
 }
 
+#[get("/portal/view/<slug>/<token>/attachments/<id>?<thumb>")]
+pub async fn client_portal_attachment(
+    db: &Db,
+    slug: &str,
+    token: &str,
+    id: i64,
+    thumb: Option<bool>,
+) -> Option<NamedFile> {
+    let tenant_id = tenant_repo::find_tenant_id_by_slug(db, slug).await.ok().flatten()?;
+    let client = client_service::find_client_by_portal_token(db, tenant_id, token)
+        .await
+        .ok()
+        .flatten()?;
+    let attachment = attachment_service::find_attachment_by_id(db, tenant_id, id)
+        .await
+        .ok()
+        .flatten()
+        .filter(|attachment| attachment.is_client_visible)?;
+    let deployment = deployment_repo::find_deployment_by_id(db, tenant_id, attachment.deployment_id)
+        .await
+        .ok()
+        .flatten()?;
+    if deployment.client_id != client.id {
+        return None;
+    }
+    let path = attachment_service::attachment_file_path(&attachment, thumb.unwrap_or(false));
+    NamedFile::open(path).await.ok()
+}
+
 #[post("/portal/view/<slug>/<token>/deployments/<deployment_id>/complete?<hide_completed>")]
 pub async fn client_portal_mark_complete(
     db: &Db,
@@ -533,7 +615,7 @@ pub async fn client_portal_mark_complete(
     token: &str,
     deployment_id: i64,
     hide_completed: Option<String>,
-) -> Redirect {
+) -> Result<Redirect, Flash<Redirect>> {
     let hide_completed_flag = is_truthy(hide_completed.as_deref());
     let portal_redirect = if hide_completed_flag {
         Redirect::to(format!("/portal/view/{}/{}?hide_completed=1", slug, token))
@@ -542,27 +624,50 @@ pub async fn client_portal_mark_complete(
     };
     let tenant_id = match tenant_repo::find_tenant_id_by_slug(db, slug).await {
         Ok(Some(id)) => id,
-        _ => return portal_redirect,
+        _ => return Ok(portal_redirect),
     };
     let client = match client_service::find_client_by_portal_token(db, tenant_id, token).await {
         Ok(Some(client)) => client,
-        _ => return portal_redirect,
+        _ => return Ok(portal_redirect),
     };
     let deployment = match deployment_repo::find_deployment_by_id(db, tenant_id, deployment_id).await {
         Ok(Some(deployment)) => deployment,
-        _ => return portal_redirect,
+        _ => return Ok(portal_redirect),
     };
     if deployment.client_id != client.id {
-        return portal_redirect;
+        return Ok(portal_redirect);
+    }
+    let required_open = match deployment_task_service::task_progress(db, tenant_id, deployment_id).await {
+        Ok(progress) => progress.required_open,
+        Err(_) => {
+            return Err(Flash::error(
+                portal_redirect,
+                "Unable to check the deployment's tasks. Please try again.",
+            ))
+        }
+    };
+    if required_open > 0 {
+        return Err(Flash::error(
+            portal_redirect,
+            format!(
+                "This deployment still has {required_open} required task(s) open. It can be marked complete once they are done."
+            ),
+        ));
     }
-    let _ = deployment_repo::update_deployment_status(
+    match deployment_repo::update_deployment_status(
         db,
         tenant_id,
         deployment_id,
         "Completed",
     )
-    .await;
-    portal_redirect
+    .await
+    {
+        Ok(_) => Ok(portal_redirect),
+        Err(_) => Err(Flash::error(
+            portal_redirect,
+            "Unable to mark the deployment complete. Please try again.",
+        )),
+    }
 }
 
 #[get("/<slug>/dashboard")]
@@ -577,6 +682,7 @@ pub async fn dashboard(
     }
     let can_view_clients = access_service::can_view(db, &user, "clients").await;
     let can_view_crew = access_service::can_view(db, &user, "crew").await;
+    let can_view_assets = access_service::can_view(db, &user, "assets").await;
     let can_view_deployments = access_service::can_view(db, &user, "deployments").await;
     let can_view_tracking = access_service::can_view(db, &user, "tracking").await;
     let can_view_invoices = access_service::can_view(db, &user, "invoices").await;
@@ -666,7 +772,7 @@ pub async fn dashboard(
     } else {
         Vec::new()
     };
-    let deployment_statuses = ["Scheduled", "Active", "Completed", "Cancelled"];
+    let deployment_statuses = ["Tentative", "Scheduled", "Active", "Completed", "Cancelled"];
     let deployment_status_chart = deployment_statuses
         .iter()
         .map(|status| {
@@ -876,6 +982,13 @@ pub async fn dashboard(
     } else {
         Vec::new()
     };
+    let certification_warnings = if can_view_crew {
+        certification_service::list_expiry_warnings(db, user.tenant_id, 8)
+            .await
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
     let outcome_kpis = if can_view_deployments {
         let avg_hours_to_ready = deployment_update_repo::average_hours_to_first_update(
             db,
@@ -887,8 +1000,8 @@ pub async fn dashboard(
             deployment_update_repo::count_completed_deployments(db, user.tenant_id)
                 .await
                 .unwrap_or(0);
-        let issue_completed =
-            deployment_update_repo::count_completed_with_issue_keywords(db, user.tenant_id)
+        let incident_completed =
+            incident_service::count_completed_with_incidents(db, user.tenant_id)
                 .await
                 .unwrap_or(0);
         let rework_completed =
@@ -896,7 +1009,7 @@ pub async fn dashboard(
                 .await
                 .unwrap_or(0);
         let first_time_fix = if completed_total > 0 {
-            (((completed_total - issue_completed) as f64 / completed_total as f64) * 100.0)
+            (((completed_total - incident_completed) as f64 / completed_total as f64) * 100.0)
                 .round() as i64
         } else {
             0
@@ -974,9 +1087,17 @@ pub async fn dashboard(
             email: user.email,
             can_view_clients: can_view_clients,
             can_view_crew: can_view_crew,
+            can_view_assets: can_view_assets,
             can_view_deployments: can_view_deployments,
             can_view_tracking: can_view_tracking,
             can_view_invoices: can_view_invoices,
+            can_view_expenses: user.is_super_admin
+                || access_service::can_review_expenses(&user.role)
+                || is_accounting,
+            can_view_incidents: can_view_tracking && !is_employee,
+            can_review_timesheets: user.is_super_admin
+                || access_service::can_review_timesheets(&user.role),
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
             can_view_settings: can_view_settings,
             is_owner: is_owner,
             is_admin: is_admin,
@@ -1003,6 +1124,7 @@ pub async fn dashboard(
             new_deployments_today: new_deployments_today,
             overdue_updates: overdue_updates,
             idle_crews: idle_crews,
+            certification_warnings: certification_warnings,
             outcome_kpis: outcome_kpis,
             active_timer: active_timer,
             active_timer_label: active_timer_label,
@@ -1050,12 +1172,12 @@ pub async fn tracking(
     db: &Db,
     slug: &str,
     deployment_id: Option<i64>,
+    flash: Option<FlashMessage<'_>>,
 ) -> Result<Template, Redirect> {
     let user = workspace_user(cookies, db, slug).await?;
     if !access_service::can_view(db, &user, "tracking").await {
         return Err(Redirect::to(uri!(dashboard(slug = user.tenant_slug))));
     }
-    let _ = tracking_service::close_stale_timers(db, user.tenant_id).await;
     let deployments = if access_service::is_employee(&user.role) {
         let crew_ids =
             crew_member_repo::list_crew_ids_for_user(db, user.tenant_id, user.id, &user.email)
@@ -1139,12 +1261,33 @@ pub async fn tracking(
         }
         _ => Vec::new(),
     };
+    let tasks = match selected_deployment {
+        Some(deployment_id) => {
+            deployment_task_service::list_tasks_by_deployment(db, user.tenant_id, deployment_id)
+                .await
+                .unwrap_or_default()
+        }
+        None => Vec::new(),
+    };
     let can_edit_tracking = access_service::can_edit(db, &user, "tracking").await;
     let can_delete_tracking = access_service::can_delete(db, &user, "tracking").await;
+    let can_manage_tasks = access_service::can_edit(db, &user, "deployments").await;
+    let (expenses, expense_currency) = tracking_expenses(db, &user, selected_deployment).await;
+    let timer_log = tracking_timer_log(db, &user, selected_deployment).await;
+    let incidents = tracking_incidents(db, &user, selected_deployment).await;
+    let update_ids = updates.iter().map(|update| update.id).collect::<Vec<_>>();
+    let attachments =
+        attachment_service::list_attachments_for_updates(db, user.tenant_id, &update_ids)
+            .await
+            .unwrap_or_default();
     let can_edit_updates = is_owner
         || updates
             .iter()
-            .any(|update| update.is_placeholder && update.user_id == Some(user.id));
+            .any(|update| {
+                update.user_id == Some(user.id)
+                    && (update.is_placeholder
+                        || update.approval_status == timesheet_service::STATUS_REJECTED)
+            });
     Ok(Template::render(
         "tracking/index",
         context! {
@@ -1159,14 +1302,35 @@ pub async fn tracking(
             coverage_map_json: coverage_map_json,
             include_map: true,
             error: Option::<String>::None,
+            notice: flash.map(|flash| flash.message().to_string()),
             is_employee: access_service::is_employee(&user.role),
             is_admin_viewer: is_admin_viewer,
             missing_user_ids: missing_user_ids,
             is_owner: is_owner,
             can_edit_updates: can_edit_updates,
+            break_kind_options: tracking_service::break_kind_options(),
+            can_review_timesheets: user.is_super_admin
+                || access_service::can_review_timesheets(&user.role),
             can_edit_tracking: can_edit_tracking,
             can_delete_tracking: can_delete_tracking,
+            attachments: attachments,
+            can_share_attachments: can_edit_tracking && !access_service::is_employee(&user.role),
             discussions: discussions,
+            tasks: tasks,
+            can_manage_tasks: can_manage_tasks,
+            expenses: expenses,
+            expense_currency: expense_currency,
+            expense_category_options: expense_service::category_options(),
+            currency_options: client_service::currency_options(),
+            can_review_expenses: user.is_super_admin
+                || access_service::can_review_expenses(&user.role),
+            today: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            timer_log: timer_log,
+            incidents: incidents,
+            incident_severity_options: incident_service::severity_options(),
+            incident_category_options: incident_service::category_options(),
+            incident_assignees: tracking_incident_assignees(db, &user).await,
+            can_view_incident_log: !access_service::is_employee(&user.role),
             active_timer: active_timer,
             form: DeploymentUpdateFormView::new(
                 selected_deployment.unwrap_or(0),
@@ -1179,22 +1343,35 @@ pub async fn tracking(
     ))
 }
 
+#[derive(Responder)]
+pub enum TrackingRedirect {
+    Plain(Redirect),
+    Warning(Flash<Redirect>),
+}
+
+fn with_warning(redirect: Redirect, warning: Option<String>) -> TrackingRedirect {
+    match warning {
+        Some(message) => TrackingRedirect::Warning(Flash::warning(redirect, message)),
+        None => TrackingRedirect::Plain(redirect),
+    }
+}
+
 #[post("/<slug>/tracking", data = "<form>")]
 pub async fn tracking_update_create(
     cookies: &CookieJar<'_>,
     db: &Db,
     slug: &str,
-    form: Form<DeploymentUpdateForm>,
-) -> Result<Redirect, Template> {
+    form: Form<DeploymentUpdateForm<'_>>,
+) -> Result<TrackingRedirect, Template> {
     let user = match workspace_user(cookies, db, slug).await {
         Ok(user) => user,
-        Err(redirect) => return Ok(redirect),
+        Err(redirect) => return Ok(TrackingRedirect::Plain(redirect)),
     };
     if !access_service::can_edit(db, &user, "tracking").await {
-        return Ok(Redirect::to(uri!(tracking(
+        return Ok(TrackingRedirect::Plain(Redirect::to(uri!(tracking(
             slug = user.tenant_slug,
             deployment_id = Option::<i64>::None
-        ))));
+        )))));
     }
     let form = form.into_inner();
     let deployments = if access_service::is_employee(&user.role) {
@@ -1246,6 +1423,9 @@ pub async fn tracking_update_create(
                         missing_user_ids: 0,
                         is_owner: access_service::is_owner(&user.role),
                         can_edit_updates: access_service::is_owner(&user.role),
+                        break_kind_options: tracking_service::break_kind_options(),
+                        can_review_timesheets: user.is_super_admin
+                            || access_service::can_review_timesheets(&user.role),
                         can_edit_tracking: access_service::can_edit(db, &user, "tracking").await,
                         can_delete_tracking: access_service::can_delete(db, &user, "tracking").await,
                         discussions: Vec::<crate::models::DeploymentDiscussion>::new(),
@@ -1264,10 +1444,13 @@ pub async fn tracking_update_create(
     }
 
     match tracking_service::create_update(db, user.tenant_id, user.id, form).await {
-        Ok(_) => Ok(Redirect::to(uri!(tracking(
-            slug = user.tenant_slug,
-            deployment_id = selected_deployment
-        )))),
+        Ok((_, warning)) => Ok(with_warning(
+            Redirect::to(uri!(tracking(
+                slug = user.tenant_slug,
+                deployment_id = selected_deployment
+            ))),
+            warning,
+        )),
         Err(err) => {
             let deployment_id = selected_deployment.unwrap_or(0);
             let is_admin_viewer =
@@ -1331,7 +1514,11 @@ pub async fn tracking_update_create(
             let can_edit_updates = is_owner
                 || updates
                     .iter()
-                    .any(|update| update.is_placeholder && update.user_id == Some(user.id));
+                    .any(|update| {
+                        update.user_id == Some(user.id)
+                            && (update.is_placeholder
+                                || update.approval_status == timesheet_service::STATUS_REJECTED)
+                    });
             Err(Template::render(
                 "tracking/index",
                 context! {
@@ -1351,6 +1538,9 @@ pub async fn tracking_update_create(
                     missing_user_ids: missing_user_ids,
                     is_owner: is_owner,
                     can_edit_updates: can_edit_updates,
+                    break_kind_options: tracking_service::break_kind_options(),
+                    can_review_timesheets: user.is_super_admin
+                        || access_service::can_review_timesheets(&user.role),
                     can_edit_tracking: can_edit_tracking,
                     can_delete_tracking: can_delete_tracking,
                     discussions: discussions,
@@ -1421,7 +1611,14 @@ pub async fn tracking_timer_start(
         None
     };
     let start_result = if error_message.is_none() {
-        tracking_service::start_timer(db, user.tenant_id, form.deployment_id, user.id).await
+        tracking_service::start_timer(
+            db,
+            user.tenant_id,
+            form.deployment_id,
+            user.id,
+            tracking_service::device_location(form.latitude, form.longitude),
+        )
+        .await
     } else {
         Err(error_message.unwrap())
     };
@@ -1479,6 +1676,7 @@ pub async fn tracking_timer_stop(
         user.tenant_id,
         form.deployment_id,
         user.id,
+        tracking_service::device_location(form.latitude, form.longitude),
     )
     .await;
     match stop_result {
@@ -1492,6 +1690,70 @@ pub async fn tracking_timer_stop(
     }
 }
 
+#[post("/<slug>/tracking/pause", data = "<form>")]
+pub async fn tracking_timer_pause(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<WorkTimerPauseForm>,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "tracking").await {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Option::<i64>::None
+        ))));
+    }
+    let form = form.into_inner();
+    let selected_deployment = Some(form.deployment_id).filter(|id| *id > 0);
+    match tracking_service::pause_timer(db, user.tenant_id, form.deployment_id, user.id, &form.kind)
+        .await
+    {
+        Ok(_) => Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = selected_deployment
+        )))),
+        Err(message) => {
+            let deployments = tracking_deployments_for_user(db, &user).await;
+            Err(render_tracking_error(db, &user, deployments, selected_deployment, &message).await)
+        }
+    }
+}
+
+#[post("/<slug>/tracking/resume", data = "<form>")]
+pub async fn tracking_timer_resume(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<WorkTimerForm>,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "tracking").await {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Option::<i64>::None
+        ))));
+    }
+    let form = form.into_inner();
+    let selected_deployment = Some(form.deployment_id).filter(|id| *id > 0);
+    match tracking_service::resume_timer(db, user.tenant_id, form.deployment_id, user.id).await {
+        Ok(_) => Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = selected_deployment
+        )))),
+        Err(message) => {
+            let deployments = tracking_deployments_for_user(db, &user).await;
+            Err(render_tracking_error(db, &user, deployments, selected_deployment, &message).await)
+        }
+    }
+}
+
 #[get("/<slug>/tracking/updates/<id>/edit")]
 pub async fn tracking_update_edit_form(
     cookies: &CookieJar<'_>,
@@ -1510,15 +1772,25 @@ pub async fn tracking_update_edit_form(
         }
     };
     let is_owner = access_service::is_owner(&user.role);
+    let is_rejected_own = update.user_id == Some(user.id)
+        && update.approval_status == timesheet_service::STATUS_REJECTED;
     let can_edit_placeholder =
         update.is_placeholder && update.user_id == Some(user.id);
-    if !is_owner && !can_edit_placeholder {
+    if (!is_owner && !can_edit_placeholder && !is_rejected_own)
+        || timesheet_service::is_locked(&update)
+    {
         return Err(Redirect::to(uri!(tracking(
             slug = user.tenant_slug,
-            deployment_id = Option::<i64>::None
+            deployment_id = Some(update.deployment_id)
         ))));
     }
 
+    let breaks = tracking_service::list_update_breaks(db, user.tenant_id, update.id)
+        .await
+        .unwrap_or_default();
+    let attachments = attachment_service::list_attachments_for_update(db, user.tenant_id, update.id)
+        .await
+        .unwrap_or_default();
     Ok(Template::render(
         "tracking/edit",
         context! {
@@ -1529,14 +1801,18 @@ pub async fn tracking_update_edit_form(
             error: Option::<String>::None,
             update_id: update.id,
             deployment_id: update.deployment_id,
-            can_edit_times: is_owner,
+            can_edit_times: is_owner || is_rejected_own,
+            review_note: update.review_note,
+            break_kind_options: tracking_service::break_kind_options(),
+            attachments: attachments,
             form: DeploymentUpdateFormView::new(
                 update.deployment_id,
                 update.work_date,
                 update.start_time,
                 update.end_time,
                 update.notes,
-            ),
+            )
+            .with_breaks(breaks),
         },
     ))
 }
@@ -1547,41 +1823,56 @@ pub async fn tracking_update_update(
     db: &Db,
     slug: &str,
     id: i64,
-    form: Form<DeploymentUpdateForm>,
-) -> Result<Redirect, Template> {
+    form: Form<DeploymentUpdateForm<'_>>,
+) -> Result<TrackingRedirect, Template> {
     let user = match workspace_user(cookies, db, slug).await {
         Ok(user) => user,
-        Err(redirect) => return Ok(redirect),
+        Err(redirect) => return Ok(TrackingRedirect::Plain(redirect)),
     };
     let existing = match tracking_service::find_update_by_id(db, user.tenant_id, id).await {
         Ok(Some(update)) => update,
         _ => {
-            return Ok(Redirect::to(uri!(tracking(
+            return Ok(TrackingRedirect::Plain(Redirect::to(uri!(tracking(
                 slug = user.tenant_slug,
                 deployment_id = Option::<i64>::None
-            ))))
+            )))))
         }
     };
     let is_owner = access_service::is_owner(&user.role);
+    let is_rejected_own = existing.user_id == Some(user.id)
+        && existing.approval_status == timesheet_service::STATUS_REJECTED;
     let can_edit_placeholder =
         existing.is_placeholder && existing.user_id == Some(user.id);
-    if !is_owner && !can_edit_placeholder {
-        return Ok(Redirect::to(uri!(tracking(
+    if !is_owner && !can_edit_placeholder && !is_rejected_own {
+        return Ok(TrackingRedirect::Plain(Redirect::to(uri!(tracking(
             slug = user.tenant_slug,
             deployment_id = Option::<i64>::None
-        ))));
+        )))));
     }
     let mut form = form.into_inner();
-    if !is_owner {
+    if !is_owner && !is_rejected_own {
         form.work_date = existing.work_date.clone();
         form.start_time = existing.start_time.clone();
         form.end_time = existing.end_time.clone();
+        form.breaks = tracking_service::list_update_breaks(db, user.tenant_id, existing.id)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|segment| UpdateBreakForm {
+                kind: segment.kind,
+                start_time: segment.start_time,
+                end_time: segment.end_time,
+            })
+            .collect();
     }
-    match tracking_service::update_update(db, user.tenant_id, id, form).await {
-        Ok(deployment_id) => Ok(Redirect::to(uri!(tracking(
-            slug = user.tenant_slug,
-            deployment_id = Some(deployment_id)
-        )))),
+    match tracking_service::update_update(db, user.tenant_id, id, user.id, form).await {
+        Ok((deployment_id, warning)) => Ok(with_warning(
+            Redirect::to(uri!(tracking(
+                slug = user.tenant_slug,
+                deployment_id = Some(deployment_id)
+            ))),
+            warning,
+        )),
         Err(err) => Err(Template::render(
             "tracking/edit",
             context! {
@@ -1592,7 +1883,12 @@ pub async fn tracking_update_update(
                 error: err.message,
                 update_id: id,
                 deployment_id: err.form.deployment_id,
-                can_edit_times: is_owner,
+                can_edit_times: is_owner || is_rejected_own,
+                review_note: existing.review_note,
+                break_kind_options: tracking_service::break_kind_options(),
+                attachments: attachment_service::list_attachments_for_update(db, user.tenant_id, id)
+                    .await
+                    .unwrap_or_default(),
                 form: err.form,
             },
         )),
@@ -1682,6 +1978,9 @@ pub async fn tracking_update_delete(
                     missing_user_ids: 0,
                     is_owner: true,
                     can_edit_updates: can_edit_updates,
+                    break_kind_options: tracking_service::break_kind_options(),
+                    can_review_timesheets: user.is_super_admin
+                        || access_service::can_review_timesheets(&user.role),
                     can_edit_tracking: can_edit_tracking,
                     can_delete_tracking: can_delete_tracking,
                     discussions: discussions,
@@ -1699,6 +1998,160 @@ pub async fn tracking_update_delete(
     }
 }
 
+async fn visible_attachment(
+    db: &Db,
+    user: &crate::models::User,
+    id: i64,
+) -> Option<crate::models::UpdateAttachment> {
+    if !access_service::can_view(db, user, "tracking").await {
+        return None;
+    }
+    let attachment = attachment_service::find_attachment_by_id(db, user.tenant_id, id)
+        .await
+        .ok()
+        .flatten()?;
+    let deployments = tracking_deployments_for_user(db, user).await;
+    if !deployments.iter().any(|item| item.id == attachment.deployment_id) {
+        return None;
+    }
+    if access_service::is_employee(&user.role) && attachment.user_id != Some(user.id) {
+        let update = tracking_service::find_update_by_id(db, user.tenant_id, attachment.update_id)
+            .await
+            .ok()
+            .flatten()?;
+        if update.user_id != Some(user.id) {
+            return None;
+        }
+    }
+    Some(attachment)
+}
+
+#[get("/<slug>/tracking/attachments/<id>?<thumb>")]
+pub async fn tracking_attachment(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+    thumb: Option<bool>,
+) -> Option<NamedFile> {
+    let user = workspace_user(cookies, db, slug).await.ok()?;
+    let attachment = visible_attachment(db, &user, id).await?;
+    let path = attachment_service::attachment_file_path(&attachment, thumb.unwrap_or(false));
+    NamedFile::open(path).await.ok()
+}
+
+#[post("/<slug>/tracking/attachments/<id>/portal")]
+pub async fn tracking_attachment_portal_toggle(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    let attachment = match visible_attachment(db, &user, id).await {
+        Some(attachment) => attachment,
+        None => {
+            return Ok(Redirect::to(uri!(tracking(
+                slug = user.tenant_slug,
+                deployment_id = Option::<i64>::None
+            ))))
+        }
+    };
+    if access_service::is_employee(&user.role)
+        || !access_service::can_edit(db, &user, "tracking").await
+    {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Some(attachment.deployment_id)
+        ))));
+    }
+    match attachment_service::set_client_visible(
+        db,
+        user.tenant_id,
+        &attachment,
+        !attachment.is_client_visible,
+    )
+    .await
+    {
+        Ok(()) => Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Some(attachment.deployment_id)
+        )))),
+        Err(message) => {
+            let deployments = tracking_deployments_for_user(db, &user).await;
+            Err(render_tracking_error(
+                db,
+                &user,
+                deployments,
+                Some(attachment.deployment_id),
+                &message,
+            )
+            .await)
+        }
+    }
+}
+
+#[post("/<slug>/tracking/attachments/<id>/delete")]
+pub async fn tracking_attachment_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    let attachment = match visible_attachment(db, &user, id).await {
+        Some(attachment) => attachment,
+        None => {
+            return Ok(Redirect::to(uri!(tracking(
+                slug = user.tenant_slug,
+                deployment_id = Option::<i64>::None
+            ))))
+        }
+    };
+    let can_manage = access_service::can_edit(db, &user, "tracking").await
+        && (!access_service::is_employee(&user.role) || attachment.user_id == Some(user.id));
+    if !can_manage {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Some(attachment.deployment_id)
+        ))));
+    }
+    let locked = tracking_service::find_update_by_id(db, user.tenant_id, attachment.update_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|update| timesheet_service::is_locked(&update))
+        .unwrap_or(false);
+    let result = if locked {
+        Err("This entry has been approved and is locked. Reopen it before removing attachments.".to_string())
+    } else {
+        attachment_service::delete_attachment(db, user.tenant_id, &attachment).await
+    };
+    match result {
+        Ok(()) => Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Some(attachment.deployment_id)
+        )))),
+        Err(message) => {
+            let deployments = tracking_deployments_for_user(db, &user).await;
+            Err(render_tracking_error(
+                db,
+                &user,
+                deployments,
+                Some(attachment.deployment_id),
+                &message,
+            )
+            .await)
+        }
+    }
+}
+
 #[get("/<slug>/tracking/deployments/<deployment_id>/discussions/new")]
 pub async fn tracking_discussion_new_form(
     cookies: &CookieJar<'_>,
@@ -2010,15 +2463,389 @@ pub async fn tracking_discussion_delete(
     ))))
 }
 
-async fn render_tracking_error(
+async fn tracking_deployments_for_user(
     db: &Db,
     user: &crate::models::User,
-    deployments: Vec<crate::models::DeploymentSelect>,
-    selected_deployment: Option<i64>,
-    message: &str,
-) -> Template {
-    let updates = match selected_deployment {
-        Some(deployment_id) if access_service::is_employee(&user.role) => {
+) -> Vec<crate::models::DeploymentSelect> {
+    if access_service::is_employee(&user.role) {
+        let crew_ids =
+            crew_member_repo::list_crew_ids_for_user(db, user.tenant_id, user.id, &user.email)
+                .await
+                .unwrap_or_default();
+        deployment_service::list_deployments_for_select_for_crews(db, user.tenant_id, &crew_ids)
+            .await
+            .unwrap_or_default()
+    } else {
+        deployment_service::list_deployments_for_select(db, user.tenant_id)
+            .await
+            .unwrap_or_default()
+    }
+}
+
+#[post("/<slug>/tracking/deployments/<deployment_id>/tasks", data = "<form>")]
+pub async fn tracking_task_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    deployment_id: i64,
+    form: Form<DeploymentTaskForm>,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "deployments").await {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Some(deployment_id)
+        ))));
+    }
+    let deployments = tracking_deployments_for_user(db, &user).await;
+    if !deployments.iter().any(|item| item.id == deployment_id) {
+        return Err(render_tracking_error(
+            db,
+            &user,
+            deployments,
+            None,
+            "Deployment not found.",
+        )
+        .await);
+    }
+
+    if let Err(message) =
+        deployment_task_service::create_task(db, user.tenant_id, deployment_id, form.into_inner())
+            .await
+    {
+        return Err(
+            render_tracking_error(db, &user, deployments, Some(deployment_id), &message).await,
+        );
+    }
+
+    Ok(Redirect::to(uri!(tracking(
+        slug = user.tenant_slug,
+        deployment_id = Some(deployment_id)
+    ))))
+}
+
+#[post("/<slug>/tracking/tasks/<task_id>/toggle")]
+pub async fn tracking_task_toggle(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    task_id: i64,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "tracking").await {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Option::<i64>::None
+        ))));
+    }
+    let deployments = tracking_deployments_for_user(db, &user).await;
+    let task = match deployment_task_service::find_task_by_id(db, user.tenant_id, task_id).await {
+        Ok(Some(task)) if deployments.iter().any(|item| item.id == task.deployment_id) => task,
+        _ => {
+            return Err(
+                render_tracking_error(db, &user, deployments, None, "Task not found.").await,
+            )
+        }
+    };
+
+    if let Err(message) =
+        deployment_task_service::toggle_task(db, user.tenant_id, &task, user.id).await
+    {
+        return Err(render_tracking_error(
+            db,
+            &user,
+            deployments,
+            Some(task.deployment_id),
+            &message,
+        )
+        .await);
+    }
+
+    Ok(Redirect::to(uri!(tracking(
+        slug = user.tenant_slug,
+        deployment_id = Some(task.deployment_id)
+    ))))
+}
+
+#[post("/<slug>/tracking/tasks/<task_id>/move/<direction>")]
+pub async fn tracking_task_move(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    task_id: i64,
+    direction: &str,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "deployments").await {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Option::<i64>::None
+        ))));
+    }
+    let deployments = tracking_deployments_for_user(db, &user).await;
+    let task = match deployment_task_service::find_task_by_id(db, user.tenant_id, task_id).await {
+        Ok(Some(task)) if deployments.iter().any(|item| item.id == task.deployment_id) => task,
+        _ => {
+            return Err(
+                render_tracking_error(db, &user, deployments, None, "Task not found.").await,
+            )
+        }
+    };
+
+    if let Err(message) =
+        deployment_task_service::move_task(db, user.tenant_id, &task, direction == "up").await
+    {
+        return Err(render_tracking_error(
+            db,
+            &user,
+            deployments,
+            Some(task.deployment_id),
+            &message,
+        )
+        .await);
+    }
+
+    Ok(Redirect::to(uri!(tracking(
+        slug = user.tenant_slug,
+        deployment_id = Some(task.deployment_id)
+    ))))
+}
+
+#[post("/<slug>/tracking/tasks/<task_id>/delete")]
+pub async fn tracking_task_delete(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    task_id: i64,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "deployments").await {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Option::<i64>::None
+        ))));
+    }
+    let deployments = tracking_deployments_for_user(db, &user).await;
+    let task = match deployment_task_service::find_task_by_id(db, user.tenant_id, task_id).await {
+        Ok(Some(task)) if deployments.iter().any(|item| item.id == task.deployment_id) => task,
+        _ => {
+            return Err(
+                render_tracking_error(db, &user, deployments, None, "Task not found.").await,
+            )
+        }
+    };
+
+    if let Err(message) = deployment_task_service::delete_task(db, user.tenant_id, task.id).await
+    {
+        return Err(render_tracking_error(
+            db,
+            &user,
+            deployments,
+            Some(task.deployment_id),
+            &message,
+        )
+        .await);
+    }
+
+    Ok(Redirect::to(uri!(tracking(
+        slug = user.tenant_slug,
+        deployment_id = Some(task.deployment_id)
+    ))))
+}
+
+#[post("/<slug>/tracking/deployments/<deployment_id>/expenses", data = "<form>")]
+pub async fn tracking_expense_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    deployment_id: i64,
+    form: Form<DeploymentExpenseForm<'_>>,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "tracking").await {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Some(deployment_id)
+        ))));
+    }
+    let deployments = tracking_deployments_for_user(db, &user).await;
+    if !deployments.iter().any(|item| item.id == deployment_id) {
+        return Err(render_tracking_error(
+            db,
+            &user,
+            deployments,
+            None,
+            "Deployment not found.",
+        )
+        .await);
+    }
+
+    if let Err(message) = expense_service::create_expense(
+        db,
+        user.tenant_id,
+        deployment_id,
+        user.id,
+        form.into_inner(),
+    )
+    .await
+    {
+        return Err(
+            render_tracking_error(db, &user, deployments, Some(deployment_id), &message).await,
+        );
+    }
+
+    Ok(Redirect::to(uri!(tracking(
+        slug = user.tenant_slug,
+        deployment_id = Some(deployment_id)
+    ))))
+}
+
+#[post("/<slug>/tracking/deployments/<deployment_id>/incidents", data = "<form>")]
+pub async fn tracking_incident_create(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    deployment_id: i64,
+    form: Form<DeploymentIncidentForm>,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "tracking").await {
+        return Ok(Redirect::to(uri!(tracking(
+            slug = user.tenant_slug,
+            deployment_id = Some(deployment_id)
+        ))));
+    }
+    let deployments = tracking_deployments_for_user(db, &user).await;
+    if !deployments.iter().any(|item| item.id == deployment_id) {
+        return Err(render_tracking_error(
+            db,
+            &user,
+            deployments,
+            None,
+            "Deployment not found.",
+        )
+        .await);
+    }
+
+    let mut form = form.into_inner();
+    if access_service::is_employee(&user.role) {
+        form.assignee_user_id = None;
+    }
+    if let Err(message) =
+        incident_service::create_incident(db, user.tenant_id, deployment_id, user.id, form).await
+    {
+        return Err(
+            render_tracking_error(db, &user, deployments, Some(deployment_id), &message).await,
+        );
+    }
+
+    Ok(Redirect::to(uri!(tracking(
+        slug = user.tenant_slug,
+        deployment_id = Some(deployment_id)
+    ))))
+}
+
+async fn tracking_incidents(
+    db: &Db,
+    user: &crate::models::User,
+    selected_deployment: Option<i64>,
+) -> Vec<crate::models::DeploymentIncident> {
+    let Some(deployment_id) = selected_deployment.filter(|id| *id > 0) else {
+        return Vec::new();
+    };
+    incident_service::list_incidents_by_deployment(db, user.tenant_id, deployment_id)
+        .await
+        .unwrap_or_default()
+}
+
+async fn tracking_incident_assignees(
+    db: &Db,
+    user: &crate::models::User,
+) -> Vec<crate::models::UserSummary> {
+    if access_service::is_employee(&user.role) {
+        return Vec::new();
+    }
+    user_repo::list_users_by_tenant(db, user.tenant_id)
+        .await
+        .unwrap_or_default()
+}
+
+async fn tracking_timer_log(
+    db: &Db,
+    user: &crate::models::User,
+    selected_deployment: Option<i64>,
+) -> Vec<crate::models::WorkTimerLog> {
+    let Some(deployment_id) = selected_deployment.filter(|id| *id > 0) else {
+        return Vec::new();
+    };
+    if !(user.is_super_admin || access_service::can_view_field_locations(&user.role)) {
+        return Vec::new();
+    }
+    tracking_service::list_timer_log(db, user.tenant_id, deployment_id)
+        .await
+        .unwrap_or_default()
+}
+
+async fn tracking_expenses(
+    db: &Db,
+    user: &crate::models::User,
+    selected_deployment: Option<i64>,
+) -> (Vec<crate::models::DeploymentExpense>, String) {
+    let Some(deployment_id) = selected_deployment.filter(|id| *id > 0) else {
+        return (Vec::new(), "USD".to_string());
+    };
+    let expenses = if access_service::is_employee(&user.role) {
+        expense_service::list_expenses_by_deployment_for_user(
+            db,
+            user.tenant_id,
+            deployment_id,
+            user.id,
+        )
+        .await
+        .unwrap_or_default()
+    } else {
+        expense_service::list_expenses_by_deployment(db, user.tenant_id, deployment_id)
+            .await
+            .unwrap_or_default()
+    };
+    let client_id = deployment_repo::find_deployment_by_id(db, user.tenant_id, deployment_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|deployment| deployment.client_id)
+        .unwrap_or(0);
+    let currency =
+        expense_service::default_currency_for_deployment(db, user.tenant_id, client_id).await;
+    (expenses, currency)
+}
+
+async fn render_tracking_error(
+    db: &Db,
+    user: &crate::models::User,
+    deployments: Vec<crate::models::DeploymentSelect>,
+    selected_deployment: Option<i64>,
+    message: &str,
+) -> Template {
+    let updates = match selected_deployment {
+        Some(deployment_id) if access_service::is_employee(&user.role) => {
             tracking_service::list_updates_for_user(db, user.tenant_id, deployment_id, user.id)
                 .await
                 .unwrap_or_default()
@@ -2052,10 +2879,19 @@ async fn render_tracking_error(
     } else {
         0
     };
+    let update_ids = updates.iter().map(|update| update.id).collect::<Vec<_>>();
+    let attachments =
+        attachment_service::list_attachments_for_updates(db, user.tenant_id, &update_ids)
+            .await
+            .unwrap_or_default();
     let can_edit_updates = is_owner
         || updates
             .iter()
-            .any(|update| update.is_placeholder && update.user_id == Some(user.id));
+            .any(|update| {
+                update.user_id == Some(user.id)
+                    && (update.is_placeholder
+                        || update.approval_status == timesheet_service::STATUS_REJECTED)
+            });
     let active_timer = tracking_service::active_timer(db, user.tenant_id, user.id)
         .await
         .ok()
@@ -2075,8 +2911,20 @@ async fn render_tracking_error(
         }
         _ => Vec::new(),
     };
+    let tasks = match selected_deployment {
+        Some(deployment_id) if deployment_id > 0 => {
+            deployment_task_service::list_tasks_by_deployment(db, user.tenant_id, deployment_id)
+                .await
+                .unwrap_or_default()
+        }
+        _ => Vec::new(),
+    };
     let can_edit_tracking = access_service::can_edit(db, &user, "tracking").await;
     let can_delete_tracking = access_service::can_delete(db, &user, "tracking").await;
+    let can_manage_tasks = access_service::can_edit(db, user, "deployments").await;
+    let (expenses, expense_currency) = tracking_expenses(db, user, selected_deployment).await;
+    let timer_log = tracking_timer_log(db, user, selected_deployment).await;
+    let incidents = tracking_incidents(db, user, selected_deployment).await;
     Template::render(
         "tracking/index",
         context! {
@@ -2096,9 +2944,29 @@ async fn render_tracking_error(
             missing_user_ids: missing_user_ids,
             is_owner: is_owner,
             can_edit_updates: can_edit_updates,
+            break_kind_options: tracking_service::break_kind_options(),
+            can_review_timesheets: user.is_super_admin
+                || access_service::can_review_timesheets(&user.role),
             can_edit_tracking: can_edit_tracking,
             can_delete_tracking: can_delete_tracking,
+            attachments: attachments,
+            can_share_attachments: can_edit_tracking && !access_service::is_employee(&user.role),
             discussions: discussions,
+            tasks: tasks,
+            can_manage_tasks: can_manage_tasks,
+            expenses: expenses,
+            expense_currency: expense_currency,
+            expense_category_options: expense_service::category_options(),
+            currency_options: client_service::currency_options(),
+            can_review_expenses: user.is_super_admin
+                || access_service::can_review_expenses(&user.role),
+            today: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            timer_log: timer_log,
+            incidents: incidents,
+            incident_severity_options: incident_service::severity_options(),
+            incident_category_options: incident_service::category_options(),
+            incident_assignees: tracking_incident_assignees(db, user).await,
+            can_view_incident_log: !access_service::is_employee(&user.role),
             active_timer: active_timer,
             form: DeploymentUpdateFormView::new(
                 selected_deployment.unwrap_or(0),
@@ -2320,6 +3188,14 @@ pub async fn settings(
             is_theme_locked: theme_locked,
             users: users_context,
             role_options: access_service::role_options(),
+            timer_policy: timer_policy_service::policy_for_tenant(db, user.tenant_id).await,
+            timer_closures: timer_policy_service::list_auto_closures(db, user.tenant_id)
+                .await
+                .unwrap_or_default(),
+            recommendation_weights: recommendation_service::weight_views(
+                &recommendation_service::weights_for_tenant(db, user.tenant_id).await,
+            ),
+            max_recommendation_weight: recommendation_service::MAX_WEIGHT,
         },
     ))
 }
@@ -2413,6 +3289,101 @@ pub async fn settings_email_update(
     }
 }
 
+#[post("/<slug>/settings/timers", data = "<form>")]
+pub async fn settings_timers_update(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<TimerPolicyForm>,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "settings").await {
+        return Ok(Redirect::to(uri!(settings(
+            slug = user.tenant_slug,
+            tab = Some("timers".to_string())
+        ))));
+    }
+    match timer_policy_service::update_policy(db, user.tenant_id, form.into_inner()).await {
+        Ok(_) => Ok(Redirect::to(uri!(settings(
+            slug = user.tenant_slug,
+            tab = Some("timers".to_string())
+        )))),
+        Err(err) => Err(Template::render(
+            "placeholders/settings",
+            context! {
+                title: "Settings",
+                current_user: Some(CurrentUserView::from(&user)),
+                workspace_brand: workspace_brand(db, user.tenant_id).await,
+                error: err.message,
+                email_form: workspace_service::default_email_settings_view(),
+                email_provider_options: workspace_service::email_provider_options(),
+                theme_form: workspace_service::default_theme_view(),
+                theme_options: workspace_service::theme_options(),
+                font_options: workspace_service::font_options(),
+                active_tab: "timers",
+                is_owner: access_service::is_owner(&user.role),
+                is_theme_locked: is_theme_locked(&user.plan_key),
+                users: Vec::<serde_json::Value>::new(),
+                role_options: access_service::role_options(),
+                timer_policy: err.form,
+                timer_closures: timer_policy_service::list_auto_closures(db, user.tenant_id)
+                    .await
+                    .unwrap_or_default(),
+            },
+        )),
+    }
+}
+
+#[post("/<slug>/settings/recommendations", data = "<form>")]
+pub async fn settings_recommendations_update(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<RecommendationWeightsForm>,
+) -> Result<Redirect, Template> {
+    let user = match workspace_user(cookies, db, slug).await {
+        Ok(user) => user,
+        Err(redirect) => return Ok(redirect),
+    };
+    if !access_service::can_edit(db, &user, "settings").await {
+        return Ok(Redirect::to(uri!(settings(
+            slug = user.tenant_slug,
+            tab = Some("recommendations".to_string())
+        ))));
+    }
+    let form = form.into_inner();
+    match recommendation_service::update_weights(db, user.tenant_id, &form.weights).await {
+        Ok(_) => Ok(Redirect::to(uri!(settings(
+            slug = user.tenant_slug,
+            tab = Some("recommendations".to_string())
+        )))),
+        Err(message) => Err(Template::render(
+            "placeholders/settings",
+            context! {
+                title: "Settings",
+                current_user: Some(CurrentUserView::from(&user)),
+                workspace_brand: workspace_brand(db, user.tenant_id).await,
+                error: message,
+                email_form: workspace_service::default_email_settings_view(),
+                email_provider_options: workspace_service::email_provider_options(),
+                theme_form: workspace_service::default_theme_view(),
+                theme_options: workspace_service::theme_options(),
+                font_options: workspace_service::font_options(),
+                active_tab: "recommendations",
+                is_owner: access_service::is_owner(&user.role),
+                is_theme_locked: is_theme_locked(&user.plan_key),
+                users: Vec::<serde_json::Value>::new(),
+                role_options: access_service::role_options(),
+                recommendation_weights: recommendation_service::weight_views(&form.weights),
+                max_recommendation_weight: recommendation_service::MAX_WEIGHT,
+            },
+        )),
+    }
+}
+
 #[post("/<slug>/settings/theme", data = "<form>")]
 pub async fn settings_theme_update(
     cookies: &CookieJar<'_>,
@@ -2803,17 +3774,15 @@ pub async fn deployments(
         .iter()
         .flat_map(|group| group.deployments.iter().map(|deployment| deployment.id))
         .collect::<Vec<_>>();
-    let issue_flags = deployment_update_repo::list_issue_flags_for_deployments(
+    let mut custom_columns = custom_field_service::list_columns(
         db,
         user.tenant_id,
+        custom_field_service::ENTITY_DEPLOYMENTS,
         &deployment_ids,
     )
-    .await
-    .unwrap_or_default();
-    let mut issue_map = HashMap::new();
-    for (deployment_id, issue_count, resolved_count) in issue_flags {
-        issue_map.insert(deployment_id, (issue_count, resolved_count));
-    }
+    .await;
+    let incident_map =
+        incident_service::incident_counts_map(db, user.tenant_id, &deployment_ids).await;
     let invoice_statuses = invoice_repo::list_invoice_statuses_for_deployments(
         db,
         user.tenant_id,
@@ -2824,6 +3793,17 @@ pub async fn deployments(
     let invoice_map = invoice_statuses
         .into_iter()
         .collect::<HashMap<i64, String>>();
+    let task_map =
+        deployment_task_service::task_progress_map(db, user.tenant_id, &deployment_ids).await;
+    let conflict_map = availability_service::conflict_counts_map(
+        db,
+        user.tenant_id,
+        &groups
+            .iter()
+            .flat_map(|group| group.deployments.iter())
+            .collect::<Vec<_>>(),
+    )
+    .await;
     let deployments = groups
         .into_iter()
         .map(|group| {
@@ -2837,7 +3817,7 @@ pub async fn deployments(
                         &deployment.end_at,
                         deployment.fee_per_hour,
                     );
-                    let (issue_count, resolved_count) = issue_map
+                    let (incident_count, open_incidents) = incident_map
                         .get(&deployment.id)
                         .copied()
                         .unwrap_or((0, 0));
@@ -2846,9 +3826,10 @@ pub async fn deployments(
                     let timeline = deployment_service::deployment_timeline(
                         &deployment.status,
                         &deployment.deployment_type,
-                        issue_count,
-                        resolved_count,
+                        incident_count,
+                        open_incidents,
                         invoice_status,
+                        task_map.get(&deployment.id),
                     );
                     context! {
                         id: deployment.id,
@@ -2861,6 +3842,14 @@ pub async fn deployments(
                         info: deployment.info,
                         status: deployment.status,
                         timeline: timeline,
+                        availability_conflicts: conflict_map
+                            .get(&deployment.id)
+                            .copied()
+                            .unwrap_or(0),
+                        custom_values: custom_columns
+                            .rows
+                            .remove(&deployment.id.to_string())
+                            .unwrap_or_default(),
                     }
                 })
                 .collect::<Vec<_>>();
@@ -2885,15 +3874,19 @@ pub async fn deployments(
             deployments: deployments,
             deployment_limit: deployment_limit.unwrap_or(0),
             deployment_limit_reached: deployment_limit_reached,
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
+            can_schedule: access_service::can_edit(db, &user, "deployments").await,
+            custom_labels: custom_columns.labels,
         },
     ))
 }
 
-#[get("/<slug>/deployments/new")]
+#[get("/<slug>/deployments/new?<template_id>")]
 pub async fn deployment_new_form(
     cookies: &CookieJar<'_>,
     db: &Db,
     slug: &str,
+    template_id: Option<i64>,
 ) -> Result<Template, Redirect> {
     let user = workspace_user(cookies, db, slug).await?;
     if !access_service::can_edit(db, &user, "deployments").await {
@@ -2905,7 +3898,43 @@ pub async fn deployment_new_form(
     let crews = crew_service::list_crews(db, user.tenant_id)
         .await
         .unwrap_or_default();
-    let recommended_crews = recommended_crews_view(&crews, "", "");
+    let templates = deployment_template_service::list_templates(db, user.tenant_id)
+        .await
+        .unwrap_or_default();
+    let selected_template = template_id
+        .and_then(|id| templates.iter().find(|template| template.id == id))
+        .cloned();
+    let form = match selected_template.as_ref() {
+        Some(template) => DeploymentFormView::new(
+            0,
+            0,
+            "",
+            "",
+            template.fee_per_hour,
+            template.info.clone(),
+            "Scheduled",
+            template.deployment_type.clone(),
+            template.required_skills.clone(),
+            template.compatibility_pref.clone(),
+            Some(template.id),
+        ),
+        None => DeploymentFormView::new(0, 0, "", "", 0.0, "", "Scheduled", "Onsite", "", "", None),
+    };
+    let recommended_crews =
+        recommended_crews_view(
+            db,
+            user.tenant_id,
+            &crews,
+            RecommendationRequest {
+                required_skills: &form.required_skills,
+                compatibility_pref: &form.compatibility_pref,
+                client_id: form.client_id,
+                start_at: &form.start_at,
+                end_at: &form.end_at,
+                deployment_id: None,
+            },
+        )
+        .await;
     Ok(Template::render(
         "deployments/new",
         context! {
@@ -2914,9 +3943,12 @@ pub async fn deployment_new_form(
                 current_user_id: user.id,
                 workspace_brand: workspace_brand(db, user.tenant_id).await,
             error: Option::<String>::None,
-            form: DeploymentFormView::new(0, 0, "", "", 0.0, "", "Scheduled", "Onsite", "", ""),
+            form: form,
+            custom_inputs: custom_field_service::inputs_for_record(db, user.tenant_id, custom_field_service::ENTITY_DEPLOYMENTS, None).await,
             clients: clients,
             crews: crews,
+            templates: templates,
+            selected_template: selected_template,
             recommended_crews: recommended_crews,
             status_options: deployment_service::status_options(),
             deployment_type_options: deployment_service::deployment_type_options(),
@@ -2946,14 +3978,32 @@ pub async fn deployment_create(
         .await
         .unwrap_or_default();
 
+    let submitted_custom = form.custom.clone();
     match deployment_service::create_deployment(db, user.tenant_id, form).await {
         Ok(_) => Ok(Redirect::to(uri!(deployments(slug = user.tenant_slug)))),
         Err(err) => {
             let recommended_crews = recommended_crews_view(
+                db,
+                user.tenant_id,
                 &crews,
-                &err.form.required_skills,
-                &err.form.compatibility_pref,
-            );
+                RecommendationRequest {
+                    required_skills: &err.form.required_skills,
+                    compatibility_pref: &err.form.compatibility_pref,
+                    client_id: err.form.client_id,
+                    start_at: &err.form.start_at,
+                    end_at: &err.form.end_at,
+                    deployment_id: None,
+                },
+            )
+            .await;
+            let templates = deployment_template_service::list_templates(db, user.tenant_id)
+                .await
+                .unwrap_or_default();
+            let selected_template = err
+                .form
+                .template_id
+                .and_then(|id| templates.iter().find(|template| template.id == id))
+                .cloned();
             Err(Template::render(
                 "deployments/new",
                 context! {
@@ -2963,8 +4013,11 @@ pub async fn deployment_create(
                         workspace_brand: workspace_brand(db, user.tenant_id).await,
                     error: err.message,
                     form: err.form,
+                    custom_inputs: custom_field_service::inputs_from_form(db, user.tenant_id, custom_field_service::ENTITY_DEPLOYMENTS, &submitted_custom).await,
                     clients: clients,
                     crews: crews,
+                    templates: templates,
+                    selected_template: selected_template,
                     recommended_crews: recommended_crews,
                     status_options: deployment_service::status_options(),
                     deployment_type_options: deployment_service::deployment_type_options(),
@@ -3007,10 +4060,35 @@ pub async fn deployment_edit_form(
         .await
         .unwrap_or_default();
     let recommended_crews = recommended_crews_view(
+        db,
+        user.tenant_id,
         &crews,
-        &deployment.required_skills,
-        &deployment.compatibility_pref,
-    );
+        RecommendationRequest {
+            required_skills: &deployment.required_skills,
+            compatibility_pref: &deployment.compatibility_pref,
+            client_id: deployment.client_id,
+            start_at: &deployment.start_at,
+            end_at: &deployment.end_at,
+            deployment_id: Some(deployment.id),
+        },
+    )
+    .await;
+    let availability_conflicts = availability_service::deployment_conflicts(
+        db,
+        user.tenant_id,
+        deployment.crew_id,
+        &deployment.start_at,
+        &deployment.end_at,
+    )
+    .await;
+    let can_view_assets = access_service::can_view(db, &user, "assets").await;
+    let equipment = if can_view_assets {
+        asset_assignment_service::list_for_deployment(db, user.tenant_id, deployment.id)
+            .await
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
 
     Ok(Template::render(
         "deployments/edit",
@@ -3032,10 +4110,15 @@ pub async fn deployment_edit_form(
                 deployment.deployment_type,
                 deployment.required_skills,
                 deployment.compatibility_pref,
+                deployment.template_id,
             ),
+            custom_inputs: custom_field_service::inputs_for_record(db, user.tenant_id, custom_field_service::ENTITY_DEPLOYMENTS, Some(deployment.id)).await,
             clients: clients,
             crews: crews,
             recommended_crews: recommended_crews,
+            availability_conflicts: availability_conflicts,
+            equipment: equipment,
+            can_view_assets: can_view_assets,
             status_options: deployment_service::status_options(),
             deployment_type_options: deployment_service::deployment_type_options(),
         },
@@ -3065,14 +4148,32 @@ pub async fn deployment_update(
         .await
         .unwrap_or_default();
 
+    let submitted_custom = form.custom.clone();
     match deployment_service::update_deployment(db, user.tenant_id, id, form).await {
         Ok(_) => Ok(Redirect::to(uri!(deployments(slug = user.tenant_slug)))),
         Err(err) => {
             let recommended_crews = recommended_crews_view(
+                db,
+                user.tenant_id,
                 &crews,
-                &err.form.required_skills,
-                &err.form.compatibility_pref,
-            );
+                RecommendationRequest {
+                    required_skills: &err.form.required_skills,
+                    compatibility_pref: &err.form.compatibility_pref,
+                    client_id: err.form.client_id,
+                    start_at: &err.form.start_at,
+                    end_at: &err.form.end_at,
+                    deployment_id: Some(id),
+                },
+            )
+            .await;
+            let can_view_assets = access_service::can_view(db, &user, "assets").await;
+            let equipment = if can_view_assets {
+                asset_assignment_service::list_for_deployment(db, user.tenant_id, id)
+                    .await
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            };
             Err(Template::render(
                 "deployments/edit",
                 context! {
@@ -3083,9 +4184,12 @@ pub async fn deployment_update(
                     error: err.message,
                     deployment_id: id,
                     form: err.form,
+                    custom_inputs: custom_field_service::inputs_from_form(db, user.tenant_id, custom_field_service::ENTITY_DEPLOYMENTS, &submitted_custom).await,
                     clients: clients,
                     crews: crews,
                     recommended_crews: recommended_crews,
+                    equipment: equipment,
+                    can_view_assets: can_view_assets,
                     status_options: deployment_service::status_options(),
                     deployment_type_options: deployment_service::deployment_type_options(),
                 },
@@ -3117,17 +4221,8 @@ pub async fn deployment_delete(
             .iter()
             .flat_map(|group| group.deployments.iter().map(|deployment| deployment.id))
             .collect::<Vec<_>>();
-        let issue_flags = deployment_update_repo::list_issue_flags_for_deployments(
-            db,
-            user.tenant_id,
-            &deployment_ids,
-        )
-        .await
-        .unwrap_or_default();
-        let mut issue_map = HashMap::new();
-        for (deployment_id, issue_count, resolved_count) in issue_flags {
-            issue_map.insert(deployment_id, (issue_count, resolved_count));
-        }
+        let incident_map =
+            incident_service::incident_counts_map(db, user.tenant_id, &deployment_ids).await;
         let invoice_statuses = invoice_repo::list_invoice_statuses_for_deployments(
             db,
             user.tenant_id,
@@ -3138,6 +4233,8 @@ pub async fn deployment_delete(
         let invoice_map = invoice_statuses
             .into_iter()
             .collect::<HashMap<i64, String>>();
+        let task_map =
+            deployment_task_service::task_progress_map(db, user.tenant_id, &deployment_ids).await;
         let deployments = groups
             .into_iter()
             .map(|group| {
@@ -3150,7 +4247,7 @@ pub async fn deployment_delete(
                             &deployment.end_at,
                             deployment.fee_per_hour,
                         );
-                        let (issue_count, resolved_count) = issue_map
+                        let (incident_count, open_incidents) = incident_map
                             .get(&deployment.id)
                             .copied()
                             .unwrap_or((0, 0));
@@ -3159,9 +4256,10 @@ pub async fn deployment_delete(
                         let timeline = deployment_service::deployment_timeline(
                             &deployment.status,
                             &deployment.deployment_type,
-                            issue_count,
-                            resolved_count,
+                            incident_count,
+                            open_incidents,
                             invoice_status,
+                            task_map.get(&deployment.id),
                         );
                         context! {
                             id: deployment.id,
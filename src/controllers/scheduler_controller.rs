@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rocket::form::Form;
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use crate::models::{CurrentUserView, SchedulePlanForm, User};
+use crate::services::{access_service, auth_service, scheduler_service, workspace_service};
+use crate::Db;
+
+async fn tenant_from_cookies(cookies: &CookieJar<'_>, db: &Db) -> Option<(i64, User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &User,
+    from: NaiveDate,
+    until: NaiveDate,
+    choices: Option<&HashMap<i64, i64>>,
+    error: Option<String>,
+) -> Template {
+    let (mut proposals, assigned, travel_km, error) =
+        match scheduler_service::propose_plan(db, tenant_id, from, until).await {
+            Ok(plan) => (plan.proposals, plan.assigned, plan.travel_km, error),
+            Err(message) => (Vec::new(), 0, 0.0, error.or(Some(message))),
+        };
+    if let Some(choices) = choices {
+        for proposal in &mut proposals {
+            if let Some(crew_id) = choices.get(&proposal.deployment_id) {
+                proposal.proposed_crew_id = *crew_id;
+            }
+        }
+    }
+
+    Template::render(
+        "scheduler/index",
+        context! {
+            title: "Crew scheduler",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            from: from.format("%Y-%m-%d").to_string(),
+            until: until.format("%Y-%m-%d").to_string(),
+            total: proposals.len(),
+            assigned: assigned,
+            travel_km: travel_km,
+            proposals: proposals,
+            can_commit: !access_service::is_plan_expired(db, user).await,
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/scheduler?<from>&<until>")]
+pub async fn scheduler_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    from: Option<String>,
+    until: Option<String>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(scheduler_index(
+            slug = current_user.tenant_slug,
+            from = from,
+            until = until
+        ))));
+    }
+    if !access_service::can_edit(db, &user, "deployments").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    let (from, until) = scheduler_service::range_bounds(from.as_deref(), until.as_deref());
+    Ok(render_index(db, tenant_id, &user, from, until, None, None).await)
+}
+
+#[post("/<slug>/scheduler", data = "<form>")]
+pub async fn scheduler_commit(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<SchedulePlanForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let form = form.into_inner();
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "deployments").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(scheduler_index(
+            slug = current_user.tenant_slug,
+            from = Some(form.from),
+            until = Some(form.until)
+        ))));
+    }
+
+    let (from, until) = scheduler_service::range_bounds(Some(&form.from), Some(&form.until));
+    if let Err(message) =
+        scheduler_service::commit_plan(db, tenant_id, from, until, &form.assignments).await
+    {
+        return Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            from,
+            until,
+            Some(&form.assignments),
+            Some(message),
+        )
+        .await);
+    }
+
+    Ok(Redirect::to(uri!(scheduler_index(
+        slug = current_user.tenant_slug,
+        from = Some(from.format("%Y-%m-%d").to_string()),
+        until = Some(until.format("%Y-%m-%d").to_string())
+    ))))
+}
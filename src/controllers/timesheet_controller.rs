@@ -0,0 +1,409 @@
+use rocket::form::Form;
+use rocket::http::CookieJar;
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+
+use std::collections::HashMap;
+
+use crate::models::{CurrentUserView, TimesheetReviewForm, WeekGridForm};
+use crate::services::{
+    access_service,
+    auth_service,
+    timesheet_service,
+    tracking_service,
+    week_grid_service,
+    workspace_service,
+};
+use crate::Db;
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+fn can_review(user: &crate::models::User) -> bool {
+    user.is_super_admin || access_service::can_review_timesheets(&user.role)
+}
+
+async fn render_index(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    week: Option<&str>,
+    selected_user_id: Option<i64>,
+    error: Option<String>,
+) -> Template {
+    let (week_start, week_end) = timesheet_service::week_bounds(week);
+    let members = timesheet_service::list_members_for_week(db, tenant_id, week_start)
+        .await
+        .unwrap_or_default();
+    let selected_user_id = selected_user_id
+        .filter(|user_id| members.iter().any(|member| member.user_id == *user_id))
+        .or_else(|| {
+            members
+                .iter()
+                .find(|member| member.submitted_count > 0)
+                .or(members.first())
+                .map(|member| member.user_id)
+        });
+    let selected_member = selected_user_id
+        .and_then(|user_id| members.iter().find(|member| member.user_id == user_id))
+        .cloned();
+    let entries = match selected_user_id {
+        Some(user_id) => timesheet_service::list_entries_for_week(db, tenant_id, user_id, week_start)
+            .await
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let days = (0..7)
+        .map(|offset| {
+            let day = week_start + chrono::Duration::days(offset);
+            let date = day.format("%Y-%m-%d").to_string();
+            let hours = entries
+                .iter()
+                .filter(|entry| entry.work_date == date)
+                .fold(0.0, |total, entry| total + entry.hours_worked);
+            context! {
+                date: date,
+                label: day.format("%a %d %b").to_string(),
+                hours: (hours * 100.0_f64).round() / 100.0,
+            }
+        })
+        .collect::<Vec<_>>();
+    let week_hours = entries
+        .iter()
+        .fold(0.0, |total, entry| total + entry.hours_worked);
+    let pending_count = timesheet_service::count_pending_entries(db, tenant_id)
+        .await
+        .unwrap_or(0);
+
+    Template::render(
+        "timesheets/index",
+        context! {
+            title: "Timesheets",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            week_start: week_start.format("%Y-%m-%d").to_string(),
+            week_end: week_end.format("%Y-%m-%d").to_string(),
+            week_label: format!(
+                "{} – {}",
+                week_start.format("%d %b"),
+                week_end.format("%d %b %Y")
+            ),
+            previous_week: (week_start - chrono::Duration::days(7)).format("%Y-%m-%d").to_string(),
+            next_week: (week_start + chrono::Duration::days(7)).format("%Y-%m-%d").to_string(),
+            members: members,
+            selected_user_id: selected_user_id,
+            selected_member: selected_member,
+            entries: entries,
+            days: days,
+            week_hours: (week_hours * 100.0_f64).round() / 100.0,
+            pending_count: pending_count,
+            can_view_costs: user.is_super_admin || access_service::can_view_costs(&user.role),
+            error: error,
+        },
+    )
+}
+
+#[get("/<slug>/timesheets?<week>&<user_id>")]
+pub async fn timesheets_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    week: Option<String>,
+    user_id: Option<i64>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(timesheets_index(
+            slug = current_user.tenant_slug,
+            week = week,
+            user_id = user_id
+        ))));
+    }
+    if !can_review(&user) {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_index(db, tenant_id, &user, week.as_deref(), user_id, None).await)
+}
+
+async fn review(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    approve: bool,
+    form: TimesheetReviewForm,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !can_review(&user)
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(timesheets_index(
+            slug = current_user.tenant_slug,
+            week = Some(form.week),
+            user_id = Some(form.user_id)
+        ))));
+    }
+
+    let result = if approve {
+        timesheet_service::approve_entries(db, tenant_id, user.id, &form.update_ids).await
+    } else {
+        timesheet_service::reject_entries(
+            db,
+            tenant_id,
+            user.id,
+            &form.update_ids,
+            form.review_note,
+        )
+        .await
+    };
+    if let Err(message) = result {
+        return Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            Some(&form.week),
+            Some(form.user_id),
+            Some(message),
+        )
+        .await);
+    }
+
+    Ok(Redirect::to(uri!(timesheets_index(
+        slug = current_user.tenant_slug,
+        week = Some(form.week),
+        user_id = Some(form.user_id)
+    ))))
+}
+
+#[post("/<slug>/timesheets/approve", data = "<form>")]
+pub async fn timesheets_approve(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<TimesheetReviewForm>,
+) -> Result<Redirect, Template> {
+    review(cookies, db, slug, true, form.into_inner()).await
+}
+
+#[post("/<slug>/timesheets/reject", data = "<form>")]
+pub async fn timesheets_reject(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<TimesheetReviewForm>,
+) -> Result<Redirect, Template> {
+    review(cookies, db, slug, false, form.into_inner()).await
+}
+
+#[post("/<slug>/timesheets/updates/<id>/reopen")]
+pub async fn timesheet_entry_reopen(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    id: i64,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug
+        || !can_review(&user)
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(timesheets_index(
+            slug = current_user.tenant_slug,
+            week = Option::<String>::None,
+            user_id = Option::<i64>::None
+        ))));
+    }
+
+    let update = match tracking_service::find_update_by_id(db, tenant_id, id).await {
+        Ok(Some(update)) => update,
+        _ => {
+            return Err(render_index(
+                db,
+                tenant_id,
+                &user,
+                None,
+                None,
+                Some("Timesheet entry not found.".to_string()),
+            )
+            .await)
+        }
+    };
+    if let Err(message) = timesheet_service::reopen_entry(db, tenant_id, user.id, &update).await {
+        return Err(render_index(
+            db,
+            tenant_id,
+            &user,
+            Some(&update.work_date),
+            update.user_id,
+            Some(message),
+        )
+        .await);
+    }
+
+    Ok(Redirect::to(uri!(timesheets_index(
+        slug = current_user.tenant_slug,
+        week = Some(update.work_date),
+        user_id = update.user_id
+    ))))
+}
+
+async fn render_week(
+    db: &Db,
+    tenant_id: i64,
+    user: &crate::models::User,
+    week: Option<&str>,
+    inputs: &week_grid_service::GridInputs,
+    notes: &HashMap<i64, String>,
+    errors: Vec<String>,
+) -> Template {
+    let (week_start, week_end) = timesheet_service::week_bounds(week);
+    let grid = week_grid_service::build_week_grid(db, user, week_start, inputs, notes).await;
+    let days = (0..7)
+        .map(|offset| {
+            let day = week_start + chrono::Duration::days(offset);
+            context! {
+                date: day.format("%Y-%m-%d").to_string(),
+                label: day.format("%a %d %b").to_string(),
+                hours: grid.day_totals[offset as usize],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Template::render(
+        "tracking/week",
+        context! {
+            title: "Week view",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            week_start: week_start.format("%Y-%m-%d").to_string(),
+            week_label: format!(
+                "{} – {}",
+                week_start.format("%d %b"),
+                week_end.format("%d %b %Y")
+            ),
+            previous_week: (week_start - chrono::Duration::days(7)).format("%Y-%m-%d").to_string(),
+            next_week: (week_start + chrono::Duration::days(7)).format("%Y-%m-%d").to_string(),
+            days: days,
+            rows: grid.rows,
+            week_hours: grid.week_total,
+            can_edit_tracking: access_service::can_edit(db, user, "tracking").await,
+            errors: errors,
+        },
+    )
+}
+
+#[get("/<slug>/tracking/week?<week>")]
+pub async fn tracking_week(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    week: Option<String>,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(tracking_week(
+            slug = current_user.tenant_slug,
+            week = week
+        ))));
+    }
+    if !access_service::can_view(db, &user, "tracking").await {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_week(
+        db,
+        tenant_id,
+        &user,
+        week.as_deref(),
+        &HashMap::new(),
+        &HashMap::new(),
+        Vec::new(),
+    )
+    .await)
+}
+
+#[post("/<slug>/tracking/week", data = "<form>")]
+pub async fn tracking_week_save(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    form: Form<WeekGridForm>,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let form = form.into_inner();
+    if current_user.tenant_slug != slug
+        || !access_service::can_edit(db, &user, "tracking").await
+        || access_service::is_plan_expired(db, &user).await
+    {
+        return Ok(Redirect::to(uri!(tracking_week(
+            slug = current_user.tenant_slug,
+            week = Some(form.week)
+        ))));
+    }
+
+    if let Err(errors) = week_grid_service::save_week_grid(db, &user, &form).await {
+        let inputs = form
+            .cells
+            .into_iter()
+            .map(|cell| ((cell.deployment_id, cell.work_date), cell.hours))
+            .collect();
+        let notes = form
+            .rows
+            .into_iter()
+            .map(|row| (row.deployment_id, row.notes))
+            .collect();
+        return Err(render_week(db, tenant_id, &user, Some(&form.week), &inputs, &notes, errors).await);
+    }
+
+    Ok(Redirect::to(uri!(tracking_week(
+        slug = current_user.tenant_slug,
+        week = Some(form.week)
+    ))))
+}
@@ -0,0 +1,44 @@
+use rocket::http::{CookieJar, Status};
+use rocket::serde::json::Json;
+
+use crate::models::{TrackingSyncRequest, TrackingSyncResponse};
+use crate::services::{access_service, auth_service, tracking_sync_service};
+use crate::Db;
+
+async fn tenant_from_cookies(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+) -> Option<(i64, crate::models::User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+#[post("/<slug>/tracking/sync", format = "json", data = "<request>")]
+pub async fn tracking_sync(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    request: Json<TrackingSyncRequest>,
+) -> Result<Json<TrackingSyncResponse>, Status> {
+    let (_, user) = tenant_from_cookies(cookies, db)
+        .await
+        .ok_or(Status::Unauthorized)?;
+    if user.tenant_slug != slug || !access_service::can_edit(db, &user, "tracking").await {
+        return Err(Status::Forbidden);
+    }
+    let request = request.into_inner();
+    if request.items.len() > tracking_sync_service::MAX_BATCH_ITEMS {
+        return Err(Status::PayloadTooLarge);
+    }
+
+    let results = tracking_sync_service::sync_items(db, &user, request.items).await;
+    Ok(Json(TrackingSyncResponse { results }))
+}
@@ -0,0 +1,57 @@
+use rocket::data::{ByteUnit, Data, Limits};
+use rocket::fairing::{self, Fairing, Info, Kind};
+use rocket::http::{Method, Status};
+use rocket::{Build, Request, Rocket};
+
+const TRACKING_FORM_LIMIT: &str = "tracking-form";
+
+struct DefaultFormLimit(ByteUnit);
+
+/// Rocket reads a single `data-form` limit for every form, so it is raised to
+/// `tracking-form` at ignite and the configured `data-form` limit is enforced
+/// here, by Content-Length, on every route except tracking update uploads.
+pub struct UploadLimit;
+
+#[rocket::async_trait]
+impl Fairing for UploadLimit {
+    fn info(&self) -> Info {
+        Info {
+            name: "Upload Limit",
+            kind: Kind::Ignite | Kind::Request,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let limits = rocket.figment().extract_inner::<Limits>("limits").unwrap_or_default();
+        let default = limits.get("data-form").unwrap_or(Limits::DATA_FORM);
+        let tracking = limits.get(TRACKING_FORM_LIMIT).unwrap_or(default).max(default);
+        let figment = rocket.figment().clone().merge(("limits.data-form", tracking));
+        Ok(rocket.configure(figment).manage(DefaultFormLimit(default)))
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        let Some(DefaultFormLimit(limit)) = request.rocket().state::<DefaultFormLimit>() else {
+            return;
+        };
+        let too_large = request
+            .headers()
+            .get_one("Content-Length")
+            .and_then(|length| length.parse::<u64>().ok())
+            .is_some_and(|length| length > limit.as_u64());
+        let is_form_data = request.content_type().is_some_and(|content_type| content_type.is_form_data());
+        if too_large && is_form_data && !is_tracking_update(request) {
+            request.set_method(Method::Post);
+            request.set_uri(uri!(upload_too_large));
+        }
+    }
+}
+
+fn is_tracking_update(request: &Request<'_>) -> bool {
+    let segments = request.uri().path().segments().collect::<Vec<_>>();
+    matches!(segments.as_slice(), [_, "tracking"] | [_, "tracking", "updates", _])
+}
+
+#[post("/upload-too-large")]
+pub fn upload_too_large() -> Status {
+    Status::PayloadTooLarge
+}
@@ -0,0 +1,153 @@
+use rocket::fs::NamedFile;
+use rocket::http::{CookieJar, Header};
+use rocket::response::Redirect;
+use rocket_dyn_templates::{context, Template};
+use serde_json::json;
+
+use crate::controllers::label_controller::RequestOrigin;
+use crate::models::{CurrentUserView, User};
+use crate::services::{access_service, auth_service, workspace_export_service, workspace_service};
+use crate::Db;
+
+#[derive(Responder)]
+pub struct ExportArchive {
+    file: NamedFile,
+    disposition: Header<'static>,
+}
+
+async fn tenant_from_cookies(cookies: &CookieJar<'_>, db: &Db) -> Option<(i64, User)> {
+    let user_id = cookies.get_private("user_id").and_then(|c| c.value().parse().ok());
+    let tenant_id = cookies.get_private("tenant_id").and_then(|c| c.value().parse().ok());
+    match (user_id, tenant_id) {
+        (Some(user_id), Some(tenant_id)) => auth_service::get_user_by_ids(db, user_id, tenant_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|user| (tenant_id, user)),
+        _ => None,
+    }
+}
+
+async fn workspace_brand(db: &Db, tenant_id: i64) -> crate::models::WorkspaceBrandView {
+    workspace_service::find_workspace_by_id(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|workspace| workspace_service::workspace_brand_view(&workspace))
+        .unwrap_or_else(workspace_service::default_workspace_brand_view)
+}
+
+async fn render_exports(db: &Db, tenant_id: i64, user: &User, error: Option<String>) -> Template {
+    let exports = workspace_export_service::list_exports(db, tenant_id)
+        .await
+        .into_iter()
+        .map(|export| {
+            json!({
+                "id": export.id,
+                "status": export.status,
+                "is_ready": export.status == workspace_export_service::STATUS_READY,
+                "token": export.token,
+                "size_mb": format!("{:.1}", export.file_size as f64 / (1024.0 * 1024.0)),
+                "error": export.error,
+                "created_at": export.created_at,
+                "expires_at": export.expires_at,
+            })
+        })
+        .collect::<Vec<_>>();
+    Template::render(
+        "workspace_exports/index",
+        context! {
+            title: "Data export",
+            current_user: Some(CurrentUserView::from(user)),
+            workspace_brand: workspace_brand(db, tenant_id).await,
+            exports: exports,
+            link_valid_hours: workspace_export_service::LINK_VALID_HOURS,
+            error: error,
+        },
+    )
+}
+
+/// Exports hold every record in the workspace, so only owners may request or
+/// download them. An expired plan does not block this: owners can always take
+/// their data with them.
+#[get("/<slug>/data-export")]
+pub async fn workspace_exports_index(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+) -> Result<Template, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug {
+        return Err(Redirect::to(uri!(workspace_exports_index(slug = current_user.tenant_slug))));
+    }
+    if !access_service::is_owner(&user.role) {
+        return Err(Redirect::to(uri!(crate::controllers::public_controller::dashboard(
+            slug = current_user.tenant_slug
+        ))));
+    }
+
+    Ok(render_exports(db, tenant_id, &user, None).await)
+}
+
+#[post("/<slug>/data-export")]
+pub async fn workspace_export_request(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    origin: RequestOrigin,
+) -> Result<Redirect, Template> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Ok(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    if current_user.tenant_slug != slug || !access_service::is_owner(&user.role) {
+        return Ok(Redirect::to(uri!(workspace_exports_index(slug = current_user.tenant_slug))));
+    }
+
+    match workspace_export_service::request_export(db, &user, &origin.0).await {
+        Ok(()) => Ok(Redirect::to(uri!(workspace_exports_index(slug = current_user.tenant_slug)))),
+        Err(message) => Err(render_exports(db, tenant_id, &user, Some(message)).await),
+    }
+}
+
+/// Target of the emailed link. The token alone is not enough: the visitor
+/// must also be signed in as an owner of the workspace.
+#[get("/<slug>/data-export/<token>")]
+pub async fn workspace_export_download(
+    cookies: &CookieJar<'_>,
+    db: &Db,
+    slug: &str,
+    token: &str,
+) -> Result<ExportArchive, Redirect> {
+    let (tenant_id, user) = match tenant_from_cookies(cookies, db).await {
+        Some(data) => data,
+        None => return Err(Redirect::to(uri!(crate::controllers::public_controller::login_form))),
+    };
+    let current_user = CurrentUserView::from(&user);
+    let index = Redirect::to(uri!(workspace_exports_index(slug = current_user.tenant_slug.clone())));
+    if current_user.tenant_slug != slug || !access_service::is_owner(&user.role) {
+        return Err(index);
+    }
+    let Some(export) = workspace_export_service::find_ready_export(db, tenant_id, token).await else {
+        return Err(index);
+    };
+    let Ok(file) = NamedFile::open(workspace_export_service::export_file_path(&export)).await else {
+        return Err(index);
+    };
+
+    Ok(ExportArchive {
+        file,
+        disposition: Header::new(
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"{}\"",
+                workspace_export_service::download_name(slug, &export)
+            ),
+        ),
+    })
+}
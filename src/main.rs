@@ -53,6 +53,28 @@ use controllers::crew_controller::{
     crew_member_delete,
     crew_update,
 };
+use controllers::deployment_template_controller::{
+    deployment_template_create,
+    deployment_template_delete,
+    deployment_template_edit_form,
+    deployment_template_new_form,
+    deployment_template_update,
+    deployment_templates_index,
+    deployment_templates_report,
+};
+use controllers::expense_controller::{
+    expense_approve,
+    expense_delete,
+    expense_receipt,
+    expense_reject,
+    expenses_index,
+};
+use controllers::incident_controller::{
+    incident_delete,
+    incident_edit_form,
+    incident_update,
+    incidents_index,
+};
 use controllers::invoice_controller::{
     invoice_create,
     invoice_delete,
@@ -61,13 +83,95 @@ use controllers::invoice_controller::{
     invoice_email_send,
     invoice_new_form,
     invoice_show,
+    invoice_attachment,
     invoice_update,
     invoices_index,
 };
+use controllers::profitability_controller::{
+    deployment_profitability,
+    profitability_report,
+};
+use controllers::timesheet_controller::{
+    timesheet_entry_reopen,
+    timesheets_approve,
+    timesheets_index,
+    timesheets_reject,
+    tracking_week,
+    tracking_week_save,
+};
+use controllers::certification_controller::{
+    member_certification_create,
+    member_certification_delete,
+    member_certification_document,
+    member_certification_edit_form,
+    member_certification_update,
+    member_certifications,
+};
+use controllers::availability_controller::{
+    availability_index,
+    holiday_create,
+    holiday_delete,
+    leave_request_approve,
+    leave_request_cancel,
+    leave_request_create,
+    leave_request_reject,
+    recurring_create,
+    recurring_delete,
+};
+use controllers::payroll_controller::{payroll_export, payroll_index, payroll_settings};
+use controllers::scheduler_controller::{scheduler_commit, scheduler_index};
+use controllers::asset_controller::{
+    asset_checkin,
+    required_kit_form,
+    required_kit_update,
+    maintenance_plans_index,
+    maintenance_plan_create,
+    maintenance_plan_delete,
+    asset_maintenance_log,
+    asset_checkout,
+    asset_create,
+    asset_delete,
+    asset_edit_form,
+    asset_new_form,
+    asset_show,
+    asset_update,
+    assets_index,
+};
+use controllers::label_controller::{
+    asset_labels,
+    asset_scan,
+    asset_scan_checkin,
+    asset_scan_checkout,
+    deployment_labels,
+    labels_index,
+};
+use controllers::custom_field_controller::{
+    custom_field_create,
+    custom_field_delete,
+    custom_fields_index,
+    records_api,
+    records_export,
+};
+use controllers::import_controller::{
+    import_commit,
+    import_errors,
+    import_mapping,
+    import_show,
+    import_upload,
+    imports_index,
+};
+use controllers::workspace_export_controller::{
+    workspace_export_download,
+    workspace_export_request,
+    workspace_exports_index,
+};
+use controllers::tracking_sync_controller::tracking_sync;
+use controllers::upload_limit_controller::{upload_too_large, UploadLimit};
 use controllers::client_controller::{
     client_create,
     client_delete,
     client_edit_form,
+    client_geofence_update,
     client_new_form,
     client_show,
     client_update,
@@ -95,6 +199,7 @@ use controllers::client_controller::{
 use controllers::public_controller::{
     client_portal,
     client_portal_mark_complete,
+    client_portal_attachment,
     dashboard,
     deployment_delete,
     deployment_edit_form,
@@ -113,13 +218,21 @@ use controllers::public_controller::{
     settings,
     settings_email_update,
     settings_theme_update,
+    settings_timers_update,
+    settings_recommendations_update,
     settings_seed_demo,
     email_log,
     tracking,
     tracking_timer_start,
     tracking_timer_stop,
+    tracking_timer_pause,
+    tracking_timer_resume,
     tracking_update_create,
     tracking_update_delete,
+    tracking_attachment,
+    tracking_attachment_portal_toggle,
+    tracking_attachment_delete,
+    tracking_incident_create,
     tracking_update_edit_form,
     tracking_update_update,
     tracking_discussion_new_form,
@@ -127,10 +240,23 @@ use controllers::public_controller::{
     tracking_discussion_edit_form,
     tracking_discussion_update,
     tracking_discussion_delete,
+    tracking_expense_create,
+    tracking_task_create,
+    tracking_task_delete,
+    tracking_task_move,
+    tracking_task_toggle,
     workspace_register_form,
     workspace_register_submit,
 };
-use services::{email_service, schema_service};
+use services::{
+    certification_service,
+    email_service,
+    gear_service,
+    maintenance_service,
+    schema_service,
+    timer_policy_service,
+    workspace_export_service,
+};
 
 #[derive(Database, Clone)]
 #[database("kinetic_db")]
@@ -141,6 +267,8 @@ fn rocket() -> _ {
     rocket::build()
         .attach(Db::init())
         .attach(Template::fairing())
+        .attach(AdHoc::config::<controllers::label_controller::OriginConfig>())
+        .attach(UploadLimit)
         .attach(AdHoc::try_on_ignite("Init DB", |rocket| async {
             let db = Db::fetch(&rocket).expect("database pool");
             if let Err(err) = schema_service::ensure_schema(db).await {
@@ -161,6 +289,66 @@ fn rocket() -> _ {
                 }
             });
         })))
+        .attach(AdHoc::on_liftoff("Timer Policy Worker", |rocket| Box::pin(async move {
+            let db = Db::fetch(rocket).expect("database pool").clone();
+            rocket::tokio::spawn(async move {
+                let mut ticker = interval(Duration::from_secs(60));
+                loop {
+                    ticker.tick().await;
+                    if let Err(err) = timer_policy_service::process_open_timers(&db).await {
+                        eprintln!("Timer policy worker error: {err}");
+                    }
+                }
+            });
+        })))
+        .attach(AdHoc::on_liftoff("Certification Expiry Worker", |rocket| Box::pin(async move {
+            let db = Db::fetch(rocket).expect("database pool").clone();
+            rocket::tokio::spawn(async move {
+                let mut ticker = interval(Duration::from_secs(3600));
+                loop {
+                    ticker.tick().await;
+                    if let Err(err) = certification_service::process_expiry_reminders(&db).await {
+                        eprintln!("Certification expiry worker error: {err}");
+                    }
+                }
+            });
+        })))
+        .attach(AdHoc::on_liftoff("Gear Score Worker", |rocket| Box::pin(async move {
+            let db = Db::fetch(rocket).expect("database pool").clone();
+            rocket::tokio::spawn(async move {
+                let mut ticker = interval(Duration::from_secs(3600));
+                loop {
+                    ticker.tick().await;
+                    if let Err(err) = gear_service::refresh_all_gear_scores(&db).await {
+                        eprintln!("Gear score worker error: {err}");
+                    }
+                }
+            });
+        })))
+        .attach(AdHoc::on_liftoff("Maintenance Reminder Worker", |rocket| Box::pin(async move {
+            let db = Db::fetch(rocket).expect("database pool").clone();
+            rocket::tokio::spawn(async move {
+                let mut ticker = interval(Duration::from_secs(3600));
+                loop {
+                    ticker.tick().await;
+                    if let Err(err) = maintenance_service::process_reminders(&db).await {
+                        eprintln!("Maintenance reminder worker error: {err}");
+                    }
+                }
+            });
+        })))
+        .attach(AdHoc::on_liftoff("Workspace Export Worker", |rocket| Box::pin(async move {
+            let db = Db::fetch(rocket).expect("database pool").clone();
+            rocket::tokio::spawn(async move {
+                let mut ticker = interval(Duration::from_secs(60));
+                loop {
+                    ticker.tick().await;
+                    if let Err(err) = workspace_export_service::process_exports(&db).await {
+                        eprintln!("Workspace export worker error: {err}");
+                    }
+                }
+            });
+        })))
         .mount(
             "/",
             routes![
@@ -172,6 +360,7 @@ fn rocket() -> _ {
                 logout,
                 client_portal,
                 client_portal_mark_complete,
+                client_portal_attachment,
                 admin_login_form,
                 admin_login_submit,
                 admin_logout,
@@ -219,6 +408,7 @@ fn rocket() -> _ {
                 client_email_blast_send,
                 client_edit_form,
                 client_update,
+                client_geofence_update,
                 client_delete,
                 appointment_new_form,
                 appointment_create,
@@ -240,19 +430,105 @@ fn rocket() -> _ {
                 tracking,
                 tracking_timer_start,
                 tracking_timer_stop,
+                tracking_timer_pause,
+                tracking_timer_resume,
                 tracking_update_create,
                 tracking_update_edit_form,
                 tracking_update_update,
                 tracking_update_delete,
+                tracking_attachment,
+                tracking_attachment_portal_toggle,
+                tracking_attachment_delete,
+                tracking_incident_create,
                 tracking_discussion_new_form,
                 tracking_discussion_create,
                 tracking_discussion_edit_form,
                 tracking_discussion_update,
                 tracking_discussion_delete,
+                tracking_task_create,
+                tracking_task_toggle,
+                tracking_task_move,
+                tracking_task_delete,
+                tracking_expense_create,
+                expenses_index,
+                incidents_index,
+                incident_edit_form,
+                incident_update,
+                incident_delete,
+                expense_approve,
+                expense_reject,
+                expense_delete,
+                expense_receipt,
+                timesheets_index,
+                timesheets_approve,
+                timesheets_reject,
+                timesheet_entry_reopen,
+                tracking_week,
+                tracking_week_save,
+                tracking_sync,
+                upload_too_large,
+                member_certifications,
+                member_certification_create,
+                member_certification_edit_form,
+                member_certification_update,
+                member_certification_delete,
+                member_certification_document,
+                payroll_index,
+                payroll_export,
+                payroll_settings,
+                availability_index,
+                leave_request_create,
+                leave_request_approve,
+                leave_request_reject,
+                leave_request_cancel,
+                holiday_create,
+                holiday_delete,
+                recurring_create,
+                recurring_delete,
+                scheduler_index,
+                scheduler_commit,
+                assets_index,
+                asset_new_form,
+                asset_create,
+                asset_show,
+                asset_edit_form,
+                asset_update,
+                asset_delete,
+                asset_checkout,
+                asset_checkin,
+                required_kit_form,
+                required_kit_update,
+                maintenance_plans_index,
+                maintenance_plan_create,
+                maintenance_plan_delete,
+                asset_maintenance_log,
+                labels_index,
+                asset_labels,
+                deployment_labels,
+                asset_scan,
+                asset_scan_checkout,
+                asset_scan_checkin,
+                custom_fields_index,
+                custom_field_create,
+                custom_field_delete,
+                records_export,
+                records_api,
+                imports_index,
+                import_upload,
+                import_show,
+                import_mapping,
+                import_commit,
+                import_errors,
+                workspace_exports_index,
+                workspace_export_request,
+                workspace_export_download,
+                profitability_report,
+                deployment_profitability,
                 invoices_index,
                 invoice_new_form,
                 invoice_create,
                 invoice_show,
+                invoice_attachment,
                 invoice_edit_form,
                 invoice_update,
                 invoice_delete,
@@ -262,6 +538,8 @@ fn rocket() -> _ {
                 settings_users_update,
                 settings_email_update,
                 settings_theme_update,
+                settings_timers_update,
+                settings_recommendations_update,
                 settings_seed_demo,
                 email_log,
                 deployment_new_form,
@@ -270,6 +548,13 @@ fn rocket() -> _ {
                 deployment_update,
                 deployment_delete,
                 deployments,
+                deployment_templates_index,
+                deployment_templates_report,
+                deployment_template_new_form,
+                deployment_template_create,
+                deployment_template_edit_form,
+                deployment_template_update,
+                deployment_template_delete,
                 plans,
                 dashboard
             ],
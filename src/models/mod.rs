@@ -1,6 +1,7 @@
 use rocket::form::FromForm;
 use rocket::fs::TempFile;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(FromForm)]
 pub struct RegisterForm {
@@ -46,6 +47,8 @@ pub struct PlanLimitsForm {
     pub crews: i64,
     pub members_per_crew: i64,
     pub users: i64,
+    pub storage_mb: i64,
+    pub assets: i64,
     pub expires_after_days: i64,
 }
 
@@ -90,9 +93,70 @@ pub struct WorkspaceThemeForm<'r> {
 pub struct CrewForm {
     pub name: String,
     pub status: String,
-    pub gear_score: i64,
+    pub gear_score: Option<i64>,
+    pub gear_override: bool,
+    pub gear_override_reason: Option<String>,
     pub skill_tags: String,
     pub compatibility_tags: String,
+    pub custom: HashMap<String, String>,
+}
+
+#[derive(FromForm)]
+pub struct RequiredKitForm {
+    pub kit: String,
+}
+
+#[derive(FromForm)]
+pub struct AssetForm {
+    pub name: String,
+    pub asset_type: String,
+    pub serial_number: String,
+    pub purchase_date: String,
+    pub purchase_cost: Option<f64>,
+    pub condition: String,
+    pub location: String,
+    pub notes: String,
+    pub custom_fields: String,
+}
+
+#[derive(FromForm)]
+pub struct AssetCheckoutForm {
+    pub target: String,
+    pub checked_out_at: String,
+    pub condition: String,
+    pub notes: String,
+}
+
+#[derive(FromForm)]
+pub struct AssetCheckinForm {
+    pub checked_in_at: String,
+    pub condition: String,
+    pub notes: String,
+}
+
+#[derive(FromForm)]
+pub struct CustomFieldForm {
+    pub entity: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: Option<String>,
+    pub is_required: bool,
+}
+
+#[derive(FromForm)]
+pub struct MaintenancePlanForm {
+    pub asset_type: String,
+    pub name: String,
+    pub interval_days: Option<i64>,
+    pub interval_hours: Option<f64>,
+}
+
+#[derive(FromForm)]
+pub struct MaintenanceLogForm {
+    pub plan_id: Option<i64>,
+    pub performed_at: String,
+    pub cost: Option<f64>,
+    pub notes: String,
 }
 
 #[derive(FromForm)]
@@ -102,6 +166,8 @@ pub struct CrewMemberForm {
     pub phone: String,
     pub position: String,
     pub availability_status: String,
+    pub hourly_cost: Option<f64>,
+    pub custom: HashMap<String, String>,
 }
 
 #[derive(FromForm)]
@@ -114,6 +180,7 @@ pub struct ClientForm {
     pub longitude: String,
     pub stage: String,
     pub currency: String,
+    pub custom: HashMap<String, String>,
 }
 
 #[derive(FromForm)]
@@ -124,6 +191,7 @@ pub struct ClientContactForm {
     pub phone: String,
     pub department: String,
     pub position: String,
+    pub custom: HashMap<String, String>,
 }
 
 #[derive(FromForm)]
@@ -152,20 +220,307 @@ pub struct DeploymentForm {
     pub deployment_type: String,
     pub required_skills: String,
     pub compatibility_pref: String,
+    pub template_id: Option<i64>,
+    pub override_open_tasks: bool,
+    pub custom: HashMap<String, String>,
+}
+
+#[derive(FromForm)]
+pub struct DeploymentTaskForm {
+    pub title: String,
+    pub is_required: bool,
+}
+
+#[derive(FromForm)]
+pub struct DeploymentExpenseForm<'r> {
+    pub category: String,
+    pub description: String,
+    pub amount: f64,
+    pub currency: String,
+    pub incurred_on: String,
+    pub is_billable: bool,
+    pub markup_percent: Option<f64>,
+    pub receipt: Option<TempFile<'r>>,
+}
+
+#[derive(FromForm)]
+pub struct DeploymentIncidentForm {
+    pub title: String,
+    pub description: String,
+    pub severity: String,
+    pub category: String,
+    pub occurred_at: String,
+    pub assignee_user_id: Option<i64>,
+}
+
+#[derive(FromForm)]
+pub struct IncidentUpdateForm {
+    pub title: String,
+    pub description: String,
+    pub severity: String,
+    pub category: String,
+    pub status: String,
+    pub assignee_user_id: Option<i64>,
+    pub resolution_notes: String,
+}
+
+#[derive(FromForm)]
+pub struct ExpenseReviewForm {
+    pub review_note: Option<String>,
+}
+
+#[derive(FromForm)]
+pub struct TimesheetReviewForm {
+    pub week: String,
+    pub user_id: i64,
+    pub update_ids: Vec<i64>,
+    pub review_note: Option<String>,
+}
+
+#[derive(FromForm)]
+pub struct DeploymentTemplateForm {
+    pub name: String,
+    pub deployment_type: String,
+    pub required_skills: String,
+    pub compatibility_pref: String,
+    pub fee_per_hour: f64,
+    pub default_duration_hours: f64,
+    pub checklist: String,
+    pub info: String,
 }
 
 #[derive(FromForm)]
-pub struct DeploymentUpdateForm {
+pub struct DeploymentUpdateForm<'r> {
     pub deployment_id: i64,
     pub work_date: String,
     pub start_time: String,
     pub end_time: String,
     pub notes: String,
+    pub breaks: Vec<UpdateBreakForm>,
+    pub attachments: Vec<TempFile<'r>>,
+}
+
+#[derive(FromForm, Deserialize, Clone)]
+pub struct UpdateBreakForm {
+    pub kind: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+#[derive(FromForm)]
+pub struct WorkTimerPauseForm {
+    pub deployment_id: i64,
+    pub kind: String,
 }
 
 #[derive(FromForm)]
 pub struct WorkTimerForm {
     pub deployment_id: i64,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+#[derive(FromForm)]
+pub struct CrewCertificationForm<'r> {
+    pub name: String,
+    pub skill_tag: String,
+    pub issuing_body: String,
+    pub certificate_number: String,
+    pub issued_on: String,
+    pub expires_on: String,
+    pub document: Option<TempFile<'r>>,
+}
+
+#[derive(FromForm)]
+pub struct LeaveRequestForm {
+    pub member_id: i64,
+    pub start_date: String,
+    pub end_date: String,
+    pub leave_type: String,
+    pub reason: String,
+    pub month: Option<String>,
+}
+
+#[derive(FromForm)]
+pub struct LeaveReviewForm {
+    pub review_note: Option<String>,
+    pub month: Option<String>,
+}
+
+#[derive(FromForm)]
+pub struct WorkspaceHolidayForm {
+    pub holiday_date: String,
+    pub name: String,
+    pub month: Option<String>,
+}
+
+#[derive(FromForm)]
+pub struct RecurringUnavailabilityForm {
+    pub member_id: i64,
+    pub weekday: i64,
+    pub note: String,
+    pub month: Option<String>,
+}
+
+#[derive(FromForm)]
+pub struct RecommendationWeightsForm {
+    pub weights: HashMap<String, i64>,
+}
+
+#[derive(FromForm)]
+pub struct SchedulePlanForm {
+    pub from: String,
+    pub until: String,
+    pub assignments: HashMap<i64, i64>,
+}
+
+#[derive(FromForm)]
+pub struct PayrollPolicyForm {
+    pub daily_overtime_hours: Option<f64>,
+    pub weekly_overtime_hours: Option<f64>,
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PayrollPolicy {
+    pub daily_overtime_hours: Option<f64>,
+    pub weekly_overtime_hours: Option<f64>,
+}
+
+pub struct PayrollEntry {
+    pub user_id: i64,
+    pub user_email: String,
+    pub member_name: Option<String>,
+    pub deployment_id: i64,
+    pub client_name: String,
+    pub crew_name: String,
+    pub work_date: String,
+    pub hours_worked: f64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PayrollLine {
+    pub user_id: i64,
+    pub member_name: String,
+    pub user_email: String,
+    pub regular_hours: f64,
+    pub daily_overtime_hours: f64,
+    pub weekly_overtime_hours: f64,
+    pub overtime_hours: f64,
+    pub total_hours: f64,
+    pub days_worked: i64,
+    pub deployments: Vec<PayrollDeployment>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PayrollDeployment {
+    pub deployment_id: i64,
+    pub label: String,
+    pub hours: f64,
+}
+
+#[derive(FromForm)]
+pub struct WeekGridForm {
+    pub week: String,
+    pub cells: Vec<WeekGridCellForm>,
+    pub rows: Vec<WeekGridRowForm>,
+}
+
+#[derive(FromForm)]
+pub struct WeekGridCellForm {
+    pub deployment_id: i64,
+    pub work_date: String,
+    pub hours: String,
+}
+
+#[derive(FromForm)]
+pub struct WeekGridRowForm {
+    pub deployment_id: i64,
+    pub notes: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WeekGridRow {
+    pub deployment_id: i64,
+    pub label: String,
+    pub notes: String,
+    pub cells: Vec<WeekGridCell>,
+    pub total_hours: f64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WeekGridCell {
+    pub work_date: String,
+    pub input: String,
+    pub hours: Option<f64>,
+    pub update_id: Option<i64>,
+    pub approval_status: Option<String>,
+    pub is_placeholder: bool,
+    pub is_missing: bool,
+    pub is_editable: bool,
+    pub in_range: bool,
+}
+
+#[derive(Deserialize)]
+pub struct TrackingSyncRequest {
+    pub items: Vec<TrackingSyncItem>,
+}
+
+#[derive(Deserialize)]
+pub struct TrackingSyncItem {
+    pub client_uuid: String,
+    pub kind: String,
+    pub deployment_id: i64,
+    pub recorded_at: String,
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    #[serde(default)]
+    pub break_kind: String,
+    #[serde(default)]
+    pub work_date: String,
+    #[serde(default)]
+    pub start_time: String,
+    #[serde(default)]
+    pub end_time: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub breaks: Vec<UpdateBreakForm>,
+    #[serde(default)]
+    pub on_conflict: String,
+}
+
+#[derive(Serialize)]
+pub struct TrackingSyncResponse {
+    pub results: Vec<TrackingSyncResult>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TrackingSyncResult {
+    pub client_uuid: String,
+    pub kind: String,
+    pub status: String,
+    pub message: String,
+    pub record_id: Option<i64>,
+    pub duplicate: bool,
+}
+
+#[derive(FromForm)]
+pub struct TimerPolicyForm {
+    pub max_shift_hours: Option<f64>,
+    pub cap_at_deployment_end: bool,
+    pub reminder_minutes_before: Option<i64>,
+    pub notify_after_close: bool,
+    pub notify_lead: bool,
+}
+
+#[derive(FromForm)]
+pub struct ClientGeofenceForm {
+    pub geofence_mode: String,
+    pub geofence_radius_m: i64,
 }
 
 #[derive(FromForm)]
@@ -198,71 +553,554 @@ pub struct User {
     pub is_super_admin: bool,
 }
 
-pub struct AdminUser {
-    pub id: i64,
-    pub email: String,
+pub struct AdminUser {
+    pub id: i64,
+    pub email: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Workspace {
+    pub id: i64,
+    pub slug: String,
+    pub name: String,
+    pub app_name: String,
+    pub logo_path: String,
+    pub theme_key: String,
+    pub background_hue: i64,
+    pub body_font: String,
+    pub heading_font: String,
+    pub plan_key: String,
+    pub plan_started_at: String,
+    pub plan_expired: bool,
+    pub email_provider: String,
+    pub email_from_name: String,
+    pub email_from_address: String,
+    pub smtp_host: String,
+    pub smtp_port: String,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    pub smtp_encryption: String,
+    pub mailgun_domain: String,
+    pub mailgun_api_key: String,
+    pub postmark_server_token: String,
+    pub resend_api_key: String,
+    pub ses_access_key: String,
+    pub ses_secret_key: String,
+    pub ses_region: String,
+    pub sendmail_path: String,
+}
+
+pub struct UserAuth {
+    pub user: User,
+    pub password_hash: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Crew {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub name: String,
+    pub members_count: i64,
+    pub status: String,
+    pub gear_score: i64,
+    pub skill_tags: String,
+    pub compatibility_tags: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Asset {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub name: String,
+    pub asset_type: String,
+    pub serial_number: String,
+    pub purchase_date: Option<String>,
+    pub purchase_cost: f64,
+    pub condition: String,
+    pub location: String,
+    pub notes: String,
+    pub custom_fields: Vec<AssetCustomField>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AssetCustomField {
+    pub label: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AssetAssignment {
+    pub id: i64,
+    pub asset_id: i64,
+    pub asset_name: String,
+    pub asset_serial_number: String,
+    pub crew_id: Option<i64>,
+    pub crew_name: Option<String>,
+    pub deployment_id: Option<i64>,
+    pub deployment_client_name: Option<String>,
+    pub deployment_crew_name: Option<String>,
+    pub deployment_start_at: Option<String>,
+    pub deployment_end_at: Option<String>,
+    pub checked_out_at: String,
+    pub checked_out_by_email: String,
+    pub checkout_condition: String,
+    pub checkout_notes: String,
+    pub checked_in_at: Option<String>,
+    pub checked_in_by_email: Option<String>,
+    pub checkin_condition: String,
+    pub checkin_notes: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RequiredKitItem {
+    pub skill_tag: String,
+    pub asset_type: String,
+    pub quantity: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CrewHeldAsset {
+    pub crew_id: i64,
+    pub asset_id: i64,
+    pub name: String,
+    pub asset_type: String,
+    pub condition: String,
+    pub maintenance: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct GearRequirementView {
+    pub asset_type: String,
+    pub skill_tags: String,
+    pub required: i64,
+    pub held: i64,
+    pub coverage: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CrewGearView {
+    pub score: i64,
+    pub computed_score: i64,
+    pub override_score: Option<i64>,
+    pub override_reason: String,
+    pub requirements: Vec<GearRequirementView>,
+    pub held: Vec<CrewHeldAsset>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AssetRegisterView {
+    pub id: i64,
+    pub name: String,
+    pub asset_type: String,
+    pub serial_number: String,
+    pub condition: String,
+    pub location: String,
+    pub custody: Option<String>,
+    pub maintenance: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LabelItem {
+    pub id: i64,
+    pub title: String,
+    pub lines: Vec<String>,
+    pub url: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CustomFieldDefinition {
+    pub id: i64,
+    pub entity: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub is_required: bool,
+    pub position: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CustomFieldInput {
+    pub id: i64,
+    pub label: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub is_required: bool,
+    pub value: String,
+}
+
+#[derive(FromForm)]
+pub struct ImportUploadForm<'r> {
+    pub entity: String,
+    pub file: Option<TempFile<'r>>,
+}
+
+#[derive(FromForm)]
+pub struct ImportMappingForm {
+    pub mapping: HashMap<String, String>,
+}
+
+pub struct DataImport {
+    pub id: i64,
+    pub entity: String,
+    pub file_name: String,
+    pub headers: Vec<String>,
+    pub header_line: usize,
+    pub rows: Vec<Vec<String>>,
+    pub mapping: HashMap<String, usize>,
+    pub status: String,
+    pub imported_count: i64,
+    pub skipped_count: i64,
+    pub error_report: String,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DataImportSummary {
+    pub id: i64,
+    pub entity: String,
+    pub file_name: String,
+    pub row_count: i64,
+    pub status: String,
+    pub imported_count: i64,
+    pub skipped_count: i64,
+    pub created_at: String,
+}
+
+pub struct ClientImportRecord {
+    pub company_name: String,
+    pub address: String,
+    pub phone: String,
+    pub email: String,
+    pub latitude: String,
+    pub longitude: String,
+    pub stage: String,
+    pub currency: String,
+    pub portal_token: String,
+    pub custom: Vec<(i64, String)>,
+}
+
+pub struct ContactImportRecord {
+    pub client_id: i64,
+    pub name: String,
+    pub address: String,
+    pub email: String,
+    pub phone: String,
+    pub department: String,
+    pub position: String,
+    pub custom: Vec<(i64, String)>,
+}
+
+pub struct CrewMemberImportRecord {
+    pub crew_id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+    pub position: String,
+    pub availability_status: String,
+    pub hourly_cost: f64,
+    pub custom: Vec<(i64, String)>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ImportColumnView {
+    pub key: String,
+    pub label: String,
+    pub is_required: bool,
+    pub selected: Option<usize>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ImportRowView {
+    pub line: usize,
+    pub status: String,
+    pub message: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ImportPreviewView {
+    pub columns: Vec<String>,
+    pub rows: Vec<ImportRowView>,
+    pub total: usize,
+    pub ready: usize,
+    pub duplicates: usize,
+    pub errors: usize,
+    pub shown: usize,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WorkspaceExport {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub requested_by: i64,
+    pub origin: String,
+    pub status: String,
+    pub token: String,
+    pub file_name: String,
+    pub file_size: i64,
+    pub error: String,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CustomFieldColumns {
+    pub labels: Vec<String>,
+    pub rows: HashMap<String, Vec<String>>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CustomFieldValueView {
+    pub label: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MaintenancePlan {
+    pub id: i64,
+    pub asset_type: String,
+    pub name: String,
+    pub interval_days: Option<i64>,
+    pub interval_hours: Option<f64>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MaintenanceLog {
+    pub id: i64,
+    pub asset_id: i64,
+    pub plan_id: Option<i64>,
+    pub plan_name: Option<String>,
+    pub performed_at: String,
+    pub performed_by_email: String,
+    pub cost: f64,
+    pub notes: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MaintenanceStatusView {
+    pub plan_id: i64,
+    pub plan_name: String,
+    pub interval_label: String,
+    pub since: String,
+    pub last_service: Option<String>,
+    pub next_due_on: Option<String>,
+    pub usage_hours: f64,
+    pub hours_remaining: Option<f64>,
+    pub state: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AssetTargetOption {
+    pub value: String,
+    pub label: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AssetStats {
+    pub total_assets: i64,
+    pub total_value: f64,
+    pub needs_attention: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CrewMember {
+    pub id: i64,
+    pub crew_id: i64,
+    pub tenant_id: i64,
+    pub user_id: Option<i64>,
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+    pub position: String,
+    pub availability_status: String,
+    pub hourly_cost: f64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CrewCertification {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub crew_id: i64,
+    pub member_id: i64,
+    pub member_name: String,
+    pub member_email: String,
+    pub crew_name: String,
+    pub name: String,
+    pub skill_tag: String,
+    pub issuing_body: String,
+    pub certificate_number: String,
+    pub issued_on: Option<String>,
+    pub expires_on: Option<String>,
+    pub days_until_expiry: Option<i64>,
+    pub status: String,
+    pub document_name: Option<String>,
+    pub document_path: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LeaveRequest {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub crew_id: i64,
+    pub member_id: i64,
+    pub member_name: String,
+    pub member_email: String,
+    pub member_user_id: Option<i64>,
+    pub crew_name: String,
+    pub requested_by_user_id: i64,
+    pub requested_by_email: Option<String>,
+    pub start_date: String,
+    pub end_date: String,
+    pub leave_type: String,
+    pub reason: String,
+    pub status: String,
+    pub review_note: String,
+    pub reviewed_by_email: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RecommendationWeight {
+    pub key: String,
+    pub label: String,
+    pub description: String,
+    pub weight: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RecommendationFactor {
+    pub key: String,
+    pub label: String,
+    pub score: i64,
+    pub weight: i64,
+    pub points: f64,
+    pub detail: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CrewRecommendation {
+    pub id: i64,
+    pub name: String,
+    pub status: String,
+    pub score: i64,
+    pub factors: Vec<RecommendationFactor>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ScheduleDeployment {
+    pub id: i64,
+    pub client_id: i64,
+    pub client_name: String,
+    pub crew_id: i64,
+    pub crew_name: String,
+    pub start_at: String,
+    pub end_at: String,
+    pub status: String,
+    pub required_skills: String,
+    pub latitude: String,
+    pub longitude: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ScheduleCrewOption {
+    pub id: i64,
+    pub name: String,
+    pub note: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ScheduleProposal {
+    pub deployment_id: i64,
+    pub client_name: String,
+    pub start_at: String,
+    pub end_at: String,
+    pub required_skills: String,
+    pub current_crew_id: i64,
+    pub current_crew_name: String,
+    pub proposed_crew_id: i64,
+    pub proposed_crew_name: String,
+    pub travel_km: Option<f64>,
+    pub note: String,
+    pub options: Vec<ScheduleCrewOption>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LeaveRequestFormView {
+    pub member_id: i64,
+    pub start_date: String,
+    pub end_date: String,
+    pub leave_type: String,
+    pub reason: String,
 }
 
 #[derive(Serialize, Clone)]
-pub struct Workspace {
-    pub id: i64,
-    pub slug: String,
-    pub name: String,
-    pub app_name: String,
-    pub logo_path: String,
-    pub theme_key: String,
-    pub background_hue: i64,
-    pub body_font: String,
-    pub heading_font: String,
-    pub plan_key: String,
-    pub plan_started_at: String,
-    pub plan_expired: bool,
-    pub email_provider: String,
-    pub email_from_name: String,
-    pub email_from_address: String,
-    pub smtp_host: String,
-    pub smtp_port: String,
-    pub smtp_username: String,
-    pub smtp_password: String,
-    pub smtp_encryption: String,
-    pub mailgun_domain: String,
-    pub mailgun_api_key: String,
-    pub postmark_server_token: String,
-    pub resend_api_key: String,
-    pub ses_access_key: String,
-    pub ses_secret_key: String,
-    pub ses_region: String,
-    pub sendmail_path: String,
+pub struct AvailabilityDay {
+    pub date: String,
+    pub day: u32,
+    pub weekday: String,
+    pub is_weekend: bool,
+    pub is_today: bool,
+    pub holiday: Option<String>,
 }
 
-pub struct UserAuth {
-    pub user: User,
-    pub password_hash: String,
+#[derive(Serialize, Clone)]
+pub struct AvailabilityCell {
+    pub kind: String,
+    pub label: String,
 }
 
 #[derive(Serialize, Clone)]
-pub struct Crew {
+pub struct AvailabilityRow {
+    pub member_id: i64,
+    pub crew_id: i64,
+    pub member_name: String,
+    pub crew_name: String,
+    pub availability_status: String,
+    pub days_unavailable: i64,
+    pub cells: Vec<AvailabilityCell>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WorkspaceHoliday {
     pub id: i64,
-    pub tenant_id: i64,
+    pub holiday_date: String,
     pub name: String,
-    pub members_count: i64,
-    pub status: String,
-    pub gear_score: i64,
-    pub skill_tags: String,
-    pub compatibility_tags: String,
 }
 
 #[derive(Serialize, Clone)]
-pub struct CrewMember {
+pub struct RecurringUnavailability {
     pub id: i64,
     pub crew_id: i64,
-    pub tenant_id: i64,
-    pub user_id: Option<i64>,
+    pub member_id: i64,
+    pub member_name: String,
+    pub member_email: String,
+    pub member_user_id: Option<i64>,
+    pub weekday: i64,
+    pub note: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AvailabilityConflict {
+    pub member_name: String,
+    pub reason: String,
+    pub dates: String,
+    pub is_pending: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CrewCertificationFormView {
     pub name: String,
-    pub phone: String,
-    pub email: String,
-    pub position: String,
-    pub availability_status: String,
+    pub skill_tag: String,
+    pub issuing_body: String,
+    pub certificate_number: String,
+    pub issued_on: String,
+    pub expires_on: String,
 }
 
 #[derive(Serialize, Clone)]
@@ -278,6 +1116,8 @@ pub struct Client {
     pub stage: String,
     pub currency: String,
     pub portal_token: String,
+    pub geofence_mode: String,
+    pub geofence_radius_m: i64,
 }
 
 #[derive(Serialize, Clone)]
@@ -359,6 +1199,181 @@ pub struct Deployment {
     pub deployment_type: String,
     pub required_skills: String,
     pub compatibility_pref: String,
+    pub template_id: Option<i64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeploymentTemplate {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub name: String,
+    pub deployment_type: String,
+    pub required_skills: String,
+    pub compatibility_pref: String,
+    pub fee_per_hour: f64,
+    pub default_duration_hours: f64,
+    pub checklist: String,
+    pub info: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeploymentTask {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub deployment_id: i64,
+    pub position: i64,
+    pub title: String,
+    pub is_required: bool,
+    pub completed_at: Option<String>,
+    pub completed_by_user_id: Option<i64>,
+    pub completed_by_email: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeploymentExpense {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub deployment_id: i64,
+    pub client_name: String,
+    pub crew_name: String,
+    pub submitted_by_user_id: i64,
+    pub submitted_by_email: String,
+    pub category: String,
+    pub description: String,
+    pub amount: f64,
+    pub currency: String,
+    pub incurred_on: String,
+    pub receipt_path: Option<String>,
+    pub is_billable: bool,
+    pub markup_percent: f64,
+    pub billable_amount: f64,
+    pub status: String,
+    pub reviewed_by_email: Option<String>,
+    pub reviewed_at: Option<String>,
+    pub review_note: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeploymentIncident {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub deployment_id: i64,
+    pub client_name: String,
+    pub crew_name: String,
+    pub title: String,
+    pub description: String,
+    pub severity: String,
+    pub category: String,
+    pub status: String,
+    pub reported_by_user_id: Option<i64>,
+    pub reported_by_email: String,
+    pub assignee_user_id: Option<i64>,
+    pub assignee_email: Option<String>,
+    pub resolution_notes: String,
+    pub occurred_at: String,
+    pub resolved_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TimesheetEntry {
+    pub id: i64,
+    pub deployment_id: i64,
+    pub client_name: String,
+    pub crew_name: String,
+    pub user_id: i64,
+    pub user_email: String,
+    pub work_date: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub hours_worked: f64,
+    pub notes: String,
+    pub approval_status: String,
+    pub reviewed_by_email: Option<String>,
+    pub reviewed_at: Option<String>,
+    pub review_note: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TimesheetMember {
+    pub user_id: i64,
+    pub user_email: String,
+    pub member_name: Option<String>,
+    pub submitted_count: i64,
+    pub submitted_hours: f64,
+    pub approved_hours: f64,
+    pub rejected_hours: f64,
+}
+
+#[derive(Serialize, Clone, Copy, Default)]
+pub struct DeploymentTaskProgress {
+    pub total: i64,
+    pub completed: i64,
+    pub required_open: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeploymentTemplateUsage {
+    pub id: i64,
+    pub name: String,
+    pub deployment_type: String,
+    pub fee_per_hour: f64,
+    pub usage_count: i64,
+    pub scheduled_count: i64,
+    pub active_count: i64,
+    pub completed_count: i64,
+    pub cancelled_count: i64,
+    pub total_hours: f64,
+    pub last_used_at: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeploymentProfitability {
+    pub deployment_id: i64,
+    pub client_id: i64,
+    pub client_name: String,
+    pub crew_id: i64,
+    pub crew_name: String,
+    pub deployment_type: String,
+    pub status: String,
+    pub start_at: String,
+    pub currency: String,
+    pub fee_per_hour: f64,
+    pub total_hours: f64,
+    pub labour_cost: f64,
+    pub uncosted_hours: f64,
+    pub expense_cost: f64,
+    pub billable_expenses: f64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LabourCostLine {
+    pub user_id: Option<i64>,
+    pub member_name: Option<String>,
+    pub email: String,
+    pub hours: f64,
+    pub hourly_cost: f64,
+    pub cost: f64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProfitabilityLine {
+    pub key: String,
+    pub label: String,
+    pub detail: String,
+    pub currency: String,
+    pub deployment_count: i64,
+    pub hours: f64,
+    pub hours_revenue: f64,
+    pub expense_revenue: f64,
+    pub revenue: f64,
+    pub labour_cost: f64,
+    pub expense_cost: f64,
+    pub margin: f64,
+    pub margin_percent: Option<f64>,
+    pub uncosted_hours: f64,
 }
 
 #[derive(Serialize, Clone)]
@@ -394,6 +1409,36 @@ pub struct DeploymentUpdate {
     pub hours_worked: f64,
     pub notes: String,
     pub is_placeholder: bool,
+    pub approval_status: String,
+    pub review_note: String,
+    pub paid_break_minutes: i64,
+    pub unpaid_break_minutes: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct UpdateAttachment {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub update_id: i64,
+    pub deployment_id: i64,
+    pub user_id: Option<i64>,
+    pub original_name: String,
+    pub stored_name: String,
+    pub thumbnail_name: Option<String>,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub is_image: bool,
+    pub is_client_visible: bool,
+    pub work_date: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct UpdateSegment {
+    pub kind: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub minutes: i64,
 }
 
 #[derive(Serialize, Clone)]
@@ -404,6 +1449,68 @@ pub struct WorkTimer {
     pub user_id: i64,
     pub start_at: String,
     pub end_at: Option<String>,
+    pub paused_at: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WorkTimerBreak {
+    pub id: i64,
+    pub timer_id: i64,
+    pub kind: String,
+    pub start_at: String,
+    pub end_at: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TimerPolicy {
+    pub max_shift_hours: f64,
+    pub cap_at_deployment_end: bool,
+    pub reminder_minutes_before: i64,
+    pub notify_after_close: bool,
+    pub notify_lead: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct OpenTimer {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub deployment_id: i64,
+    pub user_id: i64,
+    pub user_email: String,
+    pub client_name: String,
+    pub start_at: String,
+    pub deployment_end_at: String,
+    pub reminder_sent_at: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TimerAutoClosure {
+    pub id: i64,
+    pub timer_id: i64,
+    pub deployment_id: i64,
+    pub client_name: String,
+    pub user_email: String,
+    pub start_at: String,
+    pub closed_at: String,
+    pub reason: String,
+    pub notified_emails: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WorkTimerLog {
+    pub id: i64,
+    pub user_id: i64,
+    pub user_email: String,
+    pub start_at: String,
+    pub end_at: Option<String>,
+    pub start_latitude: Option<f64>,
+    pub start_longitude: Option<f64>,
+    pub start_distance_m: Option<f64>,
+    pub stop_latitude: Option<f64>,
+    pub stop_longitude: Option<f64>,
+    pub stop_distance_m: Option<f64>,
+    pub geofence_flag: String,
 }
 
 #[derive(Serialize, Clone)]
@@ -434,6 +1541,7 @@ pub struct InvoiceSummary {
     pub end_at: String,
     pub fee_per_hour: f64,
     pub total_hours: f64,
+    pub expense_total: f64,
 }
 
 #[derive(Serialize, Clone)]
@@ -446,6 +1554,7 @@ pub struct InvoiceCandidate {
     pub fee_per_hour: f64,
     pub client_currency: String,
     pub total_hours: f64,
+    pub expense_total: f64,
 }
 
 #[derive(Serialize, Clone)]
@@ -497,10 +1606,42 @@ pub struct CrewFormView {
     pub name: String,
     pub status: String,
     pub gear_score: i64,
+    pub gear_override: bool,
+    pub gear_override_reason: String,
     pub skill_tags: String,
     pub compatibility_tags: String,
 }
 
+#[derive(Serialize, Clone)]
+pub struct CustomFieldFormView {
+    pub entity: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: String,
+    pub is_required: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct MaintenancePlanFormView {
+    pub asset_type: String,
+    pub name: String,
+    pub interval_days: String,
+    pub interval_hours: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AssetFormView {
+    pub name: String,
+    pub asset_type: String,
+    pub serial_number: String,
+    pub purchase_date: String,
+    pub purchase_cost: f64,
+    pub condition: String,
+    pub location: String,
+    pub notes: String,
+    pub custom_fields: String,
+}
+
 #[derive(Serialize, Clone)]
 pub struct CrewMemberFormView {
     pub user_id: i64,
@@ -508,6 +1649,7 @@ pub struct CrewMemberFormView {
     pub phone: String,
     pub position: String,
     pub availability_status: String,
+    pub hourly_cost: Option<f64>,
 }
 
 #[derive(Serialize, Clone)]
@@ -569,6 +1711,19 @@ pub struct DeploymentFormView {
     pub deployment_type: String,
     pub required_skills: String,
     pub compatibility_pref: String,
+    pub template_id: Option<i64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeploymentTemplateFormView {
+    pub name: String,
+    pub deployment_type: String,
+    pub required_skills: String,
+    pub compatibility_pref: String,
+    pub fee_per_hour: f64,
+    pub default_duration_hours: f64,
+    pub checklist: String,
+    pub info: String,
 }
 
 #[derive(Serialize, Clone)]
@@ -578,6 +1733,7 @@ pub struct DeploymentUpdateFormView {
     pub start_time: String,
     pub end_time: String,
     pub notes: String,
+    pub breaks: Vec<UpdateSegment>,
 }
 
 #[derive(Serialize, Clone)]
@@ -813,10 +1969,18 @@ impl CrewFormView {
             name: name.into(),
             status: status.into(),
             gear_score,
+            gear_override: false,
+            gear_override_reason: String::new(),
             skill_tags: skill_tags.into(),
             compatibility_tags: compatibility_tags.into(),
         }
     }
+
+    pub fn with_gear_override(mut self, enabled: bool, reason: impl Into<String>) -> Self {
+        self.gear_override = enabled;
+        self.gear_override_reason = reason.into();
+        self
+    }
 }
 
 impl CrewMemberFormView {
@@ -826,6 +1990,7 @@ impl CrewMemberFormView {
         phone: impl Into<String>,
         position: impl Into<String>,
         availability_status: impl Into<String>,
+        hourly_cost: Option<f64>,
     ) -> Self {
         CrewMemberFormView {
             user_id,
@@ -833,6 +1998,7 @@ impl CrewMemberFormView {
             phone: phone.into(),
             position: position.into(),
             availability_status: availability_status.into(),
+            hourly_cost,
         }
     }
 }
@@ -918,6 +2084,7 @@ impl DeploymentFormView {
         deployment_type: impl Into<String>,
         required_skills: impl Into<String>,
         compatibility_pref: impl Into<String>,
+        template_id: Option<i64>,
     ) -> Self {
         DeploymentFormView {
             client_id,
@@ -930,6 +2097,31 @@ impl DeploymentFormView {
             deployment_type: deployment_type.into(),
             required_skills: required_skills.into(),
             compatibility_pref: compatibility_pref.into(),
+            template_id,
+        }
+    }
+}
+
+impl DeploymentTemplateFormView {
+    pub fn new(
+        name: impl Into<String>,
+        deployment_type: impl Into<String>,
+        required_skills: impl Into<String>,
+        compatibility_pref: impl Into<String>,
+        fee_per_hour: f64,
+        default_duration_hours: f64,
+        checklist: impl Into<String>,
+        info: impl Into<String>,
+    ) -> Self {
+        DeploymentTemplateFormView {
+            name: name.into(),
+            deployment_type: deployment_type.into(),
+            required_skills: required_skills.into(),
+            compatibility_pref: compatibility_pref.into(),
+            fee_per_hour,
+            default_duration_hours,
+            checklist: checklist.into(),
+            info: info.into(),
         }
     }
 }
@@ -948,8 +2140,14 @@ impl DeploymentUpdateFormView {
             start_time: start_time.into(),
             end_time: end_time.into(),
             notes: notes.into(),
+            breaks: Vec::new(),
         }
     }
+
+    pub fn with_breaks(mut self, breaks: Vec<UpdateSegment>) -> Self {
+        self.breaks = breaks;
+        self
+    }
 }
 
 impl InvoiceFormView {
@@ -0,0 +1,228 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::AssetAssignment;
+use crate::Db;
+
+const ASSIGNMENT_SELECT: &str = r#"
+    SELECT
+        asset_assignments.id as id,
+        asset_assignments.asset_id as asset_id,
+        assets.name as asset_name,
+        assets.serial_number as asset_serial_number,
+        asset_assignments.crew_id as crew_id,
+        crews.name as crew_name,
+        asset_assignments.deployment_id as deployment_id,
+        clients.company_name as deployment_client_name,
+        deployment_crews.name as deployment_crew_name,
+        deployments.start_at as deployment_start_at,
+        deployments.end_at as deployment_end_at,
+        asset_assignments.checked_out_at as checked_out_at,
+        COALESCE(out_users.email, '') as checked_out_by_email,
+        asset_assignments.checkout_condition as checkout_condition,
+        asset_assignments.checkout_notes as checkout_notes,
+        asset_assignments.checked_in_at as checked_in_at,
+        in_users.email as checked_in_by_email,
+        asset_assignments.checkin_condition as checkin_condition,
+        asset_assignments.checkin_notes as checkin_notes
+    FROM asset_assignments
+    JOIN assets
+        ON assets.id = asset_assignments.asset_id
+        AND assets.tenant_id = asset_assignments.tenant_id
+    LEFT JOIN crews
+        ON crews.id = asset_assignments.crew_id
+        AND crews.tenant_id = asset_assignments.tenant_id
+    LEFT JOIN deployments
+        ON deployments.id = asset_assignments.deployment_id
+        AND deployments.tenant_id = asset_assignments.tenant_id
+    LEFT JOIN clients ON clients.id = deployments.client_id
+    LEFT JOIN crews deployment_crews ON deployment_crews.id = deployments.crew_id
+    LEFT JOIN users out_users ON out_users.id = asset_assignments.checked_out_by
+    LEFT JOIN users in_users ON in_users.id = asset_assignments.checked_in_by
+"#;
+
+fn map_assignment(row: sqlx::sqlite::SqliteRow) -> AssetAssignment {
+    AssetAssignment {
+        id: row.get("id"),
+        asset_id: row.get("asset_id"),
+        asset_name: row.get("asset_name"),
+        asset_serial_number: row.get("asset_serial_number"),
+        crew_id: row.get("crew_id"),
+        crew_name: row.get("crew_name"),
+        deployment_id: row.get("deployment_id"),
+        deployment_client_name: row.get("deployment_client_name"),
+        deployment_crew_name: row.get("deployment_crew_name"),
+        deployment_start_at: row.get("deployment_start_at"),
+        deployment_end_at: row.get("deployment_end_at"),
+        checked_out_at: row.get("checked_out_at"),
+        checked_out_by_email: row.get("checked_out_by_email"),
+        checkout_condition: row.get("checkout_condition"),
+        checkout_notes: row.get("checkout_notes"),
+        checked_in_at: row.get("checked_in_at"),
+        checked_in_by_email: row.get("checked_in_by_email"),
+        checkin_condition: row.get("checkin_condition"),
+        checkin_notes: row.get("checkin_notes"),
+    }
+}
+
+fn asset_history_query() -> String {
+    format!(
+        r#"{ASSIGNMENT_SELECT}
+        WHERE asset_assignments.tenant_id = ? AND asset_assignments.asset_id = ?
+        ORDER BY asset_assignments.checked_out_at DESC, asset_assignments.id DESC"#
+    )
+}
+
+pub async fn list_assignments_for_asset(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+) -> Result<Vec<AssetAssignment>, sqlx::Error> {
+    let rows = sqlx::query(&asset_history_query())
+        .bind(tenant_id)
+        .bind(asset_id)
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(map_assignment).collect())
+}
+
+pub async fn list_assignments_for_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<AssetAssignment>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        r#"{ASSIGNMENT_SELECT}
+        WHERE asset_assignments.tenant_id = ? AND asset_assignments.deployment_id = ?
+        ORDER BY assets.name ASC, asset_assignments.checked_out_at DESC"#
+    ))
+    .bind(tenant_id)
+    .bind(deployment_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_assignment).collect())
+}
+
+pub async fn list_open_assignments(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<AssetAssignment>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        r#"{ASSIGNMENT_SELECT}
+        WHERE asset_assignments.tenant_id = ? AND asset_assignments.checked_in_at IS NULL
+        ORDER BY asset_assignments.checked_out_at ASC, asset_assignments.id ASC"#
+    ))
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_assignment).collect())
+}
+
+pub async fn find_assignment(
+    db: &Db,
+    tenant_id: i64,
+    assignment_id: i64,
+) -> Result<Option<AssetAssignment>, sqlx::Error> {
+    let row = sqlx::query(&format!(
+        "{ASSIGNMENT_SELECT} WHERE asset_assignments.tenant_id = ? AND asset_assignments.id = ?"
+    ))
+    .bind(tenant_id)
+    .bind(assignment_id)
+    .fetch_optional(&db.0)
+    .await?;
+    Ok(row.map(map_assignment))
+}
+
+/// Checks the asset out unless `conflicts` matches one of its existing
+/// assignments, in which case nothing is written and that assignment is
+/// returned. The asset row is touched first so the transaction holds SQLite's
+/// write lock before reading the history: a concurrent check-out of the same
+/// asset waits for this one to commit and then sees it.
+pub async fn create_assignment_unless_conflicting(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+    crew_id: Option<i64>,
+    deployment_id: Option<i64>,
+    checked_out_at: &str,
+    checked_out_by: i64,
+    condition: &str,
+    notes: &str,
+    conflicts: impl Fn(&AssetAssignment) -> bool,
+) -> Result<Option<AssetAssignment>, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("UPDATE assets SET id = id WHERE tenant_id = ? AND id = ?")
+        .bind(tenant_id)
+        .bind(asset_id)
+        .execute(&mut *tx)
+        .await?;
+    let rows = sqlx::query(&asset_history_query())
+        .bind(tenant_id)
+        .bind(asset_id)
+        .fetch_all(&mut *tx)
+        .await?;
+    if let Some(conflict) = rows.into_iter().map(map_assignment).find(|assignment| conflicts(assignment)) {
+        return Ok(Some(conflict));
+    }
+    sqlx::query(
+        r#"
+        INSERT INTO asset_assignments (
+            tenant_id, asset_id, crew_id, deployment_id, checked_out_at, checked_out_by,
+            checkout_condition, checkout_notes
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(asset_id)
+    .bind(crew_id)
+    .bind(deployment_id)
+    .bind(checked_out_at)
+    .bind(checked_out_by)
+    .bind(condition)
+    .bind(notes)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(None)
+}
+
+pub async fn check_in_assignment(
+    db: &Db,
+    tenant_id: i64,
+    assignment_id: i64,
+    asset_id: i64,
+    checked_in_at: &str,
+    checked_in_by: i64,
+    condition: &str,
+    notes: &str,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE asset_assignments
+        SET checked_in_at = ?,
+            checked_in_by = ?,
+            checkin_condition = ?,
+            checkin_notes = ?
+        WHERE id = ? AND tenant_id = ? AND checked_in_at IS NULL
+        "#,
+    )
+    .bind(checked_in_at)
+    .bind(checked_in_by)
+    .bind(condition)
+    .bind(notes)
+    .bind(assignment_id)
+    .bind(tenant_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE assets SET condition = ?, updated_at = datetime('now') WHERE id = ? AND tenant_id = ?",
+    )
+    .bind(condition)
+    .bind(asset_id)
+    .bind(tenant_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{Asset, AssetCustomField, AssetStats};
+use crate::Db;
+
+const ASSET_SELECT: &str = r#"
+    SELECT
+        id,
+        tenant_id,
+        name,
+        asset_type,
+        serial_number,
+        purchase_date,
+        purchase_cost,
+        condition,
+        location,
+        notes,
+        custom_fields,
+        created_at
+    FROM assets
+"#;
+
+fn map_asset(row: sqlx::sqlite::SqliteRow) -> Asset {
+    let custom_fields: String = row.get("custom_fields");
+    Asset {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        name: row.get("name"),
+        asset_type: row.get("asset_type"),
+        serial_number: row.get("serial_number"),
+        purchase_date: row.get("purchase_date"),
+        purchase_cost: row.get("purchase_cost"),
+        condition: row.get("condition"),
+        location: row.get("location"),
+        notes: row.get("notes"),
+        custom_fields: serde_json::from_str::<Vec<AssetCustomField>>(&custom_fields)
+            .unwrap_or_default(),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub async fn list_assets_paged(
+    db: &Db,
+    tenant_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Asset>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{ASSET_SELECT} WHERE tenant_id = ? ORDER BY name ASC, id ASC LIMIT ? OFFSET ?"
+    ))
+    .bind(tenant_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_asset).collect())
+}
+
+pub async fn list_assets(db: &Db, tenant_id: i64) -> Result<Vec<Asset>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{ASSET_SELECT} WHERE tenant_id = ? ORDER BY name ASC, id ASC"
+    ))
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_asset).collect())
+}
+
+pub async fn count_assets(db: &Db, tenant_id: i64) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query("SELECT COUNT(*) as count FROM assets WHERE tenant_id = ?")
+        .bind(tenant_id)
+        .fetch_one(&db.0)
+        .await?;
+    Ok(row.get("count"))
+}
+
+pub async fn asset_stats(
+    db: &Db,
+    tenant_id: i64,
+    attention_conditions: &[&str],
+) -> Result<AssetStats, sqlx::Error> {
+    let placeholders = attention_conditions
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        r#"
+        SELECT
+            COUNT(*) as total_assets,
+            COALESCE(SUM(purchase_cost), 0) as total_value,
+            COALESCE(SUM(CASE WHEN condition IN ({placeholders}) THEN 1 ELSE 0 END), 0) as needs_attention
+        FROM assets
+        WHERE tenant_id = ?
+        "#
+    );
+    let mut query = sqlx::query(&sql);
+    for condition in attention_conditions {
+        query = query.bind(*condition);
+    }
+    let row = query.bind(tenant_id).fetch_one(&db.0).await?;
+    Ok(AssetStats {
+        total_assets: row.get("total_assets"),
+        total_value: row.get("total_value"),
+        needs_attention: row.get("needs_attention"),
+    })
+}
+
+pub async fn find_asset_by_id(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+) -> Result<Option<Asset>, sqlx::Error> {
+    let row = sqlx::query(&format!("{ASSET_SELECT} WHERE tenant_id = ? AND id = ?"))
+        .bind(tenant_id)
+        .bind(asset_id)
+        .fetch_optional(&db.0)
+        .await?;
+    Ok(row.map(map_asset))
+}
+
+pub async fn serial_number_taken(
+    db: &Db,
+    tenant_id: i64,
+    serial_number: &str,
+    exclude_asset_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COUNT(*) as count FROM assets WHERE tenant_id = ? AND serial_number = ? COLLATE NOCASE AND id != ?",
+    )
+    .bind(tenant_id)
+    .bind(serial_number)
+    .bind(exclude_asset_id)
+    .fetch_one(&db.0)
+    .await?;
+    let count: i64 = row.get("count");
+    Ok(count > 0)
+}
+
+pub async fn create_asset(
+    db: &Db,
+    tenant_id: i64,
+    name: &str,
+    asset_type: &str,
+    serial_number: &str,
+    purchase_date: Option<&str>,
+    purchase_cost: f64,
+    condition: &str,
+    location: &str,
+    notes: &str,
+    custom_fields: &str,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO assets (
+            tenant_id, name, asset_type, serial_number, purchase_date, purchase_cost,
+            condition, location, notes, custom_fields
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(name)
+    .bind(asset_type)
+    .bind(serial_number)
+    .bind(purchase_date)
+    .bind(purchase_cost)
+    .bind(condition)
+    .bind(location)
+    .bind(notes)
+    .bind(custom_fields)
+    .execute(&db.0)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn update_asset(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+    name: &str,
+    asset_type: &str,
+    serial_number: &str,
+    purchase_date: Option<&str>,
+    purchase_cost: f64,
+    condition: &str,
+    location: &str,
+    notes: &str,
+    custom_fields: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE assets
+        SET name = ?,
+            asset_type = ?,
+            serial_number = ?,
+            purchase_date = ?,
+            purchase_cost = ?,
+            condition = ?,
+            location = ?,
+            notes = ?,
+            custom_fields = ?,
+            updated_at = datetime('now')
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(name)
+    .bind(asset_type)
+    .bind(serial_number)
+    .bind(purchase_date)
+    .bind(purchase_cost)
+    .bind(condition)
+    .bind(location)
+    .bind(notes)
+    .bind(custom_fields)
+    .bind(asset_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_asset(db: &Db, tenant_id: i64, asset_id: i64) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("DELETE FROM maintenance_reminders WHERE asset_id = ? AND tenant_id = ?")
+        .bind(asset_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM maintenance_logs WHERE asset_id = ? AND tenant_id = ?")
+        .bind(asset_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM asset_assignments WHERE asset_id = ? AND tenant_id = ?")
+        .bind(asset_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM assets WHERE id = ? AND tenant_id = ?")
+        .bind(asset_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
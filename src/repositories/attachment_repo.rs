@@ -0,0 +1,229 @@
+use rocket_db_pools::sqlx::{self, sqlite::SqliteRow, Row};
+
+use crate::models::UpdateAttachment;
+use crate::Db;
+
+const ATTACHMENT_COLUMNS: &str = r#"
+    deployment_update_attachments.id as id,
+    deployment_update_attachments.tenant_id as tenant_id,
+    deployment_update_attachments.update_id as update_id,
+    deployment_update_attachments.deployment_id as deployment_id,
+    deployment_update_attachments.user_id as user_id,
+    deployment_update_attachments.original_name as original_name,
+    deployment_update_attachments.stored_name as stored_name,
+    deployment_update_attachments.thumbnail_name as thumbnail_name,
+    deployment_update_attachments.content_type as content_type,
+    deployment_update_attachments.size_bytes as size_bytes,
+    deployment_update_attachments.is_client_visible as is_client_visible,
+    deployment_updates.work_date as work_date,
+    deployment_update_attachments.created_at as created_at
+"#;
+
+fn map_attachment(row: SqliteRow) -> UpdateAttachment {
+    let content_type: String = row.get("content_type");
+    UpdateAttachment {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        update_id: row.get("update_id"),
+        deployment_id: row.get("deployment_id"),
+        user_id: row.get("user_id"),
+        original_name: row.get("original_name"),
+        stored_name: row.get("stored_name"),
+        thumbnail_name: row.get("thumbnail_name"),
+        is_image: content_type.starts_with("image/"),
+        content_type,
+        size_bytes: row.get("size_bytes"),
+        is_client_visible: row.get::<i64, _>("is_client_visible") != 0,
+        work_date: row.get("work_date"),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub async fn create_attachment(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    original_name: &str,
+    stored_name: &str,
+    thumbnail_name: Option<&str>,
+    content_type: &str,
+    size_bytes: i64,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO deployment_update_attachments
+            (tenant_id, update_id, deployment_id, user_id, original_name, stored_name, thumbnail_name, content_type, size_bytes)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(update_id)
+    .bind(deployment_id)
+    .bind(user_id)
+    .bind(original_name)
+    .bind(stored_name)
+    .bind(thumbnail_name)
+    .bind(content_type)
+    .bind(size_bytes)
+    .execute(&db.0)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn find_attachment_by_id(
+    db: &Db,
+    tenant_id: i64,
+    attachment_id: i64,
+) -> Result<Option<UpdateAttachment>, sqlx::Error> {
+    let sql = format!(
+        r#"
+        SELECT {ATTACHMENT_COLUMNS}
+        FROM deployment_update_attachments
+        JOIN deployment_updates ON deployment_updates.id = deployment_update_attachments.update_id
+        WHERE deployment_update_attachments.id = ? AND deployment_update_attachments.tenant_id = ?
+        "#
+    );
+    let row = sqlx::query(&sql)
+        .bind(attachment_id)
+        .bind(tenant_id)
+        .fetch_optional(&db.0)
+        .await?;
+    Ok(row.map(map_attachment))
+}
+
+pub async fn list_attachments_for_update(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+) -> Result<Vec<UpdateAttachment>, sqlx::Error> {
+    list_attachments_for_updates(db, tenant_id, &[update_id]).await
+}
+
+pub async fn list_attachments_for_updates(
+    db: &Db,
+    tenant_id: i64,
+    update_ids: &[i64],
+) -> Result<Vec<UpdateAttachment>, sqlx::Error> {
+    if update_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = update_ids
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        r#"
+        SELECT {ATTACHMENT_COLUMNS}
+        FROM deployment_update_attachments
+        JOIN deployment_updates ON deployment_updates.id = deployment_update_attachments.update_id
+        WHERE deployment_update_attachments.tenant_id = ?
+          AND deployment_update_attachments.update_id IN ({placeholders})
+        ORDER BY deployment_update_attachments.id ASC
+        "#
+    );
+    let mut query = sqlx::query(&sql).bind(tenant_id);
+    for update_id in update_ids {
+        query = query.bind(update_id);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+    Ok(rows.into_iter().map(map_attachment).collect())
+}
+
+pub async fn list_approved_attachments_for_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<UpdateAttachment>, sqlx::Error> {
+    let sql = format!(
+        r#"
+        SELECT {ATTACHMENT_COLUMNS}
+        FROM deployment_update_attachments
+        JOIN deployment_updates ON deployment_updates.id = deployment_update_attachments.update_id
+        WHERE deployment_update_attachments.tenant_id = ?
+          AND deployment_update_attachments.deployment_id = ?
+          AND deployment_updates.approval_status = 'Approved'
+        ORDER BY deployment_updates.work_date ASC, deployment_update_attachments.id ASC
+        "#
+    );
+    let rows = sqlx::query(&sql)
+        .bind(tenant_id)
+        .bind(deployment_id)
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(map_attachment).collect())
+}
+
+pub async fn list_client_visible_attachments_for_deployments(
+    db: &Db,
+    tenant_id: i64,
+    deployment_ids: &[i64],
+) -> Result<Vec<UpdateAttachment>, sqlx::Error> {
+    if deployment_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = deployment_ids
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        r#"
+        SELECT {ATTACHMENT_COLUMNS}
+        FROM deployment_update_attachments
+        JOIN deployment_updates ON deployment_updates.id = deployment_update_attachments.update_id
+        WHERE deployment_update_attachments.tenant_id = ?
+          AND deployment_update_attachments.is_client_visible = 1
+          AND deployment_update_attachments.deployment_id IN ({placeholders})
+        ORDER BY deployment_updates.work_date DESC, deployment_update_attachments.id ASC
+        "#
+    );
+    let mut query = sqlx::query(&sql).bind(tenant_id);
+    for deployment_id in deployment_ids {
+        query = query.bind(deployment_id);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+    Ok(rows.into_iter().map(map_attachment).collect())
+}
+
+pub async fn set_client_visible(
+    db: &Db,
+    tenant_id: i64,
+    attachment_id: i64,
+    is_client_visible: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE deployment_update_attachments SET is_client_visible = ? WHERE id = ? AND tenant_id = ?",
+    )
+    .bind(if is_client_visible { 1 } else { 0 })
+    .bind(attachment_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_attachment(
+    db: &Db,
+    tenant_id: i64,
+    attachment_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM deployment_update_attachments WHERE id = ? AND tenant_id = ?")
+        .bind(attachment_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn total_size_bytes(db: &Db, tenant_id: i64) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COALESCE(SUM(size_bytes), 0) as total FROM deployment_update_attachments WHERE tenant_id = ?",
+    )
+    .bind(tenant_id)
+    .fetch_one(&db.0)
+    .await?;
+    Ok(row.get("total"))
+}
@@ -0,0 +1,338 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{LeaveRequest, RecurringUnavailability, WorkspaceHoliday};
+use crate::Db;
+
+const LEAVE_SELECT: &str = r#"
+    SELECT
+        member_leave_requests.id as id,
+        member_leave_requests.tenant_id as tenant_id,
+        member_leave_requests.crew_id as crew_id,
+        member_leave_requests.member_id as member_id,
+        crew_members.name as member_name,
+        crew_members.email as member_email,
+        crew_members.user_id as member_user_id,
+        crews.name as crew_name,
+        member_leave_requests.requested_by_user_id as requested_by_user_id,
+        requesters.email as requested_by_email,
+        member_leave_requests.start_date as start_date,
+        member_leave_requests.end_date as end_date,
+        member_leave_requests.leave_type as leave_type,
+        member_leave_requests.reason as reason,
+        member_leave_requests.status as status,
+        member_leave_requests.review_note as review_note,
+        reviewers.email as reviewed_by_email,
+        member_leave_requests.created_at as created_at
+    FROM member_leave_requests
+    JOIN crew_members ON crew_members.id = member_leave_requests.member_id
+    JOIN crews ON crews.id = member_leave_requests.crew_id
+    LEFT JOIN users requesters ON requesters.id = member_leave_requests.requested_by_user_id
+    LEFT JOIN users reviewers ON reviewers.id = member_leave_requests.reviewed_by_user_id
+"#;
+
+const RECURRING_SELECT: &str = r#"
+    SELECT
+        member_recurring_unavailability.id as id,
+        member_recurring_unavailability.crew_id as crew_id,
+        member_recurring_unavailability.member_id as member_id,
+        crew_members.name as member_name,
+        crew_members.email as member_email,
+        crew_members.user_id as member_user_id,
+        member_recurring_unavailability.weekday as weekday,
+        member_recurring_unavailability.note as note
+    FROM member_recurring_unavailability
+    JOIN crew_members ON crew_members.id = member_recurring_unavailability.member_id
+"#;
+
+fn map_leave(row: sqlx::sqlite::SqliteRow) -> LeaveRequest {
+    LeaveRequest {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        crew_id: row.get("crew_id"),
+        member_id: row.get("member_id"),
+        member_name: row.get("member_name"),
+        member_email: row.get("member_email"),
+        member_user_id: row.get("member_user_id"),
+        crew_name: row.get("crew_name"),
+        requested_by_user_id: row.get("requested_by_user_id"),
+        requested_by_email: row.get("requested_by_email"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        leave_type: row.get("leave_type"),
+        reason: row.get("reason"),
+        status: row.get("status"),
+        review_note: row.get("review_note"),
+        reviewed_by_email: row.get("reviewed_by_email"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn map_recurring(row: sqlx::sqlite::SqliteRow) -> RecurringUnavailability {
+    RecurringUnavailability {
+        id: row.get("id"),
+        crew_id: row.get("crew_id"),
+        member_id: row.get("member_id"),
+        member_name: row.get("member_name"),
+        member_email: row.get("member_email"),
+        member_user_id: row.get("member_user_id"),
+        weekday: row.get("weekday"),
+        note: row.get("note"),
+    }
+}
+
+pub async fn list_leave_overlapping(
+    db: &Db,
+    tenant_id: i64,
+    from: &str,
+    until: &str,
+    statuses: &[&str],
+) -> Result<Vec<LeaveRequest>, sqlx::Error> {
+    if statuses.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = statuses.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+    let sql = format!(
+        "{LEAVE_SELECT} WHERE member_leave_requests.tenant_id = ? AND member_leave_requests.start_date <= ? AND member_leave_requests.end_date >= ? AND member_leave_requests.status IN ({placeholders}) ORDER BY member_leave_requests.start_date ASC"
+    );
+    let mut query = sqlx::query(&sql).bind(tenant_id).bind(until).bind(from);
+    for status in statuses {
+        query = query.bind(*status);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+    Ok(rows.into_iter().map(map_leave).collect())
+}
+
+pub async fn list_recent_leave_requests(
+    db: &Db,
+    tenant_id: i64,
+    limit: i64,
+) -> Result<Vec<LeaveRequest>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{LEAVE_SELECT} WHERE member_leave_requests.tenant_id = ? ORDER BY member_leave_requests.status != 'Pending', member_leave_requests.created_at DESC, member_leave_requests.id DESC LIMIT ?"
+    ))
+    .bind(tenant_id)
+    .bind(limit)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_leave).collect())
+}
+
+pub async fn find_leave_request(
+    db: &Db,
+    tenant_id: i64,
+    request_id: i64,
+) -> Result<Option<LeaveRequest>, sqlx::Error> {
+    let row = sqlx::query(&format!(
+        "{LEAVE_SELECT} WHERE member_leave_requests.id = ? AND member_leave_requests.tenant_id = ?"
+    ))
+    .bind(request_id)
+    .bind(tenant_id)
+    .fetch_optional(&db.0)
+    .await?;
+    Ok(row.map(map_leave))
+}
+
+pub async fn create_leave_request(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    member_id: i64,
+    requested_by_user_id: i64,
+    start_date: &str,
+    end_date: &str,
+    leave_type: &str,
+    reason: &str,
+    status: &str,
+    reviewed_by_user_id: Option<i64>,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO member_leave_requests
+            (tenant_id, crew_id, member_id, requested_by_user_id, start_date, end_date, leave_type, reason, status,
+             reviewed_by_user_id, reviewed_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CASE WHEN ? IS NULL THEN NULL ELSE datetime('now') END)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(crew_id)
+    .bind(member_id)
+    .bind(requested_by_user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(leave_type)
+    .bind(reason)
+    .bind(status)
+    .bind(reviewed_by_user_id)
+    .bind(reviewed_by_user_id)
+    .execute(&db.0)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn update_leave_status(
+    db: &Db,
+    tenant_id: i64,
+    request_id: i64,
+    status: &str,
+    reviewed_by_user_id: Option<i64>,
+    review_note: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE member_leave_requests
+        SET status = ?, reviewed_by_user_id = COALESCE(?, reviewed_by_user_id), review_note = ?,
+            reviewed_at = datetime('now')
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(status)
+    .bind(reviewed_by_user_id)
+    .bind(review_note)
+    .bind(request_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_holidays(
+    db: &Db,
+    tenant_id: i64,
+    from: &str,
+    until: &str,
+) -> Result<Vec<WorkspaceHoliday>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, holiday_date, name
+        FROM workspace_holidays
+        WHERE tenant_id = ? AND holiday_date >= ? AND holiday_date <= ?
+        ORDER BY holiday_date ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(from)
+    .bind(until)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| WorkspaceHoliday {
+            id: row.get("id"),
+            holiday_date: row.get("holiday_date"),
+            name: row.get("name"),
+        })
+        .collect())
+}
+
+pub async fn save_holiday(
+    db: &Db,
+    tenant_id: i64,
+    holiday_date: &str,
+    name: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO workspace_holidays (tenant_id, holiday_date, name)
+        VALUES (?, ?, ?)
+        ON CONFLICT(tenant_id, holiday_date) DO UPDATE SET name = excluded.name
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(holiday_date)
+    .bind(name)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_holiday(db: &Db, tenant_id: i64, holiday_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM workspace_holidays WHERE id = ? AND tenant_id = ?")
+        .bind(holiday_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn list_recurring(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<RecurringUnavailability>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{RECURRING_SELECT} WHERE member_recurring_unavailability.tenant_id = ? ORDER BY crew_members.name ASC, member_recurring_unavailability.weekday ASC"
+    ))
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_recurring).collect())
+}
+
+pub async fn save_recurring(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    member_id: i64,
+    weekday: i64,
+    note: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO member_recurring_unavailability (tenant_id, crew_id, member_id, weekday, note)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(member_id, weekday) DO UPDATE SET note = excluded.note
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(crew_id)
+    .bind(member_id)
+    .bind(weekday)
+    .bind(note)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_recurring(db: &Db, tenant_id: i64, recurring_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM member_recurring_unavailability WHERE id = ? AND tenant_id = ?")
+        .bind(recurring_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_availability_for_member(
+    db: &Db,
+    tenant_id: i64,
+    member_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM member_leave_requests WHERE member_id = ? AND tenant_id = ?")
+        .bind(member_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    sqlx::query("DELETE FROM member_recurring_unavailability WHERE member_id = ? AND tenant_id = ?")
+        .bind(member_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_availability_for_crew(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM member_leave_requests WHERE crew_id = ? AND tenant_id = ?")
+        .bind(crew_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    sqlx::query("DELETE FROM member_recurring_unavailability WHERE crew_id = ? AND tenant_id = ?")
+        .bind(crew_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
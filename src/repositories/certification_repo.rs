@@ -0,0 +1,323 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::CrewCertification;
+use crate::Db;
+
+const CERTIFICATION_SELECT: &str = r#"
+    SELECT
+        crew_member_certifications.id as id,
+        crew_member_certifications.tenant_id as tenant_id,
+        crew_member_certifications.crew_id as crew_id,
+        crew_member_certifications.member_id as member_id,
+        crew_members.name as member_name,
+        crew_members.email as member_email,
+        crews.name as crew_name,
+        crew_member_certifications.name as name,
+        crew_member_certifications.skill_tag as skill_tag,
+        crew_member_certifications.issuing_body as issuing_body,
+        crew_member_certifications.certificate_number as certificate_number,
+        crew_member_certifications.issued_on as issued_on,
+        crew_member_certifications.expires_on as expires_on,
+        CAST(julianday(crew_member_certifications.expires_on) - julianday(date('now', 'localtime')) AS INTEGER) as days_until_expiry,
+        crew_member_certifications.document_name as document_name,
+        crew_member_certifications.document_path as document_path
+    FROM crew_member_certifications
+    JOIN crew_members ON crew_members.id = crew_member_certifications.member_id
+    JOIN crews ON crews.id = crew_member_certifications.crew_id
+"#;
+
+fn map_certification(row: sqlx::sqlite::SqliteRow) -> CrewCertification {
+    CrewCertification {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        crew_id: row.get("crew_id"),
+        member_id: row.get("member_id"),
+        member_name: row.get("member_name"),
+        member_email: row.get("member_email"),
+        crew_name: row.get("crew_name"),
+        name: row.get("name"),
+        skill_tag: row.get("skill_tag"),
+        issuing_body: row.get("issuing_body"),
+        certificate_number: row.get("certificate_number"),
+        issued_on: row.get("issued_on"),
+        expires_on: row.get("expires_on"),
+        days_until_expiry: row.get("days_until_expiry"),
+        status: String::new(),
+        document_name: row.get("document_name"),
+        document_path: row.get("document_path"),
+    }
+}
+
+pub async fn list_certifications_for_member(
+    db: &Db,
+    tenant_id: i64,
+    member_id: i64,
+) -> Result<Vec<CrewCertification>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{CERTIFICATION_SELECT} WHERE crew_member_certifications.tenant_id = ? AND crew_member_certifications.member_id = ? ORDER BY crew_member_certifications.expires_on IS NULL, crew_member_certifications.expires_on ASC, crew_member_certifications.name ASC"
+    ))
+    .bind(tenant_id)
+    .bind(member_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_certification).collect())
+}
+
+pub async fn list_certifications_for_crew(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+) -> Result<Vec<CrewCertification>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{CERTIFICATION_SELECT} WHERE crew_member_certifications.tenant_id = ? AND crew_member_certifications.crew_id = ? ORDER BY crew_members.name ASC, crew_member_certifications.name ASC"
+    ))
+    .bind(tenant_id)
+    .bind(crew_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_certification).collect())
+}
+
+pub async fn list_certifications_for_tenant(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<CrewCertification>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{CERTIFICATION_SELECT} WHERE crew_member_certifications.tenant_id = ?"
+    ))
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_certification).collect())
+}
+
+pub async fn list_expiring_certifications(
+    db: &Db,
+    tenant_id: i64,
+    until: &str,
+    limit: i64,
+) -> Result<Vec<CrewCertification>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{CERTIFICATION_SELECT} WHERE crew_member_certifications.tenant_id = ? AND crew_member_certifications.expires_on IS NOT NULL AND crew_member_certifications.expires_on <= ? ORDER BY crew_member_certifications.expires_on ASC LIMIT ?"
+    ))
+    .bind(tenant_id)
+    .bind(until)
+    .bind(limit)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_certification).collect())
+}
+
+pub async fn list_upcoming_expiries(
+    db: &Db,
+    from: &str,
+    until: &str,
+) -> Result<Vec<CrewCertification>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "{CERTIFICATION_SELECT} WHERE crew_member_certifications.expires_on IS NOT NULL AND crew_member_certifications.expires_on >= ? AND crew_member_certifications.expires_on <= ? ORDER BY crew_member_certifications.tenant_id ASC, crew_member_certifications.expires_on ASC"
+    ))
+    .bind(from)
+    .bind(until)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_certification).collect())
+}
+
+pub async fn find_certification_by_id(
+    db: &Db,
+    tenant_id: i64,
+    member_id: i64,
+    certification_id: i64,
+) -> Result<Option<CrewCertification>, sqlx::Error> {
+    let row = sqlx::query(&format!(
+        "{CERTIFICATION_SELECT} WHERE crew_member_certifications.id = ? AND crew_member_certifications.tenant_id = ? AND crew_member_certifications.member_id = ?"
+    ))
+    .bind(certification_id)
+    .bind(tenant_id)
+    .bind(member_id)
+    .fetch_optional(&db.0)
+    .await?;
+    Ok(row.map(map_certification))
+}
+
+pub async fn create_certification(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    member_id: i64,
+    name: &str,
+    skill_tag: &str,
+    issuing_body: &str,
+    certificate_number: &str,
+    issued_on: Option<&str>,
+    expires_on: Option<&str>,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO crew_member_certifications
+            (tenant_id, crew_id, member_id, name, skill_tag, issuing_body, certificate_number, issued_on, expires_on)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(crew_id)
+    .bind(member_id)
+    .bind(name)
+    .bind(skill_tag)
+    .bind(issuing_body)
+    .bind(certificate_number)
+    .bind(issued_on)
+    .bind(expires_on)
+    .execute(&db.0)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn update_certification(
+    db: &Db,
+    tenant_id: i64,
+    certification_id: i64,
+    name: &str,
+    skill_tag: &str,
+    issuing_body: &str,
+    certificate_number: &str,
+    issued_on: Option<&str>,
+    expires_on: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE crew_member_certifications
+        SET name = ?, skill_tag = ?, issuing_body = ?, certificate_number = ?, issued_on = ?, expires_on = ?, updated_at = datetime('now')
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(name)
+    .bind(skill_tag)
+    .bind(issuing_body)
+    .bind(certificate_number)
+    .bind(issued_on)
+    .bind(expires_on)
+    .bind(certification_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn update_document(
+    db: &Db,
+    tenant_id: i64,
+    certification_id: i64,
+    document_name: &str,
+    document_path: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE crew_member_certifications SET document_name = ?, document_path = ? WHERE id = ? AND tenant_id = ?",
+    )
+    .bind(document_name)
+    .bind(document_path)
+    .bind(certification_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_certification(
+    db: &Db,
+    tenant_id: i64,
+    certification_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM certification_reminders WHERE certification_id = ? AND tenant_id = ?")
+        .bind(certification_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    sqlx::query("DELETE FROM crew_member_certifications WHERE id = ? AND tenant_id = ?")
+        .bind(certification_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn record_reminder(
+    db: &Db,
+    tenant_id: i64,
+    certification_id: i64,
+    threshold_days: i64,
+    expires_on: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO certification_reminders (tenant_id, certification_id, threshold_days, expires_on)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(certification_id)
+    .bind(threshold_days)
+    .bind(expires_on)
+    .execute(&db.0)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn has_reminder_at_or_below(
+    db: &Db,
+    certification_id: i64,
+    threshold_days: i64,
+    expires_on: &str,
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COUNT(*) as count FROM certification_reminders WHERE certification_id = ? AND threshold_days <= ? AND expires_on = ?",
+    )
+    .bind(certification_id)
+    .bind(threshold_days)
+    .bind(expires_on)
+    .fetch_one(&db.0)
+    .await?;
+    Ok(row.get::<i64, _>("count") > 0)
+}
+
+pub async fn delete_certifications_for_member(
+    db: &Db,
+    tenant_id: i64,
+    member_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM certification_reminders WHERE tenant_id = ? AND certification_id IN (SELECT id FROM crew_member_certifications WHERE tenant_id = ? AND member_id = ?)",
+    )
+    .bind(tenant_id)
+    .bind(tenant_id)
+    .bind(member_id)
+    .execute(&db.0)
+    .await?;
+    sqlx::query("DELETE FROM crew_member_certifications WHERE tenant_id = ? AND member_id = ?")
+        .bind(tenant_id)
+        .bind(member_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_certifications_for_crew(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM certification_reminders WHERE tenant_id = ? AND certification_id IN (SELECT id FROM crew_member_certifications WHERE tenant_id = ? AND crew_id = ?)",
+    )
+    .bind(tenant_id)
+    .bind(tenant_id)
+    .bind(crew_id)
+    .execute(&db.0)
+    .await?;
+    sqlx::query("DELETE FROM crew_member_certifications WHERE tenant_id = ? AND crew_id = ?")
+        .bind(tenant_id)
+        .bind(crew_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
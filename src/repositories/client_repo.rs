@@ -1,12 +1,14 @@
 use rocket_db_pools::sqlx::{self, Row};
 
 use crate::models::{Client, ClientContact};
+use crate::repositories::custom_field_repo;
 use crate::Db;
 
 pub async fn list_clients(db: &Db, tenant_id: i64) -> Result<Vec<Client>, sqlx::Error> {
     let rows = sqlx::query(
         r#"
-        SELECT id, tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token
+        SELECT id, tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token,
+               geofence_mode, geofence_radius_m
         FROM clients
         WHERE tenant_id = ? AND is_deleted = 0
         ORDER BY id DESC
@@ -30,6 +32,8 @@ pub async fn list_clients(db: &Db, tenant_id: i64) -> Result<Vec<Client>, sqlx::
             stage: row.get("stage"),
             currency: row.get("currency"),
             portal_token: row.get("portal_token"),
+            geofence_mode: row.get("geofence_mode"),
+            geofence_radius_m: row.get("geofence_radius_m"),
         })
         .collect())
 }
@@ -42,7 +46,8 @@ pub async fn list_clients_paged(
 ) -> Result<Vec<Client>, sqlx::Error> {
     let rows = sqlx::query(
         r#"
-        SELECT id, tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token
+        SELECT id, tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token,
+               geofence_mode, geofence_radius_m
         FROM clients
         WHERE tenant_id = ? AND is_deleted = 0
         ORDER BY id DESC
@@ -69,6 +74,8 @@ pub async fn list_clients_paged(
             stage: row.get("stage"),
             currency: row.get("currency"),
             portal_token: row.get("portal_token"),
+            geofence_mode: row.get("geofence_mode"),
+            geofence_radius_m: row.get("geofence_radius_m"),
         })
         .collect())
 }
@@ -97,7 +104,8 @@ pub async fn find_client_by_id(
 ) -> Result<Option<Client>, sqlx::Error> {
     let row = sqlx::query(
         r#"
-        SELECT id, tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token
+        SELECT id, tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token,
+               geofence_mode, geofence_radius_m
         FROM clients
         WHERE id = ? AND tenant_id = ? AND is_deleted = 0
         "#,
@@ -119,6 +127,8 @@ pub async fn find_client_by_id(
         stage: row.get("stage"),
         currency: row.get("currency"),
         portal_token: row.get("portal_token"),
+        geofence_mode: row.get("geofence_mode"),
+        geofence_radius_m: row.get("geofence_radius_m"),
     }))
 }
 
@@ -134,8 +144,10 @@ pub async fn create_client(
     stage: &str,
     currency: &str,
     portal_token: &str,
-) -> Result<(), sqlx::Error> {
-    sqlx::query(
+    custom_values: &[(i64, String)],
+) -> Result<i64, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    let result = sqlx::query(
         r#"
         INSERT INTO clients (tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token)
         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
@@ -151,9 +163,12 @@ pub async fn create_client(
     .bind(stage)
     .bind(currency)
     .bind(portal_token)
-    .execute(&db.0)
+    .execute(&mut *tx)
     .await?;
-    Ok(())
+    let record_id = result.last_insert_rowid();
+    custom_field_repo::insert_values(&mut tx, tenant_id, record_id, custom_values).await?;
+    tx.commit().await?;
+    Ok(record_id)
 }
 
 pub async fn update_client(
@@ -168,7 +183,9 @@ pub async fn update_client(
     longitude: &str,
     stage: &str,
     currency: &str,
+    custom_values: &[(i64, String)],
 ) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
     sqlx::query(
         r#"
         UPDATE clients
@@ -186,8 +203,10 @@ pub async fn update_client(
     .bind(currency)
     .bind(client_id)
     .bind(tenant_id)
-    .execute(&db.0)
+    .execute(&mut *tx)
     .await?;
+    custom_field_repo::insert_values(&mut tx, tenant_id, client_id, custom_values).await?;
+    tx.commit().await?;
     Ok(())
 }
 
@@ -219,7 +238,8 @@ pub async fn find_client_by_portal_token(
 ) -> Result<Option<Client>, sqlx::Error> {
     let row = sqlx::query(
         r#"
-        SELECT id, tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token
+        SELECT id, tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token,
+               geofence_mode, geofence_radius_m
         FROM clients
         WHERE tenant_id = ? AND portal_token = ? AND is_deleted = 0
         "#,
@@ -241,6 +261,8 @@ pub async fn find_client_by_portal_token(
         stage: row.get("stage"),
         currency: row.get("currency"),
         portal_token: row.get("portal_token"),
+        geofence_mode: row.get("geofence_mode"),
+        geofence_radius_m: row.get("geofence_radius_m"),
     }))
 }
 
@@ -257,6 +279,35 @@ pub async fn mark_client_deleted(
     Ok(())
 }
 
+pub async fn list_all_contacts(db: &Db, tenant_id: i64) -> Result<Vec<ClientContact>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, client_id, tenant_id, name, address, email, phone, department, position
+        FROM client_contacts
+        WHERE tenant_id = ? AND is_rogue = 0
+        ORDER BY client_id ASC, id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ClientContact {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            tenant_id: row.get("tenant_id"),
+            name: row.get("name"),
+            address: row.get("address"),
+            email: row.get("email"),
+            phone: row.get("phone"),
+            department: row.get("department"),
+            position: row.get("position"),
+        })
+        .collect())
+}
+
 pub async fn list_contacts(
     db: &Db,
     tenant_id: i64,
@@ -387,8 +438,10 @@ pub async fn create_contact(
     phone: &str,
     department: &str,
     position: &str,
-) -> Result<(), sqlx::Error> {
-    sqlx::query(
+    custom_values: &[(i64, String)],
+) -> Result<i64, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    let result = sqlx::query(
         r#"
         INSERT INTO client_contacts (client_id, tenant_id, name, address, email, phone, department, position)
         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
@@ -402,9 +455,12 @@ pub async fn create_contact(
     .bind(phone)
     .bind(department)
     .bind(position)
-    .execute(&db.0)
+    .execute(&mut *tx)
     .await?;
-    Ok(())
+    let record_id = result.last_insert_rowid();
+    custom_field_repo::insert_values(&mut tx, tenant_id, record_id, custom_values).await?;
+    tx.commit().await?;
+    Ok(record_id)
 }
 
 pub async fn update_contact(
@@ -418,7 +474,9 @@ pub async fn update_contact(
     phone: &str,
     department: &str,
     position: &str,
+    custom_values: &[(i64, String)],
 ) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
     sqlx::query(
         r#"
         UPDATE client_contacts
@@ -435,8 +493,10 @@ pub async fn update_contact(
     .bind(contact_id)
     .bind(client_id)
     .bind(tenant_id)
-    .execute(&db.0)
+    .execute(&mut *tx)
     .await?;
+    custom_field_repo::insert_values(&mut tx, tenant_id, contact_id, custom_values).await?;
+    tx.commit().await?;
     Ok(())
 }
 
@@ -469,3 +529,26 @@ pub async fn delete_contacts_by_client(
         .await?;
     Ok(())
 }
+
+pub async fn update_geofence(
+    db: &Db,
+    tenant_id: i64,
+    client_id: i64,
+    geofence_mode: &str,
+    geofence_radius_m: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE clients
+        SET geofence_mode = ?, geofence_radius_m = ?
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(geofence_mode)
+    .bind(geofence_radius_m)
+    .bind(client_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
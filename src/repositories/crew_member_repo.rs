@@ -1,6 +1,7 @@
 use rocket_db_pools::sqlx::{self, Row};
 
 use crate::models::CrewMember;
+use crate::repositories::custom_field_repo;
 use crate::Db;
 
 pub async fn list_members(
@@ -10,7 +11,7 @@ pub async fn list_members(
 ) -> Result<Vec<CrewMember>, sqlx::Error> {
     let rows = sqlx::query(
         r#"
-        SELECT id, crew_id, tenant_id, user_id, name, phone, email, position, availability_status
+        SELECT id, crew_id, tenant_id, user_id, name, phone, email, position, availability_status, hourly_cost
         FROM crew_members
         WHERE crew_id = ? AND tenant_id = ?
         ORDER BY id DESC
@@ -33,6 +34,7 @@ pub async fn list_members(
             email: row.get("email"),
             position: row.get("position"),
             availability_status: row.get("availability_status"),
+            hourly_cost: row.get("hourly_cost"),
         })
         .collect())
 }
@@ -46,7 +48,7 @@ pub async fn list_members_paged(
 ) -> Result<Vec<CrewMember>, sqlx::Error> {
     let rows = sqlx::query(
         r#"
-        SELECT id, crew_id, tenant_id, user_id, name, phone, email, position, availability_status
+        SELECT id, crew_id, tenant_id, user_id, name, phone, email, position, availability_status, hourly_cost
         FROM crew_members
         WHERE crew_id = ? AND tenant_id = ?
         ORDER BY id DESC
@@ -72,6 +74,7 @@ pub async fn list_members_paged(
             email: row.get("email"),
             position: row.get("position"),
             availability_status: row.get("availability_status"),
+            hourly_cost: row.get("hourly_cost"),
         })
         .collect())
 }
@@ -99,7 +102,7 @@ pub async fn find_member_by_id(
 ) -> Result<Option<CrewMember>, sqlx::Error> {
     let row = sqlx::query(
         r#"
-        SELECT id, crew_id, tenant_id, user_id, name, phone, email, position, availability_status
+        SELECT id, crew_id, tenant_id, user_id, name, phone, email, position, availability_status, hourly_cost
         FROM crew_members
         WHERE id = ? AND crew_id = ? AND tenant_id = ?
         "#,
@@ -120,6 +123,7 @@ pub async fn find_member_by_id(
         email: row.get("email"),
         position: row.get("position"),
         availability_status: row.get("availability_status"),
+        hourly_cost: row.get("hourly_cost"),
     }))
 }
 
@@ -133,11 +137,14 @@ pub async fn create_member(
     email: &str,
     position: &str,
     availability_status: &str,
+    hourly_cost: f64,
+    custom_values: &[(i64, String)],
 ) -> Result<(), sqlx::Error> {
-    sqlx::query(
+    let mut tx = db.0.begin().await?;
+    let result = sqlx::query(
         r#"
-        INSERT INTO crew_members (crew_id, tenant_id, user_id, name, phone, email, position, availability_status)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO crew_members (crew_id, tenant_id, user_id, name, phone, email, position, availability_status, hourly_cost)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(crew_id)
@@ -148,8 +155,11 @@ pub async fn create_member(
     .bind(email)
     .bind(position)
     .bind(availability_status)
-    .execute(&db.0)
+    .bind(hourly_cost)
+    .execute(&mut *tx)
     .await?;
+    custom_field_repo::insert_values(&mut tx, tenant_id, result.last_insert_rowid(), custom_values).await?;
+    tx.commit().await?;
     Ok(())
 }
 
@@ -164,11 +174,15 @@ pub async fn update_member(
     email: &str,
     position: &str,
     availability_status: &str,
+    hourly_cost: Option<f64>,
+    custom_values: &[(i64, String)],
 ) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
     sqlx::query(
         r#"
         UPDATE crew_members
-        SET user_id = ?, name = ?, phone = ?, email = ?, position = ?, availability_status = ?
+        SET user_id = ?, name = ?, phone = ?, email = ?, position = ?, availability_status = ?,
+            hourly_cost = COALESCE(?, hourly_cost)
         WHERE id = ? AND crew_id = ? AND tenant_id = ?
         "#,
     )
@@ -178,11 +192,14 @@ pub async fn update_member(
     .bind(email)
     .bind(position)
     .bind(availability_status)
+    .bind(hourly_cost)
     .bind(member_id)
     .bind(crew_id)
     .bind(tenant_id)
-    .execute(&db.0)
+    .execute(&mut *tx)
     .await?;
+    custom_field_repo::insert_values(&mut tx, tenant_id, member_id, custom_values).await?;
+    tx.commit().await?;
     Ok(())
 }
 
@@ -209,6 +226,37 @@ pub async fn list_crew_ids_for_user(
     Ok(rows.into_iter().map(|row| row.get("crew_id")).collect())
 }
 
+pub async fn list_lead_emails_for_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    exclude_user_id: i64,
+) -> Result<Vec<String>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT DISTINCT COALESCE(NULLIF(TRIM(crew_members.email), ''), users.email) as email
+        FROM crew_members
+        JOIN deployments ON deployments.crew_id = crew_members.crew_id
+        LEFT JOIN users ON users.id = crew_members.user_id
+        WHERE crew_members.tenant_id = ?
+          AND deployments.id = ?
+          AND lower(crew_members.position) LIKE '%lead%'
+          AND (crew_members.user_id IS NULL OR crew_members.user_id != ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(deployment_id)
+    .bind(exclude_user_id)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| row.get::<Option<String>, _>("email"))
+        .filter(|email| !email.trim().is_empty())
+        .collect())
+}
+
 pub async fn delete_member(
     db: &Db,
     tenant_id: i64,
@@ -226,11 +274,11 @@ pub async fn delete_member(
     Ok(())
 }
 
-pub async fn count_availability_by_crew(
+pub async fn list_members_for_crews(
     db: &Db,
     tenant_id: i64,
     crew_ids: &[i64],
-) -> Result<Vec<(i64, String, i64)>, sqlx::Error> {
+) -> Result<Vec<CrewMember>, sqlx::Error> {
     if crew_ids.is_empty() {
         return Ok(Vec::new());
     }
@@ -241,10 +289,10 @@ pub async fn count_availability_by_crew(
         .join(", ");
     let sql = format!(
         r#"
-        SELECT crew_id, availability_status, COUNT(*) as count
+        SELECT id, crew_id, tenant_id, user_id, name, phone, email, position, availability_status, hourly_cost
         FROM crew_members
         WHERE tenant_id = ? AND crew_id IN ({})
-        GROUP BY crew_id, availability_status
+        ORDER BY name ASC, id ASC
         "#,
         placeholders
     );
@@ -255,6 +303,17 @@ pub async fn count_availability_by_crew(
     let rows = query.fetch_all(&db.0).await?;
     Ok(rows
         .into_iter()
-        .map(|row| (row.get("crew_id"), row.get("availability_status"), row.get("count")))
+        .map(|row| CrewMember {
+            id: row.get("id"),
+            crew_id: row.get("crew_id"),
+            tenant_id: row.get("tenant_id"),
+            user_id: row.get("user_id"),
+            name: row.get("name"),
+            phone: row.get("phone"),
+            email: row.get("email"),
+            position: row.get("position"),
+            availability_status: row.get("availability_status"),
+            hourly_cost: row.get("hourly_cost"),
+        })
         .collect())
 }
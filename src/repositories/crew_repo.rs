@@ -1,6 +1,7 @@
 use rocket_db_pools::sqlx::{self, Row};
 
 use crate::models::Crew;
+use crate::repositories::custom_field_repo;
 use crate::Db;
 
 pub async fn list_crews(db: &Db, tenant_id: i64) -> Result<Vec<Crew>, sqlx::Error> {
@@ -162,8 +163,10 @@ pub async fn create_crew(
     gear_score: i64,
     skill_tags: &str,
     compatibility_tags: &str,
-) -> Result<(), sqlx::Error> {
-    sqlx::query(
+    custom_values: &[(i64, String)],
+) -> Result<i64, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    let result = sqlx::query(
         "INSERT INTO crews (tenant_id, name, members_count, status, gear_score, skill_tags, compatibility_tags) VALUES (?, ?, ?, ?, ?, ?, ?)",
     )
     .bind(tenant_id)
@@ -173,9 +176,12 @@ pub async fn create_crew(
     .bind(gear_score)
     .bind(skill_tags)
     .bind(compatibility_tags)
-    .execute(&db.0)
+    .execute(&mut *tx)
     .await?;
-    Ok(())
+    let record_id = result.last_insert_rowid();
+    custom_field_repo::insert_values(&mut tx, tenant_id, record_id, custom_values).await?;
+    tx.commit().await?;
+    Ok(record_id)
 }
 
 pub async fn update_crew(
@@ -187,7 +193,9 @@ pub async fn update_crew(
     gear_score: i64,
     skill_tags: &str,
     compatibility_tags: &str,
+    custom_values: &[(i64, String)],
 ) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
     sqlx::query(
         "UPDATE crews SET name = ?, status = ?, gear_score = ?, skill_tags = ?, compatibility_tags = ? WHERE id = ? AND tenant_id = ?",
     )
@@ -198,8 +206,10 @@ pub async fn update_crew(
     .bind(compatibility_tags)
     .bind(crew_id)
     .bind(tenant_id)
-    .execute(&db.0)
+    .execute(&mut *tx)
     .await?;
+    custom_field_repo::insert_values(&mut tx, tenant_id, crew_id, custom_values).await?;
+    tx.commit().await?;
     Ok(())
 }
 
@@ -0,0 +1,209 @@
+use rocket_db_pools::sqlx::{self, Row, SqliteConnection};
+
+use crate::models::CustomFieldDefinition;
+use crate::Db;
+
+fn map_definition(row: sqlx::sqlite::SqliteRow) -> CustomFieldDefinition {
+    let options: String = row.get("options");
+    CustomFieldDefinition {
+        id: row.get("id"),
+        entity: row.get("entity"),
+        label: row.get("label"),
+        field_type: row.get("field_type"),
+        options: options
+            .lines()
+            .map(|option| option.trim().to_string())
+            .filter(|option| !option.is_empty())
+            .collect(),
+        is_required: row.get::<i64, _>("is_required") != 0,
+        position: row.get("position"),
+    }
+}
+
+pub async fn list_definitions(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+) -> Result<Vec<CustomFieldDefinition>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, entity, label, field_type, options, is_required, position
+        FROM custom_field_definitions
+        WHERE tenant_id = ? AND entity = ?
+        ORDER BY position ASC, id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(entity)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_definition).collect())
+}
+
+pub async fn list_all_definitions(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<CustomFieldDefinition>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, entity, label, field_type, options, is_required, position
+        FROM custom_field_definitions
+        WHERE tenant_id = ?
+        ORDER BY entity ASC, position ASC, id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_definition).collect())
+}
+
+pub async fn create_definition(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    label: &str,
+    field_type: &str,
+    options: &str,
+    is_required: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO custom_field_definitions (tenant_id, entity, label, field_type, options, is_required, position)
+        VALUES (
+            ?, ?, ?, ?, ?, ?,
+            (SELECT COALESCE(MAX(position), 0) + 1 FROM custom_field_definitions WHERE tenant_id = ? AND entity = ?)
+        )
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(entity)
+    .bind(label)
+    .bind(field_type)
+    .bind(options)
+    .bind(is_required as i64)
+    .bind(tenant_id)
+    .bind(entity)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_definition(db: &Db, tenant_id: i64, definition_id: i64) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("DELETE FROM custom_field_values WHERE definition_id = ? AND tenant_id = ?")
+        .bind(definition_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM custom_field_definitions WHERE id = ? AND tenant_id = ?")
+        .bind(definition_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list_values(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+) -> Result<Vec<(i64, i64, String)>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT custom_field_values.record_id as record_id,
+               custom_field_values.definition_id as definition_id,
+               custom_field_values.value as value
+        FROM custom_field_values
+        JOIN custom_field_definitions
+            ON custom_field_definitions.id = custom_field_values.definition_id
+            AND custom_field_definitions.tenant_id = custom_field_values.tenant_id
+        WHERE custom_field_values.tenant_id = ? AND custom_field_definitions.entity = ?
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(entity)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("record_id"), row.get("definition_id"), row.get("value")))
+        .collect())
+}
+
+pub async fn list_values_for_record(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    record_id: i64,
+) -> Result<Vec<(i64, String)>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT custom_field_values.definition_id as definition_id,
+               custom_field_values.value as value
+        FROM custom_field_values
+        JOIN custom_field_definitions
+            ON custom_field_definitions.id = custom_field_values.definition_id
+            AND custom_field_definitions.tenant_id = custom_field_values.tenant_id
+        WHERE custom_field_values.tenant_id = ?
+          AND custom_field_definitions.entity = ?
+          AND custom_field_values.record_id = ?
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(entity)
+    .bind(record_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("definition_id"), row.get("value")))
+        .collect())
+}
+
+pub async fn insert_values(
+    conn: &mut SqliteConnection,
+    tenant_id: i64,
+    record_id: i64,
+    values: &[(i64, String)],
+) -> Result<(), sqlx::Error> {
+    for (definition_id, value) in values {
+        sqlx::query(
+            r#"
+            INSERT INTO custom_field_values (tenant_id, definition_id, record_id, value)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(definition_id, record_id) DO UPDATE SET value = excluded.value
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(definition_id)
+        .bind(record_id)
+        .bind(value)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+pub async fn delete_values_for_record(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    record_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        DELETE FROM custom_field_values
+        WHERE tenant_id = ? AND record_id = ?
+          AND definition_id IN (SELECT id FROM custom_field_definitions WHERE tenant_id = ? AND entity = ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(record_id)
+    .bind(tenant_id)
+    .bind(entity)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use rocket_db_pools::sqlx::{self, Row};
 
 use crate::models::Deployment;
+use crate::repositories::{custom_field_repo, deployment_task_repo};
 use crate::Db;
 
 pub struct DeploymentRow {
@@ -302,12 +303,16 @@ pub async fn create_deployment(
     deployment_type: &str,
     required_skills: &str,
     compatibility_pref: &str,
-) -> Result<(), sqlx::Error> {
-    sqlx::query(
+    template_id: Option<i64>,
+    checklist: &[(String, bool)],
+    custom_values: &[(i64, String)],
+) -> Result<i64, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    let result = sqlx::query(
         r#"
         INSERT INTO deployments
-            (tenant_id, client_id, crew_id, start_at, end_at, fee_per_hour, info, status, deployment_type, required_skills, compatibility_pref)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            (tenant_id, client_id, crew_id, start_at, end_at, fee_per_hour, info, status, deployment_type, required_skills, compatibility_pref, template_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(tenant_id)
@@ -321,9 +326,16 @@ pub async fn create_deployment(
     .bind(deployment_type)
     .bind(required_skills)
     .bind(compatibility_pref)
-    .execute(&db.0)
+    .bind(template_id)
+    .execute(&mut *tx)
     .await?;
-    Ok(())
+    let deployment_id = result.last_insert_rowid();
+    for (title, is_required) in checklist {
+        deployment_task_repo::insert_task(&mut tx, tenant_id, deployment_id, title, *is_required).await?;
+    }
+    custom_field_repo::insert_values(&mut tx, tenant_id, deployment_id, custom_values).await?;
+    tx.commit().await?;
+    Ok(deployment_id)
 }
 
 pub async fn find_deployment_by_id(
@@ -333,7 +345,7 @@ pub async fn find_deployment_by_id(
 ) -> Result<Option<Deployment>, sqlx::Error> {
     let row = sqlx::query(
         r#"
-        SELECT id, tenant_id, client_id, crew_id, start_at, end_at, fee_per_hour, info, status, deployment_type, required_skills, compatibility_pref
+        SELECT id, tenant_id, client_id, crew_id, start_at, end_at, fee_per_hour, info, status, deployment_type, required_skills, compatibility_pref, template_id
         FROM deployments
         WHERE id = ? AND tenant_id = ?
         "#,
@@ -356,6 +368,7 @@ pub async fn find_deployment_by_id(
         deployment_type: row.get("deployment_type"),
         required_skills: row.get("required_skills"),
         compatibility_pref: row.get("compatibility_pref"),
+        template_id: row.get("template_id"),
     }))
 }
 
@@ -399,7 +412,9 @@ pub async fn update_deployment(
     deployment_type: &str,
     required_skills: &str,
     compatibility_pref: &str,
+    custom_values: &[(i64, String)],
 ) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
     sqlx::query(
         r#"
         UPDATE deployments
@@ -419,8 +434,10 @@ pub async fn update_deployment(
     .bind(compatibility_pref)
     .bind(deployment_id)
     .bind(tenant_id)
-    .execute(&db.0)
+    .execute(&mut *tx)
     .await?;
+    custom_field_repo::insert_values(&mut tx, tenant_id, deployment_id, custom_values).await?;
+    tx.commit().await?;
     Ok(())
 }
 
@@ -450,11 +467,28 @@ pub async fn delete_deployment(
     tenant_id: i64,
     deployment_id: i64,
 ) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("DELETE FROM deployment_expenses WHERE deployment_id = ? AND tenant_id = ?")
+        .bind(deployment_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM deployment_tasks WHERE deployment_id = ? AND tenant_id = ?")
+        .bind(deployment_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM deployment_incidents WHERE deployment_id = ? AND tenant_id = ?")
+        .bind(deployment_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
     sqlx::query("DELETE FROM deployments WHERE id = ? AND tenant_id = ?")
         .bind(deployment_id)
         .bind(tenant_id)
-        .execute(&db.0)
+        .execute(&mut *tx)
         .await?;
+    tx.commit().await?;
     Ok(())
 }
 
@@ -479,7 +513,7 @@ pub async fn list_deployments(
 ) -> Result<Vec<Deployment>, sqlx::Error> {
     let rows = sqlx::query(
         r#"
-        SELECT id, tenant_id, client_id, crew_id, start_at, end_at, fee_per_hour, info, status, deployment_type, required_skills, compatibility_pref
+        SELECT id, tenant_id, client_id, crew_id, start_at, end_at, fee_per_hour, info, status, deployment_type, required_skills, compatibility_pref, template_id
         FROM deployments
         WHERE tenant_id = ?
         ORDER BY id DESC
@@ -504,6 +538,7 @@ pub async fn list_deployments(
         deployment_type: row.get("deployment_type"),
         required_skills: row.get("required_skills"),
         compatibility_pref: row.get("compatibility_pref"),
+        template_id: row.get("template_id"),
     })
         .collect())
 }
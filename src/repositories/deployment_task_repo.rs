@@ -0,0 +1,227 @@
+use rocket_db_pools::sqlx::{self, Row, SqliteConnection};
+
+use crate::models::{DeploymentTask, DeploymentTaskProgress};
+use crate::Db;
+
+pub async fn list_tasks_by_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentTask>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT deployment_tasks.id as id,
+               deployment_tasks.tenant_id as tenant_id,
+               deployment_tasks.deployment_id as deployment_id,
+               deployment_tasks.position as position,
+               deployment_tasks.title as title,
+               deployment_tasks.is_required as is_required,
+               deployment_tasks.completed_at as completed_at,
+               deployment_tasks.completed_by_user_id as completed_by_user_id,
+               users.email as completed_by_email
+        FROM deployment_tasks
+        LEFT JOIN users ON deployment_tasks.completed_by_user_id = users.id
+        WHERE deployment_tasks.tenant_id = ? AND deployment_tasks.deployment_id = ?
+        ORDER BY deployment_tasks.position ASC, deployment_tasks.id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(deployment_id)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DeploymentTask {
+            id: row.get("id"),
+            tenant_id: row.get("tenant_id"),
+            deployment_id: row.get("deployment_id"),
+            position: row.get("position"),
+            title: row.get("title"),
+            is_required: row.get::<i64, _>("is_required") != 0,
+            completed_at: row.get("completed_at"),
+            completed_by_user_id: row.get("completed_by_user_id"),
+            completed_by_email: row.get("completed_by_email"),
+        })
+        .collect())
+}
+
+pub async fn find_task_by_id(
+    db: &Db,
+    tenant_id: i64,
+    task_id: i64,
+) -> Result<Option<DeploymentTask>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT deployment_tasks.id as id,
+               deployment_tasks.tenant_id as tenant_id,
+               deployment_tasks.deployment_id as deployment_id,
+               deployment_tasks.position as position,
+               deployment_tasks.title as title,
+               deployment_tasks.is_required as is_required,
+               deployment_tasks.completed_at as completed_at,
+               deployment_tasks.completed_by_user_id as completed_by_user_id,
+               users.email as completed_by_email
+        FROM deployment_tasks
+        LEFT JOIN users ON deployment_tasks.completed_by_user_id = users.id
+        WHERE deployment_tasks.id = ? AND deployment_tasks.tenant_id = ?
+        "#,
+    )
+    .bind(task_id)
+    .bind(tenant_id)
+    .fetch_optional(&db.0)
+    .await?;
+
+    Ok(row.map(|row| DeploymentTask {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        deployment_id: row.get("deployment_id"),
+        position: row.get("position"),
+        title: row.get("title"),
+        is_required: row.get::<i64, _>("is_required") != 0,
+        completed_at: row.get("completed_at"),
+        completed_by_user_id: row.get("completed_by_user_id"),
+        completed_by_email: row.get("completed_by_email"),
+    }))
+}
+
+pub async fn create_task(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    title: &str,
+    is_required: bool,
+) -> Result<(), sqlx::Error> {
+    let mut conn = db.0.acquire().await?;
+    insert_task(&mut conn, tenant_id, deployment_id, title, is_required).await
+}
+
+pub async fn insert_task(
+    conn: &mut SqliteConnection,
+    tenant_id: i64,
+    deployment_id: i64,
+    title: &str,
+    is_required: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO deployment_tasks (tenant_id, deployment_id, position, title, is_required)
+        VALUES (
+            ?,
+            ?,
+            (SELECT COALESCE(MAX(position), 0) + 1 FROM deployment_tasks WHERE tenant_id = ? AND deployment_id = ?),
+            ?,
+            ?
+        )
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(deployment_id)
+    .bind(tenant_id)
+    .bind(deployment_id)
+    .bind(title)
+    .bind(if is_required { 1 } else { 0 })
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn set_task_completed(
+    db: &Db,
+    tenant_id: i64,
+    task_id: i64,
+    completed_at: Option<&str>,
+    completed_by_user_id: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE deployment_tasks
+        SET completed_at = ?, completed_by_user_id = ?
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(completed_at)
+    .bind(completed_by_user_id)
+    .bind(task_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn swap_task_positions(
+    db: &Db,
+    tenant_id: i64,
+    first: &DeploymentTask,
+    second: &DeploymentTask,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("UPDATE deployment_tasks SET position = ? WHERE id = ? AND tenant_id = ?")
+        .bind(second.position)
+        .bind(first.id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE deployment_tasks SET position = ? WHERE id = ? AND tenant_id = ?")
+        .bind(first.position)
+        .bind(second.id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn delete_task(db: &Db, tenant_id: i64, task_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM deployment_tasks WHERE id = ? AND tenant_id = ?")
+        .bind(task_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn task_progress_for_deployments(
+    db: &Db,
+    tenant_id: i64,
+    deployment_ids: &[i64],
+) -> Result<Vec<(i64, DeploymentTaskProgress)>, sqlx::Error> {
+    if deployment_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = deployment_ids
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        r#"
+        SELECT deployment_id,
+               COUNT(*) as total,
+               SUM(CASE WHEN completed_at IS NOT NULL THEN 1 ELSE 0 END) as completed,
+               SUM(CASE WHEN completed_at IS NULL AND is_required = 1 THEN 1 ELSE 0 END) as required_open
+        FROM deployment_tasks
+        WHERE tenant_id = ? AND deployment_id IN ({})
+        GROUP BY deployment_id
+        "#,
+        placeholders
+    );
+    let mut query = sqlx::query(&sql).bind(tenant_id);
+    for deployment_id in deployment_ids {
+        query = query.bind(deployment_id);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.get("deployment_id"),
+                DeploymentTaskProgress {
+                    total: row.get("total"),
+                    completed: row.get("completed"),
+                    required_open: row.get("required_open"),
+                },
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,227 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{DeploymentTemplate, DeploymentTemplateUsage};
+use crate::Db;
+
+pub async fn list_templates(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<DeploymentTemplate>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, tenant_id, name, deployment_type, required_skills, compatibility_pref, fee_per_hour, default_duration_hours, checklist, info
+        FROM deployment_templates
+        WHERE tenant_id = ?
+        ORDER BY name ASC, id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DeploymentTemplate {
+            id: row.get("id"),
+            tenant_id: row.get("tenant_id"),
+            name: row.get("name"),
+            deployment_type: row.get("deployment_type"),
+            required_skills: row.get("required_skills"),
+            compatibility_pref: row.get("compatibility_pref"),
+            fee_per_hour: row.get("fee_per_hour"),
+            default_duration_hours: row.get("default_duration_hours"),
+            checklist: row.get("checklist"),
+            info: row.get("info"),
+        })
+        .collect())
+}
+
+pub async fn find_template_by_id(
+    db: &Db,
+    tenant_id: i64,
+    template_id: i64,
+) -> Result<Option<DeploymentTemplate>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, tenant_id, name, deployment_type, required_skills, compatibility_pref, fee_per_hour, default_duration_hours, checklist, info
+        FROM deployment_templates
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(template_id)
+    .bind(tenant_id)
+    .fetch_optional(&db.0)
+    .await?;
+
+    Ok(row.map(|row| DeploymentTemplate {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        name: row.get("name"),
+        deployment_type: row.get("deployment_type"),
+        required_skills: row.get("required_skills"),
+        compatibility_pref: row.get("compatibility_pref"),
+        fee_per_hour: row.get("fee_per_hour"),
+        default_duration_hours: row.get("default_duration_hours"),
+        checklist: row.get("checklist"),
+        info: row.get("info"),
+    }))
+}
+
+pub async fn find_template_id_by_name(
+    db: &Db,
+    tenant_id: i64,
+    name: &str,
+) -> Result<Option<i64>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT id FROM deployment_templates WHERE tenant_id = ? AND LOWER(name) = LOWER(?)",
+    )
+    .bind(tenant_id)
+    .bind(name)
+    .fetch_optional(&db.0)
+    .await?;
+    Ok(row.map(|row| row.get("id")))
+}
+
+pub async fn create_template(
+    db: &Db,
+    tenant_id: i64,
+    name: &str,
+    deployment_type: &str,
+    required_skills: &str,
+    compatibility_pref: &str,
+    fee_per_hour: f64,
+    default_duration_hours: f64,
+    checklist: &str,
+    info: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO deployment_templates
+            (tenant_id, name, deployment_type, required_skills, compatibility_pref, fee_per_hour, default_duration_hours, checklist, info)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(name)
+    .bind(deployment_type)
+    .bind(required_skills)
+    .bind(compatibility_pref)
+    .bind(fee_per_hour)
+    .bind(default_duration_hours)
+    .bind(checklist)
+    .bind(info)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn update_template(
+    db: &Db,
+    tenant_id: i64,
+    template_id: i64,
+    name: &str,
+    deployment_type: &str,
+    required_skills: &str,
+    compatibility_pref: &str,
+    fee_per_hour: f64,
+    default_duration_hours: f64,
+    checklist: &str,
+    info: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE deployment_templates
+        SET name = ?, deployment_type = ?, required_skills = ?, compatibility_pref = ?, fee_per_hour = ?, default_duration_hours = ?, checklist = ?, info = ?
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(name)
+    .bind(deployment_type)
+    .bind(required_skills)
+    .bind(compatibility_pref)
+    .bind(fee_per_hour)
+    .bind(default_duration_hours)
+    .bind(checklist)
+    .bind(info)
+    .bind(template_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_template(
+    db: &Db,
+    tenant_id: i64,
+    template_id: i64,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("UPDATE deployments SET template_id = NULL WHERE tenant_id = ? AND template_id = ?")
+        .bind(tenant_id)
+        .bind(template_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM deployment_templates WHERE id = ? AND tenant_id = ?")
+        .bind(template_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list_template_usage(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<DeploymentTemplateUsage>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            deployment_templates.id as id,
+            deployment_templates.name as name,
+            deployment_templates.deployment_type as deployment_type,
+            deployment_templates.fee_per_hour as fee_per_hour,
+            COUNT(deployments.id) as usage_count,
+            COALESCE(SUM(CASE WHEN deployments.status = 'Scheduled' THEN 1 ELSE 0 END), 0) as scheduled_count,
+            COALESCE(SUM(CASE WHEN deployments.status = 'Active' THEN 1 ELSE 0 END), 0) as active_count,
+            COALESCE(SUM(CASE WHEN deployments.status = 'Completed' THEN 1 ELSE 0 END), 0) as completed_count,
+            COALESCE(SUM(CASE WHEN deployments.status = 'Cancelled' THEN 1 ELSE 0 END), 0) as cancelled_count,
+            MAX(deployments.start_at) as last_used_at,
+            (
+                SELECT COALESCE(SUM(deployment_updates.hours_worked), 0.0)
+                FROM deployment_updates
+                JOIN deployments tracked ON deployment_updates.deployment_id = tracked.id
+                WHERE tracked.tenant_id = deployment_templates.tenant_id
+                  AND tracked.template_id = deployment_templates.id
+                  AND deployment_updates.is_placeholder = 0
+            ) as total_hours
+        FROM deployment_templates
+        LEFT JOIN deployments
+            ON deployments.template_id = deployment_templates.id
+            AND deployments.tenant_id = deployment_templates.tenant_id
+        WHERE deployment_templates.tenant_id = ?
+        GROUP BY deployment_templates.id
+        ORDER BY usage_count DESC, deployment_templates.name ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DeploymentTemplateUsage {
+            id: row.get("id"),
+            name: row.get("name"),
+            deployment_type: row.get("deployment_type"),
+            fee_per_hour: row.get("fee_per_hour"),
+            usage_count: row.get("usage_count"),
+            scheduled_count: row.get("scheduled_count"),
+            active_count: row.get("active_count"),
+            completed_count: row.get("completed_count"),
+            cancelled_count: row.get("cancelled_count"),
+            total_hours: row.get("total_hours"),
+            last_used_at: row.get("last_used_at"),
+        })
+        .collect())
+}
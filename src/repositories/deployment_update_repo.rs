@@ -1,6 +1,6 @@
 use rocket_db_pools::sqlx::{self, Row};
 
-use crate::models::DeploymentUpdate;
+use crate::models::{DeploymentUpdate, UpdateSegment};
 use crate::Db;
 
 fn is_missing_user_id(err: &sqlx::Error) -> bool {
@@ -32,6 +32,10 @@ pub async fn list_updates(
                deployment_updates.hours_worked,
                deployment_updates.notes,
                deployment_updates.is_placeholder,
+               deployment_updates.approval_status,
+               deployment_updates.review_note,
+               deployment_updates.paid_break_minutes,
+               deployment_updates.unpaid_break_minutes,
                COALESCE(users.email, '') as user_email
         FROM deployment_updates
         LEFT JOIN users ON users.id = deployment_updates.user_id
@@ -59,6 +63,10 @@ pub async fn list_updates(
                 hours_worked: row.get("hours_worked"),
                 notes: row.get("notes"),
                 is_placeholder: row.get::<i64, _>("is_placeholder") != 0,
+                approval_status: row.get("approval_status"),
+                review_note: row.get("review_note"),
+                paid_break_minutes: row.get("paid_break_minutes"),
+                unpaid_break_minutes: row.get("unpaid_break_minutes"),
             })
             .collect()),
         Err(err) if is_missing_user_id(&err) => {
@@ -89,6 +97,10 @@ pub async fn list_updates(
                     hours_worked: row.get("hours_worked"),
                     notes: row.get("notes"),
                     is_placeholder: false,
+                    approval_status: "Approved".to_string(),
+                    review_note: "".to_string(),
+                    paid_break_minutes: 0,
+                    unpaid_break_minutes: 0,
                 })
                 .collect())
         }
@@ -114,6 +126,10 @@ pub async fn list_updates_for_user(
                deployment_updates.hours_worked,
                deployment_updates.notes,
                deployment_updates.is_placeholder,
+               deployment_updates.approval_status,
+               deployment_updates.review_note,
+               deployment_updates.paid_break_minutes,
+               deployment_updates.unpaid_break_minutes,
                COALESCE(users.email, '') as user_email
         FROM deployment_updates
         LEFT JOIN users ON users.id = deployment_updates.user_id
@@ -142,6 +158,10 @@ pub async fn list_updates_for_user(
                 hours_worked: row.get("hours_worked"),
                 notes: row.get("notes"),
                 is_placeholder: row.get::<i64, _>("is_placeholder") != 0,
+                approval_status: row.get("approval_status"),
+                review_note: row.get("review_note"),
+                paid_break_minutes: row.get("paid_break_minutes"),
+                unpaid_break_minutes: row.get("unpaid_break_minutes"),
             })
             .collect()),
         Err(err) if is_missing_user_id(&err) => Ok(Vec::new()),
@@ -149,6 +169,66 @@ pub async fn list_updates_for_user(
     }
 }
 
+pub async fn list_updates_for_user_between(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<DeploymentUpdate>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT deployment_updates.id,
+               deployment_updates.tenant_id,
+               deployment_updates.deployment_id,
+               deployment_updates.user_id,
+               deployment_updates.work_date,
+               deployment_updates.start_time,
+               deployment_updates.end_time,
+               deployment_updates.hours_worked,
+               deployment_updates.notes,
+               deployment_updates.is_placeholder,
+               deployment_updates.approval_status,
+               deployment_updates.review_note,
+               deployment_updates.paid_break_minutes,
+               deployment_updates.unpaid_break_minutes,
+               COALESCE(users.email, '') as user_email
+        FROM deployment_updates
+        LEFT JOIN users ON users.id = deployment_updates.user_id
+        WHERE deployment_updates.tenant_id = ? AND deployment_updates.user_id = ?
+          AND deployment_updates.work_date >= ? AND deployment_updates.work_date <= ?
+        ORDER BY deployment_updates.work_date ASC, deployment_updates.id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DeploymentUpdate {
+            id: row.get("id"),
+            tenant_id: row.get("tenant_id"),
+            deployment_id: row.get("deployment_id"),
+            user_id: row.get("user_id"),
+            user_email: row.get("user_email"),
+            work_date: row.get("work_date"),
+            start_time: row.get("start_time"),
+            end_time: row.get("end_time"),
+            hours_worked: row.get("hours_worked"),
+            notes: row.get("notes"),
+            is_placeholder: row.get::<i64, _>("is_placeholder") != 0,
+            approval_status: row.get("approval_status"),
+            review_note: row.get("review_note"),
+            paid_break_minutes: row.get("paid_break_minutes"),
+            unpaid_break_minutes: row.get("unpaid_break_minutes"),
+        })
+        .collect())
+}
+
 pub async fn find_update_by_id(
     db: &Db,
     tenant_id: i64,
@@ -165,7 +245,11 @@ pub async fn find_update_by_id(
                end_time,
                hours_worked,
                notes,
-               is_placeholder
+               is_placeholder,
+               approval_status,
+               review_note,
+               paid_break_minutes,
+               unpaid_break_minutes
         FROM deployment_updates
         WHERE tenant_id = ? AND id = ?
         "#,
@@ -188,6 +272,10 @@ pub async fn find_update_by_id(
             hours_worked: row.get("hours_worked"),
             notes: row.get("notes"),
             is_placeholder: row.get::<i64, _>("is_placeholder") != 0,
+            approval_status: row.get("approval_status"),
+            review_note: row.get("review_note"),
+            paid_break_minutes: row.get("paid_break_minutes"),
+            unpaid_break_minutes: row.get("unpaid_break_minutes"),
         })),
         Err(err) if is_missing_user_id(&err) => {
             let row = sqlx::query(
@@ -221,6 +309,10 @@ pub async fn find_update_by_id(
                 hours_worked: row.get("hours_worked"),
                 notes: row.get("notes"),
                 is_placeholder: false,
+                approval_status: "Approved".to_string(),
+                review_note: "".to_string(),
+                paid_break_minutes: 0,
+                unpaid_break_minutes: 0,
             }))
         }
         Err(err) => Err(err),
@@ -245,6 +337,10 @@ pub async fn find_update_by_date(
                deployment_updates.hours_worked,
                deployment_updates.notes,
                deployment_updates.is_placeholder,
+               deployment_updates.approval_status,
+               deployment_updates.review_note,
+               deployment_updates.paid_break_minutes,
+               deployment_updates.unpaid_break_minutes,
                COALESCE(users.email, '') as user_email
         FROM deployment_updates
         LEFT JOIN users ON users.id = deployment_updates.user_id
@@ -270,6 +366,10 @@ pub async fn find_update_by_date(
             hours_worked: row.get("hours_worked"),
             notes: row.get("notes"),
             is_placeholder: row.get::<i64, _>("is_placeholder") != 0,
+            approval_status: row.get("approval_status"),
+            review_note: row.get("review_note"),
+            paid_break_minutes: row.get("paid_break_minutes"),
+            unpaid_break_minutes: row.get("unpaid_break_minutes"),
         })),
         Err(err) if is_missing_user_id(&err) => {
             let row = sqlx::query(
@@ -297,6 +397,10 @@ pub async fn find_update_by_date(
                 hours_worked: row.get("hours_worked"),
                 notes: row.get("notes"),
                 is_placeholder: false,
+                approval_status: "Approved".to_string(),
+                review_note: "".to_string(),
+                paid_break_minutes: 0,
+                unpaid_break_minutes: 0,
             }))
         }
         Err(err) => Err(err),
@@ -322,6 +426,10 @@ pub async fn find_update_by_date_for_user(
                deployment_updates.hours_worked,
                deployment_updates.notes,
                deployment_updates.is_placeholder,
+               deployment_updates.approval_status,
+               deployment_updates.review_note,
+               deployment_updates.paid_break_minutes,
+               deployment_updates.unpaid_break_minutes,
                COALESCE(users.email, '') as user_email
         FROM deployment_updates
         LEFT JOIN users ON users.id = deployment_updates.user_id
@@ -349,6 +457,10 @@ pub async fn find_update_by_date_for_user(
             hours_worked: row.get("hours_worked"),
             notes: row.get("notes"),
             is_placeholder: row.get::<i64, _>("is_placeholder") != 0,
+            approval_status: row.get("approval_status"),
+            review_note: row.get("review_note"),
+            paid_break_minutes: row.get("paid_break_minutes"),
+            unpaid_break_minutes: row.get("unpaid_break_minutes"),
         })),
         Err(err) if is_missing_user_id(&err) => {
             let row = sqlx::query(
@@ -378,6 +490,10 @@ pub async fn find_update_by_date_for_user(
                 hours_worked: row.get("hours_worked"),
                 notes: row.get("notes"),
                 is_placeholder: false,
+                approval_status: "Approved".to_string(),
+                review_note: "".to_string(),
+                paid_break_minutes: 0,
+                unpaid_break_minutes: 0,
             }))
         }
         Err(err) => Err(err),
@@ -394,7 +510,7 @@ pub async fn create_update(
     end_time: &str,
     hours_worked: f64,
     notes: &str,
-) -> Result<(), sqlx::Error> {
+) -> Result<i64, sqlx::Error> {
     let result = sqlx::query(
         r#"
         INSERT INTO deployment_updates
@@ -414,7 +530,7 @@ pub async fn create_update(
     .await;
 
     match result {
-        Ok(_) => Ok(()),
+        Ok(result) => Ok(result.last_insert_rowid()),
         Err(err) if is_missing_user_id(&err) => {
             sqlx::query(
                 r#"
@@ -432,11 +548,10 @@ pub async fn create_update(
             .bind(notes)
             .execute(&db.0)
             .await
-            .map(|_| ())
+            .map(|result| result.last_insert_rowid())
         }
         Err(err) => Err(err),
-    }?;
-    Ok(())
+    }
 }
 
 pub async fn count_updates_for_crews(
@@ -484,8 +599,9 @@ pub async fn update_update(
     let result = sqlx::query(
         r#"
         UPDATE deployment_updates
-        SET work_date = ?, start_time = ?, end_time = ?, hours_worked = ?, notes = ?, is_placeholder = ?
-        WHERE tenant_id = ? AND deployment_id = ? AND id = ?
+        SET work_date = ?, start_time = ?, end_time = ?, hours_worked = ?, notes = ?, is_placeholder = ?,
+            approval_status = 'Submitted', reviewed_by_user_id = NULL, reviewed_at = NULL, review_note = ''
+        WHERE tenant_id = ? AND deployment_id = ? AND id = ? AND approval_status != 'Approved'
         "#,
     )
     .bind(work_date)
@@ -537,7 +653,7 @@ pub async fn create_placeholder_update(
     end_time: &str,
     hours_worked: f64,
     notes: &str,
-) -> Result<(), sqlx::Error> {
+) -> Result<i64, sqlx::Error> {
     let result = sqlx::query(
         r#"
         INSERT INTO deployment_updates
@@ -557,7 +673,7 @@ pub async fn create_placeholder_update(
     .await;
 
     match result {
-        Ok(_) => Ok(()),
+        Ok(result) => Ok(result.last_insert_rowid()),
         Err(err) if is_missing_user_id(&err) => {
             sqlx::query(
                 r#"
@@ -575,11 +691,10 @@ pub async fn create_placeholder_update(
             .bind(notes)
             .execute(&db.0)
             .await
-            .map(|_| ())
+            .map(|result| result.last_insert_rowid())
         }
         Err(err) => Err(err),
-    }?;
-    Ok(())
+    }
 }
 
 pub async fn count_updates_missing_user_id(
@@ -644,37 +759,6 @@ pub async fn count_completed_deployments(
     Ok(row.get("count"))
 }
 
-pub async fn count_completed_with_issue_keywords(
-    db: &Db,
-    tenant_id: i64,
-) -> Result<i64, sqlx::Error> {
-    let row = sqlx::query(
-        r#"
-        SELECT COUNT(*) as count
-        FROM deployments d
-        WHERE d.tenant_id = ? AND d.status = 'Completed'
-          AND EXISTS (
-              SELECT 1
-              FROM deployment_updates u
-              WHERE u.deployment_id = d.id
-                AND u.tenant_id = d.tenant_id
-                AND u.is_placeholder = 0
-                AND (
-                    lower(u.notes) LIKE '%issue%'
-                    OR lower(u.notes) LIKE '%problem%'
-                    OR lower(u.notes) LIKE '%blocked%'
-                    OR lower(u.notes) LIKE '%delay%'
-                    OR lower(u.notes) LIKE '%incident%'
-                )
-          )
-        "#,
-    )
-    .bind(tenant_id)
-    .fetch_one(&db.0)
-    .await?;
-    Ok(row.get("count"))
-}
-
 pub async fn count_completed_with_rework_keywords(
     db: &Db,
     tenant_id: i64,
@@ -704,57 +788,6 @@ pub async fn count_completed_with_rework_keywords(
     Ok(row.get("count"))
 }
 
-pub async fn list_issue_flags_for_deployments(
-    db: &Db,
-    tenant_id: i64,
-    deployment_ids: &[i64],
-) -> Result<Vec<(i64, i64, i64)>, sqlx::Error> {
-    if deployment_ids.is_empty() {
-        return Ok(Vec::new());
-    }
-    let placeholders = deployment_ids
-        .iter()
-        .map(|_| "?")
-        .collect::<Vec<_>>()
-        .join(", ");
-    let sql = format!(
-        r#"
-        SELECT deployment_id,
-               SUM(CASE
-                       WHEN lower(notes) LIKE '%issue%'
-                         OR lower(notes) LIKE '%problem%'
-                         OR lower(notes) LIKE '%blocked%'
-                         OR lower(notes) LIKE '%delay%'
-                         OR lower(notes) LIKE '%incident%'
-                       THEN 1 ELSE 0 END) as issue_count,
-               SUM(CASE
-                       WHEN lower(notes) LIKE '%resolv%'
-                         OR lower(notes) LIKE '%fix%'
-                         OR lower(notes) LIKE '%clear%'
-                       THEN 1 ELSE 0 END) as resolved_count
-        FROM deployment_updates
-        WHERE tenant_id = ? AND deployment_id IN ({}) AND is_placeholder = 0
-        GROUP BY deployment_id
-        "#,
-        placeholders
-    );
-    let mut query = sqlx::query(&sql).bind(tenant_id);
-    for deployment_id in deployment_ids {
-        query = query.bind(deployment_id);
-    }
-    let rows = query.fetch_all(&db.0).await?;
-    Ok(rows
-        .into_iter()
-        .map(|row| {
-            (
-                row.get("deployment_id"),
-                row.get("issue_count"),
-                row.get("resolved_count"),
-            )
-        })
-        .collect())
-}
-
 #[derive(serde::Serialize)]
 pub struct OverdueDeploymentRow {
     pub deployment_id: i64,
@@ -900,3 +933,80 @@ pub async fn list_latest_updates_for_deployments(
         })
         .collect())
 }
+
+pub async fn list_segments(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+) -> Result<Vec<UpdateSegment>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT kind, start_time, end_time, minutes
+        FROM deployment_update_segments
+        WHERE tenant_id = ? AND update_id = ?
+        ORDER BY start_time ASC, id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(update_id)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| UpdateSegment {
+            kind: row.get("kind"),
+            start_time: row.get("start_time"),
+            end_time: row.get("end_time"),
+            minutes: row.get("minutes"),
+        })
+        .collect())
+}
+
+pub async fn save_segments(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+    segments: &[UpdateSegment],
+    paid_break_minutes: i64,
+    unpaid_break_minutes: i64,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("DELETE FROM deployment_update_segments WHERE tenant_id = ? AND update_id = ?")
+        .bind(tenant_id)
+        .bind(update_id)
+        .execute(&mut *tx)
+        .await?;
+    for segment in segments {
+        sqlx::query(
+            r#"
+            INSERT INTO deployment_update_segments
+                (tenant_id, update_id, kind, start_time, end_time, minutes)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(update_id)
+        .bind(&segment.kind)
+        .bind(&segment.start_time)
+        .bind(&segment.end_time)
+        .bind(segment.minutes)
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query(
+        r#"
+        UPDATE deployment_updates
+        SET paid_break_minutes = ?, unpaid_break_minutes = ?
+        WHERE tenant_id = ? AND id = ?
+        "#,
+    )
+    .bind(paid_break_minutes)
+    .bind(unpaid_break_minutes)
+    .bind(tenant_id)
+    .bind(update_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use rocket_db_pools::sqlx::{self, sqlite::SqliteRow, Row};
+
+use crate::models::DeploymentExpense;
+use crate::Db;
+
+const EXPENSE_SELECT: &str = r#"
+    SELECT
+        deployment_expenses.id as id,
+        deployment_expenses.tenant_id as tenant_id,
+        deployment_expenses.deployment_id as deployment_id,
+        clients.company_name as client_name,
+        crews.name as crew_name,
+        deployment_expenses.submitted_by_user_id as submitted_by_user_id,
+        COALESCE(submitters.email, '') as submitted_by_email,
+        deployment_expenses.category as category,
+        deployment_expenses.description as description,
+        deployment_expenses.amount as amount,
+        deployment_expenses.currency as currency,
+        deployment_expenses.incurred_on as incurred_on,
+        deployment_expenses.receipt_path as receipt_path,
+        deployment_expenses.is_billable as is_billable,
+        deployment_expenses.markup_percent as markup_percent,
+        deployment_expenses.status as status,
+        reviewers.email as reviewed_by_email,
+        deployment_expenses.reviewed_at as reviewed_at,
+        deployment_expenses.review_note as review_note,
+        deployment_expenses.created_at as created_at
+    FROM deployment_expenses
+    JOIN deployments ON deployment_expenses.deployment_id = deployments.id
+    JOIN clients ON deployments.client_id = clients.id
+    JOIN crews ON deployments.crew_id = crews.id
+    LEFT JOIN users submitters ON deployment_expenses.submitted_by_user_id = submitters.id
+    LEFT JOIN users reviewers ON deployment_expenses.reviewed_by_user_id = reviewers.id
+"#;
+
+fn map_expense(row: SqliteRow) -> DeploymentExpense {
+    let amount: f64 = row.get("amount");
+    let markup_percent: f64 = row.get("markup_percent");
+    let is_billable = row.get::<i64, _>("is_billable") != 0;
+    DeploymentExpense {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        deployment_id: row.get("deployment_id"),
+        client_name: row.get("client_name"),
+        crew_name: row.get("crew_name"),
+        submitted_by_user_id: row.get("submitted_by_user_id"),
+        submitted_by_email: row.get("submitted_by_email"),
+        category: row.get("category"),
+        description: row.get("description"),
+        amount,
+        currency: row.get("currency"),
+        incurred_on: row.get("incurred_on"),
+        receipt_path: row.get("receipt_path"),
+        is_billable,
+        markup_percent,
+        billable_amount: if is_billable {
+            (amount * (1.0 + markup_percent / 100.0) * 100.0).round() / 100.0
+        } else {
+            0.0
+        },
+        status: row.get("status"),
+        reviewed_by_email: row.get("reviewed_by_email"),
+        reviewed_at: row.get("reviewed_at"),
+        review_note: row.get("review_note"),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub async fn list_expenses_by_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentExpense>, sqlx::Error> {
+    let sql = format!(
+        "{EXPENSE_SELECT} WHERE deployment_expenses.tenant_id = ? AND deployment_expenses.deployment_id = ? ORDER BY deployment_expenses.incurred_on DESC, deployment_expenses.id DESC"
+    );
+    let rows = sqlx::query(&sql)
+        .bind(tenant_id)
+        .bind(deployment_id)
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(map_expense).collect())
+}
+
+pub async fn list_expenses_by_deployment_for_user(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+) -> Result<Vec<DeploymentExpense>, sqlx::Error> {
+    let sql = format!(
+        "{EXPENSE_SELECT} WHERE deployment_expenses.tenant_id = ? AND deployment_expenses.deployment_id = ? AND deployment_expenses.submitted_by_user_id = ? ORDER BY deployment_expenses.incurred_on DESC, deployment_expenses.id DESC"
+    );
+    let rows = sqlx::query(&sql)
+        .bind(tenant_id)
+        .bind(deployment_id)
+        .bind(user_id)
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(map_expense).collect())
+}
+
+pub async fn list_expenses_by_status(
+    db: &Db,
+    tenant_id: i64,
+    status: Option<&str>,
+) -> Result<Vec<DeploymentExpense>, sqlx::Error> {
+    let sql = match status {
+        Some(_) => format!(
+            "{EXPENSE_SELECT} WHERE deployment_expenses.tenant_id = ? AND deployment_expenses.status = ? ORDER BY deployment_expenses.created_at DESC, deployment_expenses.id DESC"
+        ),
+        None => format!(
+            "{EXPENSE_SELECT} WHERE deployment_expenses.tenant_id = ? ORDER BY deployment_expenses.created_at DESC, deployment_expenses.id DESC"
+        ),
+    };
+    let mut query = sqlx::query(&sql).bind(tenant_id);
+    if let Some(status) = status {
+        query = query.bind(status);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+    Ok(rows.into_iter().map(map_expense).collect())
+}
+
+pub async fn list_billable_expenses_for_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentExpense>, sqlx::Error> {
+    let sql = format!(
+        "{EXPENSE_SELECT} WHERE deployment_expenses.tenant_id = ? AND deployment_expenses.deployment_id = ? AND deployment_expenses.status = 'Approved' AND deployment_expenses.is_billable = 1 ORDER BY deployment_expenses.incurred_on ASC, deployment_expenses.id ASC"
+    );
+    let rows = sqlx::query(&sql)
+        .bind(tenant_id)
+        .bind(deployment_id)
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(map_expense).collect())
+}
+
+pub async fn find_expense_by_id(
+    db: &Db,
+    tenant_id: i64,
+    expense_id: i64,
+) -> Result<Option<DeploymentExpense>, sqlx::Error> {
+    let sql = format!(
+        "{EXPENSE_SELECT} WHERE deployment_expenses.tenant_id = ? AND deployment_expenses.id = ?"
+    );
+    let row = sqlx::query(&sql)
+        .bind(tenant_id)
+        .bind(expense_id)
+        .fetch_optional(&db.0)
+        .await?;
+    Ok(row.map(map_expense))
+}
+
+pub async fn create_expense(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    submitted_by_user_id: i64,
+    category: &str,
+    description: &str,
+    amount: f64,
+    currency: &str,
+    incurred_on: &str,
+    is_billable: bool,
+    markup_percent: f64,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO deployment_expenses
+            (tenant_id, deployment_id, submitted_by_user_id, category, description, amount, currency, incurred_on, is_billable, markup_percent)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(deployment_id)
+    .bind(submitted_by_user_id)
+    .bind(category)
+    .bind(description)
+    .bind(amount)
+    .bind(currency)
+    .bind(incurred_on)
+    .bind(if is_billable { 1 } else { 0 })
+    .bind(markup_percent)
+    .execute(&db.0)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn update_receipt_path(
+    db: &Db,
+    tenant_id: i64,
+    expense_id: i64,
+    receipt_path: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE deployment_expenses SET receipt_path = ? WHERE id = ? AND tenant_id = ?")
+        .bind(receipt_path)
+        .bind(expense_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn review_expense(
+    db: &Db,
+    tenant_id: i64,
+    expense_id: i64,
+    status: &str,
+    reviewed_by_user_id: i64,
+    review_note: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE deployment_expenses
+        SET status = ?, reviewed_by_user_id = ?, reviewed_at = datetime('now'), review_note = ?
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(status)
+    .bind(reviewed_by_user_id)
+    .bind(review_note)
+    .bind(expense_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_expense(db: &Db, tenant_id: i64, expense_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM deployment_expenses WHERE id = ? AND tenant_id = ?")
+        .bind(expense_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn count_expenses_by_status(
+    db: &Db,
+    tenant_id: i64,
+    status: &str,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COUNT(*) as count FROM deployment_expenses WHERE tenant_id = ? AND status = ?",
+    )
+    .bind(tenant_id)
+    .bind(status)
+    .fetch_one(&db.0)
+    .await?;
+    Ok(row.get("count"))
+}
@@ -0,0 +1,158 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{CrewHeldAsset, RequiredKitItem};
+use crate::Db;
+
+pub async fn list_required_kit(db: &Db, tenant_id: i64) -> Result<Vec<RequiredKitItem>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT skill_tag, asset_type, quantity
+        FROM required_kit
+        WHERE tenant_id = ?
+        ORDER BY skill_tag ASC, asset_type ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| RequiredKitItem {
+            skill_tag: row.get("skill_tag"),
+            asset_type: row.get("asset_type"),
+            quantity: row.get("quantity"),
+        })
+        .collect())
+}
+
+pub async fn replace_required_kit(
+    db: &Db,
+    tenant_id: i64,
+    items: &[RequiredKitItem],
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("DELETE FROM required_kit WHERE tenant_id = ?")
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    for item in items {
+        sqlx::query(
+            "INSERT INTO required_kit (tenant_id, skill_tag, asset_type, quantity) VALUES (?, ?, ?, ?)",
+        )
+        .bind(tenant_id)
+        .bind(&item.skill_tag)
+        .bind(&item.asset_type)
+        .bind(item.quantity)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list_crew_held_assets(
+    db: &Db,
+    tenant_id: i64,
+    now: &str,
+) -> Result<Vec<CrewHeldAsset>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            asset_assignments.crew_id as crew_id,
+            assets.id as asset_id,
+            assets.name as name,
+            assets.asset_type as asset_type,
+            assets.condition as condition
+        FROM asset_assignments
+        JOIN assets
+            ON assets.id = asset_assignments.asset_id
+            AND assets.tenant_id = asset_assignments.tenant_id
+        WHERE asset_assignments.tenant_id = ?
+          AND asset_assignments.crew_id IS NOT NULL
+          AND asset_assignments.checked_in_at IS NULL
+          AND asset_assignments.checked_out_at <= ?
+        ORDER BY assets.asset_type ASC, assets.name ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(now)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| CrewHeldAsset {
+            crew_id: row.get("crew_id"),
+            asset_id: row.get("asset_id"),
+            name: row.get("name"),
+            asset_type: row.get("asset_type"),
+            condition: row.get("condition"),
+            maintenance: String::new(),
+        })
+        .collect())
+}
+
+pub async fn list_gear_overrides(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<(i64, Option<i64>, String)>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, gear_override_score, gear_override_reason FROM crews WHERE tenant_id = ?",
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.get("id"),
+                row.get("gear_override_score"),
+                row.get("gear_override_reason"),
+            )
+        })
+        .collect())
+}
+
+pub async fn update_gear_override(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    score: Option<i64>,
+    reason: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE crews SET gear_override_score = ?, gear_override_reason = ? WHERE id = ? AND tenant_id = ?",
+    )
+    .bind(score)
+    .bind(reason)
+    .bind(crew_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn update_gear_scores(
+    db: &Db,
+    tenant_id: i64,
+    scores: &[(i64, i64)],
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    for (crew_id, score) in scores {
+        sqlx::query("UPDATE crews SET gear_score = ? WHERE id = ? AND tenant_id = ?")
+            .bind(score)
+            .bind(crew_id)
+            .bind(tenant_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list_tenant_ids_with_crews(db: &Db) -> Result<Vec<i64>, sqlx::Error> {
+    let rows = sqlx::query("SELECT DISTINCT tenant_id FROM crews ORDER BY tenant_id ASC")
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(|row| row.get("tenant_id")).collect())
+}
@@ -0,0 +1,322 @@
+use std::collections::{BTreeSet, HashMap};
+
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{
+    ClientImportRecord,
+    ContactImportRecord,
+    CrewMemberImportRecord,
+    DataImport,
+    DataImportSummary,
+};
+use crate::Db;
+
+pub async fn create_import(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    entity: &str,
+    file_name: &str,
+    headers: &str,
+    header_line: i64,
+    rows: &str,
+    row_count: i64,
+    mapping: &str,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO data_imports (
+            tenant_id, user_id, entity, file_name, headers, header_line, rows, row_count, mapping
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(user_id)
+    .bind(entity)
+    .bind(file_name)
+    .bind(headers)
+    .bind(header_line)
+    .bind(rows)
+    .bind(row_count)
+    .bind(mapping)
+    .execute(&db.0)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn find_import(
+    db: &Db,
+    tenant_id: i64,
+    import_id: i64,
+) -> Result<Option<DataImport>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, entity, file_name, headers, header_line, rows, mapping, status, imported_count,
+               skipped_count, error_report, created_at, completed_at
+        FROM data_imports
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(import_id)
+    .bind(tenant_id)
+    .fetch_optional(&db.0)
+    .await?;
+
+    Ok(row.map(|row| {
+        let headers: String = row.get("headers");
+        let rows: String = row.get("rows");
+        let mapping: String = row.get("mapping");
+        DataImport {
+            id: row.get("id"),
+            entity: row.get("entity"),
+            file_name: row.get("file_name"),
+            headers: serde_json::from_str(&headers).unwrap_or_default(),
+            header_line: row.get::<i64, _>("header_line").max(1) as usize,
+            rows: serde_json::from_str(&rows).unwrap_or_default(),
+            mapping: serde_json::from_str::<HashMap<String, usize>>(&mapping).unwrap_or_default(),
+            status: row.get("status"),
+            imported_count: row.get("imported_count"),
+            skipped_count: row.get("skipped_count"),
+            error_report: row.get("error_report"),
+            created_at: row.get("created_at"),
+            completed_at: row.get("completed_at"),
+        }
+    }))
+}
+
+pub async fn list_imports(
+    db: &Db,
+    tenant_id: i64,
+    limit: i64,
+) -> Result<Vec<DataImportSummary>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, entity, file_name, row_count, status, imported_count, skipped_count, created_at
+        FROM data_imports
+        WHERE tenant_id = ?
+        ORDER BY id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(limit)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DataImportSummary {
+            id: row.get("id"),
+            entity: row.get("entity"),
+            file_name: row.get("file_name"),
+            row_count: row.get("row_count"),
+            status: row.get("status"),
+            imported_count: row.get("imported_count"),
+            skipped_count: row.get("skipped_count"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+pub async fn update_mapping(
+    db: &Db,
+    tenant_id: i64,
+    import_id: i64,
+    mapping: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE data_imports SET mapping = ? WHERE id = ? AND tenant_id = ? AND status = 'Draft'")
+        .bind(mapping)
+        .bind(import_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+/// Marks a draft import as completed inside the commit transaction. Returns
+/// false when another request already committed it, so the caller can roll
+/// back instead of inserting the rows twice.
+async fn complete_import(
+    conn: &mut sqlx::SqliteConnection,
+    tenant_id: i64,
+    import_id: i64,
+    imported_count: i64,
+    skipped_count: i64,
+    error_report: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE data_imports
+        SET status = 'Completed',
+            imported_count = ?,
+            skipped_count = ?,
+            error_report = ?,
+            completed_at = datetime('now')
+        WHERE id = ? AND tenant_id = ? AND status = 'Draft'
+        "#,
+    )
+    .bind(imported_count)
+    .bind(skipped_count)
+    .bind(error_report)
+    .bind(import_id)
+    .bind(tenant_id)
+    .execute(conn)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+async fn insert_custom_values(
+    conn: &mut sqlx::SqliteConnection,
+    tenant_id: i64,
+    record_id: i64,
+    values: &[(i64, String)],
+) -> Result<(), sqlx::Error> {
+    for (definition_id, value) in values {
+        sqlx::query(
+            r#"
+            INSERT INTO custom_field_values (tenant_id, definition_id, record_id, value)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(definition_id, record_id) DO UPDATE SET value = excluded.value
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(definition_id)
+        .bind(record_id)
+        .bind(value)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+pub async fn commit_clients(
+    db: &Db,
+    tenant_id: i64,
+    import_id: i64,
+    records: &[ClientImportRecord],
+    skipped_count: i64,
+    error_report: &str,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    if !complete_import(&mut tx, tenant_id, import_id, records.len() as i64, skipped_count, error_report).await? {
+        return Ok(false);
+    }
+    for record in records {
+        let client_id = sqlx::query(
+            r#"
+            INSERT INTO clients (tenant_id, company_name, address, phone, email, latitude, longitude, stage, currency, portal_token)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(&record.company_name)
+        .bind(&record.address)
+        .bind(&record.phone)
+        .bind(&record.email)
+        .bind(&record.latitude)
+        .bind(&record.longitude)
+        .bind(&record.stage)
+        .bind(&record.currency)
+        .bind(&record.portal_token)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        insert_custom_values(&mut tx, tenant_id, client_id, &record.custom).await?;
+    }
+    tx.commit().await?;
+    Ok(true)
+}
+
+pub async fn commit_contacts(
+    db: &Db,
+    tenant_id: i64,
+    import_id: i64,
+    records: &[ContactImportRecord],
+    skipped_count: i64,
+    error_report: &str,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    if !complete_import(&mut tx, tenant_id, import_id, records.len() as i64, skipped_count, error_report).await? {
+        return Ok(false);
+    }
+    for record in records {
+        let contact_id = sqlx::query(
+            r#"
+            INSERT INTO client_contacts (tenant_id, client_id, name, address, email, phone, department, position)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(record.client_id)
+        .bind(&record.name)
+        .bind(&record.address)
+        .bind(&record.email)
+        .bind(&record.phone)
+        .bind(&record.department)
+        .bind(&record.position)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        insert_custom_values(&mut tx, tenant_id, contact_id, &record.custom).await?;
+    }
+    tx.commit().await?;
+    Ok(true)
+}
+
+pub async fn commit_members(
+    db: &Db,
+    tenant_id: i64,
+    import_id: i64,
+    records: &[CrewMemberImportRecord],
+    skipped_count: i64,
+    error_report: &str,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    if !complete_import(&mut tx, tenant_id, import_id, records.len() as i64, skipped_count, error_report).await? {
+        return Ok(false);
+    }
+    for record in records {
+        let member_id = sqlx::query(
+            r#"
+            INSERT INTO crew_members (crew_id, tenant_id, user_id, name, phone, email, position, availability_status, hourly_cost)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(record.crew_id)
+        .bind(tenant_id)
+        .bind(record.user_id)
+        .bind(&record.name)
+        .bind(&record.phone)
+        .bind(&record.email)
+        .bind(&record.position)
+        .bind(&record.availability_status)
+        .bind(record.hourly_cost)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        insert_custom_values(&mut tx, tenant_id, member_id, &record.custom).await?;
+    }
+    let crew_ids = records.iter().map(|record| record.crew_id).collect::<BTreeSet<_>>();
+    for crew_id in crew_ids {
+        sqlx::query(
+            r#"
+            UPDATE crews
+            SET members_count = (
+                SELECT COUNT(*)
+                FROM crew_members
+                WHERE crew_id = ? AND tenant_id = ?
+            )
+            WHERE id = ? AND tenant_id = ?
+            "#,
+        )
+        .bind(crew_id)
+        .bind(tenant_id)
+        .bind(crew_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(true)
+}
@@ -0,0 +1,289 @@
+use rocket_db_pools::sqlx::{self, sqlite::SqliteRow, Row};
+
+use crate::models::DeploymentIncident;
+use crate::Db;
+
+const INCIDENT_SELECT: &str = r#"
+    SELECT
+        deployment_incidents.id as id,
+        deployment_incidents.tenant_id as tenant_id,
+        deployment_incidents.deployment_id as deployment_id,
+        clients.company_name as client_name,
+        crews.name as crew_name,
+        deployment_incidents.title as title,
+        deployment_incidents.description as description,
+        deployment_incidents.severity as severity,
+        deployment_incidents.category as category,
+        deployment_incidents.status as status,
+        deployment_incidents.reported_by_user_id as reported_by_user_id,
+        COALESCE(reporters.email, '') as reported_by_email,
+        deployment_incidents.assignee_user_id as assignee_user_id,
+        assignees.email as assignee_email,
+        deployment_incidents.resolution_notes as resolution_notes,
+        deployment_incidents.occurred_at as occurred_at,
+        deployment_incidents.resolved_at as resolved_at,
+        deployment_incidents.created_at as created_at,
+        deployment_incidents.updated_at as updated_at
+    FROM deployment_incidents
+    JOIN deployments ON deployment_incidents.deployment_id = deployments.id
+    JOIN clients ON deployments.client_id = clients.id
+    JOIN crews ON deployments.crew_id = crews.id
+    LEFT JOIN users reporters ON deployment_incidents.reported_by_user_id = reporters.id
+    LEFT JOIN users assignees ON deployment_incidents.assignee_user_id = assignees.id
+"#;
+
+const INCIDENT_ORDER: &str = r#"
+    ORDER BY
+        CASE deployment_incidents.status WHEN 'Resolved' THEN 1 ELSE 0 END,
+        CASE deployment_incidents.severity
+            WHEN 'Critical' THEN 0
+            WHEN 'High' THEN 1
+            WHEN 'Medium' THEN 2
+            ELSE 3
+        END,
+        deployment_incidents.occurred_at DESC,
+        deployment_incidents.id DESC
+"#;
+
+fn map_incident(row: SqliteRow) -> DeploymentIncident {
+    DeploymentIncident {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        deployment_id: row.get("deployment_id"),
+        client_name: row.get("client_name"),
+        crew_name: row.get("crew_name"),
+        title: row.get("title"),
+        description: row.get("description"),
+        severity: row.get("severity"),
+        category: row.get("category"),
+        status: row.get("status"),
+        reported_by_user_id: row.get("reported_by_user_id"),
+        reported_by_email: row.get("reported_by_email"),
+        assignee_user_id: row.get("assignee_user_id"),
+        assignee_email: row.get("assignee_email"),
+        resolution_notes: row.get("resolution_notes"),
+        occurred_at: row.get("occurred_at"),
+        resolved_at: row.get("resolved_at"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+pub async fn list_incidents_by_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentIncident>, sqlx::Error> {
+    let sql = format!(
+        "{INCIDENT_SELECT} WHERE deployment_incidents.tenant_id = ? AND deployment_incidents.deployment_id = ? {INCIDENT_ORDER}"
+    );
+    let rows = sqlx::query(&sql)
+        .bind(tenant_id)
+        .bind(deployment_id)
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(map_incident).collect())
+}
+
+pub async fn list_incidents(
+    db: &Db,
+    tenant_id: i64,
+    status: Option<&str>,
+) -> Result<Vec<DeploymentIncident>, sqlx::Error> {
+    let rows = match status {
+        Some(status) => {
+            let sql = format!(
+                "{INCIDENT_SELECT} WHERE deployment_incidents.tenant_id = ? AND deployment_incidents.status = ? {INCIDENT_ORDER}"
+            );
+            sqlx::query(&sql)
+                .bind(tenant_id)
+                .bind(status)
+                .fetch_all(&db.0)
+                .await?
+        }
+        None => {
+            let sql = format!(
+                "{INCIDENT_SELECT} WHERE deployment_incidents.tenant_id = ? {INCIDENT_ORDER}"
+            );
+            sqlx::query(&sql).bind(tenant_id).fetch_all(&db.0).await?
+        }
+    };
+    Ok(rows.into_iter().map(map_incident).collect())
+}
+
+pub async fn find_incident_by_id(
+    db: &Db,
+    tenant_id: i64,
+    incident_id: i64,
+) -> Result<Option<DeploymentIncident>, sqlx::Error> {
+    let sql = format!(
+        "{INCIDENT_SELECT} WHERE deployment_incidents.id = ? AND deployment_incidents.tenant_id = ?"
+    );
+    let row = sqlx::query(&sql)
+        .bind(incident_id)
+        .bind(tenant_id)
+        .fetch_optional(&db.0)
+        .await?;
+    Ok(row.map(map_incident))
+}
+
+pub async fn create_incident(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    reported_by_user_id: i64,
+    title: &str,
+    description: &str,
+    severity: &str,
+    category: &str,
+    occurred_at: &str,
+    assignee_user_id: Option<i64>,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO deployment_incidents
+            (tenant_id, deployment_id, reported_by_user_id, title, description, severity, category, occurred_at, assignee_user_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(deployment_id)
+    .bind(reported_by_user_id)
+    .bind(title)
+    .bind(description)
+    .bind(severity)
+    .bind(category)
+    .bind(occurred_at)
+    .bind(assignee_user_id)
+    .execute(&db.0)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn update_incident(
+    db: &Db,
+    tenant_id: i64,
+    incident_id: i64,
+    title: &str,
+    description: &str,
+    severity: &str,
+    category: &str,
+    status: &str,
+    assignee_user_id: Option<i64>,
+    resolution_notes: &str,
+    resolved_at: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE deployment_incidents
+        SET title = ?, description = ?, severity = ?, category = ?, status = ?,
+            assignee_user_id = ?, resolution_notes = ?, resolved_at = ?,
+            updated_at = datetime('now')
+        WHERE id = ? AND tenant_id = ?
+        "#,
+    )
+    .bind(title)
+    .bind(description)
+    .bind(severity)
+    .bind(category)
+    .bind(status)
+    .bind(assignee_user_id)
+    .bind(resolution_notes)
+    .bind(resolved_at)
+    .bind(incident_id)
+    .bind(tenant_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_incident(
+    db: &Db,
+    tenant_id: i64,
+    incident_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM deployment_incidents WHERE id = ? AND tenant_id = ?")
+        .bind(incident_id)
+        .bind(tenant_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn count_incidents_by_status(
+    db: &Db,
+    tenant_id: i64,
+    status: &str,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COUNT(*) as count FROM deployment_incidents WHERE tenant_id = ? AND status = ?",
+    )
+    .bind(tenant_id)
+    .bind(status)
+    .fetch_one(&db.0)
+    .await?;
+    Ok(row.get("count"))
+}
+
+pub async fn incident_counts_for_deployments(
+    db: &Db,
+    tenant_id: i64,
+    deployment_ids: &[i64],
+) -> Result<Vec<(i64, i64, i64)>, sqlx::Error> {
+    if deployment_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = deployment_ids
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        r#"
+        SELECT deployment_id,
+               COUNT(*) as incident_count,
+               SUM(CASE WHEN status != 'Resolved' THEN 1 ELSE 0 END) as open_count
+        FROM deployment_incidents
+        WHERE tenant_id = ? AND deployment_id IN ({})
+        GROUP BY deployment_id
+        "#,
+        placeholders
+    );
+    let mut query = sqlx::query(&sql).bind(tenant_id);
+    for deployment_id in deployment_ids {
+        query = query.bind(deployment_id);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.get("deployment_id"),
+                row.get("incident_count"),
+                row.get("open_count"),
+            )
+        })
+        .collect())
+}
+
+pub async fn count_completed_with_incidents(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COUNT(*) as count
+        FROM deployments d
+        WHERE d.tenant_id = ? AND d.status = 'Completed'
+          AND EXISTS (
+              SELECT 1
+              FROM deployment_incidents i
+              WHERE i.deployment_id = d.id AND i.tenant_id = d.tenant_id
+          )
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_one(&db.0)
+    .await?;
+    Ok(row.get("count"))
+}
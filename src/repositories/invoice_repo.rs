@@ -25,7 +25,16 @@ pub async fn list_invoices_with_details(
             deployments.start_at as start_at,
             deployments.end_at as end_at,
             deployments.fee_per_hour as fee_per_hour,
-            COALESCE(SUM(deployment_updates.hours_worked), 0.0) as total_hours
+            COALESCE(SUM(deployment_updates.hours_worked), 0.0) as total_hours,
+            (
+                SELECT COALESCE(ROUND(SUM(ROUND(deployment_expenses.amount * (1 + deployment_expenses.markup_percent / 100.0), 2)), 2), 0.0)
+                FROM deployment_expenses
+                WHERE deployment_expenses.tenant_id = deployments.tenant_id
+                  AND deployment_expenses.deployment_id = deployments.id
+                  AND deployment_expenses.status = 'Approved'
+                  AND deployment_expenses.is_billable = 1
+                  AND deployment_expenses.currency = clients.currency
+            ) as expense_total
         FROM invoices
         JOIN deployments ON invoices.deployment_id = deployments.id
         JOIN clients ON deployments.client_id = clients.id
@@ -34,6 +43,7 @@ pub async fn list_invoices_with_details(
             ON deployment_updates.deployment_id = deployments.id
             AND deployment_updates.tenant_id = invoices.tenant_id
             AND deployment_updates.is_placeholder = 0
+            AND deployment_updates.approval_status = 'Approved'
         WHERE invoices.tenant_id = ?
         GROUP BY invoices.id
         ORDER BY invoices.created_at DESC, invoices.id DESC
@@ -62,6 +72,7 @@ pub async fn list_invoices_with_details(
             end_at: row.get("end_at"),
             fee_per_hour: row.get("fee_per_hour"),
             total_hours: row.get("total_hours"),
+            expense_total: row.get("expense_total"),
         })
         .collect())
 }
@@ -89,7 +100,16 @@ pub async fn find_invoice_with_details(
             deployments.start_at as start_at,
             deployments.end_at as end_at,
             deployments.fee_per_hour as fee_per_hour,
-            COALESCE(SUM(deployment_updates.hours_worked), 0.0) as total_hours
+            COALESCE(SUM(deployment_updates.hours_worked), 0.0) as total_hours,
+            (
+                SELECT COALESCE(ROUND(SUM(ROUND(deployment_expenses.amount * (1 + deployment_expenses.markup_percent / 100.0), 2)), 2), 0.0)
+                FROM deployment_expenses
+                WHERE deployment_expenses.tenant_id = deployments.tenant_id
+                  AND deployment_expenses.deployment_id = deployments.id
+                  AND deployment_expenses.status = 'Approved'
+                  AND deployment_expenses.is_billable = 1
+                  AND deployment_expenses.currency = clients.currency
+            ) as expense_total
         FROM invoices
         JOIN deployments ON invoices.deployment_id = deployments.id
         JOIN clients ON deployments.client_id = clients.id
@@ -98,6 +118,7 @@ pub async fn find_invoice_with_details(
             ON deployment_updates.deployment_id = deployments.id
             AND deployment_updates.tenant_id = invoices.tenant_id
             AND deployment_updates.is_placeholder = 0
+            AND deployment_updates.approval_status = 'Approved'
         WHERE invoices.tenant_id = ? AND invoices.id = ?
         GROUP BY invoices.id
         LIMIT 1
@@ -125,6 +146,7 @@ pub async fn find_invoice_with_details(
         end_at: row.get("end_at"),
         fee_per_hour: row.get("fee_per_hour"),
         total_hours: row.get("total_hours"),
+        expense_total: row.get("expense_total"),
     }))
 }
 
@@ -238,7 +260,16 @@ pub async fn list_completed_deployments_without_invoice(
             deployments.end_at as end_at,
             deployments.fee_per_hour as fee_per_hour,
             clients.currency as client_currency,
-            COALESCE(SUM(deployment_updates.hours_worked), 0.0) as total_hours
+            COALESCE(SUM(deployment_updates.hours_worked), 0.0) as total_hours,
+            (
+                SELECT COALESCE(ROUND(SUM(ROUND(deployment_expenses.amount * (1 + deployment_expenses.markup_percent / 100.0), 2)), 2), 0.0)
+                FROM deployment_expenses
+                WHERE deployment_expenses.tenant_id = deployments.tenant_id
+                  AND deployment_expenses.deployment_id = deployments.id
+                  AND deployment_expenses.status = 'Approved'
+                  AND deployment_expenses.is_billable = 1
+                  AND deployment_expenses.currency = clients.currency
+            ) as expense_total
         FROM deployments
         JOIN clients ON deployments.client_id = clients.id
         JOIN crews ON deployments.crew_id = crews.id
@@ -249,6 +280,7 @@ pub async fn list_completed_deployments_without_invoice(
             ON deployment_updates.deployment_id = deployments.id
             AND deployment_updates.tenant_id = deployments.tenant_id
             AND deployment_updates.is_placeholder = 0
+            AND deployment_updates.approval_status = 'Approved'
         WHERE deployments.tenant_id = ?
             AND deployments.status = 'Completed'
             AND invoices.id IS NULL
@@ -271,6 +303,7 @@ pub async fn list_completed_deployments_without_invoice(
             fee_per_hour: row.get("fee_per_hour"),
             client_currency: row.get("client_currency"),
             total_hours: row.get("total_hours"),
+            expense_total: row.get("expense_total"),
         })
         .collect())
 }
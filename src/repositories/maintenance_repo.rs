@@ -0,0 +1,271 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{MaintenanceLog, MaintenancePlan};
+use crate::Db;
+
+pub struct UsageWindow {
+    pub asset_id: i64,
+    pub checked_out_at: String,
+    pub checked_in_at: Option<String>,
+    pub work_started_at: String,
+    pub hours_worked: f64,
+}
+
+fn map_plan(row: sqlx::sqlite::SqliteRow) -> MaintenancePlan {
+    MaintenancePlan {
+        id: row.get("id"),
+        asset_type: row.get("asset_type"),
+        name: row.get("name"),
+        interval_days: row.get("interval_days"),
+        interval_hours: row.get("interval_hours"),
+        created_at: row.get("created_at"),
+    }
+}
+
+pub async fn list_plans(db: &Db, tenant_id: i64) -> Result<Vec<MaintenancePlan>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, asset_type, name, interval_days, interval_hours, created_at
+        FROM maintenance_plans
+        WHERE tenant_id = ?
+        ORDER BY asset_type ASC, name ASC, id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.into_iter().map(map_plan).collect())
+}
+
+pub async fn find_plan(
+    db: &Db,
+    tenant_id: i64,
+    plan_id: i64,
+) -> Result<Option<MaintenancePlan>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT id, asset_type, name, interval_days, interval_hours, created_at FROM maintenance_plans WHERE id = ? AND tenant_id = ?",
+    )
+    .bind(plan_id)
+    .bind(tenant_id)
+    .fetch_optional(&db.0)
+    .await?;
+    Ok(row.map(map_plan))
+}
+
+pub async fn create_plan(
+    db: &Db,
+    tenant_id: i64,
+    asset_type: &str,
+    name: &str,
+    interval_days: Option<i64>,
+    interval_hours: Option<f64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO maintenance_plans (tenant_id, asset_type, name, interval_days, interval_hours)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(asset_type)
+    .bind(name)
+    .bind(interval_days)
+    .bind(interval_hours)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_plan(db: &Db, tenant_id: i64, plan_id: i64) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    sqlx::query("UPDATE maintenance_logs SET plan_id = NULL WHERE plan_id = ? AND tenant_id = ?")
+        .bind(plan_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM maintenance_reminders WHERE plan_id = ? AND tenant_id = ?")
+        .bind(plan_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM maintenance_plans WHERE id = ? AND tenant_id = ?")
+        .bind(plan_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list_logs_for_asset(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+) -> Result<Vec<MaintenanceLog>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            maintenance_logs.id as id,
+            maintenance_logs.asset_id as asset_id,
+            maintenance_logs.plan_id as plan_id,
+            maintenance_plans.name as plan_name,
+            maintenance_logs.performed_at as performed_at,
+            COALESCE(users.email, '') as performed_by_email,
+            maintenance_logs.cost as cost,
+            maintenance_logs.notes as notes
+        FROM maintenance_logs
+        LEFT JOIN maintenance_plans
+            ON maintenance_plans.id = maintenance_logs.plan_id
+            AND maintenance_plans.tenant_id = maintenance_logs.tenant_id
+        LEFT JOIN users ON users.id = maintenance_logs.performed_by
+        WHERE maintenance_logs.tenant_id = ? AND maintenance_logs.asset_id = ?
+        ORDER BY maintenance_logs.performed_at DESC, maintenance_logs.id DESC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(asset_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| MaintenanceLog {
+            id: row.get("id"),
+            asset_id: row.get("asset_id"),
+            plan_id: row.get("plan_id"),
+            plan_name: row.get("plan_name"),
+            performed_at: row.get("performed_at"),
+            performed_by_email: row.get("performed_by_email"),
+            cost: row.get("cost"),
+            notes: row.get("notes"),
+        })
+        .collect())
+}
+
+pub async fn list_last_services(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<(i64, i64, String)>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT asset_id, plan_id, MAX(performed_at) as performed_at
+        FROM maintenance_logs
+        WHERE tenant_id = ? AND plan_id IS NOT NULL
+        GROUP BY asset_id, plan_id
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("asset_id"), row.get("plan_id"), row.get("performed_at")))
+        .collect())
+}
+
+pub async fn create_log(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+    plan_id: Option<i64>,
+    performed_at: &str,
+    performed_by: i64,
+    cost: f64,
+    notes: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO maintenance_logs (tenant_id, asset_id, plan_id, performed_at, performed_by, cost, notes)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(asset_id)
+    .bind(plan_id)
+    .bind(performed_at)
+    .bind(performed_by)
+    .bind(cost)
+    .bind(notes)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_usage_windows(
+    db: &Db,
+    tenant_id: i64,
+    excluded_statuses: &[&str],
+) -> Result<Vec<UsageWindow>, sqlx::Error> {
+    let placeholders = excluded_statuses.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+    let sql = format!(
+        r#"
+        SELECT
+            asset_assignments.asset_id as asset_id,
+            asset_assignments.checked_out_at as checked_out_at,
+            asset_assignments.checked_in_at as checked_in_at,
+            deployment_updates.work_date || ' ' || deployment_updates.start_time as work_started_at,
+            deployment_updates.hours_worked as hours_worked
+        FROM asset_assignments
+        JOIN deployments
+            ON deployments.tenant_id = asset_assignments.tenant_id
+            AND (
+                deployments.id = asset_assignments.deployment_id
+                OR (asset_assignments.crew_id IS NOT NULL AND deployments.crew_id = asset_assignments.crew_id)
+            )
+        JOIN deployment_updates
+            ON deployment_updates.deployment_id = deployments.id
+            AND deployment_updates.tenant_id = deployments.tenant_id
+        WHERE asset_assignments.tenant_id = ?
+          AND deployments.status NOT IN ({placeholders})
+          AND deployments.end_at > asset_assignments.checked_out_at
+          AND (asset_assignments.checked_in_at IS NULL OR deployments.start_at < asset_assignments.checked_in_at)
+          AND deployment_updates.is_placeholder = 0
+          AND deployment_updates.hours_worked > 0
+        "#
+    );
+    let mut query = sqlx::query(&sql).bind(tenant_id);
+    for status in excluded_statuses {
+        query = query.bind(*status);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| UsageWindow {
+            asset_id: row.get("asset_id"),
+            checked_out_at: row.get("checked_out_at"),
+            checked_in_at: row.get("checked_in_at"),
+            work_started_at: row.get("work_started_at"),
+            hours_worked: row.get("hours_worked"),
+        })
+        .collect())
+}
+
+pub async fn record_reminder(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+    plan_id: i64,
+    state: &str,
+    cycle_start: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO maintenance_reminders (tenant_id, asset_id, plan_id, state, cycle_start)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(asset_id)
+    .bind(plan_id)
+    .bind(state)
+    .bind(cycle_start)
+    .execute(&db.0)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_tenant_ids_with_plans(db: &Db) -> Result<Vec<i64>, sqlx::Error> {
+    let rows = sqlx::query("SELECT DISTINCT tenant_id FROM maintenance_plans ORDER BY tenant_id ASC")
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(|row| row.get("tenant_id")).collect())
+}
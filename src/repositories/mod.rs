@@ -1,16 +1,37 @@
 pub mod admin_repo;
+pub mod availability_repo;
+pub mod certification_repo;
 pub mod client_repo;
 pub mod discussion_repo;
 pub mod crew_repo;
 pub mod crew_member_repo;
+pub mod custom_field_repo;
 pub mod crew_discussion_repo;
 pub mod appointment_repo;
+pub mod asset_assignment_repo;
+pub mod asset_repo;
+pub mod attachment_repo;
 pub mod deployment_repo;
 pub mod deployment_discussion_repo;
+pub mod deployment_task_repo;
+pub mod deployment_template_repo;
 pub mod deployment_update_repo;
 pub mod work_timer_repo;
+pub mod workspace_export_repo;
 pub mod email_repo;
+pub mod expense_repo;
+pub mod gear_repo;
+pub mod import_repo;
+pub mod incident_repo;
 pub mod invoice_repo;
+pub mod maintenance_repo;
+pub mod payroll_repo;
+pub mod profitability_repo;
+pub mod recommendation_repo;
+pub mod scheduler_repo;
 pub mod tenant_repo;
+pub mod timesheet_repo;
+pub mod timer_policy_repo;
+pub mod tracking_sync_repo;
 pub mod user_permission_repo;
 pub mod user_repo;
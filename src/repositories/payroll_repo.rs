@@ -0,0 +1,124 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{PayrollEntry, PayrollPolicy};
+use crate::Db;
+
+pub async fn find_policy(db: &Db, tenant_id: i64) -> Result<Option<PayrollPolicy>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT daily_overtime_hours, weekly_overtime_hours FROM payroll_policies WHERE tenant_id = ?",
+    )
+    .bind(tenant_id)
+    .fetch_optional(&db.0)
+    .await?;
+
+    Ok(row.map(|row| PayrollPolicy {
+        daily_overtime_hours: row.get("daily_overtime_hours"),
+        weekly_overtime_hours: row.get("weekly_overtime_hours"),
+    }))
+}
+
+pub async fn save_policy(
+    db: &Db,
+    tenant_id: i64,
+    policy: &PayrollPolicy,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO payroll_policies (tenant_id, daily_overtime_hours, weekly_overtime_hours, updated_at)
+        VALUES (?, ?, ?, datetime('now'))
+        ON CONFLICT(tenant_id) DO UPDATE SET
+            daily_overtime_hours = excluded.daily_overtime_hours,
+            weekly_overtime_hours = excluded.weekly_overtime_hours,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(policy.daily_overtime_hours)
+    .bind(policy.weekly_overtime_hours)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_approved_entries(
+    db: &Db,
+    tenant_id: i64,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<PayrollEntry>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            deployment_updates.user_id as user_id,
+            COALESCE(users.email, '') as user_email,
+            (
+                SELECT MIN(crew_members.name)
+                FROM crew_members
+                WHERE crew_members.tenant_id = deployment_updates.tenant_id
+                  AND crew_members.user_id = deployment_updates.user_id
+            ) as member_name,
+            deployment_updates.deployment_id as deployment_id,
+            clients.company_name as client_name,
+            crews.name as crew_name,
+            deployment_updates.work_date as work_date,
+            deployment_updates.hours_worked as hours_worked
+        FROM deployment_updates
+        JOIN deployments ON deployment_updates.deployment_id = deployments.id
+        JOIN clients ON deployments.client_id = clients.id
+        JOIN crews ON deployments.crew_id = crews.id
+        LEFT JOIN users ON users.id = deployment_updates.user_id
+        WHERE deployment_updates.tenant_id = ?
+          AND deployment_updates.user_id IS NOT NULL
+          AND deployment_updates.is_placeholder = 0
+          AND deployment_updates.approval_status = 'Approved'
+          AND deployment_updates.work_date >= ?
+          AND deployment_updates.work_date <= ?
+        ORDER BY deployment_updates.user_id ASC, deployment_updates.work_date ASC, deployment_updates.id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PayrollEntry {
+            user_id: row.get("user_id"),
+            user_email: row.get("user_email"),
+            member_name: row.get("member_name"),
+            deployment_id: row.get("deployment_id"),
+            client_name: row.get("client_name"),
+            crew_name: row.get("crew_name"),
+            work_date: row.get("work_date"),
+            hours_worked: row.get("hours_worked"),
+        })
+        .collect())
+}
+
+pub async fn count_unapproved_entries(
+    db: &Db,
+    tenant_id: i64,
+    start_date: &str,
+    end_date: &str,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COUNT(*) as count
+        FROM deployment_updates
+        WHERE tenant_id = ?
+          AND user_id IS NOT NULL
+          AND is_placeholder = 0
+          AND approval_status != 'Approved'
+          AND work_date >= ?
+          AND work_date <= ?
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_one(&db.0)
+    .await?;
+    Ok(row.get("count"))
+}
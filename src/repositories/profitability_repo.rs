@@ -0,0 +1,174 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{DeploymentProfitability, LabourCostLine};
+use crate::Db;
+
+// A member's rate on the deployment's own crew wins; otherwise fall back to
+// the highest rate they carry on any crew in the workspace.
+const MEMBER_RATE: &str = r#"
+    COALESCE(
+        (
+            SELECT MAX(cm.hourly_cost)
+            FROM crew_members cm
+            WHERE cm.tenant_id = u.tenant_id AND cm.user_id = u.user_id AND cm.crew_id = d.crew_id
+        ),
+        (
+            SELECT MAX(cm.hourly_cost)
+            FROM crew_members cm
+            WHERE cm.tenant_id = u.tenant_id AND cm.user_id = u.user_id
+        ),
+        0.0
+    )
+"#;
+
+pub async fn list_deployment_profitability(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: Option<i64>,
+    start_from: Option<&str>,
+    start_to: Option<&str>,
+) -> Result<Vec<DeploymentProfitability>, sqlx::Error> {
+    let mut filters = String::new();
+    if deployment_id.is_some() {
+        filters.push_str(" AND d.id = ?");
+    }
+    if start_from.is_some() {
+        filters.push_str(" AND substr(d.start_at, 1, 10) >= ?");
+    }
+    if start_to.is_some() {
+        filters.push_str(" AND substr(d.start_at, 1, 10) <= ?");
+    }
+    let sql = format!(
+        r#"
+        WITH update_costs AS (
+            SELECT u.deployment_id as deployment_id, u.hours_worked as hours_worked, {MEMBER_RATE} as hourly_cost
+            FROM deployment_updates u
+            JOIN deployments d ON d.id = u.deployment_id
+            WHERE u.tenant_id = ? AND u.approval_status = 'Approved' AND u.is_placeholder = 0
+        )
+        SELECT
+            d.id as deployment_id,
+            d.client_id as client_id,
+            c.company_name as client_name,
+            d.crew_id as crew_id,
+            cr.name as crew_name,
+            d.deployment_type as deployment_type,
+            d.status as status,
+            d.start_at as start_at,
+            c.currency as currency,
+            d.fee_per_hour as fee_per_hour,
+            COALESCE((SELECT SUM(uc.hours_worked) FROM update_costs uc WHERE uc.deployment_id = d.id), 0.0) as total_hours,
+            COALESCE((SELECT SUM(uc.hours_worked * uc.hourly_cost) FROM update_costs uc WHERE uc.deployment_id = d.id), 0.0) as labour_cost,
+            COALESCE((SELECT SUM(uc.hours_worked) FROM update_costs uc WHERE uc.deployment_id = d.id AND uc.hourly_cost <= 0), 0.0) as uncosted_hours,
+            COALESCE((
+                SELECT SUM(e.amount)
+                FROM deployment_expenses e
+                WHERE e.tenant_id = d.tenant_id
+                  AND e.deployment_id = d.id
+                  AND e.status = 'Approved'
+                  AND e.currency = c.currency
+            ), 0.0) as expense_cost,
+            COALESCE((
+                SELECT ROUND(SUM(ROUND(e.amount * (1 + e.markup_percent / 100.0), 2)), 2)
+                FROM deployment_expenses e
+                WHERE e.tenant_id = d.tenant_id
+                  AND e.deployment_id = d.id
+                  AND e.status = 'Approved'
+                  AND e.is_billable = 1
+                  AND e.currency = c.currency
+            ), 0.0) as billable_expenses
+        FROM deployments d
+        JOIN clients c ON d.client_id = c.id
+        JOIN crews cr ON d.crew_id = cr.id
+        WHERE d.tenant_id = ?{filters}
+        ORDER BY d.start_at DESC, d.id DESC
+        "#
+    );
+    let mut query = sqlx::query(&sql).bind(tenant_id).bind(tenant_id);
+    if let Some(deployment_id) = deployment_id {
+        query = query.bind(deployment_id);
+    }
+    if let Some(start_from) = start_from {
+        query = query.bind(start_from);
+    }
+    if let Some(start_to) = start_to {
+        query = query.bind(start_to);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DeploymentProfitability {
+            deployment_id: row.get("deployment_id"),
+            client_id: row.get("client_id"),
+            client_name: row.get("client_name"),
+            crew_id: row.get("crew_id"),
+            crew_name: row.get("crew_name"),
+            deployment_type: row.get("deployment_type"),
+            status: row.get("status"),
+            start_at: row.get("start_at"),
+            currency: row.get("currency"),
+            fee_per_hour: row.get("fee_per_hour"),
+            total_hours: row.get("total_hours"),
+            labour_cost: row.get("labour_cost"),
+            uncosted_hours: row.get("uncosted_hours"),
+            expense_cost: row.get("expense_cost"),
+            billable_expenses: row.get("billable_expenses"),
+        })
+        .collect())
+}
+
+pub async fn list_labour_cost_lines(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<LabourCostLine>, sqlx::Error> {
+    let sql = format!(
+        r#"
+        SELECT
+            u.user_id as user_id,
+            COALESCE(users.email, '') as email,
+            COALESCE(
+                (
+                    SELECT MIN(cm.name)
+                    FROM crew_members cm
+                    WHERE cm.tenant_id = u.tenant_id AND cm.user_id = u.user_id AND cm.crew_id = d.crew_id
+                ),
+                (
+                    SELECT MIN(cm.name)
+                    FROM crew_members cm
+                    WHERE cm.tenant_id = u.tenant_id AND cm.user_id = u.user_id
+                )
+            ) as member_name,
+            SUM(u.hours_worked) as hours,
+            {MEMBER_RATE} as hourly_cost
+        FROM deployment_updates u
+        JOIN deployments d ON d.id = u.deployment_id
+        LEFT JOIN users ON users.id = u.user_id
+        WHERE u.tenant_id = ? AND u.deployment_id = ? AND u.approval_status = 'Approved' AND u.is_placeholder = 0
+        GROUP BY u.user_id
+        ORDER BY hours DESC
+        "#
+    );
+    let rows = sqlx::query(&sql)
+        .bind(tenant_id)
+        .bind(deployment_id)
+        .fetch_all(&db.0)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let hours: f64 = row.get("hours");
+            let hourly_cost: f64 = row.get("hourly_cost");
+            LabourCostLine {
+                user_id: row.get("user_id"),
+                member_name: row.get("member_name"),
+                email: row.get("email"),
+                hours,
+                hourly_cost,
+                cost: hours * hourly_cost,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,151 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::Db;
+
+pub struct ClientHistoryRow {
+    pub crew_id: i64,
+    pub completed: i64,
+    pub cancelled: i64,
+    pub incidents: i64,
+}
+
+pub async fn list_weights(db: &Db, tenant_id: i64) -> Result<Vec<(String, i64)>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT factor_key, weight FROM recommendation_weights WHERE tenant_id = ?",
+    )
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("factor_key"), row.get("weight")))
+        .collect())
+}
+
+pub async fn save_weights(
+    db: &Db,
+    tenant_id: i64,
+    weights: &[(String, i64)],
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    for (key, weight) in weights {
+        sqlx::query(
+            r#"
+            INSERT INTO recommendation_weights (tenant_id, factor_key, weight, updated_at)
+            VALUES (?, ?, ?, datetime('now'))
+            ON CONFLICT(tenant_id, factor_key) DO UPDATE SET
+                weight = excluded.weight,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(key)
+        .bind(weight)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list_overlapping_bookings(
+    db: &Db,
+    tenant_id: i64,
+    start_at: &str,
+    end_at: &str,
+    exclude_deployment_id: i64,
+) -> Result<Vec<(i64, i64)>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT crew_id, id
+        FROM deployments
+        WHERE tenant_id = ?
+          AND status NOT IN ('Tentative', 'Cancelled')
+          AND replace(start_at, 'T', ' ') < ?
+          AND replace(end_at, 'T', ' ') > ?
+          AND id != ?
+        ORDER BY start_at ASC, id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(end_at)
+    .bind(start_at)
+    .bind(exclude_deployment_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("crew_id"), row.get("id")))
+        .collect())
+}
+
+pub async fn list_previous_locations(
+    db: &Db,
+    tenant_id: i64,
+    before: &str,
+    exclude_deployment_id: i64,
+) -> Result<Vec<(i64, String, String)>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT deployments.crew_id as crew_id, clients.latitude as latitude, clients.longitude as longitude
+        FROM deployments
+        JOIN clients ON clients.id = deployments.client_id
+        WHERE deployments.tenant_id = ?
+          AND deployments.status != 'Cancelled'
+          AND deployments.start_at <= ?
+          AND deployments.id != ?
+        ORDER BY deployments.start_at DESC, deployments.id DESC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(before)
+    .bind(exclude_deployment_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("crew_id"), row.get("latitude"), row.get("longitude")))
+        .collect())
+}
+
+pub async fn list_client_history(
+    db: &Db,
+    tenant_id: i64,
+    client_id: i64,
+    exclude_deployment_id: i64,
+) -> Result<Vec<ClientHistoryRow>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            deployments.crew_id as crew_id,
+            SUM(CASE WHEN deployments.status = 'Completed' THEN 1 ELSE 0 END) as completed,
+            SUM(CASE WHEN deployments.status = 'Cancelled' THEN 1 ELSE 0 END) as cancelled,
+            SUM((
+                SELECT COUNT(*)
+                FROM deployment_incidents
+                WHERE deployment_incidents.deployment_id = deployments.id
+                  AND deployment_incidents.tenant_id = deployments.tenant_id
+            )) as incidents
+        FROM deployments
+        WHERE deployments.tenant_id = ?
+          AND deployments.client_id = ?
+          AND deployments.id != ?
+          AND deployments.status IN ('Completed', 'Cancelled')
+        GROUP BY deployments.crew_id
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(client_id)
+    .bind(exclude_deployment_id)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| ClientHistoryRow {
+            crew_id: row.get("crew_id"),
+            completed: row.get("completed"),
+            cancelled: row.get("cancelled"),
+            incidents: row.get("incidents"),
+        })
+        .collect())
+}
@@ -0,0 +1,94 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::ScheduleDeployment;
+use crate::Db;
+
+pub async fn list_deployments_between(
+    db: &Db,
+    tenant_id: i64,
+    from: &str,
+    until: &str,
+) -> Result<Vec<ScheduleDeployment>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            deployments.id as id,
+            deployments.client_id as client_id,
+            clients.company_name as client_name,
+            deployments.crew_id as crew_id,
+            COALESCE(crews.name, '') as crew_name,
+            deployments.start_at as start_at,
+            deployments.end_at as end_at,
+            deployments.status as status,
+            deployments.required_skills as required_skills,
+            clients.latitude as latitude,
+            clients.longitude as longitude
+        FROM deployments
+        JOIN clients ON clients.id = deployments.client_id
+        LEFT JOIN crews ON crews.id = deployments.crew_id
+        WHERE deployments.tenant_id = ?
+          AND deployments.status != 'Cancelled'
+          AND substr(deployments.start_at, 1, 10) <= ?
+          AND substr(deployments.end_at, 1, 10) >= ?
+        ORDER BY deployments.start_at ASC, deployments.id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(until)
+    .bind(from)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| ScheduleDeployment {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            client_name: row.get("client_name"),
+            crew_id: row.get("crew_id"),
+            crew_name: row.get("crew_name"),
+            start_at: row.get("start_at"),
+            end_at: row.get("end_at"),
+            status: row.get("status"),
+            required_skills: row.get("required_skills"),
+            latitude: row.get("latitude"),
+            longitude: row.get("longitude"),
+        })
+        .collect())
+}
+
+pub async fn assign_crews(
+    db: &Db,
+    tenant_id: i64,
+    assignments: &[(i64, i64)],
+    from_status: &str,
+    to_status: &str,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = db.0.begin().await?;
+    let mut updated = 0;
+    for (deployment_id, crew_id) in assignments {
+        let result = sqlx::query(
+            r#"
+            UPDATE deployments
+            SET crew_id = ?, status = ?
+            WHERE id = ? AND tenant_id = ? AND status = ?
+              AND EXISTS (SELECT 1 FROM crews WHERE crews.id = ? AND crews.tenant_id = ?)
+            "#,
+        )
+        .bind(crew_id)
+        .bind(to_status)
+        .bind(deployment_id)
+        .bind(tenant_id)
+        .bind(from_status)
+        .bind(crew_id)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+        updated += result.rows_affected();
+    }
+    if updated as usize != assignments.len() {
+        tx.rollback().await?;
+        return Ok(updated);
+    }
+    tx.commit().await?;
+    Ok(updated)
+}
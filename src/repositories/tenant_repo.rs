@@ -305,6 +305,23 @@ pub async fn set_workspace_plan_expired(
     Ok(())
 }
 
+pub async fn find_label_secret(db: &Db, id: i64) -> Result<String, sqlx::Error> {
+    let row = sqlx::query("SELECT label_secret FROM tenants WHERE id = ?")
+        .bind(id)
+        .fetch_one(&db.0)
+        .await?;
+    Ok(row.get("label_secret"))
+}
+
+pub async fn init_label_secret(db: &Db, id: i64, secret: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE tenants SET label_secret = ? WHERE id = ? AND label_secret = ''")
+        .bind(secret)
+        .bind(id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
 pub async fn update_email_settings(
     db: &Db,
     id: i64,
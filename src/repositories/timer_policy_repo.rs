@@ -0,0 +1,138 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::{TimerAutoClosure, TimerPolicy};
+use crate::Db;
+
+pub async fn find_policy(db: &Db, tenant_id: i64) -> Result<Option<TimerPolicy>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT max_shift_hours, cap_at_deployment_end, reminder_minutes_before,
+               notify_after_close, notify_lead
+        FROM timer_policies
+        WHERE tenant_id = ?
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_optional(&db.0)
+    .await?;
+
+    Ok(row.map(|row| TimerPolicy {
+        max_shift_hours: row.get("max_shift_hours"),
+        cap_at_deployment_end: row.get::<i64, _>("cap_at_deployment_end") != 0,
+        reminder_minutes_before: row.get("reminder_minutes_before"),
+        notify_after_close: row.get::<i64, _>("notify_after_close") != 0,
+        notify_lead: row.get::<i64, _>("notify_lead") != 0,
+    }))
+}
+
+pub async fn save_policy(
+    db: &Db,
+    tenant_id: i64,
+    policy: &TimerPolicy,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO timer_policies
+            (tenant_id, max_shift_hours, cap_at_deployment_end, reminder_minutes_before, notify_after_close, notify_lead, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, datetime('now'))
+        ON CONFLICT(tenant_id) DO UPDATE SET
+            max_shift_hours = excluded.max_shift_hours,
+            cap_at_deployment_end = excluded.cap_at_deployment_end,
+            reminder_minutes_before = excluded.reminder_minutes_before,
+            notify_after_close = excluded.notify_after_close,
+            notify_lead = excluded.notify_lead,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(policy.max_shift_hours)
+    .bind(if policy.cap_at_deployment_end { 1 } else { 0 })
+    .bind(policy.reminder_minutes_before)
+    .bind(if policy.notify_after_close { 1 } else { 0 })
+    .bind(if policy.notify_lead { 1 } else { 0 })
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn create_auto_closure(
+    db: &Db,
+    tenant_id: i64,
+    timer_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    start_at: &str,
+    closed_at: &str,
+    reason: &str,
+    max_shift_hours: f64,
+    notified_emails: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO timer_auto_closures
+            (tenant_id, timer_id, deployment_id, user_id, start_at, closed_at, reason, max_shift_hours, notified_emails)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(timer_id)
+    .bind(deployment_id)
+    .bind(user_id)
+    .bind(start_at)
+    .bind(closed_at)
+    .bind(reason)
+    .bind(max_shift_hours)
+    .bind(notified_emails)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_auto_closures(
+    db: &Db,
+    tenant_id: i64,
+    limit: i64,
+) -> Result<Vec<TimerAutoClosure>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            timer_auto_closures.id as id,
+            timer_auto_closures.timer_id as timer_id,
+            timer_auto_closures.deployment_id as deployment_id,
+            COALESCE(clients.company_name, '') as client_name,
+            COALESCE(users.email, '') as user_email,
+            timer_auto_closures.start_at as start_at,
+            timer_auto_closures.closed_at as closed_at,
+            timer_auto_closures.reason as reason,
+            timer_auto_closures.notified_emails as notified_emails,
+            timer_auto_closures.created_at as created_at
+        FROM timer_auto_closures
+        LEFT JOIN users ON users.id = timer_auto_closures.user_id
+        LEFT JOIN deployments ON deployments.id = timer_auto_closures.deployment_id
+        LEFT JOIN clients ON clients.id = deployments.client_id
+        WHERE timer_auto_closures.tenant_id = ?
+        ORDER BY timer_auto_closures.created_at DESC, timer_auto_closures.id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(limit)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| TimerAutoClosure {
+            id: row.get("id"),
+            timer_id: row.get("timer_id"),
+            deployment_id: row.get("deployment_id"),
+            client_name: row.get("client_name"),
+            user_email: row.get("user_email"),
+            start_at: row.get("start_at"),
+            closed_at: row.get("closed_at"),
+            reason: row.get("reason"),
+            notified_emails: row.get("notified_emails"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
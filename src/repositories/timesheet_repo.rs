@@ -0,0 +1,211 @@
+use rocket_db_pools::sqlx::{self, sqlite::SqliteRow, Row};
+
+use crate::models::{TimesheetEntry, TimesheetMember};
+use crate::Db;
+
+const ENTRY_SELECT: &str = r#"
+    SELECT
+        deployment_updates.id as id,
+        deployment_updates.deployment_id as deployment_id,
+        clients.company_name as client_name,
+        crews.name as crew_name,
+        deployment_updates.user_id as user_id,
+        COALESCE(submitters.email, '') as user_email,
+        deployment_updates.work_date as work_date,
+        deployment_updates.start_time as start_time,
+        deployment_updates.end_time as end_time,
+        deployment_updates.hours_worked as hours_worked,
+        deployment_updates.notes as notes,
+        deployment_updates.approval_status as approval_status,
+        reviewers.email as reviewed_by_email,
+        deployment_updates.reviewed_at as reviewed_at,
+        deployment_updates.review_note as review_note
+    FROM deployment_updates
+    JOIN deployments ON deployment_updates.deployment_id = deployments.id
+    JOIN clients ON deployments.client_id = clients.id
+    JOIN crews ON deployments.crew_id = crews.id
+    LEFT JOIN users submitters ON deployment_updates.user_id = submitters.id
+    LEFT JOIN users reviewers ON deployment_updates.reviewed_by_user_id = reviewers.id
+"#;
+
+fn map_entry(row: SqliteRow) -> TimesheetEntry {
+    TimesheetEntry {
+        id: row.get("id"),
+        deployment_id: row.get("deployment_id"),
+        client_name: row.get("client_name"),
+        crew_name: row.get("crew_name"),
+        user_id: row.get("user_id"),
+        user_email: row.get("user_email"),
+        work_date: row.get("work_date"),
+        start_time: row.get("start_time"),
+        end_time: row.get("end_time"),
+        hours_worked: row.get("hours_worked"),
+        notes: row.get("notes"),
+        approval_status: row.get("approval_status"),
+        reviewed_by_email: row.get("reviewed_by_email"),
+        reviewed_at: row.get("reviewed_at"),
+        review_note: row.get("review_note"),
+    }
+}
+
+pub async fn list_members_for_week(
+    db: &Db,
+    tenant_id: i64,
+    week_start: &str,
+    week_end: &str,
+) -> Result<Vec<TimesheetMember>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            deployment_updates.user_id as user_id,
+            COALESCE(users.email, '') as user_email,
+            (
+                SELECT MIN(crew_members.name)
+                FROM crew_members
+                WHERE crew_members.tenant_id = deployment_updates.tenant_id
+                  AND crew_members.user_id = deployment_updates.user_id
+            ) as member_name,
+            SUM(CASE WHEN deployment_updates.approval_status = 'Submitted' THEN 1 ELSE 0 END) as submitted_count,
+            COALESCE(SUM(CASE WHEN deployment_updates.approval_status = 'Submitted' THEN deployment_updates.hours_worked END), 0.0) as submitted_hours,
+            COALESCE(SUM(CASE WHEN deployment_updates.approval_status = 'Approved' THEN deployment_updates.hours_worked END), 0.0) as approved_hours,
+            COALESCE(SUM(CASE WHEN deployment_updates.approval_status = 'Rejected' THEN deployment_updates.hours_worked END), 0.0) as rejected_hours
+        FROM deployment_updates
+        LEFT JOIN users ON users.id = deployment_updates.user_id
+        WHERE deployment_updates.tenant_id = ?
+          AND deployment_updates.user_id IS NOT NULL
+          AND deployment_updates.is_placeholder = 0
+          AND deployment_updates.work_date >= ?
+          AND deployment_updates.work_date <= ?
+        GROUP BY deployment_updates.user_id
+        ORDER BY submitted_count DESC, user_email ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(week_start)
+    .bind(week_end)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| TimesheetMember {
+            user_id: row.get("user_id"),
+            user_email: row.get("user_email"),
+            member_name: row.get("member_name"),
+            submitted_count: row.get("submitted_count"),
+            submitted_hours: row.get("submitted_hours"),
+            approved_hours: row.get("approved_hours"),
+            rejected_hours: row.get("rejected_hours"),
+        })
+        .collect())
+}
+
+pub async fn list_entries_for_week(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    week_start: &str,
+    week_end: &str,
+) -> Result<Vec<TimesheetEntry>, sqlx::Error> {
+    let sql = format!(
+        r#"{ENTRY_SELECT}
+        WHERE deployment_updates.tenant_id = ?
+          AND deployment_updates.user_id = ?
+          AND deployment_updates.is_placeholder = 0
+          AND deployment_updates.work_date >= ?
+          AND deployment_updates.work_date <= ?
+        ORDER BY deployment_updates.work_date ASC, deployment_updates.start_time ASC, deployment_updates.id ASC
+        "#
+    );
+    let rows = sqlx::query(&sql)
+        .bind(tenant_id)
+        .bind(user_id)
+        .bind(week_start)
+        .bind(week_end)
+        .fetch_all(&db.0)
+        .await?;
+    Ok(rows.into_iter().map(map_entry).collect())
+}
+
+pub async fn list_entries_by_ids(
+    db: &Db,
+    tenant_id: i64,
+    update_ids: &[i64],
+) -> Result<Vec<TimesheetEntry>, sqlx::Error> {
+    if update_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = update_ids
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        r#"{ENTRY_SELECT}
+        WHERE deployment_updates.tenant_id = ? AND deployment_updates.id IN ({placeholders})
+        ORDER BY deployment_updates.work_date ASC, deployment_updates.id ASC
+        "#
+    );
+    let mut query = sqlx::query(&sql).bind(tenant_id);
+    for update_id in update_ids {
+        query = query.bind(update_id);
+    }
+    let rows = query.fetch_all(&db.0).await?;
+    Ok(rows.into_iter().map(map_entry).collect())
+}
+
+pub async fn count_entries_by_status(
+    db: &Db,
+    tenant_id: i64,
+    status: &str,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COUNT(*) as count
+        FROM deployment_updates
+        WHERE tenant_id = ? AND approval_status = ? AND is_placeholder = 0
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(status)
+    .fetch_one(&db.0)
+    .await?;
+    Ok(row.get("count"))
+}
+
+pub async fn set_entries_status(
+    db: &Db,
+    tenant_id: i64,
+    update_ids: &[i64],
+    from_status: &str,
+    to_status: &str,
+    reviewed_by_user_id: i64,
+    review_note: &str,
+) -> Result<u64, sqlx::Error> {
+    if update_ids.is_empty() {
+        return Ok(0);
+    }
+    let placeholders = update_ids
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        r#"
+        UPDATE deployment_updates
+        SET approval_status = ?, reviewed_by_user_id = ?, reviewed_at = datetime('now'), review_note = ?
+        WHERE tenant_id = ? AND approval_status = ? AND is_placeholder = 0 AND id IN ({placeholders})
+        "#
+    );
+    let mut query = sqlx::query(&sql)
+        .bind(to_status)
+        .bind(reviewed_by_user_id)
+        .bind(review_note)
+        .bind(tenant_id)
+        .bind(from_status);
+    for update_id in update_ids {
+        query = query.bind(update_id);
+    }
+    let result = query.execute(&db.0).await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,130 @@
+use rocket_db_pools::sqlx::{self, Row};
+
+use crate::models::TrackingSyncResult;
+use crate::Db;
+
+pub const STATUS_PROCESSING: &str = "processing";
+
+/// Claims a client UUID before the item is applied. Returns false when the
+/// UUID was already synced, or is still being applied by another request.
+/// Claims left behind by a request that died mid-way are reclaimed after a
+/// few minutes.
+pub async fn reserve_item(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    client_uuid: &str,
+    kind: &str,
+    deployment_id: i64,
+    recorded_at: &str,
+) -> Result<bool, sqlx::Error> {
+    let inserted = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO tracking_sync_items
+            (tenant_id, user_id, client_uuid, kind, deployment_id, status, recorded_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(user_id)
+    .bind(client_uuid)
+    .bind(kind)
+    .bind(deployment_id)
+    .bind(STATUS_PROCESSING)
+    .bind(recorded_at)
+    .execute(&db.0)
+    .await?;
+    if inserted.rows_affected() > 0 {
+        return Ok(true);
+    }
+    let reclaimed = sqlx::query(
+        r#"
+        UPDATE tracking_sync_items
+        SET kind = ?, deployment_id = ?, recorded_at = ?, synced_at = datetime('now')
+        WHERE tenant_id = ? AND user_id = ? AND client_uuid = ?
+          AND status = ? AND synced_at < datetime('now', '-5 minutes')
+        "#,
+    )
+    .bind(kind)
+    .bind(deployment_id)
+    .bind(recorded_at)
+    .bind(tenant_id)
+    .bind(user_id)
+    .bind(client_uuid)
+    .bind(STATUS_PROCESSING)
+    .execute(&db.0)
+    .await?;
+    Ok(reclaimed.rows_affected() > 0)
+}
+
+pub async fn find_item(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    client_uuid: &str,
+) -> Result<Option<TrackingSyncResult>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT client_uuid, kind, status, message, record_id
+        FROM tracking_sync_items
+        WHERE tenant_id = ? AND user_id = ? AND client_uuid = ?
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(user_id)
+    .bind(client_uuid)
+    .fetch_optional(&db.0)
+    .await?;
+    Ok(row.map(|row| TrackingSyncResult {
+        client_uuid: row.get("client_uuid"),
+        kind: row.get("kind"),
+        status: row.get("status"),
+        message: row.get("message"),
+        record_id: row.get("record_id"),
+        duplicate: true,
+    }))
+}
+
+pub async fn complete_item(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    client_uuid: &str,
+    status: &str,
+    message: &str,
+    record_id: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE tracking_sync_items
+        SET status = ?, message = ?, record_id = ?, synced_at = datetime('now')
+        WHERE tenant_id = ? AND user_id = ? AND client_uuid = ?
+        "#,
+    )
+    .bind(status)
+    .bind(message)
+    .bind(record_id)
+    .bind(tenant_id)
+    .bind(user_id)
+    .bind(client_uuid)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn release_item(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    client_uuid: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM tracking_sync_items WHERE tenant_id = ? AND user_id = ? AND client_uuid = ?",
+    )
+    .bind(tenant_id)
+    .bind(user_id)
+    .bind(client_uuid)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use rocket_db_pools::sqlx::{self, Row};
 
-use crate::models::WorkTimer;
+use crate::models::{OpenTimer, WorkTimer, WorkTimerBreak, WorkTimerLog};
 use crate::Db;
 
 pub async fn find_active_timer(
@@ -10,7 +10,12 @@ pub async fn find_active_timer(
 ) -> Result<Option<WorkTimer>, sqlx::Error> {
     let row = sqlx::query(
         r#"
-        SELECT id, tenant_id, deployment_id, user_id, start_at, end_at
+        SELECT id, tenant_id, deployment_id, user_id, start_at, end_at,
+               (
+                   SELECT MAX(work_timer_breaks.start_at)
+                   FROM work_timer_breaks
+                   WHERE work_timer_breaks.timer_id = work_timers.id AND work_timer_breaks.end_at IS NULL
+               ) as paused_at
         FROM work_timers
         WHERE tenant_id = ? AND user_id = ? AND end_at IS NULL
         ORDER BY id DESC
@@ -29,6 +34,7 @@ pub async fn find_active_timer(
         user_id: row.get("user_id"),
         start_at: row.get("start_at"),
         end_at: row.get("end_at"),
+        paused_at: row.get("paused_at"),
     }))
 }
 
@@ -38,17 +44,25 @@ pub async fn create_timer(
     deployment_id: i64,
     user_id: i64,
     start_at: &str,
+    location: Option<(f64, f64)>,
+    distance_m: Option<f64>,
+    geofence_flag: &str,
 ) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
-        INSERT INTO work_timers (tenant_id, deployment_id, user_id, start_at)
-        VALUES (?, ?, ?, ?)
+        INSERT INTO work_timers
+            (tenant_id, deployment_id, user_id, start_at, start_latitude, start_longitude, start_distance_m, geofence_flag)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(tenant_id)
     .bind(deployment_id)
     .bind(user_id)
     .bind(start_at)
+    .bind(location.map(|(latitude, _)| latitude))
+    .bind(location.map(|(_, longitude)| longitude))
+    .bind(distance_m)
+    .bind(geofence_flag)
     .execute(&db.0)
     .await?;
     Ok(())
@@ -79,43 +93,165 @@ pub async fn stop_timer(
     Ok(())
 }
 
-pub async fn list_stale_timers(
+pub async fn record_stop_location(
     db: &Db,
     tenant_id: i64,
-    cutoff: &str,
-) -> Result<Vec<WorkTimer>, sqlx::Error> {
+    timer_id: i64,
+    location: Option<(f64, f64)>,
+    distance_m: Option<f64>,
+    geofence_flag: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE work_timers
+        SET stop_latitude = ?,
+            stop_longitude = ?,
+            stop_distance_m = ?,
+            geofence_flag = CASE
+                WHEN ? = '' THEN geofence_flag
+                WHEN geofence_flag = '' THEN ?
+                ELSE geofence_flag || '; ' || ?
+            END
+        WHERE tenant_id = ? AND id = ?
+        "#,
+    )
+    .bind(location.map(|(latitude, _)| latitude))
+    .bind(location.map(|(_, longitude)| longitude))
+    .bind(distance_m)
+    .bind(geofence_flag)
+    .bind(geofence_flag)
+    .bind(geofence_flag)
+    .bind(tenant_id)
+    .bind(timer_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_timers_by_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    limit: i64,
+) -> Result<Vec<WorkTimerLog>, sqlx::Error> {
     let rows = sqlx::query(
         r#"
-        SELECT id, tenant_id, deployment_id, user_id, start_at, end_at
+        SELECT
+            work_timers.id as id,
+            work_timers.user_id as user_id,
+            COALESCE(users.email, '') as user_email,
+            work_timers.start_at as start_at,
+            work_timers.end_at as end_at,
+            work_timers.start_latitude as start_latitude,
+            work_timers.start_longitude as start_longitude,
+            work_timers.start_distance_m as start_distance_m,
+            work_timers.stop_latitude as stop_latitude,
+            work_timers.stop_longitude as stop_longitude,
+            work_timers.stop_distance_m as stop_distance_m,
+            work_timers.geofence_flag as geofence_flag
         FROM work_timers
-        WHERE tenant_id = ? AND end_at IS NULL AND start_at <= ?
+        LEFT JOIN users ON users.id = work_timers.user_id
+        WHERE work_timers.tenant_id = ? AND work_timers.deployment_id = ?
+        ORDER BY work_timers.start_at DESC, work_timers.id DESC
+        LIMIT ?
         "#,
     )
     .bind(tenant_id)
-    .bind(cutoff)
+    .bind(deployment_id)
+    .bind(limit)
     .fetch_all(&db.0)
     .await?;
 
     Ok(rows
         .into_iter()
-        .map(|row| WorkTimer {
+        .map(|row| WorkTimerLog {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            user_email: row.get("user_email"),
+            start_at: row.get("start_at"),
+            end_at: row.get("end_at"),
+            start_latitude: row.get("start_latitude"),
+            start_longitude: row.get("start_longitude"),
+            start_distance_m: row.get("start_distance_m"),
+            stop_latitude: row.get("stop_latitude"),
+            stop_longitude: row.get("stop_longitude"),
+            stop_distance_m: row.get("stop_distance_m"),
+            geofence_flag: row.get("geofence_flag"),
+        })
+        .collect())
+}
+
+pub async fn list_open_timers(db: &Db) -> Result<Vec<OpenTimer>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            work_timers.id as id,
+            work_timers.tenant_id as tenant_id,
+            work_timers.deployment_id as deployment_id,
+            work_timers.user_id as user_id,
+            COALESCE(users.email, '') as user_email,
+            COALESCE(clients.company_name, '') as client_name,
+            work_timers.start_at as start_at,
+            COALESCE(deployments.end_at, '') as deployment_end_at,
+            work_timers.reminder_sent_at as reminder_sent_at
+        FROM work_timers
+        LEFT JOIN users ON users.id = work_timers.user_id
+        LEFT JOIN deployments ON deployments.id = work_timers.deployment_id
+        LEFT JOIN clients ON clients.id = deployments.client_id
+        WHERE work_timers.end_at IS NULL
+        ORDER BY work_timers.tenant_id ASC, work_timers.start_at ASC
+        "#,
+    )
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| OpenTimer {
             id: row.get("id"),
             tenant_id: row.get("tenant_id"),
             deployment_id: row.get("deployment_id"),
             user_id: row.get("user_id"),
+            user_email: row.get("user_email"),
+            client_name: row.get("client_name"),
             start_at: row.get("start_at"),
-            end_at: row.get("end_at"),
+            deployment_end_at: row.get("deployment_end_at"),
+            reminder_sent_at: row.get("reminder_sent_at"),
         })
         .collect())
 }
 
+pub async fn mark_reminder_sent(
+    db: &Db,
+    tenant_id: i64,
+    timer_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE work_timers
+        SET reminder_sent_at = datetime('now')
+        WHERE tenant_id = ? AND id = ?
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(timer_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
 pub async fn list_active_timers(
     db: &Db,
     tenant_id: i64,
 ) -> Result<Vec<WorkTimer>, sqlx::Error> {
     let rows = sqlx::query(
         r#"
-        SELECT id, tenant_id, deployment_id, user_id, start_at, end_at
+        SELECT id, tenant_id, deployment_id, user_id, start_at, end_at,
+               (
+                   SELECT MAX(work_timer_breaks.start_at)
+                   FROM work_timer_breaks
+                   WHERE work_timer_breaks.timer_id = work_timers.id AND work_timer_breaks.end_at IS NULL
+               ) as paused_at
         FROM work_timers
         WHERE tenant_id = ? AND end_at IS NULL
         ORDER BY start_at DESC, id DESC
@@ -134,6 +270,81 @@ pub async fn list_active_timers(
             user_id: row.get("user_id"),
             start_at: row.get("start_at"),
             end_at: row.get("end_at"),
+            paused_at: row.get("paused_at"),
+        })
+        .collect())
+}
+
+pub async fn create_break(
+    db: &Db,
+    tenant_id: i64,
+    timer_id: i64,
+    kind: &str,
+    start_at: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO work_timer_breaks (tenant_id, timer_id, kind, start_at)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(timer_id)
+    .bind(kind)
+    .bind(start_at)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn close_open_breaks(
+    db: &Db,
+    tenant_id: i64,
+    timer_id: i64,
+    end_at: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE work_timer_breaks
+        SET end_at = CASE WHEN start_at > ? THEN start_at ELSE ? END
+        WHERE tenant_id = ? AND timer_id = ? AND end_at IS NULL
+        "#,
+    )
+    .bind(end_at)
+    .bind(end_at)
+    .bind(tenant_id)
+    .bind(timer_id)
+    .execute(&db.0)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn list_breaks(
+    db: &Db,
+    tenant_id: i64,
+    timer_id: i64,
+) -> Result<Vec<WorkTimerBreak>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, timer_id, kind, start_at, end_at
+        FROM work_timer_breaks
+        WHERE tenant_id = ? AND timer_id = ?
+        ORDER BY start_at ASC, id ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(timer_id)
+    .fetch_all(&db.0)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| WorkTimerBreak {
+            id: row.get("id"),
+            timer_id: row.get("timer_id"),
+            kind: row.get("kind"),
+            start_at: row.get("start_at"),
+            end_at: row.get("end_at"),
         })
         .collect())
 }
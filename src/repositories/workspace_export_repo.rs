@@ -0,0 +1,245 @@
+use rocket_db_pools::sqlx::{self, Row, TypeInfo, ValueRef};
+use serde_json::Value;
+
+use crate::models::WorkspaceExport;
+use crate::Db;
+
+const EXPORT_COLUMNS: &str = r#"
+    id, tenant_id, requested_by, origin, status, token, file_name, file_size, error,
+    created_at, completed_at, expires_at
+"#;
+
+fn export_from_row(row: &sqlx::sqlite::SqliteRow) -> WorkspaceExport {
+    WorkspaceExport {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        requested_by: row.get("requested_by"),
+        origin: row.get("origin"),
+        status: row.get("status"),
+        token: row.get("token"),
+        file_name: row.get("file_name"),
+        file_size: row.get("file_size"),
+        error: row.get("error"),
+        created_at: row.get("created_at"),
+        completed_at: row.get("completed_at"),
+        expires_at: row.get("expires_at"),
+    }
+}
+
+pub async fn create_export(
+    db: &Db,
+    tenant_id: i64,
+    requested_by: i64,
+    origin: &str,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        "INSERT INTO workspace_exports (tenant_id, requested_by, origin) VALUES (?, ?, ?)",
+    )
+    .bind(tenant_id)
+    .bind(requested_by)
+    .bind(origin)
+    .execute(&db.0)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn has_pending_export(db: &Db, tenant_id: i64) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM workspace_exports WHERE tenant_id = ? AND status IN ('Queued', 'Processing')",
+    )
+    .bind(tenant_id)
+    .fetch_one(&db.0)
+    .await?;
+    Ok(count > 0)
+}
+
+pub async fn list_exports(
+    db: &Db,
+    tenant_id: i64,
+    limit: i64,
+) -> Result<Vec<WorkspaceExport>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "SELECT {EXPORT_COLUMNS} FROM workspace_exports WHERE tenant_id = ? ORDER BY id DESC LIMIT ?"
+    ))
+    .bind(tenant_id)
+    .bind(limit)
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.iter().map(export_from_row).collect())
+}
+
+pub async fn find_ready_export(
+    db: &Db,
+    tenant_id: i64,
+    token: &str,
+) -> Result<Option<WorkspaceExport>, sqlx::Error> {
+    let row = sqlx::query(&format!(
+        r#"
+        SELECT {EXPORT_COLUMNS}
+        FROM workspace_exports
+        WHERE tenant_id = ? AND token = ? AND status = 'Ready' AND expires_at > datetime('now')
+        "#
+    ))
+    .bind(tenant_id)
+    .bind(token)
+    .fetch_optional(&db.0)
+    .await?;
+    Ok(row.as_ref().map(export_from_row))
+}
+
+/// Takes the oldest queued export for the worker. Exports stuck in
+/// `Processing` for over an hour (the server stopped mid-build) are picked up
+/// again.
+pub async fn claim_next_export(db: &Db) -> Result<Option<WorkspaceExport>, sqlx::Error> {
+    let row = sqlx::query(&format!(
+        r#"
+        SELECT {EXPORT_COLUMNS}
+        FROM workspace_exports
+        WHERE status = 'Queued'
+           OR (status = 'Processing' AND started_at < datetime('now', '-1 hour'))
+        ORDER BY id
+        LIMIT 1
+        "#
+    ))
+    .fetch_optional(&db.0)
+    .await?;
+    let Some(export) = row.as_ref().map(export_from_row) else {
+        return Ok(None);
+    };
+    let result = sqlx::query(
+        r#"
+        UPDATE workspace_exports
+        SET status = 'Processing', started_at = datetime('now')
+        WHERE id = ? AND status = ?
+        "#,
+    )
+    .bind(export.id)
+    .bind(&export.status)
+    .execute(&db.0)
+    .await?;
+    Ok((result.rows_affected() == 1).then_some(export))
+}
+
+pub async fn mark_ready(
+    db: &Db,
+    export_id: i64,
+    file_name: &str,
+    file_size: i64,
+    token: &str,
+    valid_hours: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE workspace_exports
+        SET status = 'Ready',
+            file_name = ?,
+            file_size = ?,
+            token = ?,
+            completed_at = datetime('now'),
+            expires_at = datetime('now', ?)
+        WHERE id = ?
+        "#,
+    )
+    .bind(file_name)
+    .bind(file_size)
+    .bind(token)
+    .bind(format!("+{valid_hours} hours"))
+    .bind(export_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn mark_failed(db: &Db, export_id: i64, error: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE workspace_exports SET status = 'Failed', error = ?, completed_at = datetime('now') WHERE id = ?",
+    )
+    .bind(error)
+    .bind(export_id)
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_expired_exports(db: &Db) -> Result<Vec<WorkspaceExport>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {EXPORT_COLUMNS}
+        FROM workspace_exports
+        WHERE status = 'Ready' AND expires_at <= datetime('now')
+        "#
+    ))
+    .fetch_all(&db.0)
+    .await?;
+    Ok(rows.iter().map(export_from_row).collect())
+}
+
+pub async fn mark_expired(db: &Db, export_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE workspace_exports SET status = 'Expired', token = '' WHERE id = ?")
+        .bind(export_id)
+        .execute(&db.0)
+        .await?;
+    Ok(())
+}
+
+pub async fn list_tenant_tables(db: &Db) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT m.name
+        FROM sqlite_master m
+        WHERE m.type = 'table'
+          AND EXISTS (SELECT 1 FROM pragma_table_info(m.name) WHERE name = 'tenant_id')
+        ORDER BY m.name
+        "#,
+    )
+    .fetch_all(&db.0)
+    .await
+}
+
+/// Column names and raw values of one table, limited to the rows matching
+/// `key_column = tenant_id`. `table` must come from `sqlite_master`.
+pub async fn fetch_table_rows(
+    db: &Db,
+    table: &str,
+    key_column: &str,
+    tenant_id: i64,
+) -> Result<(Vec<String>, Vec<Vec<Value>>), sqlx::Error> {
+    let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?) ORDER BY cid")
+        .bind(table)
+        .fetch_all(&db.0)
+        .await?;
+    let select = columns
+        .iter()
+        .map(|column| format!("\"{column}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows = sqlx::query(&format!(
+        "SELECT {select} FROM \"{table}\" WHERE \"{key_column}\" = ? ORDER BY rowid"
+    ))
+    .bind(tenant_id)
+    .fetch_all(&db.0)
+    .await?;
+
+    let mut values = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut record = Vec::with_capacity(columns.len());
+        for index in 0..columns.len() {
+            let storage = row.try_get_raw(index)?.type_info().name().to_string();
+            let value = match storage.as_str() {
+                "NULL" => Value::Null,
+                "INTEGER" => Value::from(row.try_get::<i64, _>(index)?),
+                "REAL" => Value::from(row.try_get::<f64, _>(index)?),
+                "BLOB" => Value::from(
+                    row.try_get::<Vec<u8>, _>(index)?
+                        .iter()
+                        .map(|byte| format!("{byte:02x}"))
+                        .collect::<String>(),
+                ),
+                _ => Value::from(row.try_get::<String, _>(index)?),
+            };
+            record.push(value);
+        }
+        values.push(record);
+    }
+    Ok((columns, values))
+}
@@ -7,10 +7,11 @@ use crate::repositories::{user_permission_repo, user_repo};
 use crate::services::workspace_service;
 use crate::Db;
 
-pub const RESOURCES: [(&str, &str); 7] = [
+pub const RESOURCES: [(&str, &str); 8] = [
     ("dashboard", "Dashboard"),
     ("clients", "Clients"),
     ("crew", "Crew"),
+    ("assets", "Assets"),
     ("deployments", "Deployments"),
     ("tracking", "Tracking"),
     ("invoices", "Invoices"),
@@ -59,6 +60,26 @@ pub fn is_accounting(role: &str) -> bool {
     normalize_role(role).eq_ignore_ascii_case(ROLE_ACCOUNTING)
 }
 
+pub fn can_review_expenses(role: &str) -> bool {
+    is_owner(role) || is_admin(role) || is_operations(role)
+}
+
+pub fn can_review_timesheets(role: &str) -> bool {
+    is_owner(role) || is_admin(role) || is_operations(role)
+}
+
+pub fn can_review_leave(role: &str) -> bool {
+    is_owner(role) || is_admin(role) || is_operations(role)
+}
+
+pub fn can_view_costs(role: &str) -> bool {
+    is_owner(role) || is_admin(role) || is_accounting(role)
+}
+
+pub fn can_view_field_locations(role: &str) -> bool {
+    is_owner(role) || is_admin(role) || is_operations(role)
+}
+
 pub async fn list_permissions_for_user(
     db: &Db,
     tenant_id: i64,
@@ -67,7 +88,7 @@ pub async fn list_permissions_for_user(
 ) -> Result<Vec<UserPermission>, sqlx::Error> {
     let existing = user_permission_repo::list_permissions_for_user(db, tenant_id, user_id).await?;
     if !existing.is_empty() {
-        return Ok(existing);
+        return Ok(with_role_defaults(existing, role));
     }
 
     let defaults = default_permissions_for_role(role);
@@ -118,18 +139,11 @@ async fn can_action(db: &Db, user: &User, resource: &str, action: &str) -> bool
     let permissions = user_permission_repo::list_permissions_for_user(db, user.tenant_id, user.id)
         .await
         .unwrap_or_default();
-    let mut by_resource: HashMap<String, UserPermission> = permissions
+    let by_resource: HashMap<String, UserPermission> = with_role_defaults(permissions, role)
         .into_iter()
         .map(|perm| (perm.resource.clone(), perm))
         .collect();
 
-    if by_resource.is_empty() {
-        by_resource = default_permissions_for_role(role)
-            .into_iter()
-            .map(|perm| (perm.resource.clone(), perm))
-            .collect();
-    }
-
     match by_resource.get(resource) {
         Some(permission) => match action {
             "view" => permission.can_view,
@@ -169,12 +183,14 @@ fn default_permissions_for_role(role: &str) -> Vec<UserPermission> {
             ROLE_OPERATIONS => match *resource {
                 "dashboard" => (true, false, false),
                 "crew" => (true, true, false),
+                "assets" => (true, true, false),
                 "deployments" => (true, true, false),
                 "tracking" => (true, true, false),
                 _ => (false, false, false),
             },
             ROLE_ACCOUNTING => match *resource {
                 "dashboard" => (true, false, false),
+                "assets" => (true, false, false),
                 "invoices" => (true, false, false),
                 _ => (false, false, false),
             },
@@ -198,6 +214,17 @@ fn default_permissions_for_role(role: &str) -> Vec<UserPermission> {
     perms
 }
 
+fn with_role_defaults(stored: Vec<UserPermission>, role: &str) -> Vec<UserPermission> {
+    let mut by_resource: HashMap<String, UserPermission> = stored
+        .into_iter()
+        .map(|perm| (perm.resource.clone(), perm))
+        .collect();
+    default_permissions_for_role(role)
+        .into_iter()
+        .map(|default| by_resource.remove(&default.resource).unwrap_or(default))
+        .collect()
+}
+
 fn normalize_role(role: &str) -> &str {
     let trimmed = role.trim();
     if trimmed.is_empty() {
@@ -210,3 +237,29 @@ fn normalize_role(role: &str) -> &str {
     }
     trimmed
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permission(resource: &str, can_view: bool) -> UserPermission {
+        UserPermission {
+            resource: resource.to_string(),
+            can_view,
+            can_edit: false,
+            can_delete: false,
+        }
+    }
+
+    #[test]
+    fn missing_resources_fall_back_to_role_defaults() {
+        let stored = vec![permission("dashboard", false), permission("crew", true)];
+        let merged = with_role_defaults(stored, "Operations");
+        let find = |resource: &str| merged.iter().find(|perm| perm.resource == resource).unwrap();
+
+        assert_eq!(merged.len(), RESOURCES.len());
+        assert!(!find("dashboard").can_view);
+        assert!(!find("crew").can_edit);
+        assert!(find("assets").can_view && find("assets").can_edit);
+    }
+}
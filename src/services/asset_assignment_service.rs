@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use rocket_db_pools::sqlx;
+
+use crate::models::{
+    Asset,
+    AssetAssignment,
+    AssetCheckinForm,
+    AssetCheckoutForm,
+    AssetRegisterView,
+    AssetTargetOption,
+};
+use crate::repositories::{asset_assignment_repo, deployment_repo};
+use crate::services::{
+    asset_service,
+    crew_service,
+    deployment_service,
+    gear_service,
+    maintenance_service,
+};
+use crate::Db;
+
+const TARGET_CREW: &str = "crew";
+const TARGET_DEPLOYMENT: &str = "deployment";
+
+pub async fn list_history(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+) -> Result<Vec<AssetAssignment>, sqlx::Error> {
+    asset_assignment_repo::list_assignments_for_asset(db, tenant_id, asset_id).await
+}
+
+pub async fn list_for_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<AssetAssignment>, sqlx::Error> {
+    asset_assignment_repo::list_assignments_for_deployment(db, tenant_id, deployment_id).await
+}
+
+async fn custody_labels(db: &Db, tenant_id: i64) -> HashMap<i64, String> {
+    let now = now_local();
+    let mut labels = HashMap::new();
+    for assignment in asset_assignment_repo::list_open_assignments(db, tenant_id)
+        .await
+        .unwrap_or_default()
+    {
+        let started = parse_datetime(&assignment.checked_out_at)
+            .map(|start| start <= now)
+            .unwrap_or(true);
+        let label = if started {
+            format!("Out: {}", holder_label(&assignment))
+        } else {
+            format!("Reserved: {}", holder_label(&assignment))
+        };
+        labels.entry(assignment.asset_id).or_insert(label);
+    }
+    labels
+}
+
+pub async fn current_assignment(db: &Db, tenant_id: i64, asset_id: i64) -> Option<AssetAssignment> {
+    let now = now_local();
+    asset_assignment_repo::list_assignments_for_asset(db, tenant_id, asset_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .find(|assignment| {
+            assignment.checked_in_at.is_none()
+                && parse_datetime(&assignment.checked_out_at)
+                    .map(|start| start <= now)
+                    .unwrap_or(true)
+        })
+}
+
+pub async fn register_views(
+    db: &Db,
+    tenant_id: i64,
+    assets: Vec<Asset>,
+) -> Vec<AssetRegisterView> {
+    let mut custody = custody_labels(db, tenant_id).await;
+    let mut maintenance = maintenance_service::attention_by_asset(db, tenant_id, &assets).await;
+    assets
+        .into_iter()
+        .map(|asset| AssetRegisterView {
+            custody: custody.remove(&asset.id),
+            maintenance: maintenance.remove(&asset.id),
+            id: asset.id,
+            name: asset.name,
+            asset_type: asset.asset_type,
+            serial_number: asset.serial_number,
+            condition: asset.condition,
+            location: asset.location,
+        })
+        .collect()
+}
+
+pub fn holder_label(assignment: &AssetAssignment) -> String {
+    if let Some(deployment_id) = assignment.deployment_id {
+        match &assignment.deployment_client_name {
+            Some(client_name) => format!("{client_name} deployment #{deployment_id}"),
+            None => format!("Deleted deployment #{deployment_id}"),
+        }
+    } else {
+        assignment
+            .crew_name
+            .clone()
+            .unwrap_or_else(|| "Deleted crew".to_string())
+    }
+}
+
+pub async fn crew_target_options(db: &Db, tenant_id: i64) -> Vec<AssetTargetOption> {
+    crew_service::list_crews(db, tenant_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|crew| AssetTargetOption {
+            value: format!("{TARGET_CREW}:{}", crew.id),
+            label: crew.name,
+        })
+        .collect()
+}
+
+pub async fn deployment_target_options(db: &Db, tenant_id: i64) -> Vec<AssetTargetOption> {
+    let now = now_local();
+    let mut rows = deployment_repo::list_deployments_with_names(db, tenant_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|row| {
+            row.status != deployment_service::STATUS_CANCELLED
+                && row.status != deployment_service::STATUS_COMPLETED
+                && parse_datetime(&row.end_at)
+                    .map(|end| end >= now)
+                    .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.start_at.cmp(&b.start_at));
+    rows.into_iter()
+        .map(|row| AssetTargetOption {
+            value: format!("{TARGET_DEPLOYMENT}:{}", row.id),
+            label: format!(
+                "#{} {} - {} ({} to {})",
+                row.id, row.client_name, row.crew_name, row.start_at, row.end_at
+            ),
+        })
+        .collect()
+}
+
+pub async fn check_out(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    asset: &Asset,
+    form: AssetCheckoutForm,
+) -> Result<(), String> {
+    if asset.condition == asset_service::CONDITION_OUT_OF_SERVICE {
+        return Err("Assets that are out of service cannot be checked out.".to_string());
+    }
+    let overdue = maintenance_service::overdue_plan_names(db, tenant_id, asset).await;
+    if !overdue.is_empty() {
+        return Err(format!(
+            "{} is overdue for maintenance ({}). Log the service before checking it out.",
+            asset.name,
+            overdue.join(", ")
+        ));
+    }
+    let checked_out_at = parse_input_or_now(&form.checked_out_at)
+        .ok_or_else(|| "Check-out time must be a valid date and time.".to_string())?;
+    let (crew_id, deployment_id, window_end) = match form.target.split_once(':') {
+        Some((TARGET_CREW, id)) => {
+            let crew_id = id.parse::<i64>().map_err(|_| "Select a crew or deployment.".to_string())?;
+            match crew_service::find_crew_by_id(db, tenant_id, crew_id).await {
+                Ok(Some(_)) => (Some(crew_id), None, None),
+                _ => return Err("Crew not found.".to_string()),
+            }
+        }
+        Some((TARGET_DEPLOYMENT, id)) => {
+            let deployment_id = id
+                .parse::<i64>()
+                .map_err(|_| "Select a crew or deployment.".to_string())?;
+            let deployment = match deployment_service::find_deployment_by_id(db, tenant_id, deployment_id).await {
+                Ok(Some(deployment)) => deployment,
+                _ => return Err("Deployment not found.".to_string()),
+            };
+            if deployment.status == deployment_service::STATUS_CANCELLED
+                || deployment.status == deployment_service::STATUS_COMPLETED
+            {
+                return Err(format!(
+                    "Equipment cannot be checked out to a {} deployment.",
+                    deployment.status.to_lowercase()
+                ));
+            }
+            let end = parse_datetime(&deployment.end_at)
+                .ok_or_else(|| "Deployment end time is invalid.".to_string())?;
+            if checked_out_at >= end {
+                return Err("Check-out time must be before the deployment ends.".to_string());
+            }
+            (None, Some(deployment_id), Some(end))
+        }
+        _ => return Err("Select a crew or deployment.".to_string()),
+    };
+
+    let condition = if form.condition.trim().is_empty() {
+        asset.condition.clone()
+    } else {
+        asset_service::normalize_condition(&form.condition)
+    };
+    let now = now_local();
+    let conflict = asset_assignment_repo::create_assignment_unless_conflicting(
+        db,
+        tenant_id,
+        asset.id,
+        crew_id,
+        deployment_id,
+        &checked_out_at.format("%Y-%m-%d %H:%M").to_string(),
+        user_id,
+        &condition,
+        form.notes.trim(),
+        |assignment| {
+            custody_window(assignment, now)
+                .is_some_and(|(start, end)| overlaps(checked_out_at, window_end, start, end))
+        },
+    )
+    .await
+    .map_err(|err| format!("Unable to check out asset: {err}"))?;
+    if let Some(assignment) = conflict {
+        let end = custody_window(&assignment, now).and_then(|(_, end)| end);
+        return Err(format!(
+            "{} is assigned to {} from {}{}. Check it in or pick another time.",
+            asset.name,
+            holder_label(&assignment),
+            assignment.checked_out_at,
+            end.map(|end| format!(" until {}", end.format("%Y-%m-%d %H:%M")))
+                .unwrap_or_default()
+        ));
+    }
+    gear_service::refresh_gear_scores(db, tenant_id).await
+}
+
+pub async fn check_in(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    asset_id: i64,
+    assignment_id: i64,
+    form: AssetCheckinForm,
+) -> Result<(), String> {
+    let assignment = match asset_assignment_repo::find_assignment(db, tenant_id, assignment_id).await {
+        Ok(Some(assignment)) if assignment.asset_id == asset_id => assignment,
+        _ => return Err("Assignment not found.".to_string()),
+    };
+    if assignment.checked_in_at.is_some() {
+        return Err("This assignment has already been checked in.".to_string());
+    }
+    let checked_in_at = parse_input_or_now(&form.checked_in_at)
+        .ok_or_else(|| "Check-in time must be a valid date and time.".to_string())?;
+    if parse_datetime(&assignment.checked_out_at)
+        .map(|start| checked_in_at < start)
+        .unwrap_or(false)
+    {
+        return Err("Check-in time cannot be before the check-out time.".to_string());
+    }
+    asset_assignment_repo::check_in_assignment(
+        db,
+        tenant_id,
+        assignment_id,
+        asset_id,
+        &checked_in_at.format("%Y-%m-%d %H:%M").to_string(),
+        user_id,
+        &asset_service::normalize_condition(&form.condition),
+        form.notes.trim(),
+    )
+    .await
+    .map_err(|err| format!("Unable to check in asset: {err}"))?;
+    gear_service::refresh_gear_scores(db, tenant_id).await
+}
+
+/// The span an assignment keeps the asset busy. Returned assignments end at
+/// check-in. Open deployment custody lasts until the deployment ends (or until
+/// now when it is overdue for return); crew custody and custody for deleted
+/// deployments stay open until check-in.
+fn custody_window(
+    assignment: &AssetAssignment,
+    now: NaiveDateTime,
+) -> Option<(NaiveDateTime, Option<NaiveDateTime>)> {
+    let start = parse_datetime(&assignment.checked_out_at)?;
+    if let Some(checked_in_at) = &assignment.checked_in_at {
+        return Some((start, parse_datetime(checked_in_at)));
+    }
+    let end = assignment
+        .deployment_end_at
+        .as_deref()
+        .and_then(parse_datetime)
+        .map(|end| end.max(now));
+    Some((start, end))
+}
+
+fn overlaps(
+    start: NaiveDateTime,
+    end: Option<NaiveDateTime>,
+    other_start: NaiveDateTime,
+    other_end: Option<NaiveDateTime>,
+) -> bool {
+    end.map(|end| other_start < end).unwrap_or(true)
+        && other_end.map(|other_end| start < other_end).unwrap_or(true)
+}
+
+fn now_local() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+fn parse_input_or_now(value: &str) -> Option<NaiveDateTime> {
+    if value.trim().is_empty() {
+        let now = now_local();
+        return parse_datetime(&now.format("%Y-%m-%d %H:%M").to_string());
+    }
+    parse_datetime(&value.trim().replace('T', " "))
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value.trim(), format).ok())
+}
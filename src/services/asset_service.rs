@@ -0,0 +1,320 @@
+use chrono::NaiveDate;
+use rocket_db_pools::sqlx;
+
+use crate::models::{Asset, AssetCustomField, AssetForm, AssetFormView, AssetStats};
+use crate::repositories::{asset_assignment_repo, asset_repo};
+use crate::services::{gear_service, workspace_service};
+use crate::Db;
+
+pub struct AssetError {
+    pub message: String,
+    pub form: AssetFormView,
+}
+
+const TYPE_TOOL: &str = "Tool";
+const TYPE_POWER_TOOL: &str = "Power tool";
+const TYPE_VEHICLE: &str = "Vehicle";
+const TYPE_SAFETY: &str = "Safety equipment";
+const TYPE_IT: &str = "IT equipment";
+const TYPE_OTHER: &str = "Other";
+pub const CONDITION_NEW: &str = "New";
+pub const CONDITION_GOOD: &str = "Good";
+pub const CONDITION_FAIR: &str = "Fair";
+pub const CONDITION_POOR: &str = "Poor";
+pub const CONDITION_OUT_OF_SERVICE: &str = "Out of service";
+const MAX_CUSTOM_FIELDS: usize = 20;
+
+pub fn type_options() -> [&'static str; 6] {
+    [
+        TYPE_TOOL,
+        TYPE_POWER_TOOL,
+        TYPE_VEHICLE,
+        TYPE_SAFETY,
+        TYPE_IT,
+        TYPE_OTHER,
+    ]
+}
+
+pub fn condition_options() -> [&'static str; 5] {
+    [
+        CONDITION_NEW,
+        CONDITION_GOOD,
+        CONDITION_FAIR,
+        CONDITION_POOR,
+        CONDITION_OUT_OF_SERVICE,
+    ]
+}
+
+pub fn attention_conditions() -> [&'static str; 2] {
+    [CONDITION_POOR, CONDITION_OUT_OF_SERVICE]
+}
+
+pub fn empty_form_view() -> AssetFormView {
+    AssetFormView {
+        name: String::new(),
+        asset_type: TYPE_TOOL.to_string(),
+        serial_number: String::new(),
+        purchase_date: String::new(),
+        purchase_cost: 0.0,
+        condition: CONDITION_GOOD.to_string(),
+        location: String::new(),
+        notes: String::new(),
+        custom_fields: String::new(),
+    }
+}
+
+pub fn form_view(asset: &Asset) -> AssetFormView {
+    AssetFormView {
+        name: asset.name.clone(),
+        asset_type: asset.asset_type.clone(),
+        serial_number: asset.serial_number.clone(),
+        purchase_date: asset.purchase_date.clone().unwrap_or_default(),
+        purchase_cost: asset.purchase_cost,
+        condition: asset.condition.clone(),
+        location: asset.location.clone(),
+        notes: asset.notes.clone(),
+        custom_fields: format_custom_fields(&asset.custom_fields),
+    }
+}
+
+pub async fn list_assets_paged(
+    db: &Db,
+    tenant_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Asset>, sqlx::Error> {
+    asset_repo::list_assets_paged(db, tenant_id, limit, offset).await
+}
+
+pub async fn asset_stats(db: &Db, tenant_id: i64) -> Result<AssetStats, sqlx::Error> {
+    asset_repo::asset_stats(db, tenant_id, &attention_conditions()).await
+}
+
+pub async fn find_asset_by_id(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+) -> Result<Option<Asset>, sqlx::Error> {
+    asset_repo::find_asset_by_id(db, tenant_id, asset_id).await
+}
+
+pub async fn create_asset(db: &Db, tenant_id: i64, form: AssetForm) -> Result<i64, AssetError> {
+    let view = form_fields(form);
+    let (plan_key, limits) = workspace_service::plan_limits_for_tenant(db, tenant_id).await;
+    if let Some(limit) = limits.assets {
+        let existing = asset_repo::count_assets(db, tenant_id).await.unwrap_or(0);
+        if existing >= limit {
+            let plan_name = workspace_service::plan_name(&plan_key);
+            return Err(AssetError {
+                message: format!(
+                    "{plan_name} plan workspaces can have up to {limit} assets. Upgrade to add more."
+                ),
+                form: view,
+            });
+        }
+    }
+    let fields = validate(db, tenant_id, 0, view).await?;
+    asset_repo::create_asset(
+        db,
+        tenant_id,
+        &fields.view.name,
+        &fields.view.asset_type,
+        &fields.view.serial_number,
+        fields.purchase_date.as_deref(),
+        fields.view.purchase_cost,
+        &fields.view.condition,
+        &fields.view.location,
+        &fields.view.notes,
+        &fields.custom_fields_json,
+    )
+    .await
+    .map_err(|err| fields.error(format!("Unable to create asset: {err}")))
+}
+
+pub async fn update_asset(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+    form: AssetForm,
+) -> Result<(), AssetError> {
+    let fields = validate(db, tenant_id, asset_id, form_fields(form)).await?;
+    asset_repo::update_asset(
+        db,
+        tenant_id,
+        asset_id,
+        &fields.view.name,
+        &fields.view.asset_type,
+        &fields.view.serial_number,
+        fields.purchase_date.as_deref(),
+        fields.view.purchase_cost,
+        &fields.view.condition,
+        &fields.view.location,
+        &fields.view.notes,
+        &fields.custom_fields_json,
+    )
+    .await
+    .map_err(|err| fields.error(format!("Unable to update asset: {err}")))?;
+    gear_service::refresh_gear_scores(db, tenant_id)
+        .await
+        .map_err(|message| fields.error(message))
+}
+
+pub async fn delete_asset(db: &Db, tenant_id: i64, asset_id: i64) -> Result<(), String> {
+    let checked_out = asset_assignment_repo::list_assignments_for_asset(db, tenant_id, asset_id)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .any(|assignment| assignment.checked_in_at.is_none());
+    if checked_out {
+        return Err("Check this asset in before deleting it.".to_string());
+    }
+    asset_repo::delete_asset(db, tenant_id, asset_id)
+        .await
+        .map_err(|err| format!("Unable to delete asset: {err}"))
+}
+
+pub fn parse_custom_fields(input: &str) -> Result<Vec<AssetCustomField>, String> {
+    let mut fields: Vec<AssetCustomField> = Vec::new();
+    for line in input.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((label, value)) = line.split_once(':') else {
+            return Err(format!(
+                "Custom field \"{line}\" needs a label and a value separated by a colon."
+            ));
+        };
+        let label = label.trim();
+        if label.is_empty() {
+            return Err("Custom field labels cannot be empty.".to_string());
+        }
+        if fields
+            .iter()
+            .any(|field| field.label.eq_ignore_ascii_case(label))
+        {
+            return Err(format!("Custom field \"{label}\" is listed twice."));
+        }
+        fields.push(AssetCustomField {
+            label: label.to_string(),
+            value: value.trim().to_string(),
+        });
+    }
+    if fields.len() > MAX_CUSTOM_FIELDS {
+        return Err(format!(
+            "Assets can have up to {MAX_CUSTOM_FIELDS} custom fields."
+        ));
+    }
+    Ok(fields)
+}
+
+pub fn format_custom_fields(fields: &[AssetCustomField]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.label, field.value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct ValidatedFields {
+    view: AssetFormView,
+    purchase_date: Option<String>,
+    custom_fields_json: String,
+}
+
+impl ValidatedFields {
+    fn error(&self, message: String) -> AssetError {
+        AssetError {
+            message,
+            form: self.view.clone(),
+        }
+    }
+}
+
+fn form_fields(form: AssetForm) -> AssetFormView {
+    AssetFormView {
+        name: form.name.trim().to_string(),
+        asset_type: normalize_type(&form.asset_type),
+        serial_number: form.serial_number.trim().to_string(),
+        purchase_date: form.purchase_date.trim().to_string(),
+        purchase_cost: form.purchase_cost.unwrap_or(0.0),
+        condition: normalize_condition(&form.condition),
+        location: form.location.trim().to_string(),
+        notes: form.notes.trim().to_string(),
+        custom_fields: form.custom_fields.trim().to_string(),
+    }
+}
+
+async fn validate(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+    view: AssetFormView,
+) -> Result<ValidatedFields, AssetError> {
+    let fail = |message: &str, view: AssetFormView| AssetError {
+        message: message.to_string(),
+        form: view,
+    };
+    if view.name.is_empty() {
+        return Err(fail("Asset name is required.", view));
+    }
+    if view.name.chars().count() > 120 {
+        return Err(fail("Asset name must be 120 characters or fewer.", view));
+    }
+    if !view.purchase_cost.is_finite() || view.purchase_cost < 0.0 {
+        return Err(fail("Purchase cost cannot be negative.", view));
+    }
+    let purchase_date = if view.purchase_date.is_empty() {
+        None
+    } else {
+        match NaiveDate::parse_from_str(&view.purchase_date, "%Y-%m-%d") {
+            Ok(date) => Some(date.format("%Y-%m-%d").to_string()),
+            Err(_) => return Err(fail("Purchase date must be a valid date.", view)),
+        }
+    };
+    let custom_fields = match parse_custom_fields(&view.custom_fields) {
+        Ok(fields) => fields,
+        Err(message) => return Err(fail(&message, view)),
+    };
+    if !view.serial_number.is_empty()
+        && asset_repo::serial_number_taken(db, tenant_id, &view.serial_number, asset_id)
+            .await
+            .unwrap_or(false)
+    {
+        return Err(fail(
+            "Another asset already uses this serial number.",
+            view,
+        ));
+    }
+    let mut view = view;
+    view.custom_fields = format_custom_fields(&custom_fields);
+    Ok(ValidatedFields {
+        view,
+        purchase_date,
+        custom_fields_json: serde_json::to_string(&custom_fields).unwrap_or_else(|_| "[]".to_string()),
+    })
+}
+
+fn normalize_type(input: &str) -> String {
+    let asset_type = input.trim();
+    for option in type_options() {
+        if option.eq_ignore_ascii_case(asset_type) {
+            return option.to_string();
+        }
+    }
+    if asset_type.is_empty() {
+        TYPE_OTHER.to_string()
+    } else {
+        asset_type.to_string()
+    }
+}
+
+pub fn normalize_condition(input: &str) -> String {
+    let condition = input.trim();
+    for option in condition_options() {
+        if option.eq_ignore_ascii_case(condition) {
+            return option.to_string();
+        }
+    }
+    CONDITION_GOOD.to_string()
+}
@@ -0,0 +1,326 @@
+use std::path::{Path, PathBuf};
+
+use rocket::fs::TempFile;
+use rocket::tokio::io::AsyncReadExt;
+use rocket_db_pools::sqlx;
+
+use crate::models::UpdateAttachment;
+use crate::repositories::attachment_repo;
+use crate::services::{certification_service, expense_service, workspace_service};
+use crate::Db;
+
+const MAX_ATTACHMENT_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_FILES_PER_UPDATE: usize = 10;
+const THUMBNAIL_SIZE: u32 = 320;
+
+pub fn attachment_dir(tenant_id: i64) -> PathBuf {
+    Path::new("uploads")
+        .join("attachments")
+        .join(format!("tenant-{tenant_id}"))
+}
+
+pub fn attachment_file_path(attachment: &UpdateAttachment, thumbnail: bool) -> PathBuf {
+    let name = match (&attachment.thumbnail_name, thumbnail) {
+        (Some(thumbnail_name), true) => thumbnail_name,
+        _ => &attachment.stored_name,
+    };
+    attachment_dir(attachment.tenant_id).join(name)
+}
+
+pub async fn find_attachment_by_id(
+    db: &Db,
+    tenant_id: i64,
+    attachment_id: i64,
+) -> Result<Option<UpdateAttachment>, sqlx::Error> {
+    attachment_repo::find_attachment_by_id(db, tenant_id, attachment_id).await
+}
+
+pub async fn list_attachments_for_update(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+) -> Result<Vec<UpdateAttachment>, sqlx::Error> {
+    attachment_repo::list_attachments_for_update(db, tenant_id, update_id).await
+}
+
+pub async fn list_attachments_for_updates(
+    db: &Db,
+    tenant_id: i64,
+    update_ids: &[i64],
+) -> Result<Vec<UpdateAttachment>, sqlx::Error> {
+    attachment_repo::list_attachments_for_updates(db, tenant_id, update_ids).await
+}
+
+pub async fn list_approved_attachments_for_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<UpdateAttachment>, sqlx::Error> {
+    attachment_repo::list_approved_attachments_for_deployment(db, tenant_id, deployment_id).await
+}
+
+pub async fn list_client_visible_attachments_for_deployments(
+    db: &Db,
+    tenant_id: i64,
+    deployment_ids: &[i64],
+) -> Result<Vec<UpdateAttachment>, sqlx::Error> {
+    attachment_repo::list_client_visible_attachments_for_deployments(db, tenant_id, deployment_ids)
+        .await
+}
+
+pub async fn validate_uploads(
+    db: &Db,
+    tenant_id: i64,
+    files: &[TempFile<'_>],
+) -> Result<(), String> {
+    let files = files.iter().filter(|file| file.len() > 0).collect::<Vec<_>>();
+    if files.is_empty() {
+        return Ok(());
+    }
+    if files.len() > MAX_FILES_PER_UPDATE {
+        return Err(format!(
+            "Attach up to {MAX_FILES_PER_UPDATE} files per update."
+        ));
+    }
+    let mut upload_bytes = 0;
+    for file in &files {
+        if file.len() > MAX_ATTACHMENT_BYTES {
+            return Err("Attachments must be 10 MB or smaller.".to_string());
+        }
+        let Some(kind) = declared_kind(file) else {
+            return Err("Attachments must be PNG, JPG, WebP, or PDF files.".to_string());
+        };
+        if !content_matches(file, kind).await {
+            return Err("An attachment's contents do not match its file type.".to_string());
+        }
+        upload_bytes += file.len();
+    }
+
+    let (plan_key, limits) = workspace_service::plan_limits_for_tenant(db, tenant_id).await;
+    if let Some(limit_mb) = limits.storage_mb {
+        let used = storage_used_bytes(db, tenant_id).await;
+        if used + upload_bytes > limit_mb as u64 * 1024 * 1024 {
+            let plan_name = workspace_service::plan_name(&plan_key);
+            return Err(format!(
+                "{plan_name} plan workspaces can store up to {limit_mb} MB of files. Upgrade or remove older files to add more."
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn storage_used_bytes(db: &Db, tenant_id: i64) -> u64 {
+    let attachments = attachment_repo::total_size_bytes(db, tenant_id).await.unwrap_or(0);
+    let files = [
+        expense_service::receipt_dir(tenant_id),
+        certification_service::document_dir(tenant_id),
+    ]
+    .iter()
+    .map(|dir| dir_size(dir))
+    .sum::<u64>();
+    attachments.max(0) as u64 + files
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+pub async fn store_attachments(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    files: Vec<TempFile<'_>>,
+) -> Result<(), String> {
+    let files = files.into_iter().filter(|file| file.len() > 0).collect::<Vec<_>>();
+    if files.is_empty() {
+        return Ok(());
+    }
+    let dir = attachment_dir(tenant_id);
+    if let Err(err) = std::fs::create_dir_all(&dir) {
+        eprintln!("Unable to create attachment directory: {err}");
+        return Err("The update was saved, but its attachments could not be stored. Try uploading them again.".to_string());
+    }
+    let timestamp = chrono::Utc::now().timestamp();
+    let mut failed = Vec::new();
+    for (index, mut file) in files.into_iter().enumerate() {
+        let Some(kind) = declared_kind(&file) else {
+            continue;
+        };
+        let size_bytes = file.len() as i64;
+        let original_name = original_name(&file, kind);
+        let stored_name = format!(
+            "update-{update_id}-{timestamp}-{index}.{}",
+            kind.extension
+        );
+        let path = dir.join(&stored_name);
+        if let Err(err) = file.move_copy_to(&path).await {
+            eprintln!("Unable to save attachment for update {update_id}: {err}");
+            failed.push(original_name);
+            continue;
+        }
+        let thumbnail_name = if kind.is_image {
+            let thumbnail_name = format!("thumb-update-{update_id}-{timestamp}-{index}.jpg");
+            create_thumbnail(path.clone(), dir.join(&thumbnail_name))
+                .await
+                .then_some(thumbnail_name)
+        } else {
+            None
+        };
+        if let Err(err) = attachment_repo::create_attachment(
+            db,
+            tenant_id,
+            update_id,
+            deployment_id,
+            user_id,
+            &original_name,
+            &stored_name,
+            thumbnail_name.as_deref(),
+            kind.content_type,
+            size_bytes,
+        )
+        .await
+        {
+            eprintln!("Unable to record attachment for update {update_id}: {err}");
+            let _ = std::fs::remove_file(&path);
+            if let Some(thumbnail_name) = thumbnail_name {
+                let _ = std::fs::remove_file(dir.join(thumbnail_name));
+            }
+            failed.push(original_name);
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "The update was saved, but these attachments could not be stored: {}. Try uploading them again.",
+            failed.join(", ")
+        ))
+    }
+}
+
+pub async fn set_client_visible(
+    db: &Db,
+    tenant_id: i64,
+    attachment: &UpdateAttachment,
+    is_client_visible: bool,
+) -> Result<(), String> {
+    attachment_repo::set_client_visible(db, tenant_id, attachment.id, is_client_visible)
+        .await
+        .map_err(|err| format!("Unable to update attachment: {err}"))
+}
+
+pub async fn delete_attachment(
+    db: &Db,
+    tenant_id: i64,
+    attachment: &UpdateAttachment,
+) -> Result<(), String> {
+    attachment_repo::delete_attachment(db, tenant_id, attachment.id)
+        .await
+        .map_err(|err| format!("Unable to delete attachment: {err}"))?;
+    remove_files(attachment);
+    Ok(())
+}
+
+pub async fn delete_attachments_for_update(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+) -> Result<(), sqlx::Error> {
+    for attachment in attachment_repo::list_attachments_for_update(db, tenant_id, update_id).await? {
+        attachment_repo::delete_attachment(db, tenant_id, attachment.id).await?;
+        remove_files(&attachment);
+    }
+    Ok(())
+}
+
+fn remove_files(attachment: &UpdateAttachment) {
+    let _ = std::fs::remove_file(attachment_file_path(attachment, false));
+    if attachment.thumbnail_name.is_some() {
+        let _ = std::fs::remove_file(attachment_file_path(attachment, true));
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct FileKind {
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub is_image: bool,
+}
+
+pub fn declared_kind(file: &TempFile<'_>) -> Option<FileKind> {
+    let media_type = file.content_type()?.media_type();
+    let (content_type, extension, is_image) =
+        match (media_type.top().as_str(), media_type.sub().as_str()) {
+            ("image", "png") => ("image/png", "png", true),
+            ("image", "jpeg") | ("image", "jpg") => ("image/jpeg", "jpg", true),
+            ("image", "webp") => ("image/webp", "webp", true),
+            ("application", "pdf") => ("application/pdf", "pdf", false),
+            _ => return None,
+        };
+    Some(FileKind {
+        content_type,
+        extension,
+        is_image,
+    })
+}
+
+/// Compares the file's leading bytes with the signature of its declared type,
+/// so a renamed executable cannot be uploaded as a photo.
+pub async fn content_matches(file: &TempFile<'_>, kind: FileKind) -> bool {
+    let mut header = [0u8; 12];
+    let read = match file.open().await {
+        Ok(mut reader) => reader.read(&mut header).await.unwrap_or(0),
+        Err(_) => return false,
+    };
+    let header = &header[..read];
+    match kind.extension {
+        "png" => header.starts_with(&[0x89, b'P', b'N', b'G']),
+        "jpg" => header.starts_with(&[0xFF, 0xD8, 0xFF]),
+        "webp" => header.len() >= 12 && &header[..4] == b"RIFF" && &header[8..12] == b"WEBP",
+        "pdf" => header.starts_with(b"%PDF"),
+        _ => false,
+    }
+}
+
+fn original_name(file: &TempFile<'_>, kind: FileKind) -> String {
+    let name = file
+        .raw_name()
+        .map(|name| name.dangerous_unsafe_unsanitized_raw().as_str())
+        .unwrap_or_default();
+    let name = Path::new(name)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .chars()
+        .filter(|ch| !ch.is_control())
+        .collect::<String>();
+    let name = name.trim();
+    if name.is_empty() {
+        format!("attachment.{}", kind.extension)
+    } else {
+        name.chars().take(120).collect()
+    }
+}
+
+async fn create_thumbnail(source: PathBuf, target: PathBuf) -> bool {
+    rocket::tokio::task::spawn_blocking(move || {
+        image::open(&source)
+            .map(|image| image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8())
+            .and_then(|thumbnail| thumbnail.save_with_format(&target, image::ImageFormat::Jpeg))
+            .is_ok()
+    })
+    .await
+    .unwrap_or(false)
+}
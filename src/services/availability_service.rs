@@ -0,0 +1,702 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use rocket_db_pools::sqlx;
+
+use crate::models::{
+    AvailabilityCell,
+    AvailabilityConflict,
+    AvailabilityDay,
+    AvailabilityRow,
+    CrewMember,
+    DeploymentSummary,
+    LeaveRequest,
+    LeaveRequestForm,
+    LeaveRequestFormView,
+    RecurringUnavailability,
+    RecurringUnavailabilityForm,
+    User,
+    WorkspaceHoliday,
+    WorkspaceHolidayForm,
+};
+use crate::repositories::{availability_repo, crew_member_repo, crew_repo};
+use crate::services::email_service;
+use crate::services::utils::escape_html;
+use crate::Db;
+
+pub const STATUS_PENDING: &str = "Pending";
+pub const STATUS_APPROVED: &str = "Approved";
+pub const STATUS_REJECTED: &str = "Rejected";
+pub const STATUS_CANCELLED: &str = "Cancelled";
+
+pub const KIND_HOLIDAY: &str = "holiday";
+pub const KIND_LEAVE: &str = "leave";
+pub const KIND_PENDING: &str = "pending";
+pub const KIND_RECURRING: &str = "recurring";
+
+const LEAVE_ANNUAL: &str = "Annual leave";
+const LEAVE_SICK: &str = "Sick leave";
+const LEAVE_TRAINING: &str = "Training";
+const LEAVE_PERSONAL: &str = "Personal";
+const LEAVE_OTHER: &str = "Other";
+const MAX_LEAVE_DAYS: i64 = 366;
+const MAX_WINDOW_DAYS: i64 = 62;
+const RECENT_REQUEST_LIMIT: i64 = 50;
+
+pub struct LeaveRequestError {
+    pub message: String,
+    pub form: LeaveRequestFormView,
+}
+
+#[derive(Default)]
+pub struct AvailabilityCalendar {
+    holidays: HashMap<NaiveDate, String>,
+    leave: Vec<LeaveRequest>,
+    recurring: Vec<RecurringUnavailability>,
+}
+
+pub struct Absence {
+    pub kind: &'static str,
+    pub label: String,
+}
+
+pub fn leave_type_options() -> [&'static str; 5] {
+    [
+        LEAVE_ANNUAL,
+        LEAVE_SICK,
+        LEAVE_TRAINING,
+        LEAVE_PERSONAL,
+        LEAVE_OTHER,
+    ]
+}
+
+pub fn weekday_options() -> [&'static str; 7] {
+    [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ]
+}
+
+pub fn empty_leave_form() -> LeaveRequestFormView {
+    LeaveRequestFormView {
+        member_id: 0,
+        start_date: String::new(),
+        end_date: String::new(),
+        leave_type: LEAVE_ANNUAL.to_string(),
+        reason: String::new(),
+    }
+}
+
+pub fn month_bounds(month: Option<&str>) -> (NaiveDate, NaiveDate) {
+    let first = month
+        .and_then(|value| NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d").ok())
+        .unwrap_or_else(|| {
+            let today = Local::now().date_naive();
+            today.with_day(1).unwrap_or(today)
+        });
+    let next = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    };
+    let last = next.map(|next| next - Duration::days(1)).unwrap_or(first);
+    (first, last)
+}
+
+pub fn deployment_dates(start_at: &str, end_at: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let start = parse_date_prefix(start_at)?;
+    let end = parse_date_prefix(end_at).unwrap_or(start);
+    Some((start, end.max(start)))
+}
+
+pub fn window_days(start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    let days = (end - start).num_days().clamp(0, MAX_WINDOW_DAYS - 1);
+    (0..=days).map(|offset| start + Duration::days(offset)).collect()
+}
+
+pub async fn load_calendar(
+    db: &Db,
+    tenant_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> AvailabilityCalendar {
+    let from = start.format("%Y-%m-%d").to_string();
+    let until = end.format("%Y-%m-%d").to_string();
+    let holidays = availability_repo::list_holidays(db, tenant_id, &from, &until)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|holiday| {
+            parse_date_prefix(&holiday.holiday_date).map(|date| (date, holiday.name))
+        })
+        .collect();
+    let leave = availability_repo::list_leave_overlapping(
+        db,
+        tenant_id,
+        &from,
+        &until,
+        &[STATUS_APPROVED, STATUS_PENDING],
+    )
+    .await
+    .unwrap_or_default();
+    let recurring = availability_repo::list_recurring(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    AvailabilityCalendar {
+        holidays,
+        leave,
+        recurring,
+    }
+}
+
+impl AvailabilityCalendar {
+    pub fn absence(&self, member: &CrewMember, date: NaiveDate) -> Option<Absence> {
+        let date_key = date.format("%Y-%m-%d").to_string();
+        let mut pending = None;
+        for leave in &self.leave {
+            if leave.start_date.as_str() > date_key.as_str()
+                || leave.end_date.as_str() < date_key.as_str()
+                || !same_person(member, leave.member_id, leave.member_user_id, &leave.member_email)
+            {
+                continue;
+            }
+            if leave.status == STATUS_APPROVED {
+                return Some(Absence {
+                    kind: KIND_LEAVE,
+                    label: leave.leave_type.clone(),
+                });
+            }
+            pending.get_or_insert_with(|| Absence {
+                kind: KIND_PENDING,
+                label: format!("{} (pending)", leave.leave_type),
+            });
+        }
+        if let Some(name) = self.holidays.get(&date) {
+            return Some(Absence {
+                kind: KIND_HOLIDAY,
+                label: name.clone(),
+            });
+        }
+        let weekday = date.weekday().num_days_from_monday() as i64;
+        if let Some(rule) = self.recurring.iter().find(|rule| {
+            rule.weekday == weekday
+                && same_person(member, rule.member_id, rule.member_user_id, &rule.member_email)
+        }) {
+            let day = weekday_options()[weekday as usize];
+            return Some(Absence {
+                kind: KIND_RECURRING,
+                label: if rule.note.trim().is_empty() {
+                    format!("Not available on {day}s")
+                } else {
+                    rule.note.clone()
+                },
+            });
+        }
+        pending
+    }
+
+    pub fn is_unavailable(&self, member: &CrewMember, date: NaiveDate) -> bool {
+        self.absence(member, date)
+            .is_some_and(|absence| absence.kind != KIND_PENDING)
+    }
+
+    pub fn holiday(&self, date: NaiveDate) -> Option<&String> {
+        self.holidays.get(&date)
+    }
+
+    pub fn crew_conflicts(
+        &self,
+        members: &[&CrewMember],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Vec<AvailabilityConflict> {
+        let days = window_days(start, end);
+        let mut conflicts = Vec::new();
+        if !members.is_empty() {
+            for day in &days {
+                if let Some(name) = self.holidays.get(day) {
+                    conflicts.push(AvailabilityConflict {
+                        member_name: "Whole crew".to_string(),
+                        reason: format!("Public holiday: {name}"),
+                        dates: day.format("%d %b %Y").to_string(),
+                        is_pending: false,
+                    });
+                }
+            }
+        }
+        for member in members {
+            let mut runs: Vec<(String, &'static str, NaiveDate, NaiveDate)> = Vec::new();
+            for day in &days {
+                let Some(absence) = self.absence(member, *day) else {
+                    continue;
+                };
+                if absence.kind == KIND_HOLIDAY {
+                    continue;
+                }
+                match runs.last_mut() {
+                    Some((label, _, _, last))
+                        if *label == absence.label && *last + Duration::days(1) == *day =>
+                    {
+                        *last = *day;
+                    }
+                    _ => runs.push((absence.label, absence.kind, *day, *day)),
+                }
+            }
+            for (label, kind, first, last) in runs {
+                conflicts.push(AvailabilityConflict {
+                    member_name: member.name.clone(),
+                    reason: label,
+                    dates: if first == last {
+                        first.format("%d %b %Y").to_string()
+                    } else {
+                        format!("{} – {}", first.format("%d %b"), last.format("%d %b %Y"))
+                    },
+                    is_pending: kind == KIND_PENDING,
+                });
+            }
+        }
+        conflicts
+    }
+}
+
+pub async fn list_members(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: Option<i64>,
+) -> Result<Vec<CrewMember>, sqlx::Error> {
+    let crew_ids = match crew_id {
+        Some(crew_id) => vec![crew_id],
+        None => crew_repo::list_crews(db, tenant_id)
+            .await?
+            .into_iter()
+            .map(|crew| crew.id)
+            .collect(),
+    };
+    crew_member_repo::list_members_for_crews(db, tenant_id, &crew_ids).await
+}
+
+pub fn is_own_member(member: &CrewMember, user: &User) -> bool {
+    member.user_id == Some(user.id)
+        || (member.user_id.is_none()
+            && !member.email.trim().is_empty()
+            && member.email.trim().eq_ignore_ascii_case(user.email.trim()))
+}
+
+pub fn calendar_days(
+    calendar: &AvailabilityCalendar,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<AvailabilityDay> {
+    let today = Local::now().date_naive();
+    let mut days = Vec::new();
+    let mut day = start;
+    while day <= end {
+        days.push(AvailabilityDay {
+            date: day.format("%Y-%m-%d").to_string(),
+            day: day.day(),
+            weekday: day.format("%a").to_string(),
+            is_weekend: day.weekday().num_days_from_monday() >= 5,
+            is_today: day == today,
+            holiday: calendar.holiday(day).cloned(),
+        });
+        day += Duration::days(1);
+    }
+    days
+}
+
+pub fn calendar_rows(
+    calendar: &AvailabilityCalendar,
+    members: &[CrewMember],
+    crew_names: &HashMap<i64, String>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<AvailabilityRow> {
+    members
+        .iter()
+        .map(|member| {
+            let mut cells = Vec::new();
+            let mut days_unavailable = 0;
+            let mut day = start;
+            while day <= end {
+                let cell = match calendar.absence(member, day) {
+                    Some(absence) => {
+                        if absence.kind != KIND_PENDING {
+                            days_unavailable += 1;
+                        }
+                        AvailabilityCell {
+                            kind: absence.kind.to_string(),
+                            label: absence.label,
+                        }
+                    }
+                    None => AvailabilityCell {
+                        kind: String::new(),
+                        label: String::new(),
+                    },
+                };
+                cells.push(cell);
+                day += Duration::days(1);
+            }
+            AvailabilityRow {
+                member_id: member.id,
+                crew_id: member.crew_id,
+                member_name: member.name.clone(),
+                crew_name: crew_names.get(&member.crew_id).cloned().unwrap_or_default(),
+                availability_status: member.availability_status.clone(),
+                days_unavailable,
+                cells,
+            }
+        })
+        .collect()
+}
+
+pub async fn list_leave_requests(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<LeaveRequest>, sqlx::Error> {
+    availability_repo::list_recent_leave_requests(db, tenant_id, RECENT_REQUEST_LIMIT).await
+}
+
+pub async fn find_leave_request(
+    db: &Db,
+    tenant_id: i64,
+    request_id: i64,
+) -> Result<Option<LeaveRequest>, sqlx::Error> {
+    availability_repo::find_leave_request(db, tenant_id, request_id).await
+}
+
+pub async fn list_holidays(
+    db: &Db,
+    tenant_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<WorkspaceHoliday>, sqlx::Error> {
+    availability_repo::list_holidays(
+        db,
+        tenant_id,
+        &start.format("%Y-%m-%d").to_string(),
+        &end.format("%Y-%m-%d").to_string(),
+    )
+    .await
+}
+
+pub async fn list_recurring(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<RecurringUnavailability>, sqlx::Error> {
+    availability_repo::list_recurring(db, tenant_id).await
+}
+
+pub async fn request_leave(
+    db: &Db,
+    tenant_id: i64,
+    user: &User,
+    member: &CrewMember,
+    form: &LeaveRequestForm,
+    auto_approve: bool,
+) -> Result<(), LeaveRequestError> {
+    let view = LeaveRequestFormView {
+        member_id: form.member_id,
+        start_date: form.start_date.trim().to_string(),
+        end_date: form.end_date.trim().to_string(),
+        leave_type: normalize_leave_type(&form.leave_type),
+        reason: form.reason.trim().to_string(),
+    };
+    let invalid = |message: &str| LeaveRequestError {
+        message: message.to_string(),
+        form: view.clone(),
+    };
+    let Some(start) = parse_date_prefix(&view.start_date) else {
+        return Err(invalid("Leave start date is required."));
+    };
+    let end = if view.end_date.is_empty() {
+        start
+    } else {
+        match parse_date_prefix(&view.end_date) {
+            Some(end) => end,
+            None => return Err(invalid("Leave end date must be a valid date.")),
+        }
+    };
+    if end < start {
+        return Err(invalid("Leave must end on or after its start date."));
+    }
+    if (end - start).num_days() >= MAX_LEAVE_DAYS {
+        return Err(invalid("A single leave request can cover at most a year."));
+    }
+    let start_key = start.format("%Y-%m-%d").to_string();
+    let end_key = end.format("%Y-%m-%d").to_string();
+    let overlapping = availability_repo::list_leave_overlapping(
+        db,
+        tenant_id,
+        &start_key,
+        &end_key,
+        &[STATUS_APPROVED, STATUS_PENDING],
+    )
+    .await
+    .unwrap_or_default();
+    if overlapping.iter().any(|leave| {
+        same_person(member, leave.member_id, leave.member_user_id, &leave.member_email)
+    }) {
+        return Err(invalid(
+            "This member already has leave booked or pending on some of these dates.",
+        ));
+    }
+
+    let (status, reviewer) = if auto_approve {
+        (STATUS_APPROVED, Some(user.id))
+    } else {
+        (STATUS_PENDING, None)
+    };
+    availability_repo::create_leave_request(
+        db,
+        tenant_id,
+        member.crew_id,
+        member.id,
+        user.id,
+        &start_key,
+        &end_key,
+        &view.leave_type,
+        &view.reason,
+        status,
+        reviewer,
+    )
+    .await
+    .map(|_| ())
+    .map_err(|err| LeaveRequestError {
+        message: format!("Unable to save leave request: {err}"),
+        form: view.clone(),
+    })
+}
+
+pub async fn review_leave(
+    db: &Db,
+    tenant_id: i64,
+    reviewer: &User,
+    request: &LeaveRequest,
+    approve: bool,
+    review_note: Option<&str>,
+) -> Result<(), String> {
+    if request.status != STATUS_PENDING {
+        return Err("Only pending leave requests can be reviewed.".to_string());
+    }
+    let status = if approve { STATUS_APPROVED } else { STATUS_REJECTED };
+    let note = review_note.unwrap_or("").trim();
+    availability_repo::update_leave_status(db, tenant_id, request.id, status, Some(reviewer.id), note)
+        .await
+        .map_err(|err| format!("Unable to update leave request: {err}"))?;
+    notify_decision(db, request, status, note).await;
+    Ok(())
+}
+
+pub async fn cancel_leave(db: &Db, tenant_id: i64, request: &LeaveRequest) -> Result<(), String> {
+    if request.status != STATUS_PENDING && request.status != STATUS_APPROVED {
+        return Err("This leave request is already closed.".to_string());
+    }
+    availability_repo::update_leave_status(
+        db,
+        tenant_id,
+        request.id,
+        STATUS_CANCELLED,
+        None,
+        &request.review_note,
+    )
+    .await
+    .map_err(|err| format!("Unable to cancel leave request: {err}"))
+}
+
+pub async fn add_holiday(
+    db: &Db,
+    tenant_id: i64,
+    form: &WorkspaceHolidayForm,
+) -> Result<(), String> {
+    let Some(date) = parse_date_prefix(&form.holiday_date) else {
+        return Err("Holiday date must be a valid date.".to_string());
+    };
+    let name = form.name.trim();
+    if name.is_empty() {
+        return Err("Holiday name is required.".to_string());
+    }
+    availability_repo::save_holiday(db, tenant_id, &date.format("%Y-%m-%d").to_string(), name)
+        .await
+        .map_err(|err| format!("Unable to save holiday: {err}"))
+}
+
+pub async fn delete_holiday(db: &Db, tenant_id: i64, holiday_id: i64) -> Result<(), String> {
+    availability_repo::delete_holiday(db, tenant_id, holiday_id)
+        .await
+        .map_err(|err| format!("Unable to delete holiday: {err}"))
+}
+
+pub async fn add_recurring(
+    db: &Db,
+    tenant_id: i64,
+    member: &CrewMember,
+    form: &RecurringUnavailabilityForm,
+) -> Result<(), String> {
+    if !(0..7).contains(&form.weekday) {
+        return Err("Choose a day of the week.".to_string());
+    }
+    availability_repo::save_recurring(
+        db,
+        tenant_id,
+        member.crew_id,
+        member.id,
+        form.weekday,
+        form.note.trim(),
+    )
+    .await
+    .map_err(|err| format!("Unable to save recurring unavailability: {err}"))
+}
+
+pub async fn delete_recurring(db: &Db, tenant_id: i64, recurring_id: i64) -> Result<(), String> {
+    availability_repo::delete_recurring(db, tenant_id, recurring_id)
+        .await
+        .map_err(|err| format!("Unable to delete recurring unavailability: {err}"))
+}
+
+pub async fn delete_availability_for_member(
+    db: &Db,
+    tenant_id: i64,
+    member_id: i64,
+) -> Result<(), sqlx::Error> {
+    availability_repo::delete_availability_for_member(db, tenant_id, member_id).await
+}
+
+pub async fn delete_availability_for_crew(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+) -> Result<(), sqlx::Error> {
+    availability_repo::delete_availability_for_crew(db, tenant_id, crew_id).await
+}
+
+pub async fn deployment_conflicts(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    start_at: &str,
+    end_at: &str,
+) -> Vec<AvailabilityConflict> {
+    let Some((start, end)) = deployment_dates(start_at, end_at) else {
+        return Vec::new();
+    };
+    let members = crew_member_repo::list_members_for_crews(db, tenant_id, &[crew_id])
+        .await
+        .unwrap_or_default();
+    let calendar = load_calendar(db, tenant_id, start, end).await;
+    calendar.crew_conflicts(&members.iter().collect::<Vec<_>>(), start, end)
+}
+
+pub async fn conflict_counts_map(
+    db: &Db,
+    tenant_id: i64,
+    deployments: &[&DeploymentSummary],
+) -> HashMap<i64, usize> {
+    let today = Local::now().date_naive();
+    let upcoming = deployments
+        .iter()
+        .filter(|deployment| deployment.status != "Completed" && deployment.status != "Cancelled")
+        .filter_map(|deployment| {
+            deployment_dates(&deployment.start_at, &deployment.end_at)
+                .filter(|(_, end)| *end >= today)
+                .map(|(start, end)| (*deployment, start.max(today), end))
+        })
+        .collect::<Vec<_>>();
+    let (Some(from), Some(until)) = (
+        upcoming.iter().map(|(_, start, _)| *start).min(),
+        upcoming.iter().map(|(_, _, end)| *end).max(),
+    ) else {
+        return HashMap::new();
+    };
+    let mut crew_ids = upcoming
+        .iter()
+        .map(|(deployment, _, _)| deployment.crew_id)
+        .collect::<Vec<_>>();
+    crew_ids.sort_unstable();
+    crew_ids.dedup();
+    let members = crew_member_repo::list_members_for_crews(db, tenant_id, &crew_ids)
+        .await
+        .unwrap_or_default();
+    let calendar = load_calendar(db, tenant_id, from, until).await;
+
+    upcoming
+        .into_iter()
+        .filter_map(|(deployment, start, end)| {
+            let crew_members = members
+                .iter()
+                .filter(|member| member.crew_id == deployment.crew_id)
+                .collect::<Vec<_>>();
+            let count = calendar
+                .crew_conflicts(&crew_members, start, end)
+                .iter()
+                .filter(|conflict| !conflict.is_pending)
+                .count();
+            (count > 0).then_some((deployment.id, count))
+        })
+        .collect()
+}
+
+async fn notify_decision(db: &Db, request: &LeaveRequest, status: &str, note: &str) {
+    let to = if request.member_email.trim().is_empty() {
+        request.requested_by_email.clone().unwrap_or_default()
+    } else {
+        request.member_email.clone()
+    };
+    if to.trim().is_empty() {
+        return;
+    }
+    let dates = if request.start_date == request.end_date {
+        request.start_date.clone()
+    } else {
+        format!("{} to {}", request.start_date, request.end_date)
+    };
+    let subject = format!("{} request {}", request.leave_type, status.to_lowercase());
+    let note = if note.is_empty() {
+        String::new()
+    } else {
+        format!("<p>Note from the reviewer: {}</p>", escape_html(note))
+    };
+    let body = format!(
+        "<p>{}'s {} request for <strong>{}</strong> has been <strong>{}</strong>.</p>{note}",
+        escape_html(&request.member_name),
+        escape_html(&request.leave_type.to_lowercase()),
+        escape_html(&dates),
+        status.to_lowercase(),
+    );
+    let _ = email_service::queue_email(
+        db,
+        request.tenant_id,
+        None,
+        None,
+        to,
+        Vec::new(),
+        subject,
+        body,
+    )
+    .await;
+}
+
+fn same_person(member: &CrewMember, member_id: i64, user_id: Option<i64>, email: &str) -> bool {
+    member.id == member_id
+        || (member.user_id.is_some() && member.user_id == user_id)
+        || (!member.email.trim().is_empty()
+            && member.email.trim().eq_ignore_ascii_case(email.trim()))
+}
+
+fn normalize_leave_type(input: &str) -> String {
+    let value = input.trim();
+    leave_type_options()
+        .into_iter()
+        .find(|option| option.eq_ignore_ascii_case(value))
+        .unwrap_or(LEAVE_OTHER)
+        .to_string()
+}
+
+fn parse_date_prefix(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    NaiveDate::parse_from_str(value.get(..10).unwrap_or(value), "%Y-%m-%d").ok()
+}
@@ -0,0 +1,532 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::Duration;
+use rocket::fs::TempFile;
+use rocket_db_pools::sqlx;
+
+use crate::models::{CrewCertification, CrewCertificationForm, CrewCertificationFormView};
+use crate::repositories::{certification_repo, user_repo};
+use crate::services::{access_service, attachment_service, email_service};
+use crate::services::utils::escape_html;
+use crate::Db;
+
+pub const STATUS_VALID: &str = "Valid";
+pub const STATUS_EXPIRING: &str = "Expiring";
+pub const STATUS_EXPIRED: &str = "Expired";
+
+pub const EXPIRY_WARNING_DAYS: i64 = 60;
+const REMINDER_THRESHOLDS: [i64; 3] = [7, 30, 60];
+const MAX_DOCUMENT_BYTES: u64 = 10 * 1024 * 1024;
+
+pub struct CertificationError {
+    pub message: String,
+    pub form: CrewCertificationFormView,
+}
+
+#[derive(Default)]
+pub struct CertifiedSkills {
+    pub valid: HashSet<String>,
+    pub lapsed: HashSet<String>,
+}
+
+pub fn document_dir(tenant_id: i64) -> PathBuf {
+    Path::new("uploads")
+        .join("certifications")
+        .join(format!("tenant-{tenant_id}"))
+}
+
+pub fn document_file_path(certification: &CrewCertification) -> Option<PathBuf> {
+    certification
+        .document_path
+        .as_deref()
+        .filter(|name| !name.is_empty())
+        .map(|name| document_dir(certification.tenant_id).join(name))
+}
+
+pub fn empty_form_view() -> CrewCertificationFormView {
+    CrewCertificationFormView {
+        name: String::new(),
+        skill_tag: String::new(),
+        issuing_body: String::new(),
+        certificate_number: String::new(),
+        issued_on: String::new(),
+        expires_on: String::new(),
+    }
+}
+
+pub fn form_view(certification: &CrewCertification) -> CrewCertificationFormView {
+    CrewCertificationFormView {
+        name: certification.name.clone(),
+        skill_tag: certification.skill_tag.clone(),
+        issuing_body: certification.issuing_body.clone(),
+        certificate_number: certification.certificate_number.clone(),
+        issued_on: certification.issued_on.clone().unwrap_or_default(),
+        expires_on: certification.expires_on.clone().unwrap_or_default(),
+    }
+}
+
+pub async fn list_certifications_for_member(
+    db: &Db,
+    tenant_id: i64,
+    member_id: i64,
+) -> Result<Vec<CrewCertification>, sqlx::Error> {
+    certification_repo::list_certifications_for_member(db, tenant_id, member_id)
+        .await
+        .map(with_statuses)
+}
+
+pub async fn list_certifications_for_crew(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+) -> Result<Vec<CrewCertification>, sqlx::Error> {
+    certification_repo::list_certifications_for_crew(db, tenant_id, crew_id)
+        .await
+        .map(with_statuses)
+}
+
+pub async fn list_expiry_warnings(
+    db: &Db,
+    tenant_id: i64,
+    limit: i64,
+) -> Result<Vec<CrewCertification>, sqlx::Error> {
+    let until = (chrono::Local::now().date_naive() + Duration::days(EXPIRY_WARNING_DAYS))
+        .format("%Y-%m-%d")
+        .to_string();
+    certification_repo::list_expiring_certifications(db, tenant_id, &until, limit)
+        .await
+        .map(with_statuses)
+}
+
+pub async fn find_certification_by_id(
+    db: &Db,
+    tenant_id: i64,
+    member_id: i64,
+    certification_id: i64,
+) -> Result<Option<CrewCertification>, sqlx::Error> {
+    certification_repo::find_certification_by_id(db, tenant_id, member_id, certification_id)
+        .await
+        .map(|certification| certification.map(with_status))
+}
+
+pub async fn certified_skills_by_crew(db: &Db, tenant_id: i64) -> HashMap<i64, CertifiedSkills> {
+    let certifications = certification_repo::list_certifications_for_tenant(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    certified_skills(&with_statuses(certifications))
+}
+
+pub fn certified_skills(certifications: &[CrewCertification]) -> HashMap<i64, CertifiedSkills> {
+    let mut skills: HashMap<i64, CertifiedSkills> = HashMap::new();
+    for certification in certifications {
+        let entry = skills.entry(certification.crew_id).or_default();
+        if certification.status == STATUS_EXPIRED {
+            entry.lapsed.insert(certification.skill_tag.clone());
+        } else {
+            entry.valid.insert(certification.skill_tag.clone());
+        }
+    }
+    for entry in skills.values_mut() {
+        let valid = entry.valid.clone();
+        entry.lapsed.retain(|skill| !valid.contains(skill));
+    }
+    skills
+}
+
+pub async fn create_certification(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    member_id: i64,
+    form: CrewCertificationForm<'_>,
+) -> Result<(), CertificationError> {
+    let mut form = form;
+    let view = form_fields(&form);
+    let fields = validate(&view).map_err(|message| CertificationError {
+        message,
+        form: view.clone(),
+    })?;
+    let document = take_document(&mut form).await.map_err(|message| fields.error(message))?;
+    let certification_id = certification_repo::create_certification(
+        db,
+        tenant_id,
+        crew_id,
+        member_id,
+        &fields.view.name,
+        &fields.view.skill_tag,
+        &fields.view.issuing_body,
+        &fields.view.certificate_number,
+        fields.issued_on.as_deref(),
+        fields.expires_on.as_deref(),
+    )
+    .await
+    .map_err(|err| fields.error(format!("Unable to save certification: {err}")))?;
+
+    if let Some((file, extension)) = document
+        && let Err(message) =
+            store_document(db, tenant_id, certification_id, file, extension).await
+    {
+        let _ = certification_repo::delete_certification(db, tenant_id, certification_id).await;
+        return Err(fields.error(message));
+    }
+    Ok(())
+}
+
+pub async fn update_certification(
+    db: &Db,
+    tenant_id: i64,
+    certification: &CrewCertification,
+    form: CrewCertificationForm<'_>,
+) -> Result<(), CertificationError> {
+    let mut form = form;
+    let view = form_fields(&form);
+    let fields = validate(&view).map_err(|message| CertificationError {
+        message,
+        form: view.clone(),
+    })?;
+    let document = take_document(&mut form).await.map_err(|message| fields.error(message))?;
+    certification_repo::update_certification(
+        db,
+        tenant_id,
+        certification.id,
+        &fields.view.name,
+        &fields.view.skill_tag,
+        &fields.view.issuing_body,
+        &fields.view.certificate_number,
+        fields.issued_on.as_deref(),
+        fields.expires_on.as_deref(),
+    )
+    .await
+    .map_err(|err| fields.error(format!("Unable to save certification: {err}")))?;
+
+    if let Some((file, extension)) = document {
+        store_document(db, tenant_id, certification.id, file, extension)
+            .await
+            .map_err(|message| fields.error(message))?;
+        if let Some(path) = document_file_path(certification) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    Ok(())
+}
+
+pub async fn delete_certification(
+    db: &Db,
+    tenant_id: i64,
+    certification: &CrewCertification,
+) -> Result<(), String> {
+    certification_repo::delete_certification(db, tenant_id, certification.id)
+        .await
+        .map_err(|err| format!("Unable to delete certification: {err}"))?;
+    if let Some(path) = document_file_path(certification) {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+pub async fn delete_certifications_for_member(
+    db: &Db,
+    tenant_id: i64,
+    member_id: i64,
+) -> Result<(), sqlx::Error> {
+    let certifications =
+        certification_repo::list_certifications_for_member(db, tenant_id, member_id).await?;
+    certification_repo::delete_certifications_for_member(db, tenant_id, member_id).await?;
+    remove_documents(&certifications);
+    Ok(())
+}
+
+pub async fn delete_certifications_for_crew(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+) -> Result<(), sqlx::Error> {
+    let certifications =
+        certification_repo::list_certifications_for_crew(db, tenant_id, crew_id).await?;
+    certification_repo::delete_certifications_for_crew(db, tenant_id, crew_id).await?;
+    remove_documents(&certifications);
+    Ok(())
+}
+
+pub async fn process_expiry_reminders(db: &Db) -> Result<(), sqlx::Error> {
+    let today = chrono::Local::now().date_naive();
+    let until = today + Duration::days(EXPIRY_WARNING_DAYS);
+    let certifications = certification_repo::list_upcoming_expiries(
+        db,
+        &today.format("%Y-%m-%d").to_string(),
+        &until.format("%Y-%m-%d").to_string(),
+    )
+    .await?;
+    let mut owners: HashMap<i64, Vec<String>> = HashMap::new();
+
+    for certification in certifications {
+        let (Some(expires_on), Some(days_left)) =
+            (certification.expires_on.clone(), certification.days_until_expiry)
+        else {
+            continue;
+        };
+        let Some(threshold) = REMINDER_THRESHOLDS
+            .into_iter()
+            .find(|threshold| days_left <= *threshold)
+        else {
+            continue;
+        };
+        if certification_repo::has_reminder_at_or_below(db, certification.id, threshold, &expires_on)
+            .await?
+        {
+            continue;
+        }
+        if !certification_repo::record_reminder(
+            db,
+            certification.tenant_id,
+            certification.id,
+            threshold,
+            &expires_on,
+        )
+        .await?
+        {
+            continue;
+        }
+
+        let owner_emails = match owners.get(&certification.tenant_id) {
+            Some(emails) => emails.clone(),
+            None => {
+                let emails = user_repo::list_users_by_tenant(db, certification.tenant_id)
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|user| access_service::is_owner(&user.role))
+                    .map(|user| user.email)
+                    .collect::<Vec<_>>();
+                owners.insert(certification.tenant_id, emails.clone());
+                emails
+            }
+        };
+        notify_expiry(db, &certification, &expires_on, days_left, owner_emails).await;
+    }
+    Ok(())
+}
+
+async fn notify_expiry(
+    db: &Db,
+    certification: &CrewCertification,
+    expires_on: &str,
+    days_left: i64,
+    owner_emails: Vec<String>,
+) {
+    let mut recipients = Vec::new();
+    if !certification.member_email.trim().is_empty() {
+        recipients.push(certification.member_email.clone());
+    }
+    for owner in owner_emails {
+        if !recipients.iter().any(|email| email.eq_ignore_ascii_case(&owner)) {
+            recipients.push(owner);
+        }
+    }
+    let Some((to, cc)) = recipients.split_first() else {
+        return;
+    };
+
+    let when = match days_left {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        days => format!("in {days} days"),
+    };
+    let subject = format!(
+        "{} certification for {} expires {when}",
+        certification.name, certification.member_name
+    );
+    let number = if certification.certificate_number.trim().is_empty() {
+        String::new()
+    } else {
+        format!(" (no. {})", escape_html(&certification.certificate_number))
+    };
+    let body = format!(
+        "<p>{}'s <strong>{}</strong> certification{number} with {} expires on <strong>{}</strong>.</p>\
+         <p>Once it expires, the {} skill stops counting toward crew recommendations for {}. Upload the renewed certificate to keep it current.</p>",
+        escape_html(&certification.member_name),
+        escape_html(&certification.name),
+        escape_html(if certification.issuing_body.trim().is_empty() {
+            "the issuing body"
+        } else {
+            &certification.issuing_body
+        }),
+        escape_html(expires_on),
+        escape_html(&certification.skill_tag),
+        escape_html(&certification.crew_name),
+    );
+    let _ = email_service::queue_email(
+        db,
+        certification.tenant_id,
+        None,
+        None,
+        to.clone(),
+        cc.to_vec(),
+        subject,
+        body,
+    )
+    .await;
+}
+
+struct ValidatedFields {
+    view: CrewCertificationFormView,
+    issued_on: Option<String>,
+    expires_on: Option<String>,
+}
+
+impl ValidatedFields {
+    fn error(&self, message: String) -> CertificationError {
+        CertificationError {
+            message,
+            form: self.view.clone(),
+        }
+    }
+}
+
+fn form_fields(form: &CrewCertificationForm<'_>) -> CrewCertificationFormView {
+    let name = form.name.trim().to_string();
+    let skill_tag = normalize_skill_tag(if form.skill_tag.trim().is_empty() {
+        &name
+    } else {
+        &form.skill_tag
+    });
+    CrewCertificationFormView {
+        name,
+        skill_tag,
+        issuing_body: form.issuing_body.trim().to_string(),
+        certificate_number: form.certificate_number.trim().to_string(),
+        issued_on: form.issued_on.trim().to_string(),
+        expires_on: form.expires_on.trim().to_string(),
+    }
+}
+
+fn validate(view: &CrewCertificationFormView) -> Result<ValidatedFields, String> {
+    let message = if view.name.is_empty() {
+        Some("Certification name is required.")
+    } else if view.name.chars().count() > 120 {
+        Some("Certification name must be 120 characters or fewer.")
+    } else if view.skill_tag.is_empty() {
+        Some("Enter the skill this certification covers.")
+    } else {
+        None
+    };
+    if let Some(message) = message {
+        return Err(message.to_string());
+    }
+    let issued_on = parse_optional_date(&view.issued_on)
+        .map_err(|_| "Issue date must be a valid date.".to_string())?;
+    let expires_on = parse_optional_date(&view.expires_on)
+        .map_err(|_| "Expiry date must be a valid date.".to_string())?;
+    if let (Some(issued_on), Some(expires_on)) = (&issued_on, &expires_on)
+        && expires_on < issued_on
+    {
+        return Err("Expiry date must be on or after the issue date.".to_string());
+    }
+    Ok(ValidatedFields {
+        view: view.clone(),
+        issued_on,
+        expires_on,
+    })
+}
+
+async fn take_document<'r>(
+    form: &mut CrewCertificationForm<'r>,
+) -> Result<Option<(TempFile<'r>, &'static str)>, String> {
+    let Some(file) = form.document.take().filter(|file| file.len() > 0) else {
+        return Ok(None);
+    };
+    if file.len() > MAX_DOCUMENT_BYTES {
+        return Err("Documents must be 10 MB or smaller.".to_string());
+    }
+    let kind = attachment_service::declared_kind(&file)
+        .ok_or_else(|| "Documents must be a PNG, JPG, WebP, or PDF file.".to_string())?;
+    if !attachment_service::content_matches(&file, kind).await {
+        return Err("The document's contents do not match its file type.".to_string());
+    }
+    Ok(Some((file, kind.extension)))
+}
+
+async fn store_document(
+    db: &Db,
+    tenant_id: i64,
+    certification_id: i64,
+    mut file: TempFile<'_>,
+    extension: &str,
+) -> Result<(), String> {
+    let dir = document_dir(tenant_id);
+    let original_name = original_name(&file, extension);
+    let filename = format!(
+        "certification-{}-{}.{}",
+        certification_id,
+        chrono::Utc::now().timestamp(),
+        extension
+    );
+    let stored = match std::fs::create_dir_all(&dir) {
+        Ok(_) => file.move_copy_to(dir.join(&filename)).await,
+        Err(err) => Err(err),
+    };
+    stored.map_err(|err| format!("Unable to save document: {err}"))?;
+    certification_repo::update_document(db, tenant_id, certification_id, &original_name, &filename)
+        .await
+        .map_err(|err| format!("Unable to save document: {err}"))
+}
+
+fn remove_documents(certifications: &[CrewCertification]) {
+    for certification in certifications {
+        if let Some(path) = document_file_path(certification) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn with_status(mut certification: CrewCertification) -> CrewCertification {
+    certification.status = match certification.days_until_expiry {
+        Some(days) if days < 0 => STATUS_EXPIRED,
+        Some(days) if days <= EXPIRY_WARNING_DAYS => STATUS_EXPIRING,
+        _ => STATUS_VALID,
+    }
+    .to_string();
+    certification
+}
+
+fn with_statuses(certifications: Vec<CrewCertification>) -> Vec<CrewCertification> {
+    certifications.into_iter().map(with_status).collect()
+}
+
+fn normalize_skill_tag(input: &str) -> String {
+    input
+        .replace(',', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn parse_optional_date(value: &str) -> Result<Option<String>, chrono::ParseError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| Some(date.format("%Y-%m-%d").to_string()))
+}
+
+fn original_name(file: &TempFile<'_>, extension: &str) -> String {
+    let name = file
+        .raw_name()
+        .map(|name| name.dangerous_unsafe_unsanitized_raw().as_str())
+        .unwrap_or_default();
+    let name = Path::new(name)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .chars()
+        .filter(|ch| !ch.is_control())
+        .collect::<String>();
+    let name = name.trim();
+    if name.is_empty() {
+        format!("certificate.{extension}")
+    } else {
+        name.chars().take(120).collect()
+    }
+}
@@ -8,9 +8,10 @@ use crate::models::{
     ClientContactFormView,
     ClientForm,
     ClientFormView,
+    ClientGeofenceForm,
 };
 use crate::repositories::client_repo;
-use crate::services::workspace_service;
+use crate::services::{custom_field_service, workspace_service};
 use crate::Db;
 
 pub struct ClientError {
@@ -23,10 +24,30 @@ pub struct ContactError {
     pub form: ClientContactFormView,
 }
 
+pub const GEOFENCE_OFF: &str = "Off";
+pub const GEOFENCE_FLAG: &str = "Flag";
+pub const GEOFENCE_ENFORCE: &str = "Enforce";
+
+const MIN_GEOFENCE_RADIUS_M: i64 = 25;
+const MAX_GEOFENCE_RADIUS_M: i64 = 50_000;
+
 pub fn client_stage_options() -> Vec<&'static str> {
     vec!["Proposal", "Negotiation", "Closed"]
 }
 
+pub fn geofence_mode_options() -> [&'static str; 3] {
+    [GEOFENCE_OFF, GEOFENCE_FLAG, GEOFENCE_ENFORCE]
+}
+
+pub fn site_coordinates(client: &Client) -> Option<(f64, f64)> {
+    let latitude = client.latitude.trim().parse::<f64>().ok()?;
+    let longitude = client.longitude.trim().parse::<f64>().ok()?;
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return None;
+    }
+    Some((latitude, longitude))
+}
+
 pub fn currency_options() -> Vec<&'static str> {
     vec![
         "USD", "EUR", "GBP", "CAD", "AUD", "NZD", "JPY", "CNY", "HKD", "SGD",
@@ -103,41 +124,11 @@ pub async fn create_client(
         }
     }
     let company_name = form.company_name.trim().to_string();
-    if company_name.is_empty() {
-        return Err(ClientError {
-            message: "Company name is required.".to_string(),
-            form: ClientFormView::new(
-                "",
-                form.address,
-                form.phone,
-                form.email,
-                form.latitude,
-                form.longitude,
-                form.stage,
-                form.currency,
-            ),
-        });
-    }
     let stage = form.stage.trim().to_string();
-    if stage.is_empty() {
-        return Err(ClientError {
-            message: "Client stage is required.".to_string(),
-            form: ClientFormView::new(
-                company_name,
-                form.address,
-                form.phone,
-                form.email,
-                form.latitude,
-                form.longitude,
-                "",
-                form.currency,
-            ),
-        });
-    }
     let currency = form.currency.trim().to_string();
-    if currency.is_empty() || !currency_options().iter().any(|option| option.eq(&currency)) {
+    if let Err(message) = validate_client_fields(&company_name, &stage, &currency) {
         return Err(ClientError {
-            message: "Client currency is required.".to_string(),
+            message,
             form: ClientFormView::new(
                 company_name,
                 form.address,
@@ -151,6 +142,32 @@ pub async fn create_client(
         });
     }
 
+    let custom_values = match custom_field_service::validate_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_CLIENTS,
+        &form.custom,
+    )
+    .await
+    {
+        Ok(values) => values,
+        Err(message) => {
+            return Err(ClientError {
+                message,
+                form: ClientFormView::new(
+                    company_name,
+                    form.address,
+                    form.phone,
+                    form.email,
+                    form.latitude,
+                    form.longitude,
+                    stage,
+                    currency,
+                ),
+            });
+        }
+    };
+
     let portal_token = generate_portal_token();
     if let Err(err) = client_repo::create_client(
         db,
@@ -164,6 +181,7 @@ pub async fn create_client(
         stage.trim(),
         currency.trim(),
         &portal_token,
+        &custom_values,
     )
     .await
     {
@@ -185,6 +203,42 @@ pub async fn create_client(
     Ok(())
 }
 
+pub fn validate_client_fields(company_name: &str, stage: &str, currency: &str) -> Result<(), String> {
+    if company_name.is_empty() {
+        return Err("Company name is required.".to_string());
+    }
+    if stage.is_empty() {
+        return Err("Client stage is required.".to_string());
+    }
+    if currency.is_empty() || !currency_options().iter().any(|option| option.eq(&currency)) {
+        return Err("Client currency is required.".to_string());
+    }
+    Ok(())
+}
+
+pub async fn update_geofence(
+    db: &Db,
+    tenant_id: i64,
+    client: &Client,
+    form: ClientGeofenceForm,
+) -> Result<(), String> {
+    let mode = geofence_mode_options()
+        .into_iter()
+        .find(|option| option.eq_ignore_ascii_case(form.geofence_mode.trim()))
+        .ok_or_else(|| "Select a valid geofence mode.".to_string())?;
+    if !(MIN_GEOFENCE_RADIUS_M..=MAX_GEOFENCE_RADIUS_M).contains(&form.geofence_radius_m) {
+        return Err(format!(
+            "Geofence radius must be between {MIN_GEOFENCE_RADIUS_M} and {MAX_GEOFENCE_RADIUS_M} metres."
+        ));
+    }
+    if mode != GEOFENCE_OFF && site_coordinates(client).is_none() {
+        return Err("Add valid site coordinates before enabling a geofence.".to_string());
+    }
+    client_repo::update_geofence(db, tenant_id, client.id, mode, form.geofence_radius_m)
+        .await
+        .map_err(|err| format!("Unable to update geofence: {err}"))
+}
+
 pub async fn ensure_portal_token(
     db: &Db,
     tenant_id: i64,
@@ -206,41 +260,11 @@ pub async fn update_client(
     form: ClientForm,
 ) -> Result<(), ClientError> {
     let company_name = form.company_name.trim().to_string();
-    if company_name.is_empty() {
-        return Err(ClientError {
-            message: "Company name is required.".to_string(),
-            form: ClientFormView::new(
-                "",
-                form.address,
-                form.phone,
-                form.email,
-                form.latitude,
-                form.longitude,
-                form.stage,
-                form.currency,
-            ),
-        });
-    }
     let stage = form.stage.trim().to_string();
-    if stage.is_empty() {
-        return Err(ClientError {
-            message: "Client stage is required.".to_string(),
-            form: ClientFormView::new(
-                company_name,
-                form.address,
-                form.phone,
-                form.email,
-                form.latitude,
-                form.longitude,
-                "",
-                form.currency,
-            ),
-        });
-    }
     let currency = form.currency.trim().to_string();
-    if currency.is_empty() || !currency_options().iter().any(|option| option.eq(&currency)) {
+    if let Err(message) = validate_client_fields(&company_name, &stage, &currency) {
         return Err(ClientError {
-            message: "Client currency is required.".to_string(),
+            message,
             form: ClientFormView::new(
                 company_name,
                 form.address,
@@ -254,6 +278,32 @@ pub async fn update_client(
         });
     }
 
+    let custom_values = match custom_field_service::validate_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_CLIENTS,
+        &form.custom,
+    )
+    .await
+    {
+        Ok(values) => values,
+        Err(message) => {
+            return Err(ClientError {
+                message,
+                form: ClientFormView::new(
+                    company_name,
+                    form.address,
+                    form.phone,
+                    form.email,
+                    form.latitude,
+                    form.longitude,
+                    stage,
+                    currency,
+                ),
+            });
+        }
+    };
+
     if let Err(err) = client_repo::update_client(
         db,
         tenant_id,
@@ -266,6 +316,7 @@ pub async fn update_client(
         form.longitude.trim(),
         stage.trim(),
         currency.trim(),
+        &custom_values,
     )
     .await
     {
@@ -365,9 +416,9 @@ pub async fn create_contact(
         }
     }
     let name = form.name.trim().to_string();
-    if name.is_empty() {
+    if let Err(message) = validate_contact_fields(&name) {
         return Err(ContactError {
-            message: "Contact name is required.".to_string(),
+            message,
             form: ClientContactFormView::new(
                 "",
                 form.address,
@@ -379,6 +430,30 @@ pub async fn create_contact(
         });
     }
 
+    let custom_values = match custom_field_service::validate_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_CONTACTS,
+        &form.custom,
+    )
+    .await
+    {
+        Ok(values) => values,
+        Err(message) => {
+            return Err(ContactError {
+                message,
+                form: ClientContactFormView::new(
+                    name,
+                    form.address,
+                    form.email,
+                    form.phone,
+                    form.department,
+                    form.position,
+                ),
+            });
+        }
+    };
+
     if let Err(err) = client_repo::create_contact(
         db,
         tenant_id,
@@ -389,6 +464,7 @@ pub async fn create_contact(
         form.phone.trim(),
         form.department.trim(),
         form.position.trim(),
+        &custom_values,
     )
     .await
     {
@@ -416,9 +492,9 @@ pub async fn update_contact(
     form: ClientContactForm,
 ) -> Result<(), ContactError> {
     let name = form.name.trim().to_string();
-    if name.is_empty() {
+    if let Err(message) = validate_contact_fields(&name) {
         return Err(ContactError {
-            message: "Contact name is required.".to_string(),
+            message,
             form: ClientContactFormView::new(
                 "",
                 form.address,
@@ -430,6 +506,30 @@ pub async fn update_contact(
         });
     }
 
+    let custom_values = match custom_field_service::validate_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_CONTACTS,
+        &form.custom,
+    )
+    .await
+    {
+        Ok(values) => values,
+        Err(message) => {
+            return Err(ContactError {
+                message,
+                form: ClientContactFormView::new(
+                    name,
+                    form.address,
+                    form.email,
+                    form.phone,
+                    form.department,
+                    form.position,
+                ),
+            });
+        }
+    };
+
     if let Err(err) = client_repo::update_contact(
         db,
         tenant_id,
@@ -441,6 +541,7 @@ pub async fn update_contact(
         form.phone.trim(),
         form.department.trim(),
         form.position.trim(),
+        &custom_values,
     )
     .await
     {
@@ -468,10 +569,18 @@ pub async fn delete_contact(
 ) -> Result<(), String> {
     client_repo::delete_contact(db, tenant_id, client_id, contact_id)
         .await
-        .map_err(|err| format!("Unable to delete contact: {err}"))
+        .map_err(|err| format!("Unable to delete contact: {err}"))?;
+    custom_field_service::delete_values(db, tenant_id, custom_field_service::ENTITY_CONTACTS, contact_id).await
+}
+
+pub fn validate_contact_fields(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Contact name is required.".to_string());
+    }
+    Ok(())
 }
 
-fn generate_portal_token() -> String {
+pub fn generate_portal_token() -> String {
     let mut bytes = [0u8; 16];
     OsRng.fill_bytes(&mut bytes);
     bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
@@ -1,6 +1,6 @@
+use chrono::{Local, NaiveDate};
 use rocket_db_pools::sqlx;
 use std::collections::HashMap;
-use std::collections::HashSet;
 
 use crate::models::{
     Crew,
@@ -13,6 +13,10 @@ use crate::models::{
     CrewRosterView,
 };
 use crate::repositories::{crew_member_repo, crew_repo, deployment_repo, user_repo};
+use crate::services::availability_service::{self, AvailabilityCalendar};
+use crate::services::certification_service;
+use crate::services::custom_field_service;
+use crate::services::gear_service;
 use crate::services::workspace_service;
 use crate::Db;
 
@@ -26,15 +30,6 @@ pub struct CrewMemberError {
     pub form: CrewMemberFormView,
 }
 
-pub struct CrewRecommendation {
-    pub id: i64,
-    pub name: String,
-    pub status: String,
-    pub skill_matches: i64,
-    pub compatibility_matches: i64,
-    pub score: i64,
-}
-
 const STATUS_ACTIVE: &str = "Active";
 const STATUS_IDLE: &str = "Idle";
 const STATUS_ON_LEAVE: &str = "On Leave";
@@ -169,44 +164,39 @@ pub async fn create_crew(
                 form: CrewFormView::new(
                     form.name,
                     form.status,
-                    form.gear_score,
+                    form.gear_score.unwrap_or(100),
                     form.skill_tags,
                     form.compatibility_tags,
-                ),
+                )
+                .with_gear_override(form.gear_override, form.gear_override_reason.unwrap_or_default()),
             });
         }
     }
-    let name = form.name.trim().to_string();
-    let status = normalize_status(form.status);
-    let gear_score = normalize_gear_score(form.gear_score);
-    let skill_tags = normalize_tags(form.skill_tags);
-    let compatibility_tags = normalize_tags(form.compatibility_tags);
-
-    if name.is_empty() {
-        return Err(CrewError {
-            message: "Crew name is required.".to_string(),
-            form: CrewFormView::new("", status, gear_score, skill_tags, compatibility_tags),
-        });
-    }
+    let fields = crew_fields(form);
+    validate_crew_fields(&fields).map_err(|message| fields.error(message))?;
+    let custom_values =
+        custom_field_service::validate_values(db, tenant_id, custom_field_service::ENTITY_CREWS, &fields.custom)
+            .await
+            .map_err(|message| fields.error(message))?;
 
-    if let Err(err) = crew_repo::create_crew(
+    let crew_id = match crew_repo::create_crew(
         db,
         tenant_id,
-        &name,
-        &status,
-        gear_score,
-        &skill_tags,
-        &compatibility_tags,
+        &fields.name,
+        &fields.status,
+        fields.gear_score,
+        &fields.skill_tags,
+        &fields.compatibility_tags,
+        &custom_values,
     )
     .await
     {
-        return Err(CrewError {
-            message: format!("Unable to create crew: {err}"),
-            form: CrewFormView::new(name, status, gear_score, skill_tags, compatibility_tags),
-        });
-    }
-
-    Ok(())
+        Ok(crew_id) => crew_id,
+        Err(err) => {
+            return Err(fields.error(format!("Unable to create crew: {err}")));
+        }
+    };
+    save_gear_override(db, tenant_id, crew_id, &fields).await
 }
 
 pub async fn update_crew(
@@ -215,44 +205,114 @@ pub async fn update_crew(
     crew_id: i64,
     form: CrewForm,
 ) -> Result<(), CrewError> {
-    let name = form.name.trim().to_string();
-    let status = normalize_status(form.status);
-    let gear_score = normalize_gear_score(form.gear_score);
-    let skill_tags = normalize_tags(form.skill_tags);
-    let compatibility_tags = normalize_tags(form.compatibility_tags);
-
-    if name.is_empty() {
-        return Err(CrewError {
-            message: "Crew name is required.".to_string(),
-            form: CrewFormView::new("", status, gear_score, skill_tags, compatibility_tags),
-        });
-    }
+    let fields = crew_fields(form);
+    validate_crew_fields(&fields).map_err(|message| fields.error(message))?;
+    let custom_values =
+        custom_field_service::validate_values(db, tenant_id, custom_field_service::ENTITY_CREWS, &fields.custom)
+            .await
+            .map_err(|message| fields.error(message))?;
 
     if let Err(err) = crew_repo::update_crew(
         db,
         tenant_id,
         crew_id,
-        &name,
-        &status,
-        gear_score,
-        &skill_tags,
-        &compatibility_tags,
+        &fields.name,
+        &fields.status,
+        fields.gear_score,
+        &fields.skill_tags,
+        &fields.compatibility_tags,
+        &custom_values,
     )
     .await
     {
-        return Err(CrewError {
-            message: format!("Unable to update crew: {err}"),
-            form: CrewFormView::new(name, status, gear_score, skill_tags, compatibility_tags),
-        });
+        return Err(fields.error(format!("Unable to update crew: {err}")));
+    }
+    save_gear_override(db, tenant_id, crew_id, &fields).await
+}
+
+struct CrewFields {
+    name: String,
+    status: String,
+    gear_score: i64,
+    gear_override: bool,
+    gear_override_reason: String,
+    skill_tags: String,
+    compatibility_tags: String,
+    custom: HashMap<String, String>,
+}
+
+impl CrewFields {
+    fn error(&self, message: String) -> CrewError {
+        CrewError {
+            message,
+            form: CrewFormView::new(
+                self.name.clone(),
+                self.status.clone(),
+                self.gear_score,
+                self.skill_tags.clone(),
+                self.compatibility_tags.clone(),
+            )
+            .with_gear_override(self.gear_override, self.gear_override_reason.clone()),
+        }
     }
+}
 
+fn crew_fields(form: CrewForm) -> CrewFields {
+    CrewFields {
+        name: form.name.trim().to_string(),
+        status: normalize_status(form.status),
+        gear_score: normalize_gear_score(form.gear_score.unwrap_or(100)),
+        gear_override: form.gear_override,
+        gear_override_reason: form.gear_override_reason.unwrap_or_default().trim().to_string(),
+        skill_tags: normalize_tags(form.skill_tags),
+        compatibility_tags: normalize_tags(form.compatibility_tags),
+        custom: form.custom,
+    }
+}
+
+fn validate_crew_fields(fields: &CrewFields) -> Result<(), String> {
+    if fields.name.is_empty() {
+        return Err("Crew name is required.".to_string());
+    }
+    if fields.gear_override && fields.gear_override_reason.is_empty() {
+        return Err("Give a reason when overriding the computed gear score.".to_string());
+    }
     Ok(())
 }
 
+async fn save_gear_override(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    fields: &CrewFields,
+) -> Result<(), CrewError> {
+    let (score, reason) = if fields.gear_override {
+        (Some(fields.gear_score), fields.gear_override_reason.as_str())
+    } else {
+        (None, "")
+    };
+    gear_service::set_override(db, tenant_id, crew_id, score, reason)
+        .await
+        .map_err(|message| fields.error(message))
+}
+
 pub async fn delete_crew(db: &Db, tenant_id: i64, crew_id: i64) -> Result<(), String> {
+    availability_service::delete_availability_for_crew(db, tenant_id, crew_id)
+        .await
+        .map_err(|err| format!("Unable to delete crew availability: {err}"))?;
+    certification_service::delete_certifications_for_crew(db, tenant_id, crew_id)
+        .await
+        .map_err(|err| format!("Unable to delete crew certifications: {err}"))?;
+    let members = crew_member_repo::list_members(db, tenant_id, crew_id)
+        .await
+        .map_err(|err| format!("Unable to load crew members: {err}"))?;
     crew_repo::delete_crew(db, tenant_id, crew_id)
         .await
-        .map_err(|err| format!("Unable to delete crew: {err}"))
+        .map_err(|err| format!("Unable to delete crew: {err}"))?;
+    for member in members {
+        custom_field_service::delete_values(db, tenant_id, custom_field_service::ENTITY_MEMBERS, member.id).await?;
+    }
+    custom_field_service::delete_values(db, tenant_id, custom_field_service::ENTITY_CREWS, crew_id).await
 }
 
 pub async fn create_member(
@@ -261,6 +321,7 @@ pub async fn create_member(
     crew_id: i64,
     form: CrewMemberForm,
 ) -> Result<(), CrewMemberError> {
+    let hourly_cost = form.hourly_cost;
     let (plan_key, limits) = workspace_service::plan_limits_for_tenant(db, tenant_id).await;
     if let Some(limit) = limits.members_per_crew {
         let existing = crew_member_repo::count_members(db, tenant_id, crew_id)
@@ -278,43 +339,40 @@ pub async fn create_member(
                     form.phone,
                     form.position,
                     form.availability_status.clone(),
+                    hourly_cost,
                 ),
             });
         }
     }
     let name = form.name.trim().to_string();
     let availability_status = normalize_availability(form.availability_status.clone());
-    if name.is_empty() {
+    let phone = form.phone.trim().to_string();
+    if let Err(message) = validate_member_fields(&name, &phone, hourly_cost) {
         return Err(CrewMemberError {
-            message: "Member name is required.".to_string(),
+            message,
             form: CrewMemberFormView::new(
                 form.user_id,
-                "",
-                form.phone,
+                name,
+                phone,
                 form.position,
                 availability_status,
+                hourly_cost,
             ),
         });
     }
-    let phone = form.phone.trim().to_string();
-    if phone.is_empty() {
+    if form.user_id <= 0 {
         return Err(CrewMemberError {
-            message: "Member phone is required.".to_string(),
+            message: "User account is required.".to_string(),
             form: CrewMemberFormView::new(
-                form.user_id,
+                0,
                 name,
-                "",
+                phone,
                 form.position,
                 availability_status,
+                hourly_cost,
             ),
         });
     }
-    if form.user_id <= 0 {
-        return Err(CrewMemberError {
-            message: "User account is required.".to_string(),
-            form: CrewMemberFormView::new(0, name, phone, form.position, availability_status),
-        });
-    }
     let user = match user_repo::find_user_by_id(db, tenant_id, form.user_id).await {
         Ok(Some(user)) => user,
         _ => {
@@ -326,6 +384,31 @@ pub async fn create_member(
                     phone,
                     form.position,
                     availability_status,
+                    hourly_cost,
+                ),
+            })
+        }
+    };
+
+    let custom_values = match custom_field_service::validate_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_MEMBERS,
+        &form.custom,
+    )
+    .await
+    {
+        Ok(values) => values,
+        Err(message) => {
+            return Err(CrewMemberError {
+                message,
+                form: CrewMemberFormView::new(
+                    form.user_id,
+                    name,
+                    phone,
+                    form.position,
+                    availability_status,
+                    hourly_cost,
                 ),
             })
         }
@@ -341,6 +424,8 @@ pub async fn create_member(
         &user.email,
         form.position.trim(),
         &availability_status,
+        hourly_cost.unwrap_or(0.0),
+        &custom_values,
     )
     .await
     {
@@ -352,6 +437,7 @@ pub async fn create_member(
                 phone,
                 form.position,
                 availability_status,
+                hourly_cost,
             ),
         });
     }
@@ -365,6 +451,7 @@ pub async fn create_member(
                 phone,
                 form.position,
                 availability_status,
+                hourly_cost,
             ),
         });
     }
@@ -372,6 +459,19 @@ pub async fn create_member(
     Ok(())
 }
 
+pub fn validate_member_fields(name: &str, phone: &str, hourly_cost: Option<f64>) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Member name is required.".to_string());
+    }
+    if phone.is_empty() {
+        return Err("Member phone is required.".to_string());
+    }
+    if hourly_cost.is_some_and(|cost| !cost.is_finite() || cost < 0.0) {
+        return Err("Hourly cost must be 0 or greater.".to_string());
+    }
+    Ok(())
+}
+
 pub async fn update_member(
     db: &Db,
     tenant_id: i64,
@@ -379,39 +479,36 @@ pub async fn update_member(
     member_id: i64,
     form: CrewMemberForm,
 ) -> Result<(), CrewMemberError> {
+    let hourly_cost = form.hourly_cost;
     let name = form.name.trim().to_string();
     let availability_status = normalize_availability(form.availability_status.clone());
-    if name.is_empty() {
+    let phone = form.phone.trim().to_string();
+    if let Err(message) = validate_member_fields(&name, &phone, hourly_cost) {
         return Err(CrewMemberError {
-            message: "Member name is required.".to_string(),
+            message,
             form: CrewMemberFormView::new(
                 form.user_id,
-                "",
-                form.phone,
+                name,
+                phone,
                 form.position,
                 availability_status,
+                hourly_cost,
             ),
         });
     }
-    let phone = form.phone.trim().to_string();
-    if phone.is_empty() {
+    if form.user_id <= 0 {
         return Err(CrewMemberError {
-            message: "Member phone is required.".to_string(),
+            message: "User account is required.".to_string(),
             form: CrewMemberFormView::new(
-                form.user_id,
+                0,
                 name,
-                "",
+                phone,
                 form.position,
                 availability_status,
+                hourly_cost,
             ),
         });
     }
-    if form.user_id <= 0 {
-        return Err(CrewMemberError {
-            message: "User account is required.".to_string(),
-            form: CrewMemberFormView::new(0, name, phone, form.position, availability_status),
-        });
-    }
     let user = match user_repo::find_user_by_id(db, tenant_id, form.user_id).await {
         Ok(Some(user)) => user,
         _ => {
@@ -423,6 +520,31 @@ pub async fn update_member(
                     phone,
                     form.position,
                     availability_status,
+                    hourly_cost,
+                ),
+            })
+        }
+    };
+
+    let custom_values = match custom_field_service::validate_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_MEMBERS,
+        &form.custom,
+    )
+    .await
+    {
+        Ok(values) => values,
+        Err(message) => {
+            return Err(CrewMemberError {
+                message,
+                form: CrewMemberFormView::new(
+                    form.user_id,
+                    name,
+                    phone,
+                    form.position,
+                    availability_status,
+                    hourly_cost,
                 ),
             })
         }
@@ -439,6 +561,8 @@ pub async fn update_member(
         &user.email,
         form.position.trim(),
         &availability_status,
+        hourly_cost,
+        &custom_values,
     )
     .await
     {
@@ -450,6 +574,7 @@ pub async fn update_member(
                 phone,
                 form.position,
                 availability_status,
+                hourly_cost,
             ),
         });
     }
@@ -463,27 +588,12 @@ pub async fn roster_views(
     crews: Vec<Crew>,
 ) -> Vec<CrewRosterView> {
     let crew_ids = crews.iter().map(|crew| crew.id).collect::<Vec<_>>();
-    let availability_counts = crew_member_repo::count_availability_by_crew(db, tenant_id, &crew_ids)
-        .await
-        .unwrap_or_default();
-    let availability_map = availability_counts
-        .into_iter()
-        .fold(HashMap::new(), |mut acc, (crew_id, status, count)| {
-            let entry = acc.entry(crew_id).or_insert((0, 0, 0));
-            match status.as_str() {
-                AVAILABILITY_AVAILABLE => entry.0 += count,
-                AVAILABILITY_AWAY => entry.1 += count,
-                AVAILABILITY_UNAVAILABLE => entry.2 += count,
-                _ => {}
-            }
-            acc
-        });
+    let today = Local::now().date_naive();
+    let availability_map = availability_scores(db, tenant_id, &crew_ids, today, today).await;
 
     let mut roster = Vec::with_capacity(crews.len());
     for crew in crews {
-        let (available, away, unavailable) =
-            availability_map.get(&crew.id).copied().unwrap_or((0, 0, 0));
-        let availability_score = availability_score(available, away, unavailable);
+        let availability_score = availability_map.get(&crew.id).copied().unwrap_or(0);
         let recent_statuses = deployment_repo::list_recent_statuses_for_crew(
             db,
             tenant_id,
@@ -516,49 +626,43 @@ pub async fn delete_member(
     crew_id: i64,
     member_id: i64,
 ) -> Result<(), String> {
+    availability_service::delete_availability_for_member(db, tenant_id, member_id)
+        .await
+        .map_err(|err| format!("Unable to delete member availability: {err}"))?;
+    certification_service::delete_certifications_for_member(db, tenant_id, member_id)
+        .await
+        .map_err(|err| format!("Unable to delete member certifications: {err}"))?;
     crew_member_repo::delete_member(db, tenant_id, crew_id, member_id)
         .await
         .map_err(|err| format!("Unable to delete crew member: {err}"))?;
+    custom_field_service::delete_values(db, tenant_id, custom_field_service::ENTITY_MEMBERS, member_id).await?;
     crew_repo::update_members_count(db, tenant_id, crew_id)
         .await
         .map_err(|err| format!("Unable to update crew members count: {err}"))?;
     Ok(())
 }
 
-pub fn recommend_crews(
-    crews: &[Crew],
-    required_skills: &str,
-    compatibility_pref: &str,
-) -> Vec<CrewRecommendation> {
-    let required_set = tag_set(required_skills);
-    let compatibility_set = tag_set(compatibility_pref);
-    let mut recommendations = crews
+pub async fn availability_scores(
+    db: &Db,
+    tenant_id: i64,
+    crew_ids: &[i64],
+    start: NaiveDate,
+    end: NaiveDate,
+) -> HashMap<i64, i64> {
+    let members = crew_member_repo::list_members_for_crews(db, tenant_id, crew_ids)
+        .await
+        .unwrap_or_default();
+    let calendar = availability_service::load_calendar(db, tenant_id, start, end).await;
+    crew_ids
         .iter()
-        .map(|crew| {
-            let crew_skills = tag_set(&crew.skill_tags);
-            let crew_compat = tag_set(&crew.compatibility_tags);
-            let skill_matches = count_matches(&crew_skills, &required_set);
-            let compatibility_matches = count_matches(&crew_compat, &compatibility_set);
-            let status_bonus = match crew.status.as_str() {
-                STATUS_ACTIVE => 2,
-                STATUS_IDLE => 1,
-                STATUS_ON_LEAVE => -1,
-                _ => 0,
-            };
-            let gear_bonus = (crew.gear_score / 20).clamp(0, 5);
-            let score = skill_matches * 4 + compatibility_matches * 2 + status_bonus + gear_bonus;
-            CrewRecommendation {
-                id: crew.id,
-                name: crew.name.clone(),
-                status: crew.status.clone(),
-                skill_matches,
-                compatibility_matches,
-                score,
-            }
+        .map(|crew_id| {
+            let crew_members = members
+                .iter()
+                .filter(|member| member.crew_id == *crew_id)
+                .collect::<Vec<_>>();
+            (*crew_id, availability_score(&crew_members, &calendar, start, end))
         })
-        .collect::<Vec<_>>();
-    recommendations.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
-    recommendations
+        .collect()
 }
 
 fn normalize_status(input: String) -> String {
@@ -571,7 +675,7 @@ fn normalize_status(input: String) -> String {
     STATUS_ACTIVE.to_string()
 }
 
-fn normalize_availability(input: String) -> String {
+pub fn normalize_availability(input: String) -> String {
     let status = input.trim();
     for option in availability_options() {
         if option.eq_ignore_ascii_case(status) {
@@ -601,27 +705,31 @@ fn normalize_tags(input: String) -> String {
     unique.join(", ")
 }
 
-fn tag_set(input: &str) -> HashSet<String> {
-    input
-        .split(',')
-        .map(|tag| tag.trim().to_lowercase())
-        .filter(|tag| !tag.is_empty())
-        .collect()
-}
-
-fn count_matches(source: &HashSet<String>, target: &HashSet<String>) -> i64 {
-    if target.is_empty() {
-        return 0;
-    }
-    source.iter().filter(|tag| target.contains(*tag)).count() as i64
-}
-
-fn availability_score(available: i64, away: i64, unavailable: i64) -> i64 {
-    let total = available + away + unavailable;
+pub fn availability_score(
+    members: &[&CrewMember],
+    calendar: &AvailabilityCalendar,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> i64 {
+    let days = availability_service::window_days(start, end);
+    let total = (members.len() * days.len()) as i64;
     if total == 0 {
         return 0;
     }
-    let weighted = available * 100 + away * 50;
+    let weighted: i64 = members
+        .iter()
+        .map(|member| {
+            let status_weight = match member.availability_status.as_str() {
+                AVAILABILITY_AVAILABLE => 100,
+                AVAILABILITY_AWAY => 50,
+                _ => 0,
+            };
+            days.iter()
+                .filter(|day| !calendar.is_unavailable(member, **day))
+                .count() as i64
+                * status_weight
+        })
+        .sum();
     (weighted / total).clamp(0, 100)
 }
 
@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rocket_db_pools::sqlx;
+
+use crate::models::{
+    CustomFieldColumns,
+    CustomFieldDefinition,
+    CustomFieldForm,
+    CustomFieldFormView,
+    CustomFieldInput,
+    CustomFieldValueView,
+};
+use crate::repositories::custom_field_repo;
+use crate::Db;
+
+pub struct CustomFieldError {
+    pub message: String,
+    pub form: CustomFieldFormView,
+}
+
+pub const ENTITY_CLIENTS: &str = "clients";
+pub const ENTITY_CONTACTS: &str = "contacts";
+pub const ENTITY_CREWS: &str = "crews";
+pub const ENTITY_DEPLOYMENTS: &str = "deployments";
+pub const ENTITY_MEMBERS: &str = "members";
+const TYPE_TEXT: &str = "text";
+const TYPE_NUMBER: &str = "number";
+const TYPE_DATE: &str = "date";
+const TYPE_SELECT: &str = "select";
+const TYPE_CHECKBOX: &str = "checkbox";
+const CHECKED: &str = "true";
+const MAX_FIELDS_PER_ENTITY: usize = 20;
+const MAX_LABEL_CHARS: usize = 60;
+const MAX_OPTIONS: usize = 50;
+const MAX_TEXT_CHARS: usize = 500;
+
+pub fn entity_options() -> [&'static str; 5] {
+    [ENTITY_CLIENTS, ENTITY_CONTACTS, ENTITY_CREWS, ENTITY_MEMBERS, ENTITY_DEPLOYMENTS]
+}
+
+pub fn type_options() -> [&'static str; 5] {
+    [TYPE_TEXT, TYPE_NUMBER, TYPE_DATE, TYPE_SELECT, TYPE_CHECKBOX]
+}
+
+pub fn empty_form_view() -> CustomFieldFormView {
+    CustomFieldFormView {
+        entity: ENTITY_CLIENTS.to_string(),
+        label: String::new(),
+        field_type: TYPE_TEXT.to_string(),
+        options: String::new(),
+        is_required: false,
+    }
+}
+
+pub async fn list_definitions(db: &Db, tenant_id: i64, entity: &str) -> Vec<CustomFieldDefinition> {
+    custom_field_repo::list_definitions(db, tenant_id, entity)
+        .await
+        .unwrap_or_default()
+}
+
+pub async fn list_all_definitions(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<CustomFieldDefinition>, sqlx::Error> {
+    custom_field_repo::list_all_definitions(db, tenant_id).await
+}
+
+pub async fn create_definition(
+    db: &Db,
+    tenant_id: i64,
+    form: CustomFieldForm,
+) -> Result<(), CustomFieldError> {
+    let view = CustomFieldFormView {
+        entity: form.entity.trim().to_lowercase(),
+        label: form.label.trim().to_string(),
+        field_type: form.field_type.trim().to_lowercase(),
+        options: form.options.unwrap_or_default(),
+        is_required: form.is_required,
+    };
+    let options = view
+        .options
+        .lines()
+        .map(|option| option.trim().to_string())
+        .filter(|option| !option.is_empty())
+        .fold(Vec::<String>::new(), |mut options, option| {
+            if !options.iter().any(|existing| existing.eq_ignore_ascii_case(&option)) {
+                options.push(option);
+            }
+            options
+        });
+    let existing = list_definitions(db, tenant_id, &view.entity).await;
+    let error = |message: String| CustomFieldError {
+        message,
+        form: view.clone(),
+    };
+
+    if !entity_options().contains(&view.entity.as_str()) {
+        return Err(error("Choose where the field appears.".to_string()));
+    }
+    if !type_options().contains(&view.field_type.as_str()) {
+        return Err(error("Choose a field type.".to_string()));
+    }
+    if view.label.is_empty() {
+        return Err(error("Field label is required.".to_string()));
+    }
+    if view.label.chars().count() > MAX_LABEL_CHARS {
+        return Err(error(format!("Field labels can be at most {MAX_LABEL_CHARS} characters.")));
+    }
+    if existing
+        .iter()
+        .any(|definition| definition.label.eq_ignore_ascii_case(&view.label))
+    {
+        return Err(error(format!("{} already have a field called {}.", capitalize(&view.entity), view.label)));
+    }
+    if existing.len() >= MAX_FIELDS_PER_ENTITY {
+        return Err(error(format!(
+            "{} can have up to {MAX_FIELDS_PER_ENTITY} custom fields.",
+            capitalize(&view.entity)
+        )));
+    }
+    if view.field_type == TYPE_SELECT && options.is_empty() {
+        return Err(error("Select fields need at least one option, one per line.".to_string()));
+    }
+    if options.len() > MAX_OPTIONS {
+        return Err(error(format!("Select fields can have up to {MAX_OPTIONS} options.")));
+    }
+    let options = if view.field_type == TYPE_SELECT {
+        options.join("\n")
+    } else {
+        String::new()
+    };
+
+    custom_field_repo::create_definition(
+        db,
+        tenant_id,
+        &view.entity,
+        &view.label,
+        &view.field_type,
+        &options,
+        view.is_required,
+    )
+    .await
+    .map_err(|err| error(format!("Unable to save custom field: {err}")))
+}
+
+pub async fn delete_definition(db: &Db, tenant_id: i64, definition_id: i64) -> Result<(), String> {
+    custom_field_repo::delete_definition(db, tenant_id, definition_id)
+        .await
+        .map_err(|err| format!("Unable to delete custom field: {err}"))
+}
+
+pub async fn inputs_for_record(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    record_id: Option<i64>,
+) -> Vec<CustomFieldInput> {
+    let saved = match record_id {
+        Some(record_id) => custom_field_repo::list_values_for_record(db, tenant_id, entity, record_id)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(definition_id, value)| (definition_id.to_string(), value))
+            .collect(),
+        None => HashMap::new(),
+    };
+    inputs_from_form(db, tenant_id, entity, &saved).await
+}
+
+pub async fn inputs_from_form(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    submitted: &HashMap<String, String>,
+) -> Vec<CustomFieldInput> {
+    list_definitions(db, tenant_id, entity)
+        .await
+        .into_iter()
+        .map(|definition| CustomFieldInput {
+            value: submitted
+                .get(&definition.id.to_string())
+                .cloned()
+                .unwrap_or_default(),
+            id: definition.id,
+            label: definition.label,
+            field_type: definition.field_type,
+            options: definition.options,
+            is_required: definition.is_required,
+        })
+        .collect()
+}
+
+pub async fn validate_values(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    submitted: &HashMap<String, String>,
+) -> Result<Vec<(i64, String)>, String> {
+    let definitions = list_definitions(db, tenant_id, entity).await;
+    let mut values = Vec::with_capacity(definitions.len());
+    for definition in &definitions {
+        let raw = submitted
+            .get(&definition.id.to_string())
+            .map(|value| value.trim())
+            .unwrap_or("");
+        values.push((definition.id, normalize_value(definition, raw)?));
+    }
+    Ok(values)
+}
+
+pub fn normalize_value(definition: &CustomFieldDefinition, raw: &str) -> Result<String, String> {
+    let label = &definition.label;
+    if definition.field_type == TYPE_CHECKBOX {
+        let checked = matches!(raw.to_lowercase().as_str(), "true" | "on" | "yes" | "1");
+        if definition.is_required && !checked {
+            return Err(format!("{label} must be ticked."));
+        }
+        return Ok(if checked { CHECKED.to_string() } else { String::new() });
+    }
+    if raw.is_empty() {
+        if definition.is_required {
+            return Err(format!("{label} is required."));
+        }
+        return Ok(String::new());
+    }
+    match definition.field_type.as_str() {
+        TYPE_NUMBER => raw
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .map(|_| raw.to_string())
+            .ok_or_else(|| format!("{label} must be a number.")),
+        TYPE_DATE => NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .map_err(|_| format!("{label} must be a date (YYYY-MM-DD).")),
+        TYPE_SELECT => definition
+            .options
+            .iter()
+            .find(|option| option.eq_ignore_ascii_case(raw))
+            .cloned()
+            .ok_or_else(|| format!("{label} must be one of: {}.", definition.options.join(", "))),
+        _ if raw.chars().count() > MAX_TEXT_CHARS => {
+            Err(format!("{label} can be at most {MAX_TEXT_CHARS} characters."))
+        }
+        _ => Ok(raw.to_string()),
+    }
+}
+
+pub async fn delete_values(db: &Db, tenant_id: i64, entity: &str, record_id: i64) -> Result<(), String> {
+    custom_field_repo::delete_values_for_record(db, tenant_id, entity, record_id)
+        .await
+        .map_err(|err| format!("Unable to delete custom field values: {err}"))
+}
+
+pub async fn values_for_record(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    record_id: i64,
+) -> Vec<CustomFieldValueView> {
+    let saved = custom_field_repo::list_values_for_record(db, tenant_id, entity, record_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .collect::<HashMap<i64, String>>();
+    list_definitions(db, tenant_id, entity)
+        .await
+        .into_iter()
+        .map(|definition| CustomFieldValueView {
+            value: display_value(&definition, saved.get(&definition.id).map(String::as_str).unwrap_or("")),
+            label: definition.label,
+        })
+        .collect()
+}
+
+pub async fn value_table(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+) -> (Vec<CustomFieldDefinition>, HashMap<i64, HashMap<i64, String>>) {
+    let definitions = list_definitions(db, tenant_id, entity).await;
+    let mut values: HashMap<i64, HashMap<i64, String>> = HashMap::new();
+    for (record_id, definition_id, value) in custom_field_repo::list_values(db, tenant_id, entity)
+        .await
+        .unwrap_or_default()
+    {
+        values.entry(record_id).or_default().insert(definition_id, value);
+    }
+    (definitions, values)
+}
+
+pub async fn list_columns(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    record_ids: &[i64],
+) -> CustomFieldColumns {
+    let (definitions, values) = value_table(db, tenant_id, entity).await;
+    let rows = record_ids
+        .iter()
+        .map(|record_id| {
+            let saved = values.get(record_id);
+            let row = definitions
+                .iter()
+                .map(|definition| {
+                    display_value(
+                        definition,
+                        saved
+                            .and_then(|saved| saved.get(&definition.id))
+                            .map(String::as_str)
+                            .unwrap_or(""),
+                    )
+                })
+                .collect();
+            (record_id.to_string(), row)
+        })
+        .collect();
+    CustomFieldColumns {
+        labels: definitions.into_iter().map(|definition| definition.label).collect(),
+        rows,
+    }
+}
+
+pub fn display_value(definition: &CustomFieldDefinition, raw: &str) -> String {
+    if definition.field_type == TYPE_CHECKBOX {
+        if raw == CHECKED { "Yes" } else { "No" }.to_string()
+    } else {
+        raw.to_string()
+    }
+}
+
+pub fn json_value(definition: &CustomFieldDefinition, raw: &str) -> serde_json::Value {
+    match definition.field_type.as_str() {
+        TYPE_CHECKBOX => serde_json::Value::Bool(raw == CHECKED),
+        _ if raw.is_empty() => serde_json::Value::Null,
+        TYPE_NUMBER => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        _ => serde_json::Value::String(raw.to_string()),
+    }
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
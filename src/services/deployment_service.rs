@@ -9,8 +9,14 @@ use crate::models::{
     DeploymentSummary,
     DeploymentTimelineStep,
 };
-use crate::repositories::deployment_repo;
-use crate::services::workspace_service;
+use crate::models::DeploymentTaskProgress;
+use crate::repositories::{deployment_repo, deployment_template_repo};
+use crate::services::{
+    custom_field_service,
+    deployment_task_service,
+    deployment_template_service,
+    workspace_service,
+};
 use crate::Db;
 
 pub struct DeploymentError {
@@ -18,16 +24,23 @@ pub struct DeploymentError {
     pub form: DeploymentFormView,
 }
 
-const STATUS_SCHEDULED: &str = "Scheduled";
+pub const STATUS_TENTATIVE: &str = "Tentative";
+pub const STATUS_SCHEDULED: &str = "Scheduled";
 const STATUS_ACTIVE: &str = "Active";
-const STATUS_COMPLETED: &str = "Completed";
-const STATUS_CANCELLED: &str = "Cancelled";
+pub const STATUS_COMPLETED: &str = "Completed";
+pub const STATUS_CANCELLED: &str = "Cancelled";
 const TYPE_ONSITE: &str = "Onsite";
 const TYPE_REMOTE: &str = "Remote";
 const TYPE_HYBRID: &str = "Hybrid";
 
-pub fn status_options() -> [&'static str; 4] {
-    [STATUS_SCHEDULED, STATUS_ACTIVE, STATUS_COMPLETED, STATUS_CANCELLED]
+pub fn status_options() -> [&'static str; 5] {
+    [
+        STATUS_TENTATIVE,
+        STATUS_SCHEDULED,
+        STATUS_ACTIVE,
+        STATUS_COMPLETED,
+        STATUS_CANCELLED,
+    ]
 }
 
 pub fn deployment_type_options() -> [&'static str; 3] {
@@ -103,6 +116,7 @@ pub async fn create_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -128,6 +142,7 @@ pub async fn create_deployment(
                     form.deployment_type,
                     required_skills.clone(),
                     compatibility_pref.clone(),
+                    form.template_id,
                 ),
             });
         }
@@ -146,6 +161,7 @@ pub async fn create_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -164,10 +180,49 @@ pub async fn create_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
-    let end_input = form.end_at.trim().to_string();
+    let template = match form.template_id.filter(|id| *id > 0) {
+        Some(template_id) => {
+            match deployment_template_repo::find_template_by_id(db, tenant_id, template_id).await {
+                Ok(Some(template)) => Ok(Some(template)),
+                Ok(None) => Err("Deployment template not found.".to_string()),
+                Err(err) => Err(format!("Unable to load deployment template: {err}")),
+            }
+        }
+        None => Ok(None),
+    };
+    let template = match template {
+        Ok(template) => template,
+        Err(message) => {
+            return Err(DeploymentError {
+                message,
+                form: DeploymentFormView::new(
+                    form.client_id,
+                    form.crew_id,
+                    start_input,
+                    form.end_at,
+                    form.fee_per_hour,
+                    form.info,
+                    form.status,
+                    form.deployment_type,
+                    required_skills.clone(),
+                    compatibility_pref.clone(),
+                    form.template_id,
+                ),
+            });
+        }
+    };
+    let template_id = template.as_ref().map(|template| template.id);
+    let mut end_input = form.end_at.trim().to_string();
+    if end_input.is_empty()
+        && let Some(template) = template.as_ref()
+    {
+        end_input =
+            end_from_duration(&start_input, template.default_duration_hours).unwrap_or_default();
+    }
     if end_input.is_empty() {
         return Err(DeploymentError {
             message: "Finish time is required.".to_string(),
@@ -182,6 +237,7 @@ pub async fn create_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -201,6 +257,7 @@ pub async fn create_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -220,6 +277,7 @@ pub async fn create_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -228,6 +286,59 @@ pub async fn create_deployment(
     let deployment_type = normalize_deployment_type(form.deployment_type);
     let start_at = normalize_datetime(&start_input);
     let end_at = normalize_datetime(&end_input);
+    let checklist = template
+        .as_ref()
+        .map(|template| deployment_template_service::checklist_tasks(&template.checklist))
+        .unwrap_or_default();
+
+    let required_tasks = checklist.iter().filter(|(_, is_required)| *is_required).count() as i64;
+    if status == STATUS_COMPLETED && required_tasks > 0 && !form.override_open_tasks {
+        return Err(DeploymentError {
+            message: open_tasks_message(required_tasks),
+            form: DeploymentFormView::new(
+                form.client_id,
+                form.crew_id,
+                start_at,
+                end_at,
+                fee_per_hour,
+                info,
+                status,
+                deployment_type,
+                required_skills.clone(),
+                compatibility_pref.clone(),
+                form.template_id,
+            ),
+        });
+    }
+
+    let custom_values = match custom_field_service::validate_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_DEPLOYMENTS,
+        &form.custom,
+    )
+    .await
+    {
+        Ok(values) => values,
+        Err(message) => {
+            return Err(DeploymentError {
+                message,
+                form: DeploymentFormView::new(
+                    form.client_id,
+                    form.crew_id,
+                    start_at,
+                    end_at,
+                    fee_per_hour,
+                    info,
+                    status,
+                    deployment_type,
+                    required_skills.clone(),
+                    compatibility_pref.clone(),
+                    form.template_id,
+                ),
+            });
+        }
+    };
 
     if let Err(err) = deployment_repo::create_deployment(
         db,
@@ -242,6 +353,9 @@ pub async fn create_deployment(
         &deployment_type,
         &required_skills,
         &compatibility_pref,
+        template_id,
+        &checklist,
+        &custom_values,
     )
     .await
     {
@@ -258,6 +372,7 @@ pub async fn create_deployment(
                 deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -287,6 +402,7 @@ pub async fn update_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -304,6 +420,7 @@ pub async fn update_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -322,6 +439,7 @@ pub async fn update_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -340,6 +458,7 @@ pub async fn update_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -359,6 +478,7 @@ pub async fn update_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -378,6 +498,7 @@ pub async fn update_deployment(
                 form.deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -387,6 +508,68 @@ pub async fn update_deployment(
     let start_at = normalize_datetime(&start_input);
     let end_at = normalize_datetime(&end_input);
 
+    if status == STATUS_COMPLETED && !form.override_open_tasks {
+        let required_open = match deployment_repo::find_deployment_by_id(db, tenant_id, deployment_id).await {
+            Ok(Some(deployment)) if deployment.status.eq_ignore_ascii_case(STATUS_COMPLETED) => Ok(0),
+            Ok(_) => deployment_task_service::task_progress(db, tenant_id, deployment_id)
+                .await
+                .map(|progress| progress.required_open),
+            Err(err) => Err(err),
+        };
+        let message = match required_open {
+            Ok(0) => None,
+            Ok(required_open) => Some(open_tasks_message(required_open)),
+            Err(err) => Some(format!("Unable to check open tasks: {err}")),
+        };
+        if let Some(message) = message {
+            return Err(DeploymentError {
+                message,
+                form: DeploymentFormView::new(
+                    form.client_id,
+                    form.crew_id,
+                    start_at,
+                    end_at,
+                    fee_per_hour,
+                    info,
+                    status,
+                    deployment_type,
+                    required_skills.clone(),
+                    compatibility_pref.clone(),
+                    form.template_id,
+                ),
+            });
+        }
+    }
+
+    let custom_values = match custom_field_service::validate_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_DEPLOYMENTS,
+        &form.custom,
+    )
+    .await
+    {
+        Ok(values) => values,
+        Err(message) => {
+            return Err(DeploymentError {
+                message,
+                form: DeploymentFormView::new(
+                    form.client_id,
+                    form.crew_id,
+                    start_at,
+                    end_at,
+                    fee_per_hour,
+                    info,
+                    status,
+                    deployment_type,
+                    required_skills.clone(),
+                    compatibility_pref.clone(),
+                    form.template_id,
+                ),
+            });
+        }
+    };
+
     if let Err(err) = deployment_repo::update_deployment(
         db,
         tenant_id,
@@ -401,6 +584,7 @@ pub async fn update_deployment(
         &deployment_type,
         &required_skills,
         &compatibility_pref,
+        &custom_values,
     )
     .await
     {
@@ -417,6 +601,7 @@ pub async fn update_deployment(
                 deployment_type,
                 required_skills.clone(),
                 compatibility_pref.clone(),
+                form.template_id,
             ),
         });
     }
@@ -447,7 +632,14 @@ pub async fn delete_deployment(
 ) -> Result<(), String> {
     deployment_repo::delete_deployment(db, tenant_id, deployment_id)
         .await
-        .map_err(|err| format!("Unable to delete deployment: {err}"))
+        .map_err(|err| format!("Unable to delete deployment: {err}"))?;
+    custom_field_service::delete_values(
+        db,
+        tenant_id,
+        custom_field_service::ENTITY_DEPLOYMENTS,
+        deployment_id,
+    )
+    .await
 }
 
 pub async fn count_deployments(db: &Db, tenant_id: i64) -> Result<i64, sqlx::Error> {
@@ -497,7 +689,7 @@ fn normalize_status(input: String) -> String {
     STATUS_SCHEDULED.to_string()
 }
 
-fn normalize_deployment_type(input: String) -> String {
+pub fn normalize_deployment_type(input: String) -> String {
     let deployment_type = input.trim();
     for option in deployment_type_options() {
         if option.eq_ignore_ascii_case(deployment_type) {
@@ -518,7 +710,7 @@ fn normalize_datetime(input: &str) -> String {
     trimmed.to_string()
 }
 
-fn normalize_tags(input: String) -> String {
+pub fn normalize_tags(input: String) -> String {
     let mut unique: Vec<String> = Vec::new();
     for raw in input.split(',') {
         let trimmed = raw.trim();
@@ -602,17 +794,23 @@ pub fn calculated_fee(start_at: &str, end_at: &str, fee_per_hour: f64) -> f64 {
 pub fn deployment_timeline(
     status: &str,
     deployment_type: &str,
-    issue_count: i64,
-    resolved_count: i64,
+    incident_count: i64,
+    open_incidents: i64,
     invoice_status: Option<&str>,
+    tasks: Option<&DeploymentTaskProgress>,
 ) -> Vec<DeploymentTimelineStep> {
     let is_scheduled = status.eq_ignore_ascii_case(STATUS_SCHEDULED);
     let is_active = status.eq_ignore_ascii_case(STATUS_ACTIVE);
     let is_completed = status.eq_ignore_ascii_case(STATUS_COMPLETED);
     let is_cancelled = status.eq_ignore_ascii_case(STATUS_CANCELLED);
-
-    let prep_state = if is_scheduled { "active" } else { "complete" };
-    let prep_note = if is_scheduled {
+    let tasks = tasks.filter(|progress| progress.total > 0);
+    let tasks_started = tasks.map(|progress| progress.completed > 0).unwrap_or(false);
+    let tasks_done = tasks
+        .map(|progress| progress.completed >= progress.total)
+        .unwrap_or(false);
+
+    let prep_state = if is_scheduled && !tasks_started { "active" } else { "complete" };
+    let prep_note = if is_scheduled && !tasks_started {
         "Crew prep underway".to_string()
     } else if is_cancelled {
         match deployment_type {
@@ -624,7 +822,17 @@ pub fn deployment_timeline(
         "Crew and assets prepared".to_string()
     };
 
-    let onsite_state = if is_active {
+    let onsite_state = if is_cancelled {
+        "pending"
+    } else if tasks.is_some() {
+        if tasks_done {
+            "complete"
+        } else if tasks_started || is_active || is_completed {
+            "active"
+        } else {
+            "pending"
+        }
+    } else if is_active {
         "active"
     } else if is_completed {
         "complete"
@@ -636,7 +844,13 @@ pub fn deployment_timeline(
         TYPE_HYBRID => "Field + Remote",
         _ => "Onsite",
     };
-    let onsite_note = if is_scheduled {
+    let onsite_note = if let Some(progress) = tasks.filter(|_| !is_cancelled) {
+        format!(
+            "{completed}/{total} tasks complete",
+            completed = progress.completed,
+            total = progress.total
+        )
+    } else if is_scheduled {
         format!(
             "Awaiting {label} start",
             label = onsite_label.to_lowercase()
@@ -651,33 +865,33 @@ pub fn deployment_timeline(
         format!("{onsite_label} status pending")
     };
 
-    let has_issue = issue_count > 0;
-    let has_resolution = resolved_count > 0 || is_completed;
-    let issues_state = if has_issue {
-        if has_resolution { "complete" } else { "active" }
+    let has_incident = incident_count > 0;
+    let all_resolved = open_incidents <= 0;
+    let issues_state = if has_incident {
+        if all_resolved { "complete" } else { "active" }
     } else if is_active || is_completed {
         "complete"
     } else {
         "pending"
     };
-    let issues_note = if has_issue {
-        format!("{issue_count} issue(s) logged")
+    let issues_note = if has_incident {
+        format!("{incident_count} incident(s) reported")
     } else if is_active || is_completed {
-        "No issues reported".to_string()
+        "No incidents reported".to_string()
     } else {
-        "Monitoring for issues".to_string()
+        "Monitoring for incidents".to_string()
     };
 
-    let resolution_state = if has_issue {
-        if has_resolution { "complete" } else { "active" }
+    let resolution_state = if has_incident {
+        if all_resolved { "complete" } else { "active" }
     } else {
         "pending"
     };
-    let resolution_note = if has_issue {
-        if has_resolution {
-            "Resolution logged".to_string()
+    let resolution_note = if has_incident {
+        if all_resolved {
+            "All incidents resolved".to_string()
         } else {
-            "Resolution pending".to_string()
+            format!("{open_incidents} incident(s) awaiting resolution")
         }
     } else {
         "No resolution needed".to_string()
@@ -727,6 +941,22 @@ pub fn deployment_timeline(
     ]
 }
 
+fn open_tasks_message(required_open: i64) -> String {
+    format!(
+        "{required_open} required task(s) are still open. Tick them off on the tracking page or allow completion with open required tasks."
+    )
+}
+
+fn end_from_duration(start_at: &str, duration_hours: f64) -> Option<String> {
+    if duration_hours <= 0.0 {
+        return None;
+    }
+    let start = parse_datetime(&normalize_datetime(start_at))?;
+    let minutes = (duration_hours * 60.0).round() as i64;
+    let end = start + chrono::Duration::minutes(minutes);
+    Some(end.format("%Y-%m-%d %H:%M").to_string())
+}
+
 fn parse_datetime(value: &str) -> Option<chrono::NaiveDateTime> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
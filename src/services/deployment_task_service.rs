@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use rocket_db_pools::sqlx;
+
+use crate::models::{DeploymentTask, DeploymentTaskForm, DeploymentTaskProgress};
+use crate::repositories::deployment_task_repo;
+use crate::Db;
+
+const MAX_TITLE_LENGTH: usize = 200;
+
+pub async fn list_tasks_by_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentTask>, sqlx::Error> {
+    deployment_task_repo::list_tasks_by_deployment(db, tenant_id, deployment_id).await
+}
+
+pub async fn find_task_by_id(
+    db: &Db,
+    tenant_id: i64,
+    task_id: i64,
+) -> Result<Option<DeploymentTask>, sqlx::Error> {
+    deployment_task_repo::find_task_by_id(db, tenant_id, task_id).await
+}
+
+pub async fn task_progress(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<DeploymentTaskProgress, sqlx::Error> {
+    let rows =
+        deployment_task_repo::task_progress_for_deployments(db, tenant_id, &[deployment_id]).await?;
+    Ok(rows
+        .into_iter()
+        .map(|(_, progress)| progress)
+        .next()
+        .unwrap_or_default())
+}
+
+pub async fn task_progress_map(
+    db: &Db,
+    tenant_id: i64,
+    deployment_ids: &[i64],
+) -> HashMap<i64, DeploymentTaskProgress> {
+    deployment_task_repo::task_progress_for_deployments(db, tenant_id, deployment_ids)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .collect()
+}
+
+pub fn completion_percent(progress: &DeploymentTaskProgress) -> Option<i64> {
+    if progress.total <= 0 {
+        return None;
+    }
+    Some(((progress.completed as f64 / progress.total as f64) * 100.0).round() as i64)
+}
+
+pub async fn create_task(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    form: DeploymentTaskForm,
+) -> Result<(), String> {
+    let title = form.title.trim();
+    if title.is_empty() {
+        return Err("Task title is required.".to_string());
+    }
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(format!(
+            "Task title must be {MAX_TITLE_LENGTH} characters or fewer."
+        ));
+    }
+    deployment_task_repo::create_task(db, tenant_id, deployment_id, title, form.is_required)
+        .await
+        .map_err(|err| format!("Unable to add task: {err}"))
+}
+
+pub async fn toggle_task(
+    db: &Db,
+    tenant_id: i64,
+    task: &DeploymentTask,
+    user_id: i64,
+) -> Result<(), String> {
+    let result = if task.completed_at.is_some() {
+        deployment_task_repo::set_task_completed(db, tenant_id, task.id, None, None).await
+    } else {
+        let now = chrono::Local::now()
+            .naive_local()
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+        deployment_task_repo::set_task_completed(db, tenant_id, task.id, Some(&now), Some(user_id))
+            .await
+    };
+    result.map_err(|err| format!("Unable to update task: {err}"))
+}
+
+pub async fn move_task(
+    db: &Db,
+    tenant_id: i64,
+    task: &DeploymentTask,
+    move_up: bool,
+) -> Result<(), String> {
+    let tasks = list_tasks_by_deployment(db, tenant_id, task.deployment_id)
+        .await
+        .map_err(|err| format!("Unable to load tasks: {err}"))?;
+    let index = match tasks.iter().position(|item| item.id == task.id) {
+        Some(index) => index,
+        None => return Err("Task not found.".to_string()),
+    };
+    let neighbour = if move_up {
+        index.checked_sub(1).and_then(|prev| tasks.get(prev))
+    } else {
+        tasks.get(index + 1)
+    };
+    let Some(neighbour) = neighbour else {
+        return Ok(());
+    };
+    deployment_task_repo::swap_task_positions(db, tenant_id, &tasks[index], neighbour)
+        .await
+        .map_err(|err| format!("Unable to reorder tasks: {err}"))
+}
+
+pub async fn delete_task(db: &Db, tenant_id: i64, task_id: i64) -> Result<(), String> {
+    deployment_task_repo::delete_task(db, tenant_id, task_id)
+        .await
+        .map_err(|err| format!("Unable to delete task: {err}"))
+}
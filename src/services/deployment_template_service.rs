@@ -0,0 +1,204 @@
+use rocket_db_pools::sqlx;
+
+use crate::models::{
+    DeploymentTemplate,
+    DeploymentTemplateForm,
+    DeploymentTemplateFormView,
+    DeploymentTemplateUsage,
+};
+use crate::repositories::deployment_template_repo;
+use crate::services::deployment_service;
+use crate::Db;
+
+pub struct DeploymentTemplateError {
+    pub message: String,
+    pub form: DeploymentTemplateFormView,
+}
+
+const MAX_DURATION_HOURS: f64 = 720.0;
+const OPTIONAL_MARKER: &str = "(optional)";
+
+pub async fn list_templates(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<DeploymentTemplate>, sqlx::Error> {
+    deployment_template_repo::list_templates(db, tenant_id).await
+}
+
+pub async fn find_template_by_id(
+    db: &Db,
+    tenant_id: i64,
+    template_id: i64,
+) -> Result<Option<DeploymentTemplate>, sqlx::Error> {
+    deployment_template_repo::find_template_by_id(db, tenant_id, template_id).await
+}
+
+pub async fn list_template_usage(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<DeploymentTemplateUsage>, sqlx::Error> {
+    deployment_template_repo::list_template_usage(db, tenant_id).await
+}
+
+pub async fn create_template(
+    db: &Db,
+    tenant_id: i64,
+    form: DeploymentTemplateForm,
+) -> Result<(), DeploymentTemplateError> {
+    let view = validate_template(db, tenant_id, None, form).await?;
+
+    if let Err(err) = deployment_template_repo::create_template(
+        db,
+        tenant_id,
+        &view.name,
+        &view.deployment_type,
+        &view.required_skills,
+        &view.compatibility_pref,
+        view.fee_per_hour,
+        view.default_duration_hours,
+        &view.checklist,
+        &view.info,
+    )
+    .await
+    {
+        return Err(DeploymentTemplateError {
+            message: format!("Unable to create template: {err}"),
+            form: view,
+        });
+    }
+
+    Ok(())
+}
+
+pub async fn update_template(
+    db: &Db,
+    tenant_id: i64,
+    template_id: i64,
+    form: DeploymentTemplateForm,
+) -> Result<(), DeploymentTemplateError> {
+    let view = validate_template(db, tenant_id, Some(template_id), form).await?;
+
+    if let Err(err) = deployment_template_repo::update_template(
+        db,
+        tenant_id,
+        template_id,
+        &view.name,
+        &view.deployment_type,
+        &view.required_skills,
+        &view.compatibility_pref,
+        view.fee_per_hour,
+        view.default_duration_hours,
+        &view.checklist,
+        &view.info,
+    )
+    .await
+    {
+        return Err(DeploymentTemplateError {
+            message: format!("Unable to update template: {err}"),
+            form: view,
+        });
+    }
+
+    Ok(())
+}
+
+pub async fn delete_template(db: &Db, tenant_id: i64, template_id: i64) -> Result<(), String> {
+    deployment_template_repo::delete_template(db, tenant_id, template_id)
+        .await
+        .map_err(|err| format!("Unable to delete template: {err}"))
+}
+
+pub fn template_form_view(template: &DeploymentTemplate) -> DeploymentTemplateFormView {
+    DeploymentTemplateFormView::new(
+        template.name.clone(),
+        template.deployment_type.clone(),
+        template.required_skills.clone(),
+        template.compatibility_pref.clone(),
+        template.fee_per_hour,
+        template.default_duration_hours,
+        template.checklist.clone(),
+        template.info.clone(),
+    )
+}
+
+pub fn checklist_items(checklist: &str) -> Vec<String> {
+    checklist
+        .lines()
+        .map(|line| line.trim().trim_start_matches(['-', '*']).trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+pub fn checklist_tasks(checklist: &str) -> Vec<(String, bool)> {
+    checklist_items(checklist)
+        .into_iter()
+        .map(|item| {
+            let split = item.len().saturating_sub(OPTIONAL_MARKER.len());
+            let title = match (item.get(..split), item.get(split..)) {
+                (Some(title), Some(marker)) if marker.eq_ignore_ascii_case(OPTIONAL_MARKER) => title.trim(),
+                _ => "",
+            };
+            if title.is_empty() {
+                (item, true)
+            } else {
+                (title.to_string(), false)
+            }
+        })
+        .collect()
+}
+
+async fn validate_template(
+    db: &Db,
+    tenant_id: i64,
+    template_id: Option<i64>,
+    form: DeploymentTemplateForm,
+) -> Result<DeploymentTemplateFormView, DeploymentTemplateError> {
+    let view = DeploymentTemplateFormView::new(
+        form.name.trim(),
+        deployment_service::normalize_deployment_type(form.deployment_type),
+        deployment_service::normalize_tags(form.required_skills),
+        deployment_service::normalize_tags(form.compatibility_pref),
+        form.fee_per_hour,
+        form.default_duration_hours,
+        checklist_items(&form.checklist).join("\n"),
+        form.info.trim(),
+    );
+
+    if view.name.is_empty() {
+        return Err(DeploymentTemplateError {
+            message: "Template name is required.".to_string(),
+            form: view,
+        });
+    }
+    if view.fee_per_hour < 0.0 {
+        return Err(DeploymentTemplateError {
+            message: "Default fee per hour cannot be negative.".to_string(),
+            form: view,
+        });
+    }
+    if view.default_duration_hours <= 0.0 || view.default_duration_hours > MAX_DURATION_HOURS {
+        return Err(DeploymentTemplateError {
+            message: format!(
+                "Default duration must be between 0 and {MAX_DURATION_HOURS} hours."
+            ),
+            form: view,
+        });
+    }
+    match deployment_template_repo::find_template_id_by_name(db, tenant_id, &view.name).await {
+        Ok(Some(existing_id)) if Some(existing_id) != template_id => {
+            return Err(DeploymentTemplateError {
+                message: "A template with this name already exists.".to_string(),
+                form: view,
+            });
+        }
+        Err(err) => {
+            return Err(DeploymentTemplateError {
+                message: format!("Unable to check template name: {err}"),
+                form: view,
+            });
+        }
+        _ => {}
+    }
+
+    Ok(view)
+}
@@ -0,0 +1,219 @@
+use std::path::{Path, PathBuf};
+
+use rocket_db_pools::sqlx;
+
+use crate::models::{DeploymentExpense, DeploymentExpenseForm};
+use crate::repositories::expense_repo;
+use crate::services::{attachment_service, client_service};
+use crate::Db;
+
+pub const STATUS_SUBMITTED: &str = "Submitted";
+pub const STATUS_APPROVED: &str = "Approved";
+pub const STATUS_REJECTED: &str = "Rejected";
+
+const MAX_RECEIPT_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_MARKUP_PERCENT: f64 = 500.0;
+
+pub fn category_options() -> [&'static str; 6] {
+    ["Materials", "Fuel", "Parking", "Equipment hire", "Travel", "Other"]
+}
+
+pub fn status_options() -> [&'static str; 3] {
+    [STATUS_SUBMITTED, STATUS_APPROVED, STATUS_REJECTED]
+}
+
+pub fn receipt_dir(tenant_id: i64) -> PathBuf {
+    Path::new("uploads")
+        .join("receipts")
+        .join(format!("tenant-{tenant_id}"))
+}
+
+pub fn receipt_file_path(expense: &DeploymentExpense) -> Option<PathBuf> {
+    expense
+        .receipt_path
+        .as_deref()
+        .filter(|name| !name.is_empty())
+        .map(|name| receipt_dir(expense.tenant_id).join(name))
+}
+
+pub async fn list_expenses_by_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentExpense>, sqlx::Error> {
+    expense_repo::list_expenses_by_deployment(db, tenant_id, deployment_id).await
+}
+
+pub async fn list_expenses_by_deployment_for_user(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+) -> Result<Vec<DeploymentExpense>, sqlx::Error> {
+    expense_repo::list_expenses_by_deployment_for_user(db, tenant_id, deployment_id, user_id).await
+}
+
+pub async fn list_expenses_by_status(
+    db: &Db,
+    tenant_id: i64,
+    status: Option<&str>,
+) -> Result<Vec<DeploymentExpense>, sqlx::Error> {
+    expense_repo::list_expenses_by_status(db, tenant_id, status).await
+}
+
+pub async fn list_billable_expenses_for_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentExpense>, sqlx::Error> {
+    expense_repo::list_billable_expenses_for_deployment(db, tenant_id, deployment_id).await
+}
+
+pub async fn find_expense_by_id(
+    db: &Db,
+    tenant_id: i64,
+    expense_id: i64,
+) -> Result<Option<DeploymentExpense>, sqlx::Error> {
+    expense_repo::find_expense_by_id(db, tenant_id, expense_id).await
+}
+
+pub async fn count_pending_expenses(db: &Db, tenant_id: i64) -> Result<i64, sqlx::Error> {
+    expense_repo::count_expenses_by_status(db, tenant_id, STATUS_SUBMITTED).await
+}
+
+pub async fn default_currency_for_deployment(db: &Db, tenant_id: i64, client_id: i64) -> String {
+    client_service::find_client_by_id(db, tenant_id, client_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|client| client.currency)
+        .unwrap_or_else(|| "USD".to_string())
+}
+
+pub async fn create_expense(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    form: DeploymentExpenseForm<'_>,
+) -> Result<(), String> {
+    let mut form = form;
+    let category = category_options()
+        .into_iter()
+        .find(|option| option.eq_ignore_ascii_case(form.category.trim()))
+        .ok_or_else(|| "Select an expense category.".to_string())?;
+    if !form.amount.is_finite() || form.amount <= 0.0 {
+        return Err("Amount must be greater than 0.".to_string());
+    }
+    let currency = form.currency.trim().to_uppercase();
+    if !client_service::currency_options()
+        .iter()
+        .any(|option| option.eq(&currency))
+    {
+        return Err("Select a valid currency.".to_string());
+    }
+    let incurred_on = chrono::NaiveDate::parse_from_str(form.incurred_on.trim(), "%Y-%m-%d")
+        .map_err(|_| "Expense date must be a valid date.".to_string())?
+        .format("%Y-%m-%d")
+        .to_string();
+    let markup_percent = if form.is_billable {
+        form.markup_percent.unwrap_or(0.0)
+    } else {
+        0.0
+    };
+    if !(0.0..=MAX_MARKUP_PERCENT).contains(&markup_percent) {
+        return Err(format!(
+            "Markup must be between 0 and {MAX_MARKUP_PERCENT}%."
+        ));
+    }
+
+    let receipt = form.receipt.take().filter(|file| file.len() > 0);
+    let extension = match receipt.as_ref() {
+        Some(file) => {
+            if file.len() > MAX_RECEIPT_BYTES {
+                return Err("Receipts must be 5 MB or smaller.".to_string());
+            }
+            let kind = attachment_service::declared_kind(file)
+                .ok_or_else(|| "Receipts must be a PNG, JPG, WebP, or PDF file.".to_string())?;
+            if !attachment_service::content_matches(file, kind).await {
+                return Err("The receipt's contents do not match its file type.".to_string());
+            }
+            Some(kind.extension)
+        }
+        None => None,
+    };
+
+    let expense_id = expense_repo::create_expense(
+        db,
+        tenant_id,
+        deployment_id,
+        user_id,
+        category,
+        form.description.trim(),
+        (form.amount * 100.0).round() / 100.0,
+        &currency,
+        &incurred_on,
+        form.is_billable,
+        markup_percent,
+    )
+    .await
+    .map_err(|err| format!("Unable to save expense: {err}"))?;
+
+    if let (Some(mut file), Some(extension)) = (receipt, extension) {
+        let dir = receipt_dir(tenant_id);
+        let filename = format!(
+            "expense-{}-{}.{}",
+            expense_id,
+            chrono::Utc::now().timestamp(),
+            extension
+        );
+        let stored = match std::fs::create_dir_all(&dir) {
+            Ok(_) => file.move_copy_to(dir.join(&filename)).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = stored {
+            let _ = expense_repo::delete_expense(db, tenant_id, expense_id).await;
+            return Err(format!("Unable to save receipt: {err}"));
+        }
+        expense_repo::update_receipt_path(db, tenant_id, expense_id, &filename)
+            .await
+            .map_err(|err| format!("Unable to save receipt: {err}"))?;
+    }
+
+    Ok(())
+}
+
+pub async fn review_expense(
+    db: &Db,
+    tenant_id: i64,
+    expense: &DeploymentExpense,
+    reviewer_id: i64,
+    approve: bool,
+    review_note: Option<String>,
+) -> Result<(), String> {
+    if expense.status != STATUS_SUBMITTED {
+        return Err("Only submitted expenses can be reviewed.".to_string());
+    }
+    let review_note = review_note.unwrap_or_default().trim().to_string();
+    if !approve && review_note.is_empty() {
+        return Err("Add a note explaining why the expense was rejected.".to_string());
+    }
+    let status = if approve { STATUS_APPROVED } else { STATUS_REJECTED };
+    expense_repo::review_expense(db, tenant_id, expense.id, status, reviewer_id, &review_note)
+        .await
+        .map_err(|err| format!("Unable to review expense: {err}"))
+}
+
+pub async fn delete_expense(
+    db: &Db,
+    tenant_id: i64,
+    expense: &DeploymentExpense,
+) -> Result<(), String> {
+    expense_repo::delete_expense(db, tenant_id, expense.id)
+        .await
+        .map_err(|err| format!("Unable to delete expense: {err}"))?;
+    if let Some(path) = receipt_file_path(expense) {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
@@ -0,0 +1,439 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::models::User;
+use crate::repositories::{client_repo, crew_member_repo, crew_repo, deployment_repo};
+use crate::services::access_service;
+use crate::services::custom_field_service::{
+    self,
+    ENTITY_CLIENTS,
+    ENTITY_CONTACTS,
+    ENTITY_CREWS,
+    ENTITY_DEPLOYMENTS,
+    ENTITY_MEMBERS,
+};
+use crate::services::utils::csv_field;
+use crate::Db;
+
+struct ExportRecord {
+    id: i64,
+    crew_id: Option<i64>,
+    values: Vec<Value>,
+}
+
+pub struct ExportScope {
+    pub crew_ids: Option<Vec<i64>>,
+    pub include_costs: bool,
+}
+
+const COST_COLUMNS: [&str; 2] = ["fee_per_hour", "hourly_cost"];
+
+const CLIENT_COLUMNS: [&str; 9] = [
+    "id",
+    "company_name",
+    "address",
+    "phone",
+    "email",
+    "latitude",
+    "longitude",
+    "stage",
+    "currency",
+];
+const CONTACT_COLUMNS: [&str; 9] = [
+    "id",
+    "client_id",
+    "client_name",
+    "name",
+    "email",
+    "phone",
+    "address",
+    "department",
+    "position",
+];
+const CREW_COLUMNS: [&str; 7] = [
+    "id",
+    "name",
+    "status",
+    "members",
+    "gear_score",
+    "skill_tags",
+    "compatibility_tags",
+];
+const MEMBER_COLUMNS: [&str; 9] = [
+    "id",
+    "crew_id",
+    "crew_name",
+    "name",
+    "email",
+    "phone",
+    "position",
+    "availability_status",
+    "hourly_cost",
+];
+const DEPLOYMENT_COLUMNS: [&str; 12] = [
+    "id",
+    "client_id",
+    "client_name",
+    "crew_id",
+    "crew_name",
+    "start_at",
+    "end_at",
+    "fee_per_hour",
+    "currency",
+    "status",
+    "deployment_type",
+    "info",
+];
+
+fn columns(entity: &str) -> &'static [&'static str] {
+    match entity {
+        ENTITY_CLIENTS => &CLIENT_COLUMNS,
+        ENTITY_CONTACTS => &CONTACT_COLUMNS,
+        ENTITY_CREWS => &CREW_COLUMNS,
+        ENTITY_MEMBERS => &MEMBER_COLUMNS,
+        ENTITY_DEPLOYMENTS => &DEPLOYMENT_COLUMNS,
+        _ => &[],
+    }
+}
+
+pub async fn scope_for(db: &Db, user: &User) -> ExportScope {
+    let crew_ids = if access_service::is_employee(&user.role) {
+        Some(
+            crew_member_repo::list_crew_ids_for_user(db, user.tenant_id, user.id, &user.email)
+                .await
+                .unwrap_or_default(),
+        )
+    } else {
+        None
+    };
+    ExportScope {
+        crew_ids,
+        include_costs: access_service::can_view_costs(&user.role),
+    }
+}
+
+fn apply_scope(
+    entity: &str,
+    mut records: Vec<ExportRecord>,
+    scope: &ExportScope,
+) -> (Vec<&'static str>, Vec<ExportRecord>) {
+    if (entity == ENTITY_DEPLOYMENTS || entity == ENTITY_MEMBERS)
+        && let Some(crew_ids) = &scope.crew_ids
+    {
+        records.retain(|record| record.crew_id.is_some_and(|crew_id| crew_ids.contains(&crew_id)));
+    }
+    let keep = columns(entity)
+        .iter()
+        .map(|column| scope.include_costs || !COST_COLUMNS.contains(column))
+        .collect::<Vec<_>>();
+    for record in &mut records {
+        let mut index = 0;
+        record.values.retain(|_| {
+            let kept = keep.get(index).copied().unwrap_or(true);
+            index += 1;
+            kept
+        });
+    }
+    let columns = columns(entity)
+        .iter()
+        .zip(&keep)
+        .filter(|(_, kept)| **kept)
+        .map(|(column, _)| *column)
+        .collect();
+    (columns, records)
+}
+
+pub fn resource_for(entity: &str) -> Option<&'static str> {
+    match entity {
+        ENTITY_CLIENTS | ENTITY_CONTACTS => Some("clients"),
+        ENTITY_CREWS | ENTITY_MEMBERS => Some("crew"),
+        ENTITY_DEPLOYMENTS => Some("deployments"),
+        _ => None,
+    }
+}
+
+async fn records(db: &Db, tenant_id: i64, entity: &str) -> Result<Vec<ExportRecord>, String> {
+    match entity {
+        ENTITY_CLIENTS => Ok(client_repo::list_clients(db, tenant_id)
+            .await
+            .map_err(|err| format!("Unable to load clients: {err}"))?
+            .into_iter()
+            .rev()
+            .map(|client| ExportRecord {
+                id: client.id,
+                crew_id: None,
+                values: vec![
+                    json!(client.id),
+                    json!(client.company_name),
+                    json!(client.address),
+                    json!(client.phone),
+                    json!(client.email),
+                    json!(client.latitude),
+                    json!(client.longitude),
+                    json!(client.stage),
+                    json!(client.currency),
+                ],
+            })
+            .collect()),
+        ENTITY_CONTACTS => {
+            let client_names = client_repo::list_clients(db, tenant_id)
+                .await
+                .map_err(|err| format!("Unable to load clients: {err}"))?
+                .into_iter()
+                .map(|client| (client.id, client.company_name))
+                .collect::<HashMap<_, _>>();
+            Ok(client_repo::list_all_contacts(db, tenant_id)
+                .await
+                .map_err(|err| format!("Unable to load contacts: {err}"))?
+                .into_iter()
+                .map(|contact| ExportRecord {
+                    id: contact.id,
+                    crew_id: None,
+                    values: vec![
+                        json!(contact.id),
+                        json!(contact.client_id),
+                        json!(client_names.get(&contact.client_id).cloned().unwrap_or_default()),
+                        json!(contact.name),
+                        json!(contact.email),
+                        json!(contact.phone),
+                        json!(contact.address),
+                        json!(contact.department),
+                        json!(contact.position),
+                    ],
+                })
+                .collect())
+        }
+        ENTITY_CREWS => Ok(crew_repo::list_crews(db, tenant_id)
+            .await
+            .map_err(|err| format!("Unable to load crews: {err}"))?
+            .into_iter()
+            .map(|crew| ExportRecord {
+                id: crew.id,
+                crew_id: None,
+                values: vec![
+                    json!(crew.id),
+                    json!(crew.name),
+                    json!(crew.status),
+                    json!(crew.members_count),
+                    json!(crew.gear_score),
+                    json!(crew.skill_tags),
+                    json!(crew.compatibility_tags),
+                ],
+            })
+            .collect()),
+        ENTITY_MEMBERS => {
+            let crews = crew_repo::list_crews(db, tenant_id)
+                .await
+                .map_err(|err| format!("Unable to load crews: {err}"))?;
+            let crew_ids = crews.iter().map(|crew| crew.id).collect::<Vec<_>>();
+            let crew_names = crews
+                .into_iter()
+                .map(|crew| (crew.id, crew.name))
+                .collect::<HashMap<_, _>>();
+            Ok(crew_member_repo::list_members_for_crews(db, tenant_id, &crew_ids)
+                .await
+                .map_err(|err| format!("Unable to load crew members: {err}"))?
+                .into_iter()
+                .map(|member| ExportRecord {
+                    id: member.id,
+                    crew_id: Some(member.crew_id),
+                    values: vec![
+                        json!(member.id),
+                        json!(member.crew_id),
+                        json!(crew_names.get(&member.crew_id).cloned().unwrap_or_default()),
+                        json!(member.name),
+                        json!(member.email),
+                        json!(member.phone),
+                        json!(member.position),
+                        json!(member.availability_status),
+                        json!(member.hourly_cost),
+                    ],
+                })
+                .collect())
+        }
+        ENTITY_DEPLOYMENTS => Ok(deployment_repo::list_deployments_with_names(db, tenant_id)
+            .await
+            .map_err(|err| format!("Unable to load deployments: {err}"))?
+            .into_iter()
+            .map(|row| ExportRecord {
+                id: row.id,
+                crew_id: Some(row.crew_id),
+                values: vec![
+                    json!(row.id),
+                    json!(row.client_id),
+                    json!(row.client_name),
+                    json!(row.crew_id),
+                    json!(row.crew_name),
+                    json!(row.start_at),
+                    json!(row.end_at),
+                    json!(row.fee_per_hour),
+                    json!(row.client_currency),
+                    json!(row.status),
+                    json!(row.deployment_type),
+                    json!(row.info),
+                ],
+            })
+            .collect()),
+        _ => Err("Unknown export.".to_string()),
+    }
+}
+
+pub async fn export_csv(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    scope: &ExportScope,
+) -> Result<String, String> {
+    let (columns, records) = apply_scope(entity, records(db, tenant_id, entity).await?, scope);
+    let (definitions, values) = custom_field_service::value_table(db, tenant_id, entity).await;
+    let mut headers = columns.iter().map(|column| column.to_string()).collect::<Vec<_>>();
+    headers.extend(definitions.iter().map(|definition| definition.label.clone()));
+
+    let mut csv = headers.iter().map(|header| csv_field(header)).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+    for record in &records {
+        let saved = values.get(&record.id);
+        let mut row = record
+            .values
+            .iter()
+            .map(|value| match value {
+                Value::String(text) => text.clone(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>();
+        row.extend(definitions.iter().map(|definition| {
+            let raw = saved
+                .and_then(|saved| saved.get(&definition.id))
+                .map(String::as_str)
+                .unwrap_or("");
+            custom_field_service::display_value(definition, raw)
+        }));
+        csv.push_str(&row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+    Ok(csv)
+}
+
+pub async fn export_json(
+    db: &Db,
+    tenant_id: i64,
+    entity: &str,
+    scope: &ExportScope,
+) -> Result<Vec<Value>, String> {
+    let (columns, records) = apply_scope(entity, records(db, tenant_id, entity).await?, scope);
+    let (definitions, values) = custom_field_service::value_table(db, tenant_id, entity).await;
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let saved = values.get(&record.id);
+            let custom = definitions
+                .iter()
+                .map(|definition| {
+                    let raw = saved
+                        .and_then(|saved| saved.get(&definition.id))
+                        .map(String::as_str)
+                        .unwrap_or("");
+                    (definition.label.clone(), custom_field_service::json_value(definition, raw))
+                })
+                .collect::<Map<_, _>>();
+            let mut object = columns
+                .iter()
+                .map(|column| column.to_string())
+                .zip(record.values)
+                .collect::<Map<_, _>>();
+            object.insert("custom_fields".to_string(), Value::Object(custom));
+            Value::Object(object)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(id: i64, crew_id: i64) -> ExportRecord {
+        ExportRecord {
+            id,
+            crew_id: Some(crew_id),
+            values: DEPLOYMENT_COLUMNS
+                .iter()
+                .map(|column| match *column {
+                    "id" => json!(id),
+                    "crew_id" => json!(crew_id),
+                    "fee_per_hour" => json!(95.0),
+                    other => json!(other),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn employee_only_exports_deployments_of_their_crews() {
+        let records = vec![deployment(1, 10), deployment(2, 20), deployment(3, 10)];
+        let scope = ExportScope {
+            crew_ids: Some(vec![10]),
+            include_costs: false,
+        };
+
+        let (columns, records) = apply_scope(ENTITY_DEPLOYMENTS, records, &scope);
+
+        assert_eq!(records.iter().map(|record| record.id).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(!columns.contains(&"fee_per_hour"));
+        assert!(records.iter().all(|record| record.values.len() == columns.len()));
+        assert!(records.iter().all(|record| !record.values.contains(&json!(95.0))));
+    }
+
+    #[test]
+    fn employee_without_crews_exports_no_deployments() {
+        let scope = ExportScope {
+            crew_ids: Some(Vec::new()),
+            include_costs: false,
+        };
+
+        let (_, records) = apply_scope(ENTITY_DEPLOYMENTS, vec![deployment(1, 10)], &scope);
+
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn unscoped_export_keeps_every_row_and_cost_column() {
+        let scope = ExportScope {
+            crew_ids: None,
+            include_costs: true,
+        };
+
+        let (columns, records) =
+            apply_scope(ENTITY_DEPLOYMENTS, vec![deployment(1, 10), deployment(2, 20)], &scope);
+
+        assert_eq!(columns, DEPLOYMENT_COLUMNS.to_vec());
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].values.len(), DEPLOYMENT_COLUMNS.len());
+    }
+
+    #[test]
+    fn member_export_hides_hourly_cost_without_cost_access() {
+        let member = ExportRecord {
+            id: 7,
+            crew_id: Some(10),
+            values: MEMBER_COLUMNS
+                .iter()
+                .map(|column| match *column {
+                    "hourly_cost" => json!(42.5),
+                    other => json!(other),
+                })
+                .collect(),
+        };
+        let scope = ExportScope {
+            crew_ids: None,
+            include_costs: false,
+        };
+
+        let (columns, records) = apply_scope(ENTITY_MEMBERS, vec![member], &scope);
+
+        assert!(!columns.contains(&"hourly_cost"));
+        assert_eq!(records[0].values.len(), columns.len());
+        assert!(!records[0].values.contains(&json!(42.5)));
+    }
+}
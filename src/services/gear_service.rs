@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+
+use rocket_db_pools::sqlx;
+
+use crate::models::{Crew, CrewGearView, CrewHeldAsset, GearRequirementView, RequiredKitItem};
+use crate::repositories::{asset_repo, crew_repo, gear_repo};
+use crate::services::{asset_service, maintenance_service, recommendation_service};
+use crate::Db;
+
+const MAX_KIT_QUANTITY: i64 = 50;
+
+fn condition_quality(condition: &str) -> f64 {
+    match condition {
+        asset_service::CONDITION_NEW | asset_service::CONDITION_GOOD => 1.0,
+        asset_service::CONDITION_FAIR => 0.75,
+        asset_service::CONDITION_POOR => 0.4,
+        asset_service::CONDITION_OUT_OF_SERVICE => 0.0,
+        _ => 0.75,
+    }
+}
+
+/// Overdue maintenance blocks an asset like being out of service; assets
+/// only due soon still count but are flagged.
+fn held_quality(asset: &CrewHeldAsset) -> f64 {
+    if asset.maintenance == maintenance_service::STATE_OVERDUE {
+        0.0
+    } else {
+        condition_quality(&asset.condition)
+    }
+}
+
+async fn list_held_assets(db: &Db, tenant_id: i64) -> Result<Vec<CrewHeldAsset>, sqlx::Error> {
+    let mut held = gear_repo::list_crew_held_assets(db, tenant_id, &now_string()).await?;
+    if held.is_empty() {
+        return Ok(held);
+    }
+    let assets = asset_repo::list_assets(db, tenant_id).await?;
+    let states = maintenance_service::states_by_asset(db, tenant_id, &assets).await;
+    for asset in &mut held {
+        if let Some(state) = states.get(&asset.asset_id) {
+            asset.maintenance = state.to_string();
+        }
+    }
+    Ok(held)
+}
+
+fn maintenance_warnings(held: &[CrewHeldAsset]) -> Vec<String> {
+    held.iter()
+        .filter_map(|asset| match asset.maintenance.as_str() {
+            maintenance_service::STATE_OVERDUE => Some(format!(
+                "{} is overdue for maintenance and does not count toward readiness.",
+                asset.name
+            )),
+            maintenance_service::STATE_DUE => Some(format!("{} is due for maintenance soon.", asset.name)),
+            _ => None,
+        })
+        .collect()
+}
+
+pub async fn list_required_kit(db: &Db, tenant_id: i64) -> Result<Vec<RequiredKitItem>, sqlx::Error> {
+    gear_repo::list_required_kit(db, tenant_id).await
+}
+
+pub fn format_required_kit(items: &[RequiredKitItem]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            if item.quantity == 1 {
+                format!("{}: {}", item.skill_tag, item.asset_type)
+            } else {
+                format!("{}: {} x{}", item.skill_tag, item.asset_type, item.quantity)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn parse_required_kit(input: &str) -> Result<Vec<RequiredKitItem>, String> {
+    let mut items: Vec<RequiredKitItem> = Vec::new();
+    for line in input.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((skill_tag, rest)) = line.split_once(':') else {
+            return Err(format!(
+                "\"{line}\" needs a skill tag and an asset type separated by a colon."
+            ));
+        };
+        let skill_tag = skill_tag.trim().to_lowercase();
+        let rest = rest.trim();
+        let (asset_type, quantity) = match rest.rsplit_once(" x") {
+            Some((asset_type, quantity)) if quantity.trim().parse::<i64>().is_ok() => {
+                (asset_type.trim(), quantity.trim().parse::<i64>().unwrap_or(1))
+            }
+            _ => (rest, 1),
+        };
+        if skill_tag.is_empty() || asset_type.is_empty() {
+            return Err(format!(
+                "\"{line}\" needs a skill tag and an asset type separated by a colon."
+            ));
+        }
+        if !(1..=MAX_KIT_QUANTITY).contains(&quantity) {
+            return Err(format!(
+                "Quantity for \"{line}\" must be between 1 and {MAX_KIT_QUANTITY}."
+            ));
+        }
+        let asset_type = asset_service::type_options()
+            .iter()
+            .find(|option| option.eq_ignore_ascii_case(asset_type))
+            .map(|option| option.to_string())
+            .unwrap_or_else(|| asset_type.to_string());
+        if items.iter().any(|item| {
+            item.skill_tag == skill_tag && item.asset_type.eq_ignore_ascii_case(&asset_type)
+        }) {
+            return Err(format!(
+                "{asset_type} is listed twice for {skill_tag}."
+            ));
+        }
+        items.push(RequiredKitItem {
+            skill_tag,
+            asset_type,
+            quantity,
+        });
+    }
+    Ok(items)
+}
+
+pub async fn update_required_kit(db: &Db, tenant_id: i64, input: &str) -> Result<(), String> {
+    let items = parse_required_kit(input)?;
+    gear_repo::replace_required_kit(db, tenant_id, &items)
+        .await
+        .map_err(|err| format!("Unable to save required kit: {err}"))?;
+    refresh_gear_scores(db, tenant_id).await
+}
+
+fn assess(crew: &Crew, kit: &[RequiredKitItem], held: Vec<CrewHeldAsset>) -> (i64, Vec<GearRequirementView>) {
+    let skills = recommendation_service::tag_set(&crew.skill_tags);
+    let mut required: Vec<(String, i64, Vec<String>)> = Vec::new();
+    for item in kit.iter().filter(|item| skills.contains(&item.skill_tag)) {
+        match required
+            .iter_mut()
+            .find(|(asset_type, _, _)| asset_type.eq_ignore_ascii_case(&item.asset_type))
+        {
+            Some((_, quantity, tags)) => {
+                *quantity = (*quantity).max(item.quantity);
+                tags.push(item.skill_tag.clone());
+            }
+            None => required.push((
+                item.asset_type.clone(),
+                item.quantity,
+                vec![item.skill_tag.clone()],
+            )),
+        }
+    }
+
+    if required.is_empty() {
+        if held.is_empty() {
+            return (100, Vec::new());
+        }
+        let total = held
+            .iter()
+            .map(held_quality)
+            .sum::<f64>();
+        return (((total / held.len() as f64) * 100.0).round() as i64, Vec::new());
+    }
+
+    let mut lines = Vec::new();
+    let mut coverage_total = 0.0;
+    for (asset_type, quantity, tags) in &required {
+        let mut qualities = held
+            .iter()
+            .filter(|asset| asset.asset_type.eq_ignore_ascii_case(asset_type))
+            .map(held_quality)
+            .collect::<Vec<_>>();
+        qualities.sort_by(|a, b| b.total_cmp(a));
+        let filled = qualities.iter().take(*quantity as usize).sum::<f64>();
+        let coverage = filled / *quantity as f64;
+        coverage_total += coverage;
+        lines.push(GearRequirementView {
+            asset_type: asset_type.clone(),
+            skill_tags: tags.join(", "),
+            required: *quantity,
+            held: qualities.len() as i64,
+            coverage: (coverage * 100.0).round() as i64,
+        });
+    }
+    let score = ((coverage_total / required.len() as f64) * 100.0).round() as i64;
+    (score.clamp(0, 100), lines)
+}
+
+fn now_string() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()
+}
+
+pub async fn refresh_gear_scores(db: &Db, tenant_id: i64) -> Result<(), String> {
+    let crews = crew_repo::list_crews(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load crews: {err}"))?;
+    let kit = gear_repo::list_required_kit(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load required kit: {err}"))?;
+    let mut held_by_crew: HashMap<i64, Vec<CrewHeldAsset>> = HashMap::new();
+    for asset in list_held_assets(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load crew equipment: {err}"))?
+    {
+        held_by_crew.entry(asset.crew_id).or_default().push(asset);
+    }
+    let overrides = gear_repo::list_gear_overrides(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load gear overrides: {err}"))?
+        .into_iter()
+        .filter_map(|(crew_id, score, _)| score.map(|score| (crew_id, score)))
+        .collect::<HashMap<_, _>>();
+
+    let changed = crews
+        .iter()
+        .filter_map(|crew| {
+            let score = match overrides.get(&crew.id) {
+                Some(score) => *score,
+                None => assess(crew, &kit, held_by_crew.remove(&crew.id).unwrap_or_default()).0,
+            };
+            (score != crew.gear_score).then_some((crew.id, score))
+        })
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        return Ok(());
+    }
+    gear_repo::update_gear_scores(db, tenant_id, &changed)
+        .await
+        .map_err(|err| format!("Unable to update gear scores: {err}"))
+}
+
+pub async fn refresh_all_gear_scores(db: &Db) -> Result<(), String> {
+    let tenant_ids = gear_repo::list_tenant_ids_with_crews(db)
+        .await
+        .map_err(|err| format!("Unable to load workspaces: {err}"))?;
+    for tenant_id in tenant_ids {
+        refresh_gear_scores(db, tenant_id).await?;
+    }
+    Ok(())
+}
+
+pub async fn find_override(db: &Db, tenant_id: i64, crew_id: i64) -> (Option<i64>, String) {
+    gear_repo::list_gear_overrides(db, tenant_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .find(|(id, _, _)| *id == crew_id)
+        .map(|(_, score, reason)| (score, reason))
+        .unwrap_or((None, String::new()))
+}
+
+pub async fn set_override(
+    db: &Db,
+    tenant_id: i64,
+    crew_id: i64,
+    score: Option<i64>,
+    reason: &str,
+) -> Result<(), String> {
+    gear_repo::update_gear_override(db, tenant_id, crew_id, score, reason)
+        .await
+        .map_err(|err| format!("Unable to save gear override: {err}"))?;
+    refresh_gear_scores(db, tenant_id).await
+}
+
+pub async fn crew_gear_view(db: &Db, tenant_id: i64, crew: &Crew) -> CrewGearView {
+    let kit = gear_repo::list_required_kit(db, tenant_id)
+        .await
+        .unwrap_or_default();
+    let held = list_held_assets(db, tenant_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|asset| asset.crew_id == crew.id)
+        .collect::<Vec<_>>();
+    let (computed_score, requirements) = assess(crew, &kit, held.clone());
+    let (override_score, override_reason) = find_override(db, tenant_id, crew.id).await;
+    CrewGearView {
+        score: override_score.unwrap_or(computed_score),
+        computed_score,
+        override_score,
+        override_reason,
+        requirements,
+        warnings: maintenance_warnings(&held),
+        held,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held(name: &str, maintenance: &str) -> CrewHeldAsset {
+        CrewHeldAsset {
+            crew_id: 1,
+            asset_id: 1,
+            name: name.to_string(),
+            asset_type: "Generator".to_string(),
+            condition: asset_service::CONDITION_GOOD.to_string(),
+            maintenance: maintenance.to_string(),
+        }
+    }
+
+    #[test]
+    fn overdue_maintenance_blocks_and_due_maintenance_warns() {
+        let crew = Crew {
+            id: 1,
+            tenant_id: 1,
+            name: "Alpha".to_string(),
+            members_count: 2,
+            status: "Active".to_string(),
+            gear_score: 0,
+            skill_tags: "power".to_string(),
+            compatibility_tags: String::new(),
+        };
+        let kit = [RequiredKitItem {
+            skill_tag: "power".to_string(),
+            asset_type: "Generator".to_string(),
+            quantity: 2,
+        }];
+        let assets = vec![
+            held("Gen A", ""),
+            held("Gen B", maintenance_service::STATE_OVERDUE),
+            held("Gen C", maintenance_service::STATE_DUE),
+        ];
+
+        let (score, _) = assess(&crew, &kit, assets.clone());
+        assert_eq!(score, 100);
+        let (score, _) = assess(&crew, &kit, assets[..2].to_vec());
+        assert_eq!(score, 50);
+
+        let warnings = maintenance_warnings(&assets);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("Gen B is overdue"));
+    }
+}
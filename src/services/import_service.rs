@@ -0,0 +1,1080 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use calamine::{Data, DataType, Reader, Sheets, Xlsx, XlsxError};
+use rocket::fs::TempFile;
+use rocket::tokio::io::AsyncReadExt;
+
+use crate::models::{
+    ClientImportRecord,
+    ContactImportRecord,
+    CrewMemberImportRecord,
+    CustomFieldDefinition,
+    DataImport,
+    DataImportSummary,
+    ImportColumnView,
+    ImportPreviewView,
+    ImportRowView,
+};
+use crate::repositories::{client_repo, crew_member_repo, crew_repo, import_repo, user_repo};
+use crate::services::custom_field_service::{self, ENTITY_CLIENTS, ENTITY_CONTACTS, ENTITY_MEMBERS};
+use crate::services::utils::csv_field;
+use crate::services::{client_service, crew_service, workspace_service};
+use crate::Db;
+
+pub const STATUS_DRAFT: &str = "Draft";
+const ROW_READY: &str = "Ready";
+const ROW_DUPLICATE: &str = "Duplicate";
+const ROW_ERROR: &str = "Error";
+const MAX_IMPORT_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_IMPORT_ROWS: usize = 2000;
+const MAX_TABLE_ROWS: usize = MAX_IMPORT_ROWS + 1;
+const PREVIEW_ROWS: usize = 200;
+const RECENT_IMPORTS: i64 = 20;
+const DEFAULT_STAGE: &str = "Proposal";
+const DEFAULT_CURRENCY: &str = "USD";
+const CUSTOM_PREFIX: &str = "custom_";
+
+struct ImportTarget {
+    key: &'static str,
+    label: &'static str,
+    is_required: bool,
+    aliases: &'static [&'static str],
+}
+
+const CLIENT_TARGETS: [ImportTarget; 8] = [
+    ImportTarget { key: "company_name", label: "Company name", is_required: true, aliases: &["company", "client", "name", "organisation", "organization"] },
+    ImportTarget { key: "address", label: "Address", is_required: false, aliases: &[] },
+    ImportTarget { key: "phone", label: "Phone", is_required: false, aliases: &["telephone", "tel", "mobile"] },
+    ImportTarget { key: "email", label: "Email", is_required: false, aliases: &["emailaddress", "mail"] },
+    ImportTarget { key: "latitude", label: "Latitude", is_required: false, aliases: &["lat"] },
+    ImportTarget { key: "longitude", label: "Longitude", is_required: false, aliases: &["lng", "lon", "long"] },
+    ImportTarget { key: "stage", label: "Stage", is_required: false, aliases: &[] },
+    ImportTarget { key: "currency", label: "Currency", is_required: false, aliases: &[] },
+];
+const CONTACT_TARGETS: [ImportTarget; 7] = [
+    ImportTarget { key: "client", label: "Client", is_required: true, aliases: &["company", "companyname", "clientname"] },
+    ImportTarget { key: "name", label: "Name", is_required: true, aliases: &["contact", "contactname", "fullname"] },
+    ImportTarget { key: "email", label: "Email", is_required: false, aliases: &["emailaddress", "mail"] },
+    ImportTarget { key: "phone", label: "Phone", is_required: false, aliases: &["telephone", "tel", "mobile"] },
+    ImportTarget { key: "address", label: "Address", is_required: false, aliases: &[] },
+    ImportTarget { key: "department", label: "Department", is_required: false, aliases: &["dept"] },
+    ImportTarget { key: "position", label: "Position", is_required: false, aliases: &["title", "jobtitle", "role"] },
+];
+const MEMBER_TARGETS: [ImportTarget; 7] = [
+    ImportTarget { key: "crew", label: "Crew", is_required: true, aliases: &["team", "crewname"] },
+    ImportTarget { key: "email", label: "User email", is_required: true, aliases: &["email", "emailaddress"] },
+    ImportTarget { key: "name", label: "Name", is_required: true, aliases: &["member", "membername", "fullname"] },
+    ImportTarget { key: "phone", label: "Phone", is_required: true, aliases: &["telephone", "tel", "mobile"] },
+    ImportTarget { key: "position", label: "Position", is_required: false, aliases: &["title", "role"] },
+    ImportTarget { key: "availability_status", label: "Availability", is_required: false, aliases: &["status"] },
+    ImportTarget { key: "hourly_cost", label: "Hourly cost", is_required: false, aliases: &["rate", "hourlyrate", "cost"] },
+];
+
+struct CheckedRow {
+    line: usize,
+    status: &'static str,
+    message: String,
+}
+
+enum PreparedRecords {
+    Clients(Vec<ClientImportRecord>),
+    Contacts(Vec<ContactImportRecord>),
+    Members(Vec<CrewMemberImportRecord>),
+}
+
+struct Prepared {
+    rows: Vec<CheckedRow>,
+    records: PreparedRecords,
+}
+
+pub fn entity_options() -> [&'static str; 3] {
+    [ENTITY_CLIENTS, ENTITY_CONTACTS, ENTITY_MEMBERS]
+}
+
+pub fn entity_label(entity: &str) -> &'static str {
+    match entity {
+        ENTITY_CLIENTS => "Clients",
+        ENTITY_CONTACTS => "Client contacts",
+        ENTITY_MEMBERS => "Crew members",
+        _ => "Records",
+    }
+}
+
+pub fn resource_for(entity: &str) -> Option<&'static str> {
+    match entity {
+        ENTITY_CLIENTS | ENTITY_CONTACTS => Some("clients"),
+        ENTITY_MEMBERS => Some("crew"),
+        _ => None,
+    }
+}
+
+fn targets(entity: &str) -> &'static [ImportTarget] {
+    match entity {
+        ENTITY_CLIENTS => &CLIENT_TARGETS,
+        ENTITY_CONTACTS => &CONTACT_TARGETS,
+        ENTITY_MEMBERS => &MEMBER_TARGETS,
+        _ => &[],
+    }
+}
+
+async fn custom_definitions(db: &Db, tenant_id: i64, entity: &str) -> Vec<CustomFieldDefinition> {
+    match entity {
+        ENTITY_CLIENTS | ENTITY_CONTACTS | ENTITY_MEMBERS => {
+            custom_field_service::list_definitions(db, tenant_id, entity).await
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn custom_key(definition: &CustomFieldDefinition) -> String {
+    format!("{CUSTOM_PREFIX}{}", definition.id)
+}
+
+pub async fn list_imports(db: &Db, tenant_id: i64) -> Vec<DataImportSummary> {
+    import_repo::list_imports(db, tenant_id, RECENT_IMPORTS)
+        .await
+        .unwrap_or_default()
+}
+
+pub async fn find_import(db: &Db, tenant_id: i64, import_id: i64) -> Option<DataImport> {
+    import_repo::find_import(db, tenant_id, import_id)
+        .await
+        .ok()
+        .flatten()
+}
+
+pub async fn create_import(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    entity: &str,
+    file: Option<TempFile<'_>>,
+) -> Result<i64, String> {
+    if resource_for(entity).is_none() {
+        return Err("Choose what you are importing.".to_string());
+    }
+    let Some(file) = file.filter(|file| file.len() > 0) else {
+        return Err("Choose a CSV or Excel file to import.".to_string());
+    };
+    if file.len() > MAX_IMPORT_BYTES {
+        return Err("Import files must be 5 MB or smaller.".to_string());
+    }
+    let file_name = file
+        .raw_name()
+        .map(|name| name.dangerous_unsafe_unsanitized_raw().as_str())
+        .and_then(|name| Path::new(name).file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or("import.csv")
+        .chars()
+        .filter(|ch| !ch.is_control())
+        .take(120)
+        .collect::<String>();
+    let extension = Path::new(&file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+
+    let mut bytes = Vec::new();
+    file.open()
+        .await
+        .map_err(|err| format!("Unable to read the upload: {err}"))?
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(|err| format!("Unable to read the upload: {err}"))?;
+
+    let table = match extension.as_str() {
+        "csv" | "txt" => parse_csv(&bytes)?,
+        "xlsx" | "xlsm" | "xls" | "ods" => parse_workbook(bytes)?,
+        _ => return Err("Imports must be a .csv, .xlsx, .xls or .ods file.".to_string()),
+    };
+    let (headers, header_line, rows) = split_header(table)?;
+
+    let definitions = custom_definitions(db, tenant_id, entity).await;
+    let mapping = guess_mapping(entity, &definitions, &headers);
+    let headers_json = serde_json::to_string(&headers).map_err(|err| err.to_string())?;
+    let rows_json = serde_json::to_string(&rows).map_err(|err| err.to_string())?;
+    let mapping_json = serde_json::to_string(&mapping).map_err(|err| err.to_string())?;
+    import_repo::create_import(
+        db,
+        tenant_id,
+        user_id,
+        entity,
+        &file_name,
+        &headers_json,
+        header_line as i64,
+        &rows_json,
+        rows.iter().filter(|row| !is_blank(row)).count() as i64,
+        &mapping_json,
+    )
+    .await
+    .map_err(|err| format!("Unable to save the import: {err}"))
+}
+
+fn parse_csv(bytes: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start_matches('\u{feff}');
+    let first_line = text.lines().next().unwrap_or("");
+    let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes());
+    let mut table = Vec::new();
+    let mut filled = 0;
+    let bytes = text.as_bytes();
+    let (mut scanned, mut newlines) = (0, 0);
+    let mut record = csv::StringRecord::new();
+    while reader
+        .read_record(&mut record)
+        .map_err(|err| format!("Unable to read the CSV file: {err}"))?
+    {
+        // The reader skips blank lines and its record positions do not count
+        // them, so work the line out from where the record ends. Pad the
+        // skipped lines back so row numbers in the preview and error report
+        // match the line numbers in the file.
+        let mut end = (reader.position().byte() as usize).min(bytes.len());
+        while end > scanned && matches!(bytes[end - 1], b'\r' | b'\n') {
+            end -= 1;
+        }
+        newlines += bytes[scanned..end].iter().filter(|byte| **byte == b'\n').count();
+        scanned = end;
+        let line = newlines + 1 - record.iter().map(|field| field.matches('\n').count()).sum::<usize>();
+        while table.len() + 1 < line {
+            table.push(Vec::new());
+        }
+        let row = record.iter().map(|field| field.trim().to_string()).collect::<Vec<_>>();
+        if !is_blank(&row) {
+            filled += 1;
+            if filled > MAX_TABLE_ROWS {
+                return Err(too_many_rows());
+            }
+        }
+        table.push(row);
+    }
+    Ok(table)
+}
+
+fn parse_workbook(bytes: Vec<u8>) -> Result<Vec<Vec<String>>, String> {
+    let mut workbook = calamine::open_workbook_auto_from_rs(Cursor::new(bytes))
+        .map_err(|err| format!("Unable to read the spreadsheet: {err}"))?;
+    if let Sheets::Xlsx(xlsx) = &mut workbook {
+        return read_xlsx(xlsx);
+    }
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| "The spreadsheet has no sheets.".to_string())?
+        .map_err(|err| format!("Unable to read the spreadsheet: {err}"))?;
+    let first_row = range.start().map(|(row, _)| row as usize).unwrap_or(0);
+    let mut table = vec![Vec::new(); first_row];
+    let mut filled = 0;
+    for row in range.rows() {
+        let row = row.iter().map(cell_text).collect::<Vec<_>>();
+        if !is_blank(&row) {
+            filled += 1;
+            if filled > MAX_TABLE_ROWS {
+                return Err(too_many_rows());
+            }
+        }
+        table.push(row);
+    }
+    Ok(table)
+}
+
+fn read_xlsx<RS: Read + Seek>(xlsx: &mut Xlsx<RS>) -> Result<Vec<Vec<String>>, String> {
+    let read_error = |err: XlsxError| format!("Unable to read the spreadsheet: {err}");
+    let name = xlsx
+        .sheet_names()
+        .first()
+        .cloned()
+        .ok_or_else(|| "The spreadsheet has no sheets.".to_string())?;
+    let mut cells = xlsx.worksheet_cells_reader(&name).map_err(read_error)?;
+    let mut table: Vec<Vec<String>> = Vec::new();
+    let mut filled = 0;
+    while let Some(cell) = cells.next_cell().map_err(read_error)? {
+        let text = cell_text(&Data::from(cell.get_value().clone()));
+        if text.is_empty() {
+            continue;
+        }
+        let (row, column) = cell.get_position();
+        let (row, column) = (row as usize, column as usize);
+        if table.len() <= row {
+            filled += 1;
+            if filled > MAX_TABLE_ROWS {
+                return Err(too_many_rows());
+            }
+            table.resize(row + 1, Vec::new());
+        }
+        let cells = &mut table[row];
+        if cells.len() <= column {
+            cells.resize(column + 1, String::new());
+        }
+        cells[column] = text;
+    }
+    let first_column = table
+        .iter()
+        .filter_map(|row| row.iter().position(|value| !value.is_empty()))
+        .min()
+        .unwrap_or(0);
+    for row in &mut table {
+        row.drain(..first_column.min(row.len()));
+    }
+    Ok(table)
+}
+
+fn too_many_rows() -> String {
+    format!("Imports are limited to {MAX_IMPORT_ROWS} rows per file.")
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(_) | Data::DateTimeIso(_) => cell
+            .as_datetime()
+            .map(|value| {
+                if value.time() == chrono::NaiveTime::MIN {
+                    value.format("%Y-%m-%d").to_string()
+                } else {
+                    value.format("%Y-%m-%d %H:%M").to_string()
+                }
+            })
+            .unwrap_or_else(|| cell.to_string()),
+        // Phone numbers and ids typed into Excel come back as whole floats.
+        Data::Float(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+            format!("{}", *value as i64)
+        }
+        _ => cell.to_string().trim().to_string(),
+    }
+}
+
+fn is_blank(row: &[String]) -> bool {
+    row.iter().all(|value| value.trim().is_empty())
+}
+
+type Table = Vec<Vec<String>>;
+
+fn split_header(mut table: Table) -> Result<(Vec<String>, usize, Table), String> {
+    while table.last().is_some_and(|row| is_blank(row)) {
+        table.pop();
+    }
+    let header_index = table
+        .iter()
+        .position(|row| !is_blank(row))
+        .ok_or_else(|| "The file is empty.".to_string())?;
+    let mut rows = table.split_off(header_index + 1);
+    let headers = table
+        .pop()
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(index, header)| {
+            if header.is_empty() {
+                format!("Column {}", index + 1)
+            } else {
+                header
+            }
+        })
+        .collect::<Vec<_>>();
+    if rows.is_empty() {
+        return Err("The file has a header row but no data rows.".to_string());
+    }
+    if rows.iter().filter(|row| !is_blank(row)).count() > MAX_IMPORT_ROWS {
+        return Err(too_many_rows());
+    }
+    for row in &mut rows {
+        row.truncate(headers.len());
+    }
+    Ok((headers, header_index + 1, rows))
+}
+
+fn normalize_header(value: &str) -> String {
+    value
+        .chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn guess_mapping(
+    entity: &str,
+    definitions: &[CustomFieldDefinition],
+    headers: &[String],
+) -> HashMap<String, usize> {
+    let normalized = headers.iter().map(|header| normalize_header(header)).collect::<Vec<_>>();
+    let mut candidates = targets(entity)
+        .iter()
+        .map(|target| {
+            let mut names = vec![normalize_header(target.key), normalize_header(target.label)];
+            names.extend(target.aliases.iter().map(|alias| alias.to_string()));
+            (target.key.to_string(), names)
+        })
+        .collect::<Vec<_>>();
+    candidates.extend(
+        definitions
+            .iter()
+            .map(|definition| (custom_key(definition), vec![normalize_header(&definition.label)])),
+    );
+
+    let mut mapping = HashMap::new();
+    for pass in 0..2 {
+        for (key, names) in &candidates {
+            if mapping.contains_key(key) {
+                continue;
+            }
+            let names = if pass == 0 { &names[..names.len().min(2)] } else { &names[..] };
+            let found = normalized.iter().enumerate().find(|(index, header)| {
+                !header.is_empty()
+                    && names.contains(header)
+                    && !mapping.values().any(|taken| taken == index)
+            });
+            if let Some((index, _)) = found {
+                mapping.insert(key.clone(), index);
+            }
+        }
+    }
+    mapping
+}
+
+pub async fn column_views(db: &Db, tenant_id: i64, import: &DataImport) -> Vec<ImportColumnView> {
+    let mut columns = targets(&import.entity)
+        .iter()
+        .map(|target| ImportColumnView {
+            key: target.key.to_string(),
+            label: target.label.to_string(),
+            is_required: target.is_required,
+            selected: import.mapping.get(target.key).copied(),
+        })
+        .collect::<Vec<_>>();
+    columns.extend(
+        custom_definitions(db, tenant_id, &import.entity)
+            .await
+            .into_iter()
+            .map(|definition| {
+                let key = custom_key(&definition);
+                ImportColumnView {
+                    selected: import.mapping.get(&key).copied(),
+                    key,
+                    label: definition.label,
+                    is_required: definition.is_required,
+                }
+            }),
+    );
+    columns
+}
+
+pub async fn save_mapping(
+    db: &Db,
+    tenant_id: i64,
+    import: &DataImport,
+    submitted: &HashMap<String, String>,
+) -> Result<(), String> {
+    if import.status != STATUS_DRAFT {
+        return Err("This import has already been committed.".to_string());
+    }
+    let known = column_views(db, tenant_id, import)
+        .await
+        .into_iter()
+        .map(|column| column.key)
+        .collect::<Vec<_>>();
+    let mut mapping = HashMap::<String, usize>::new();
+    for (key, value) in submitted {
+        let Ok(index) = value.trim().parse::<usize>() else {
+            continue;
+        };
+        if !known.contains(key) || index >= import.headers.len() {
+            continue;
+        }
+        if let Some((other, _)) = mapping.iter().find(|(_, taken)| **taken == index) {
+            let header = &import.headers[index];
+            let other_label = label_for(db, tenant_id, import, other).await;
+            let label = label_for(db, tenant_id, import, key).await;
+            return Err(format!(
+                "Column \"{header}\" is mapped to both {other_label} and {label}."
+            ));
+        }
+        mapping.insert(key.clone(), index);
+    }
+    let mapping_json = serde_json::to_string(&mapping).map_err(|err| err.to_string())?;
+    import_repo::update_mapping(db, tenant_id, import.id, &mapping_json)
+        .await
+        .map_err(|err| format!("Unable to save the column mapping: {err}"))
+}
+
+async fn label_for(db: &Db, tenant_id: i64, import: &DataImport, key: &str) -> String {
+    column_views(db, tenant_id, import)
+        .await
+        .into_iter()
+        .find(|column| column.key == key)
+        .map(|column| column.label)
+        .unwrap_or_else(|| key.to_string())
+}
+
+pub async fn missing_columns(db: &Db, tenant_id: i64, import: &DataImport) -> Vec<String> {
+    column_views(db, tenant_id, import)
+        .await
+        .into_iter()
+        .filter(|column| column.is_required && column.selected.is_none())
+        .map(|column| column.label)
+        .collect()
+}
+
+fn cell<'a>(import: &DataImport, row: &'a [String], key: &str) -> &'a str {
+    import
+        .mapping
+        .get(key)
+        .and_then(|index| row.get(*index))
+        .map(|value| value.trim())
+        .unwrap_or("")
+}
+
+fn custom_values(
+    import: &DataImport,
+    row: &[String],
+    definitions: &[CustomFieldDefinition],
+) -> Result<Vec<(i64, String)>, String> {
+    definitions
+        .iter()
+        .map(|definition| {
+            custom_field_service::normalize_value(definition, cell(import, row, &custom_key(definition)))
+                .map(|value| (definition.id, value))
+        })
+        .collect()
+}
+
+fn data_rows(import: &DataImport) -> impl Iterator<Item = (usize, &Vec<String>)> {
+    import
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(index, row)| (import.header_line + 1 + index, row))
+        .filter(|(_, row)| !is_blank(row))
+}
+
+fn row_at(import: &DataImport, line: usize) -> Option<&Vec<String>> {
+    line.checked_sub(import.header_line + 1)
+        .and_then(|index| import.rows.get(index))
+}
+
+fn ready(line: usize) -> CheckedRow {
+    CheckedRow { line, status: ROW_READY, message: String::new() }
+}
+
+fn duplicate(line: usize, message: String) -> CheckedRow {
+    CheckedRow { line, status: ROW_DUPLICATE, message }
+}
+
+fn invalid(line: usize, message: String) -> CheckedRow {
+    CheckedRow { line, status: ROW_ERROR, message }
+}
+
+async fn prepare(db: &Db, tenant_id: i64, import: &DataImport) -> Result<Prepared, String> {
+    match import.entity.as_str() {
+        ENTITY_CLIENTS => prepare_clients(db, tenant_id, import).await,
+        ENTITY_CONTACTS => prepare_contacts(db, tenant_id, import).await,
+        ENTITY_MEMBERS => prepare_members(db, tenant_id, import).await,
+        _ => Err("Unknown import type.".to_string()),
+    }
+}
+
+async fn prepare_clients(db: &Db, tenant_id: i64, import: &DataImport) -> Result<Prepared, String> {
+    let existing = client_repo::list_clients(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load clients: {err}"))?;
+    let definitions = custom_definitions(db, tenant_id, ENTITY_CLIENTS).await;
+    let (plan_key, limits) = workspace_service::plan_limits_for_tenant(db, tenant_id).await;
+    let stage_options = client_service::client_stage_options();
+
+    let mut names = HashMap::new();
+    let mut emails = HashMap::new();
+    for client in &existing {
+        names.insert(client.company_name.trim().to_lowercase(), format!("existing client {}", client.company_name));
+        if !client.email.trim().is_empty() {
+            emails.insert(client.email.trim().to_lowercase(), format!("existing client {}", client.company_name));
+        }
+    }
+    let mut total = existing.len() as i64;
+    let mut rows = Vec::new();
+    let mut records = Vec::new();
+    for (line, row) in data_rows(import) {
+        let company_name = cell(import, row, "company_name").to_string();
+        let email = cell(import, row, "email").to_string();
+        let raw_stage = cell(import, row, "stage");
+        let stage = if raw_stage.is_empty() {
+            Some(DEFAULT_STAGE)
+        } else {
+            stage_options.iter().copied().find(|option| option.eq_ignore_ascii_case(raw_stage))
+        };
+        let Some(stage) = stage else {
+            rows.push(invalid(line, format!("Stage must be one of: {}.", stage_options.join(", "))));
+            continue;
+        };
+        let currency = match cell(import, row, "currency") {
+            "" => DEFAULT_CURRENCY.to_string(),
+            value => value.to_uppercase(),
+        };
+        if let Err(message) = client_service::validate_client_fields(&company_name, stage, &currency) {
+            rows.push(invalid(line, message));
+            continue;
+        }
+        let custom = match custom_values(import, row, &definitions) {
+            Ok(custom) => custom,
+            Err(message) => {
+                rows.push(invalid(line, message));
+                continue;
+            }
+        };
+        let name_key = company_name.to_lowercase();
+        let email_key = email.to_lowercase();
+        if let Some(other) = names.get(&name_key) {
+            rows.push(duplicate(line, format!("Company name matches {other}.")));
+            continue;
+        }
+        if let Some(other) = emails.get(&email_key).filter(|_| !email_key.is_empty()) {
+            rows.push(duplicate(line, format!("Email matches {other}.")));
+            continue;
+        }
+        if let Some(limit) = limits.clients.filter(|limit| total >= *limit) {
+            let plan_name = workspace_service::plan_name(&plan_key);
+            rows.push(invalid(
+                line,
+                format!("{plan_name} plan workspaces can have up to {limit} clients. Upgrade to add more."),
+            ));
+            continue;
+        }
+        names.insert(name_key, format!("row {line}"));
+        if !email_key.is_empty() {
+            emails.insert(email_key, format!("row {line}"));
+        }
+        total += 1;
+        rows.push(ready(line));
+        records.push(ClientImportRecord {
+            company_name,
+            address: cell(import, row, "address").to_string(),
+            phone: cell(import, row, "phone").to_string(),
+            email,
+            latitude: cell(import, row, "latitude").to_string(),
+            longitude: cell(import, row, "longitude").to_string(),
+            stage: stage.to_string(),
+            currency,
+            portal_token: client_service::generate_portal_token(),
+            custom,
+        });
+    }
+    Ok(Prepared { rows, records: PreparedRecords::Clients(records) })
+}
+
+async fn prepare_contacts(db: &Db, tenant_id: i64, import: &DataImport) -> Result<Prepared, String> {
+    let clients = client_repo::list_clients(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load clients: {err}"))?;
+    let contacts = client_repo::list_all_contacts(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load contacts: {err}"))?;
+    let definitions = custom_definitions(db, tenant_id, ENTITY_CONTACTS).await;
+    let (plan_key, limits) = workspace_service::plan_limits_for_tenant(db, tenant_id).await;
+
+    let mut client_ids = HashMap::new();
+    for client in clients.iter().rev() {
+        client_ids
+            .entry(client.company_name.trim().to_lowercase())
+            .or_insert(client.id);
+    }
+    let mut counts = HashMap::<i64, i64>::new();
+    let mut emails = HashMap::new();
+    for contact in &contacts {
+        *counts.entry(contact.client_id).or_default() += 1;
+        if !contact.email.trim().is_empty() {
+            emails.insert(
+                (contact.client_id, contact.email.trim().to_lowercase()),
+                format!("existing contact {}", contact.name),
+            );
+        }
+    }
+    let mut rows = Vec::new();
+    let mut records = Vec::new();
+    for (line, row) in data_rows(import) {
+        let client_name = cell(import, row, "client");
+        let Some(client_id) = client_ids.get(&client_name.to_lowercase()).copied() else {
+            let message = if client_name.is_empty() {
+                "Client is required.".to_string()
+            } else {
+                format!("No client named \"{client_name}\".")
+            };
+            rows.push(invalid(line, message));
+            continue;
+        };
+        let name = cell(import, row, "name").to_string();
+        if let Err(message) = client_service::validate_contact_fields(&name) {
+            rows.push(invalid(line, message));
+            continue;
+        }
+        let custom = match custom_values(import, row, &definitions) {
+            Ok(custom) => custom,
+            Err(message) => {
+                rows.push(invalid(line, message));
+                continue;
+            }
+        };
+        let email = cell(import, row, "email").to_string();
+        let email_key = (client_id, email.to_lowercase());
+        if let Some(other) = emails.get(&email_key).filter(|_| !email.is_empty()) {
+            rows.push(duplicate(line, format!("Email matches {other} at {client_name}.")));
+            continue;
+        }
+        let count = counts.entry(client_id).or_default();
+        if let Some(limit) = limits.contacts_per_client.filter(|limit| *count >= *limit) {
+            let plan_name = workspace_service::plan_name(&plan_key);
+            rows.push(invalid(
+                line,
+                format!("{plan_name} plan workspaces can have up to {limit} contacts per client. Upgrade to add more."),
+            ));
+            continue;
+        }
+        *count += 1;
+        if !email.is_empty() {
+            emails.insert(email_key, format!("row {line}"));
+        }
+        rows.push(ready(line));
+        records.push(ContactImportRecord {
+            client_id,
+            name,
+            address: cell(import, row, "address").to_string(),
+            email,
+            phone: cell(import, row, "phone").to_string(),
+            department: cell(import, row, "department").to_string(),
+            position: cell(import, row, "position").to_string(),
+            custom,
+        });
+    }
+    Ok(Prepared { rows, records: PreparedRecords::Contacts(records) })
+}
+
+async fn prepare_members(db: &Db, tenant_id: i64, import: &DataImport) -> Result<Prepared, String> {
+    let crews = crew_repo::list_crews(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load crews: {err}"))?;
+    let users = user_repo::list_users_by_tenant(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load users: {err}"))?;
+    let (plan_key, limits) = workspace_service::plan_limits_for_tenant(db, tenant_id).await;
+    let definitions = custom_definitions(db, tenant_id, ENTITY_MEMBERS).await;
+
+    let mut crew_ids = HashMap::new();
+    let mut counts = HashMap::<i64, i64>::new();
+    let mut members = HashMap::new();
+    for crew in &crews {
+        crew_ids.entry(crew.name.trim().to_lowercase()).or_insert(crew.id);
+        let crew_members = crew_member_repo::list_members(db, tenant_id, crew.id)
+            .await
+            .map_err(|err| format!("Unable to load crew members: {err}"))?;
+        counts.insert(crew.id, crew_members.len() as i64);
+        for member in crew_members {
+            members.insert(
+                (crew.id, member.email.trim().to_lowercase()),
+                format!("existing member {}", member.name),
+            );
+        }
+    }
+    let user_ids = users
+        .iter()
+        .map(|user| (user.email.trim().to_lowercase(), user.id))
+        .collect::<HashMap<_, _>>();
+
+    let mut rows = Vec::new();
+    let mut records = Vec::new();
+    for (line, row) in data_rows(import) {
+        let crew_name = cell(import, row, "crew");
+        let Some(crew_id) = crew_ids.get(&crew_name.to_lowercase()).copied() else {
+            let message = if crew_name.is_empty() {
+                "Crew is required.".to_string()
+            } else {
+                format!("No crew named \"{crew_name}\".")
+            };
+            rows.push(invalid(line, message));
+            continue;
+        };
+        let name = cell(import, row, "name").to_string();
+        let phone = cell(import, row, "phone").to_string();
+        let hourly_cost = match cell(import, row, "hourly_cost") {
+            "" => None,
+            value => match value.trim_start_matches(['$', '€', '£']).parse::<f64>() {
+                Ok(cost) => Some(cost),
+                Err(_) => {
+                    rows.push(invalid(line, "Hourly cost must be a number.".to_string()));
+                    continue;
+                }
+            },
+        };
+        if let Err(message) = crew_service::validate_member_fields(&name, &phone, hourly_cost) {
+            rows.push(invalid(line, message));
+            continue;
+        }
+        let custom = match custom_values(import, row, &definitions) {
+            Ok(custom) => custom,
+            Err(message) => {
+                rows.push(invalid(line, message));
+                continue;
+            }
+        };
+        let email = cell(import, row, "email").to_lowercase();
+        if email.is_empty() {
+            rows.push(invalid(line, "User account is required.".to_string()));
+            continue;
+        }
+        let Some(user_id) = user_ids.get(&email).copied() else {
+            rows.push(invalid(line, format!("No workspace user with email {email}.")));
+            continue;
+        };
+        let member_key = (crew_id, email.clone());
+        if let Some(other) = members.get(&member_key) {
+            rows.push(duplicate(line, format!("Email matches {other} of {crew_name}.")));
+            continue;
+        }
+        let count = counts.entry(crew_id).or_default();
+        if let Some(limit) = limits.members_per_crew.filter(|limit| *count >= *limit) {
+            let plan_name = workspace_service::plan_name(&plan_key);
+            rows.push(invalid(
+                line,
+                format!("{plan_name} plan workspaces can have up to {limit} members per crew. Upgrade to add more."),
+            ));
+            continue;
+        }
+        *count += 1;
+        members.insert(member_key, format!("row {line}"));
+        rows.push(ready(line));
+        records.push(CrewMemberImportRecord {
+            crew_id,
+            user_id,
+            name,
+            phone,
+            email,
+            position: cell(import, row, "position").to_string(),
+            availability_status: crew_service::normalize_availability(
+                cell(import, row, "availability_status").to_string(),
+            ),
+            hourly_cost: hourly_cost.unwrap_or(0.0),
+            custom,
+        });
+    }
+    Ok(Prepared { rows, records: PreparedRecords::Members(records) })
+}
+
+pub async fn preview(db: &Db, tenant_id: i64, import: &DataImport) -> Result<ImportPreviewView, String> {
+    let prepared = prepare(db, tenant_id, import).await?;
+    let mapped = column_views(db, tenant_id, import)
+        .await
+        .into_iter()
+        .filter_map(|column| column.selected.map(|index| (column.label, index)))
+        .collect::<Vec<_>>();
+    let count = |status: &str| prepared.rows.iter().filter(|row| row.status == status).count();
+    let (ready, duplicates, errors) = (count(ROW_READY), count(ROW_DUPLICATE), count(ROW_ERROR));
+    let rows = prepared
+        .rows
+        .iter()
+        .take(PREVIEW_ROWS)
+        .map(|checked| {
+            let row = row_at(import, checked.line);
+            ImportRowView {
+                line: checked.line,
+                status: checked.status.to_string(),
+                message: checked.message.clone(),
+                values: mapped
+                    .iter()
+                    .map(|(_, index)| {
+                        row.and_then(|row| row.get(*index)).cloned().unwrap_or_default()
+                    })
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    Ok(ImportPreviewView {
+        columns: mapped.into_iter().map(|(label, _)| label).collect(),
+        shown: rows.len(),
+        rows,
+        total: prepared.rows.len(),
+        ready,
+        duplicates,
+        errors,
+    })
+}
+
+pub async fn commit_import(db: &Db, tenant_id: i64, import: &DataImport) -> Result<i64, String> {
+    if import.status != STATUS_DRAFT {
+        return Err("This import has already been committed.".to_string());
+    }
+    let missing = missing_columns(db, tenant_id, import).await;
+    if !missing.is_empty() {
+        return Err(format!("Map a column to {} before importing.", missing.join(", ")));
+    }
+    let prepared = prepare(db, tenant_id, import).await?;
+    let skipped = prepared
+        .rows
+        .iter()
+        .filter(|row| row.status != ROW_READY)
+        .collect::<Vec<_>>();
+    let error_report = if skipped.is_empty() {
+        String::new()
+    } else {
+        error_report(import, &skipped)
+    };
+    if skipped.len() == prepared.rows.len() {
+        return Err("There are no valid rows to import.".to_string());
+    }
+    let skipped_count = skipped.len() as i64;
+    let (imported, committed) = match &prepared.records {
+        PreparedRecords::Clients(records) => (
+            records.len(),
+            import_repo::commit_clients(db, tenant_id, import.id, records, skipped_count, &error_report).await,
+        ),
+        PreparedRecords::Contacts(records) => (
+            records.len(),
+            import_repo::commit_contacts(db, tenant_id, import.id, records, skipped_count, &error_report).await,
+        ),
+        PreparedRecords::Members(records) => (
+            records.len(),
+            import_repo::commit_members(db, tenant_id, import.id, records, skipped_count, &error_report).await,
+        ),
+    };
+    match committed {
+        Ok(true) => Ok(imported as i64),
+        Ok(false) => Err("This import has already been committed.".to_string()),
+        Err(err) => Err(format!("Import failed and nothing was saved: {err}")),
+    }
+}
+
+fn error_report(import: &DataImport, skipped: &[&CheckedRow]) -> String {
+    let mut headers = vec!["row".to_string(), "status".to_string(), "reason".to_string()];
+    headers.extend(import.headers.iter().cloned());
+    let mut csv = headers.iter().map(|header| csv_field(header)).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+    for checked in skipped {
+        let mut row = vec![checked.line.to_string(), checked.status.to_string(), checked.message.clone()];
+        if let Some(values) = row_at(import, checked.line) {
+            row.extend(values.iter().cloned());
+        }
+        csv.push_str(&row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    use super::*;
+
+    fn xlsx(cells: &[(usize, usize, &str)]) -> Vec<u8> {
+        let mut rows = String::new();
+        let mut current = None;
+        for (row, column, value) in cells {
+            if current != Some(*row) {
+                if current.is_some() {
+                    rows.push_str("</row>");
+                }
+                rows.push_str(&format!(r#"<row r="{}">"#, row + 1));
+                current = Some(*row);
+            }
+            let reference = format!("{}{}", (b'A' + *column as u8) as char, row + 1);
+            rows.push_str(&format!(r#"<c r="{reference}" t="inlineStr"><is><t>{value}</t></is></c>"#));
+        }
+        if current.is_some() {
+            rows.push_str("</row>");
+        }
+        let files = [
+            (
+                "[Content_Types].xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#.to_string(),
+            ),
+            (
+                "_rels/.rels",
+                r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#.to_string(),
+            ),
+            (
+                "xl/workbook.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#.to_string(),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#.to_string(),
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>{rows}</sheetData></worksheet>"#
+                ),
+            ),
+        ];
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn csv_line_numbers_count_blank_and_wrapped_lines() {
+        let table = parse_csv(b"\r\n\r\nname,notes\r\nAcme,\"two\nlines\"\r\n\r\nGlobex,\r\n").unwrap();
+        let (headers, header_line, rows) = split_header(table).unwrap();
+        let import = DataImport {
+            id: 1,
+            entity: ENTITY_CLIENTS.to_string(),
+            file_name: "clients.csv".to_string(),
+            headers,
+            header_line,
+            rows,
+            mapping: HashMap::new(),
+            status: STATUS_DRAFT.to_string(),
+            imported_count: 0,
+            skipped_count: 0,
+            error_report: String::new(),
+            created_at: String::new(),
+            completed_at: None,
+        };
+
+        assert_eq!(header_line, 3);
+        let lines = data_rows(&import).map(|(line, row)| (line, row[0].clone())).collect::<Vec<_>>();
+        assert_eq!(lines, vec![(4, "Acme".to_string()), (7, "Globex".to_string())]);
+        assert_eq!(row_at(&import, 7).map(|row| row[0].as_str()), Some("Globex"));
+    }
+
+    #[test]
+    fn xlsx_rows_keep_their_sheet_position() {
+        let table = parse_workbook(xlsx(&[
+            (2, 1, "name"),
+            (2, 2, "email"),
+            (3, 1, "Acme"),
+            (3, 2, "a@example.com"),
+        ]))
+        .unwrap();
+        let (headers, header_line, rows) = split_header(table).unwrap();
+
+        assert_eq!(headers, vec!["name".to_string(), "email".to_string()]);
+        assert_eq!(header_line, 3);
+        assert_eq!(rows, vec![vec!["Acme".to_string(), "a@example.com".to_string()]]);
+    }
+
+    #[test]
+    fn reading_stops_past_the_row_cap() {
+        let mut csv = "name\n".to_string();
+        for index in 0..MAX_IMPORT_ROWS {
+            csv.push_str(&format!("Client {index}\n"));
+        }
+        assert!(parse_csv(csv.as_bytes()).is_ok());
+        csv.push_str("One too many\n");
+        assert_eq!(parse_csv(csv.as_bytes()).unwrap_err(), too_many_rows());
+
+        let cells = (0..=MAX_TABLE_ROWS).map(|row| (row, 0, "x")).collect::<Vec<_>>();
+        assert_eq!(parse_workbook(xlsx(&cells)).unwrap_err(), too_many_rows());
+    }
+}
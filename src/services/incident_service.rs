@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use rocket_db_pools::sqlx;
+
+use crate::models::{DeploymentIncident, DeploymentIncidentForm, IncidentUpdateForm};
+use crate::repositories::{incident_repo, user_repo};
+use crate::services::email_service;
+use crate::services::utils::escape_html;
+use crate::Db;
+
+pub const STATUS_OPEN: &str = "Open";
+pub const STATUS_INVESTIGATING: &str = "Investigating";
+pub const STATUS_RESOLVED: &str = "Resolved";
+
+const MAX_TITLE_LENGTH: usize = 200;
+
+pub fn severity_options() -> [&'static str; 4] {
+    ["Low", "Medium", "High", "Critical"]
+}
+
+pub fn category_options() -> [&'static str; 4] {
+    ["Safety", "Equipment", "Client", "Quality"]
+}
+
+pub fn status_options() -> [&'static str; 3] {
+    [STATUS_OPEN, STATUS_INVESTIGATING, STATUS_RESOLVED]
+}
+
+pub async fn list_incidents_by_deployment(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentIncident>, sqlx::Error> {
+    incident_repo::list_incidents_by_deployment(db, tenant_id, deployment_id).await
+}
+
+pub async fn list_incidents(
+    db: &Db,
+    tenant_id: i64,
+    status: Option<&str>,
+) -> Result<Vec<DeploymentIncident>, sqlx::Error> {
+    incident_repo::list_incidents(db, tenant_id, status).await
+}
+
+pub async fn find_incident_by_id(
+    db: &Db,
+    tenant_id: i64,
+    incident_id: i64,
+) -> Result<Option<DeploymentIncident>, sqlx::Error> {
+    incident_repo::find_incident_by_id(db, tenant_id, incident_id).await
+}
+
+pub async fn count_unresolved_incidents(db: &Db, tenant_id: i64) -> i64 {
+    let open = incident_repo::count_incidents_by_status(db, tenant_id, STATUS_OPEN)
+        .await
+        .unwrap_or(0);
+    let investigating =
+        incident_repo::count_incidents_by_status(db, tenant_id, STATUS_INVESTIGATING)
+            .await
+            .unwrap_or(0);
+    open + investigating
+}
+
+pub async fn incident_counts_map(
+    db: &Db,
+    tenant_id: i64,
+    deployment_ids: &[i64],
+) -> HashMap<i64, (i64, i64)> {
+    incident_repo::incident_counts_for_deployments(db, tenant_id, deployment_ids)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(deployment_id, total, open)| (deployment_id, (total, open)))
+        .collect()
+}
+
+pub async fn count_completed_with_incidents(db: &Db, tenant_id: i64) -> Result<i64, sqlx::Error> {
+    incident_repo::count_completed_with_incidents(db, tenant_id).await
+}
+
+pub async fn create_incident(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    form: DeploymentIncidentForm,
+) -> Result<(), String> {
+    let title = validate_title(&form.title)?;
+    let severity = pick_option(&severity_options(), &form.severity)
+        .ok_or_else(|| "Select an incident severity.".to_string())?;
+    let category = pick_option(&category_options(), &form.category)
+        .ok_or_else(|| "Select an incident category.".to_string())?;
+    let occurred_at = if form.occurred_at.trim().is_empty() {
+        chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()
+    } else {
+        parse_occurred_at(&form.occurred_at)
+            .ok_or_else(|| "Enter when the incident happened.".to_string())?
+    };
+    let assignee_user_id = validate_assignee(db, tenant_id, form.assignee_user_id).await?;
+
+    let incident_id = incident_repo::create_incident(
+        db,
+        tenant_id,
+        deployment_id,
+        user_id,
+        &title,
+        form.description.trim(),
+        severity,
+        category,
+        &occurred_at,
+        assignee_user_id,
+    )
+    .await
+    .map_err(|err| format!("Unable to report incident: {err}"))?;
+
+    if let Some(assignee_id) = assignee_user_id.filter(|id| *id != user_id)
+        && let Ok(Some(incident)) = find_incident_by_id(db, tenant_id, incident_id).await
+    {
+        notify_assignee(db, &incident, assignee_id).await;
+    }
+    Ok(())
+}
+
+pub async fn update_incident(
+    db: &Db,
+    tenant_id: i64,
+    incident: &DeploymentIncident,
+    user_id: i64,
+    form: IncidentUpdateForm,
+) -> Result<(), String> {
+    let title = validate_title(&form.title)?;
+    let severity = pick_option(&severity_options(), &form.severity)
+        .ok_or_else(|| "Select an incident severity.".to_string())?;
+    let category = pick_option(&category_options(), &form.category)
+        .ok_or_else(|| "Select an incident category.".to_string())?;
+    let status = pick_option(&status_options(), &form.status)
+        .ok_or_else(|| "Select an incident status.".to_string())?;
+    let assignee_user_id = validate_assignee(db, tenant_id, form.assignee_user_id).await?;
+    let resolution_notes = form.resolution_notes.trim();
+    if status == STATUS_RESOLVED && resolution_notes.is_empty() {
+        return Err("Add resolution notes before marking the incident resolved.".to_string());
+    }
+    let resolved_at = if status == STATUS_RESOLVED {
+        Some(incident.resolved_at.clone().unwrap_or_else(|| {
+            chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()
+        }))
+    } else {
+        None
+    };
+
+    incident_repo::update_incident(
+        db,
+        tenant_id,
+        incident.id,
+        &title,
+        form.description.trim(),
+        severity,
+        category,
+        status,
+        assignee_user_id,
+        resolution_notes,
+        resolved_at.as_deref(),
+    )
+    .await
+    .map_err(|err| format!("Unable to update incident: {err}"))?;
+
+    if let Some(assignee_id) = assignee_user_id
+        .filter(|id| *id != user_id && incident.assignee_user_id != Some(*id))
+        && let Ok(Some(incident)) = find_incident_by_id(db, tenant_id, incident.id).await
+    {
+        notify_assignee(db, &incident, assignee_id).await;
+    }
+    Ok(())
+}
+
+pub async fn delete_incident(db: &Db, tenant_id: i64, incident_id: i64) -> Result<(), String> {
+    incident_repo::delete_incident(db, tenant_id, incident_id)
+        .await
+        .map_err(|err| format!("Unable to delete incident: {err}"))
+}
+
+fn validate_title(value: &str) -> Result<String, String> {
+    let title = value.trim();
+    if title.is_empty() {
+        return Err("Incident title is required.".to_string());
+    }
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(format!(
+            "Incident title must be {MAX_TITLE_LENGTH} characters or fewer."
+        ));
+    }
+    Ok(title.to_string())
+}
+
+fn pick_option(options: &[&'static str], value: &str) -> Option<&'static str> {
+    options
+        .iter()
+        .copied()
+        .find(|option| option.eq_ignore_ascii_case(value.trim()))
+}
+
+fn parse_occurred_at(value: &str) -> Option<String> {
+    let value = value.trim();
+    ["%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|parsed| parsed.format("%Y-%m-%d %H:%M").to_string())
+}
+
+async fn validate_assignee(
+    db: &Db,
+    tenant_id: i64,
+    assignee_user_id: Option<i64>,
+) -> Result<Option<i64>, String> {
+    let Some(assignee_id) = assignee_user_id.filter(|id| *id > 0) else {
+        return Ok(None);
+    };
+    let users = user_repo::list_users_by_tenant(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load users: {err}"))?;
+    if users.iter().any(|user| user.id == assignee_id) {
+        Ok(Some(assignee_id))
+    } else {
+        Err("Select a valid assignee.".to_string())
+    }
+}
+
+async fn notify_assignee(db: &Db, incident: &DeploymentIncident, assignee_id: i64) {
+    if incident.assignee_user_id != Some(assignee_id) {
+        return;
+    }
+    let Some(email) = incident
+        .assignee_email
+        .as_deref()
+        .filter(|email| !email.trim().is_empty())
+    else {
+        return;
+    };
+    let body = format!(
+        "<p>You have been assigned a {} {} incident on deployment #{} ({}).</p>\
+         <p><strong>{}</strong></p><p>{}</p>\
+         <p>Open Tracking to investigate and record the resolution.</p>",
+        escape_html(&incident.severity).to_lowercase(),
+        escape_html(&incident.category).to_lowercase(),
+        incident.deployment_id,
+        escape_html(&incident.client_name),
+        escape_html(&incident.title),
+        escape_html(&incident.description)
+    );
+    let _ = email_service::queue_email(
+        db,
+        incident.tenant_id,
+        None,
+        None,
+        email.to_string(),
+        Vec::new(),
+        format!("Incident assigned: {}", incident.title),
+        body,
+    )
+    .await;
+}
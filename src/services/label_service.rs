@@ -0,0 +1,405 @@
+use hmac::{Hmac, Mac};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+use qrcode::{Color, EcLevel, QrCode};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+
+use crate::models::{Asset, LabelItem};
+use crate::repositories::{asset_repo, deployment_repo, tenant_repo};
+use crate::services::deployment_service;
+use crate::services::utils::escape_html;
+use crate::Db;
+
+pub const FORMAT_SVG: &str = "svg";
+pub const FORMAT_PDF: &str = "pdf";
+
+// A4 sheet of 21 labels (3 x 7, 63.5 x 38.1 mm), in millimetres.
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const COLUMNS: usize = 3;
+const ROWS: usize = 7;
+const LABEL_WIDTH: f32 = 63.5;
+const LABEL_HEIGHT: f32 = 38.1;
+const COLUMN_PITCH: f32 = 66.04;
+const MARGIN_LEFT: f32 = 7.2;
+const MARGIN_TOP: f32 = 15.15;
+const QR_SIZE: f32 = 32.0;
+const QR_QUIET_MODULES: usize = 2;
+const TEXT_OFFSET: f32 = 36.5;
+const TITLE_SIZE: f32 = 3.2;
+const LINE_SIZE: f32 = 2.4;
+const TITLE_CHARS: usize = 15;
+const LINE_CHARS: usize = 19;
+const SIGNATURE_BYTES: usize = 16;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub fn format_options() -> [&'static str; 2] {
+    [FORMAT_PDF, FORMAT_SVG]
+}
+
+pub fn normalize_format(input: Option<&str>) -> &'static str {
+    match input {
+        Some(value) if value.eq_ignore_ascii_case(FORMAT_SVG) => FORMAT_SVG,
+        _ => FORMAT_PDF,
+    }
+}
+
+async fn label_secret(db: &Db, tenant_id: i64) -> Result<String, String> {
+    let existing = tenant_repo::find_label_secret(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load label key: {err}"))?;
+    if !existing.is_empty() {
+        return Ok(existing);
+    }
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let secret = bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    tenant_repo::init_label_secret(db, tenant_id, &secret)
+        .await
+        .map_err(|err| format!("Unable to save label key: {err}"))?;
+    tenant_repo::find_label_secret(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load label key: {err}"))
+}
+
+fn asset_mac(secret: &str, asset_id: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("asset:{asset_id}").as_bytes());
+    mac
+}
+
+pub async fn asset_signature(db: &Db, tenant_id: i64, asset_id: i64) -> Result<String, String> {
+    let secret = label_secret(db, tenant_id).await?;
+    let digest = asset_mac(&secret, asset_id).finalize().into_bytes();
+    Ok(digest[..SIGNATURE_BYTES]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+pub async fn verify_asset_signature(db: &Db, tenant_id: i64, asset_id: i64, signature: &str) -> bool {
+    let Ok(secret) = label_secret(db, tenant_id).await else {
+        return false;
+    };
+    if signature.len() != SIGNATURE_BYTES * 2 {
+        return false;
+    }
+    let Some(bytes) = (0..signature.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(signature.get(index..index + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()
+    else {
+        return false;
+    };
+    asset_mac(&secret, asset_id).verify_truncated_left(&bytes).is_ok()
+}
+
+pub fn asset_scan_path(slug: &str, asset_id: i64, signature: &str) -> String {
+    format!("/{slug}/scan/assets/{asset_id}/{signature}")
+}
+
+pub async fn asset_labels(
+    db: &Db,
+    tenant_id: i64,
+    origin: &str,
+    slug: &str,
+    ids: &[i64],
+) -> Result<Vec<LabelItem>, String> {
+    let assets = asset_repo::list_assets(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load assets: {err}"))?
+        .into_iter()
+        .filter(|asset| ids.is_empty() || ids.contains(&asset.id))
+        .collect::<Vec<Asset>>();
+    let mut labels = Vec::with_capacity(assets.len());
+    for asset in assets {
+        let signature = asset_signature(db, tenant_id, asset.id).await?;
+        let mut lines = vec![asset.asset_type.clone()];
+        if !asset.serial_number.is_empty() {
+            lines.push(format!("S/N {}", asset.serial_number));
+        }
+        lines.push(format!("Asset #{}", asset.id));
+        labels.push(LabelItem {
+            id: asset.id,
+            title: asset.name,
+            lines,
+            url: format!("{origin}{}", asset_scan_path(slug, asset.id, &signature)),
+        });
+    }
+    Ok(labels)
+}
+
+pub async fn deployment_options(db: &Db, tenant_id: i64) -> Vec<LabelItem> {
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let mut rows = deployment_repo::list_deployments_with_names(db, tenant_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|row| {
+            row.status != deployment_service::STATUS_CANCELLED
+                && row.status != deployment_service::STATUS_COMPLETED
+                && row.end_at >= now
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.start_at.cmp(&b.start_at));
+    rows.into_iter()
+        .map(|row| LabelItem {
+            id: row.id,
+            title: row.client_name,
+            lines: vec![
+                row.crew_name,
+                row.start_at.clone(),
+                format!("Deployment #{}", row.id),
+            ],
+            url: String::new(),
+        })
+        .collect()
+}
+
+pub async fn deployment_labels(
+    db: &Db,
+    tenant_id: i64,
+    origin: &str,
+    slug: &str,
+    ids: &[i64],
+) -> Result<Vec<LabelItem>, String> {
+    let rows = deployment_repo::list_deployments_with_names(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load deployments: {err}"))?;
+    let selected = if ids.is_empty() {
+        deployment_options(db, tenant_id)
+            .await
+            .into_iter()
+            .map(|item| item.id)
+            .collect::<Vec<_>>()
+    } else {
+        ids.to_vec()
+    };
+    Ok(rows
+        .into_iter()
+        .filter(|row| selected.contains(&row.id))
+        .map(|row| LabelItem {
+            id: row.id,
+            title: row.client_name,
+            lines: vec![
+                row.crew_name,
+                row.start_at,
+                format!("Deployment #{}", row.id),
+            ],
+            url: format!("{origin}/{slug}/tracking?deployment_id={}", row.id),
+        })
+        .collect())
+}
+
+fn qr_modules(url: &str) -> Option<(usize, Vec<bool>)> {
+    let code = QrCode::with_error_correction_level(url.as_bytes(), EcLevel::M).ok()?;
+    let width = code.width();
+    let dark = code
+        .to_colors()
+        .into_iter()
+        .map(|color| color == Color::Dark)
+        .collect();
+    Some((width, dark))
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    if value.chars().count() <= max_chars {
+        value.to_string()
+    } else {
+        let mut shortened = value.chars().take(max_chars - 1).collect::<String>();
+        shortened.push('…');
+        shortened
+    }
+}
+
+fn title_lines(title: &str) -> Vec<String> {
+    if title.chars().count() <= TITLE_CHARS {
+        return vec![title.to_string()];
+    }
+    let split = title
+        .char_indices()
+        .rev()
+        .find(|(index, ch)| *ch == ' ' && title[..*index].chars().count() <= TITLE_CHARS)
+        .map(|(index, _)| index);
+    match split {
+        Some(index) => vec![
+            title[..index].to_string(),
+            truncate(title[index + 1..].trim(), TITLE_CHARS),
+        ],
+        None => vec![truncate(title, TITLE_CHARS)],
+    }
+}
+
+fn slot_origin(slot: usize) -> (f32, f32) {
+    let column = slot % COLUMNS;
+    let row = (slot / COLUMNS) % ROWS;
+    (
+        MARGIN_LEFT + column as f32 * COLUMN_PITCH,
+        MARGIN_TOP + row as f32 * LABEL_HEIGHT,
+    )
+}
+
+fn page_count(items: &[LabelItem]) -> usize {
+    items.len().div_ceil(COLUMNS * ROWS).max(1)
+}
+
+pub fn render_svg(items: &[LabelItem]) -> String {
+    let pages = page_count(items);
+    let height = PAGE_HEIGHT * pages as f32;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{PAGE_WIDTH}mm\" height=\"{height}mm\" viewBox=\"0 0 {PAGE_WIDTH} {height}\" font-family=\"Helvetica, Arial, sans-serif\">\n"
+    );
+    for (index, item) in items.iter().enumerate() {
+        let (x, y) = slot_origin(index);
+        let y = y + (index / (COLUMNS * ROWS)) as f32 * PAGE_HEIGHT;
+        svg.push_str(&format!(
+            "<rect x=\"{x:.2}\" y=\"{y:.2}\" width=\"{LABEL_WIDTH}\" height=\"{LABEL_HEIGHT}\" rx=\"2\" fill=\"none\" stroke=\"#d0d0d0\" stroke-width=\"0.2\"/>\n"
+        ));
+        if let Some((width, dark)) = qr_modules(&item.url) {
+            let module = QR_SIZE / (width + QR_QUIET_MODULES * 2) as f32;
+            let qr_x = x + 1.5 + QR_QUIET_MODULES as f32 * module;
+            let qr_y = y + (LABEL_HEIGHT - QR_SIZE) / 2.0 + QR_QUIET_MODULES as f32 * module;
+            let mut path = String::new();
+            for (position, is_dark) in dark.iter().enumerate() {
+                if *is_dark {
+                    path.push_str(&format!(
+                        "M{:.3} {:.3}h{module:.3}v{module:.3}h-{module:.3}z",
+                        qr_x + (position % width) as f32 * module,
+                        qr_y + (position / width) as f32 * module,
+                    ));
+                }
+            }
+            svg.push_str(&format!("<path d=\"{path}\" fill=\"#000\"/>\n"));
+        }
+        let mut text_y = y + 8.0;
+        for line in title_lines(&item.title) {
+            svg.push_str(&format!(
+                "<text x=\"{:.2}\" y=\"{text_y:.2}\" font-size=\"{TITLE_SIZE}\" font-weight=\"bold\">{}</text>\n",
+                x + TEXT_OFFSET,
+                escape_html(&line)
+            ));
+            text_y += TITLE_SIZE + 0.8;
+        }
+        text_y += 1.0;
+        for line in &item.lines {
+            svg.push_str(&format!(
+                "<text x=\"{:.2}\" y=\"{text_y:.2}\" font-size=\"{LINE_SIZE}\">{}</text>\n",
+                x + TEXT_OFFSET,
+                escape_html(&truncate(line, LINE_CHARS))
+            ));
+            text_y += LINE_SIZE + 1.0;
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn mm(value: f32) -> f32 {
+    value * 72.0 / 25.4
+}
+
+fn pdf_text(value: &str) -> Vec<u8> {
+    value
+        .chars()
+        .map(|ch| match ch {
+            '…' => 0x85,
+            ch if (ch as u32) < 0x100 => ch as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+pub fn render_pdf(items: &[LabelItem]) -> Vec<u8> {
+    let pages = page_count(items);
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let regular_font_id = Ref::new(3);
+    let bold_font_id = Ref::new(4);
+    let regular_font = Name(b"F1");
+    let bold_font = Name(b"F2");
+    let page_ids = (0..pages)
+        .map(|page| Ref::new(5 + page as i32 * 2))
+        .collect::<Vec<_>>();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(pages as i32);
+    pdf.type1_font(regular_font_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_font_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    for (page, page_id) in page_ids.iter().enumerate() {
+        let content_id = Ref::new(page_id.get() + 1);
+        let mut content = Content::new();
+        let start = page * COLUMNS * ROWS;
+        let end = (start + COLUMNS * ROWS).min(items.len());
+        for (slot, item) in items[start..end].iter().enumerate() {
+            let (x, y) = slot_origin(slot);
+            let bottom = PAGE_HEIGHT - y - LABEL_HEIGHT;
+            content.set_stroke_gray(0.8);
+            content.set_line_width(0.5);
+            content.rect(mm(x), mm(bottom), mm(LABEL_WIDTH), mm(LABEL_HEIGHT));
+            content.stroke();
+
+            if let Some((width, dark)) = qr_modules(&item.url) {
+                let module = QR_SIZE / (width + QR_QUIET_MODULES * 2) as f32;
+                let qr_left = x + 1.5 + QR_QUIET_MODULES as f32 * module;
+                let qr_top = y + (LABEL_HEIGHT - QR_SIZE) / 2.0 + QR_QUIET_MODULES as f32 * module;
+                content.set_fill_gray(0.0);
+                for (position, is_dark) in dark.iter().enumerate() {
+                    if *is_dark {
+                        let module_x = qr_left + (position % width) as f32 * module;
+                        let module_y = qr_top + (position / width) as f32 * module;
+                        content.rect(
+                            mm(module_x),
+                            mm(PAGE_HEIGHT - module_y - module),
+                            mm(module),
+                            mm(module),
+                        );
+                    }
+                }
+                content.fill_nonzero();
+            }
+
+            let mut text_y = y + 8.0;
+            for line in title_lines(&item.title) {
+                content.begin_text();
+                content.set_font(bold_font, mm(TITLE_SIZE));
+                content.next_line(mm(x + TEXT_OFFSET), mm(PAGE_HEIGHT - text_y));
+                content.show(Str(&pdf_text(&line)));
+                content.end_text();
+                text_y += TITLE_SIZE + 0.8;
+            }
+            text_y += 1.0;
+            for line in &item.lines {
+                content.begin_text();
+                content.set_font(regular_font, mm(LINE_SIZE));
+                content.next_line(mm(x + TEXT_OFFSET), mm(PAGE_HEIGHT - text_y));
+                content.show(Str(&pdf_text(&truncate(line, LINE_CHARS))));
+                content.end_text();
+                text_y += LINE_SIZE + 1.0;
+            }
+        }
+
+        let mut page_writer = pdf.page(*page_id);
+        page_writer
+            .media_box(Rect::new(0.0, 0.0, mm(PAGE_WIDTH), mm(PAGE_HEIGHT)))
+            .parent(page_tree_id)
+            .contents(content_id);
+        let mut resources = page_writer.resources();
+        let mut fonts = resources.fonts();
+        fonts.pair(regular_font, regular_font_id);
+        fonts.pair(bold_font, bold_font_id);
+        fonts.finish();
+        resources.finish();
+        page_writer.finish();
+        pdf.stream(content_id, &content.finish());
+    }
+    pdf.finish()
+}
@@ -0,0 +1,554 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use rocket_db_pools::sqlx;
+
+use crate::models::{
+    Asset,
+    MaintenanceLog,
+    MaintenanceLogForm,
+    MaintenancePlan,
+    MaintenancePlanForm,
+    MaintenancePlanFormView,
+    MaintenanceStatusView,
+};
+use crate::repositories::{asset_repo, maintenance_repo, user_repo};
+use crate::services::{access_service, asset_service, deployment_service, email_service, gear_service};
+use crate::services::utils::escape_html;
+use crate::Db;
+
+pub struct MaintenancePlanError {
+    pub message: String,
+    pub form: MaintenancePlanFormView,
+}
+
+pub const STATE_OK: &str = "OK";
+pub const STATE_DUE: &str = "Due";
+pub const STATE_OVERDUE: &str = "Overdue";
+const DUE_SOON_DAYS: i64 = 7;
+const DUE_SOON_USAGE: f64 = 0.9;
+const MAX_INTERVAL_DAYS: i64 = 3650;
+const MAX_INTERVAL_HOURS: f64 = 100_000.0;
+
+pub fn empty_plan_form_view() -> MaintenancePlanFormView {
+    MaintenancePlanFormView {
+        asset_type: String::new(),
+        name: String::new(),
+        interval_days: String::new(),
+        interval_hours: String::new(),
+    }
+}
+
+pub async fn list_plans(db: &Db, tenant_id: i64) -> Result<Vec<MaintenancePlan>, sqlx::Error> {
+    maintenance_repo::list_plans(db, tenant_id).await
+}
+
+pub async fn plans_for_asset(db: &Db, tenant_id: i64, asset: &Asset) -> Vec<MaintenancePlan> {
+    maintenance_repo::list_plans(db, tenant_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|plan| plan.asset_type.eq_ignore_ascii_case(&asset.asset_type))
+        .collect()
+}
+
+pub async fn list_logs(
+    db: &Db,
+    tenant_id: i64,
+    asset_id: i64,
+) -> Result<Vec<MaintenanceLog>, sqlx::Error> {
+    maintenance_repo::list_logs_for_asset(db, tenant_id, asset_id).await
+}
+
+fn interval_label(plan: &MaintenancePlan) -> String {
+    let days = plan.interval_days.map(|days| format!("{days} days"));
+    let hours = plan
+        .interval_hours
+        .map(|hours| format!("{} usage hours", format_hours(hours)));
+    match (days, hours) {
+        (Some(days), Some(hours)) => format!("Every {days} or {hours}"),
+        (Some(days), None) => format!("Every {days}"),
+        (None, Some(hours)) => format!("Every {hours}"),
+        (None, None) => "No interval".to_string(),
+    }
+}
+
+pub async fn create_plan(
+    db: &Db,
+    tenant_id: i64,
+    form: MaintenancePlanForm,
+) -> Result<(), MaintenancePlanError> {
+    let view = MaintenancePlanFormView {
+        asset_type: asset_service::type_options()
+            .iter()
+            .find(|option| option.eq_ignore_ascii_case(form.asset_type.trim()))
+            .map(|option| option.to_string())
+            .unwrap_or_else(|| form.asset_type.trim().to_string()),
+        name: form.name.trim().to_string(),
+        interval_days: form.interval_days.map(|days| days.to_string()).unwrap_or_default(),
+        interval_hours: form
+            .interval_hours
+            .map(format_hours)
+            .unwrap_or_default(),
+    };
+    let fail = |message: &str, view: MaintenancePlanFormView| MaintenancePlanError {
+        message: message.to_string(),
+        form: view,
+    };
+    if view.asset_type.is_empty() {
+        return Err(fail("Choose the asset type this plan applies to.", view));
+    }
+    if view.name.is_empty() {
+        return Err(fail("Describe the maintenance task.", view));
+    }
+    if form.interval_days.is_none() && form.interval_hours.is_none() {
+        return Err(fail("Set an interval in days, usage hours, or both.", view));
+    }
+    if form
+        .interval_days
+        .map(|days| !(1..=MAX_INTERVAL_DAYS).contains(&days))
+        .unwrap_or(false)
+    {
+        return Err(fail(
+            &format!("Interval in days must be between 1 and {MAX_INTERVAL_DAYS}."),
+            view,
+        ));
+    }
+    if form
+        .interval_hours
+        .map(|hours| !hours.is_finite() || hours <= 0.0 || hours > MAX_INTERVAL_HOURS)
+        .unwrap_or(false)
+    {
+        return Err(fail(
+            "Interval in usage hours must be greater than zero.",
+            view,
+        ));
+    }
+    maintenance_repo::create_plan(
+        db,
+        tenant_id,
+        &view.asset_type,
+        &view.name,
+        form.interval_days,
+        form.interval_hours,
+    )
+    .await
+    .map_err(|err| fail(&format!("Unable to save maintenance plan: {err}"), view.clone()))
+}
+
+pub async fn delete_plan(db: &Db, tenant_id: i64, plan_id: i64) -> Result<(), String> {
+    match maintenance_repo::find_plan(db, tenant_id, plan_id).await {
+        Ok(Some(_)) => maintenance_repo::delete_plan(db, tenant_id, plan_id)
+            .await
+            .map_err(|err| format!("Unable to delete maintenance plan: {err}")),
+        _ => Err("Maintenance plan not found.".to_string()),
+    }
+}
+
+pub async fn log_maintenance(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    asset: &Asset,
+    form: MaintenanceLogForm,
+) -> Result<(), String> {
+    if let Some(plan_id) = form.plan_id {
+        match maintenance_repo::find_plan(db, tenant_id, plan_id).await {
+            Ok(Some(plan)) if plan.asset_type.eq_ignore_ascii_case(&asset.asset_type) => {}
+            _ => return Err("Maintenance plan not found.".to_string()),
+        }
+    }
+    let now = now_local();
+    let performed_at = if form.performed_at.trim().is_empty() {
+        now
+    } else {
+        parse_datetime(&form.performed_at.replace('T', " "))
+            .ok_or_else(|| "Service time must be a valid date and time.".to_string())?
+    };
+    if performed_at > now {
+        return Err("Service time cannot be in the future.".to_string());
+    }
+    let cost = form.cost.unwrap_or(0.0);
+    if !cost.is_finite() || cost < 0.0 {
+        return Err("Cost cannot be negative.".to_string());
+    }
+    let notes = form.notes.trim();
+    if form.plan_id.is_none() && notes.is_empty() {
+        return Err("Describe the work done when it is not part of a plan.".to_string());
+    }
+    maintenance_repo::create_log(
+        db,
+        tenant_id,
+        asset.id,
+        form.plan_id,
+        &performed_at.format("%Y-%m-%d %H:%M").to_string(),
+        user_id,
+        cost,
+        notes,
+    )
+    .await
+    .map_err(|err| format!("Unable to log maintenance: {err}"))?;
+    gear_service::refresh_gear_scores(db, tenant_id).await
+}
+
+struct Schedule {
+    plans: Vec<MaintenancePlan>,
+    last_services: HashMap<(i64, i64), String>,
+    usage: HashMap<i64, Vec<maintenance_repo::UsageWindow>>,
+    now: NaiveDateTime,
+}
+
+async fn load_schedule(db: &Db, tenant_id: i64) -> Result<Schedule, sqlx::Error> {
+    let plans = maintenance_repo::list_plans(db, tenant_id).await?;
+    let last_services = maintenance_repo::list_last_services(db, tenant_id)
+        .await?
+        .into_iter()
+        .map(|(asset_id, plan_id, performed_at)| ((asset_id, plan_id), performed_at))
+        .collect();
+    let mut usage: HashMap<i64, Vec<maintenance_repo::UsageWindow>> = HashMap::new();
+    if plans.iter().any(|plan| plan.interval_hours.is_some()) {
+        for window in maintenance_repo::list_usage_windows(
+            db,
+            tenant_id,
+            &[deployment_service::STATUS_CANCELLED, deployment_service::STATUS_TENTATIVE],
+        )
+        .await?
+        {
+            usage.entry(window.asset_id).or_default().push(window);
+        }
+    }
+    Ok(Schedule {
+        plans,
+        last_services,
+        usage,
+        now: now_local(),
+    })
+}
+
+/// Tracked hours worked with the asset checked out since `since`. Each log
+/// entry covers `hours_worked` from its start time; overlapping entries
+/// (several members on one job, or the asset on concurrent deployments) count
+/// once.
+fn usage_hours(windows: &[maintenance_repo::UsageWindow], since: NaiveDateTime, now: NaiveDateTime) -> f64 {
+    let mut spans = windows
+        .iter()
+        .filter_map(|window| {
+            let worked_from = parse_datetime(&window.work_started_at)?;
+            let worked_until = worked_from + Duration::minutes((window.hours_worked * 60.0).round() as i64);
+            let start = [parse_datetime(&window.checked_out_at)?, worked_from, since]
+                .into_iter()
+                .max()?;
+            let mut end = worked_until.min(now);
+            if let Some(checked_in_at) = window.checked_in_at.as_deref().and_then(parse_datetime) {
+                end = end.min(checked_in_at);
+            }
+            (end > start).then_some((start, end))
+        })
+        .collect::<Vec<_>>();
+    spans.sort();
+
+    let mut minutes = 0;
+    let mut current: Option<(NaiveDateTime, NaiveDateTime)> = None;
+    for (start, end) in spans {
+        match current {
+            Some((open_start, open_end)) if start <= open_end => {
+                current = Some((open_start, open_end.max(end)));
+            }
+            _ => {
+                if let Some((open_start, open_end)) = current {
+                    minutes += (open_end - open_start).num_minutes();
+                }
+                current = Some((start, end));
+            }
+        }
+    }
+    if let Some((open_start, open_end)) = current {
+        minutes += (open_end - open_start).num_minutes();
+    }
+    minutes as f64 / 60.0
+}
+
+fn evaluate(schedule: &Schedule, asset: &Asset) -> Vec<MaintenanceStatusView> {
+    let in_service_since = asset
+        .purchase_date
+        .as_deref()
+        .and_then(parse_datetime)
+        .or_else(|| parse_datetime(&asset.created_at))
+        .unwrap_or(schedule.now);
+    schedule
+        .plans
+        .iter()
+        .filter(|plan| plan.asset_type.eq_ignore_ascii_case(&asset.asset_type))
+        .map(|plan| {
+            let last_service = schedule.last_services.get(&(asset.id, plan.id)).cloned();
+            // Assets already in service when a plan is added start counting
+            // from the plan, not from the purchase date.
+            let since = last_service
+                .as_deref()
+                .and_then(parse_datetime)
+                .unwrap_or_else(|| {
+                    parse_datetime(&plan.created_at)
+                        .map(|created| created.max(in_service_since))
+                        .unwrap_or(in_service_since)
+                });
+
+            let mut state = STATE_OK;
+            let next_due = plan.interval_days.map(|days| since + Duration::days(days));
+            if let Some(next_due) = next_due {
+                if schedule.now >= next_due {
+                    state = STATE_OVERDUE;
+                } else if schedule.now >= next_due - Duration::days(DUE_SOON_DAYS) {
+                    state = STATE_DUE;
+                }
+            }
+            let usage = schedule
+                .usage
+                .get(&asset.id)
+                .map(|windows| usage_hours(windows, since, schedule.now))
+                .unwrap_or(0.0);
+            if let Some(interval) = plan.interval_hours {
+                if usage >= interval {
+                    state = STATE_OVERDUE;
+                } else if usage >= interval * DUE_SOON_USAGE && state == STATE_OK {
+                    state = STATE_DUE;
+                }
+            }
+
+            MaintenanceStatusView {
+                plan_id: plan.id,
+                plan_name: plan.name.clone(),
+                interval_label: interval_label(plan),
+                since: since.format("%Y-%m-%d %H:%M").to_string(),
+                last_service,
+                next_due_on: next_due.map(|due| due.format("%Y-%m-%d").to_string()),
+                usage_hours: (usage * 10.0).round() / 10.0,
+                hours_remaining: plan
+                    .interval_hours
+                    .map(|interval| (((interval - usage).max(0.0)) * 10.0).round() / 10.0),
+                state: state.to_string(),
+            }
+        })
+        .collect()
+}
+
+pub async fn statuses_for_asset(db: &Db, tenant_id: i64, asset: &Asset) -> Vec<MaintenanceStatusView> {
+    match load_schedule(db, tenant_id).await {
+        Ok(schedule) => evaluate(&schedule, asset),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub async fn states_by_asset(db: &Db, tenant_id: i64, assets: &[Asset]) -> HashMap<i64, &'static str> {
+    let Ok(schedule) = load_schedule(db, tenant_id).await else {
+        return HashMap::new();
+    };
+    if schedule.plans.is_empty() {
+        return HashMap::new();
+    }
+    assets
+        .iter()
+        .filter_map(|asset| {
+            let statuses = evaluate(&schedule, asset);
+            if statuses.iter().any(|status| status.state == STATE_OVERDUE) {
+                Some((asset.id, STATE_OVERDUE))
+            } else if statuses.iter().any(|status| status.state == STATE_DUE) {
+                Some((asset.id, STATE_DUE))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+pub async fn attention_by_asset(db: &Db, tenant_id: i64, assets: &[Asset]) -> HashMap<i64, String> {
+    states_by_asset(db, tenant_id, assets)
+        .await
+        .into_iter()
+        .map(|(asset_id, state)| (asset_id, format!("Maintenance {}", state.to_lowercase())))
+        .collect()
+}
+
+pub async fn overdue_plan_names(db: &Db, tenant_id: i64, asset: &Asset) -> Vec<String> {
+    statuses_for_asset(db, tenant_id, asset)
+        .await
+        .into_iter()
+        .filter(|status| status.state == STATE_OVERDUE)
+        .map(|status| status.plan_name)
+        .collect()
+}
+
+pub async fn process_reminders(db: &Db) -> Result<(), String> {
+    let tenant_ids = maintenance_repo::list_tenant_ids_with_plans(db)
+        .await
+        .map_err(|err| format!("Unable to load workspaces: {err}"))?;
+    for tenant_id in tenant_ids {
+        let schedule = load_schedule(db, tenant_id)
+            .await
+            .map_err(|err| format!("Unable to load maintenance plans: {err}"))?;
+        let assets = asset_repo::list_assets(db, tenant_id)
+            .await
+            .map_err(|err| format!("Unable to load assets: {err}"))?;
+        let mut owner_emails: Option<Vec<String>> = None;
+        for asset in assets {
+            for status in evaluate(&schedule, &asset) {
+                if status.state == STATE_OK {
+                    continue;
+                }
+                let recorded = maintenance_repo::record_reminder(
+                    db,
+                    tenant_id,
+                    asset.id,
+                    status.plan_id,
+                    &status.state,
+                    &status.since,
+                )
+                .await
+                .map_err(|err| format!("Unable to record maintenance reminder: {err}"))?;
+                if !recorded {
+                    continue;
+                }
+                if owner_emails.is_none() {
+                    owner_emails = Some(
+                        user_repo::list_users_by_tenant(db, tenant_id)
+                            .await
+                            .unwrap_or_default()
+                            .into_iter()
+                            .filter(|user| access_service::is_owner(&user.role))
+                            .map(|user| user.email)
+                            .collect(),
+                    );
+                }
+                notify(db, tenant_id, &asset, &status, owner_emails.as_deref().unwrap_or_default()).await;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn notify(
+    db: &Db,
+    tenant_id: i64,
+    asset: &Asset,
+    status: &MaintenanceStatusView,
+    owner_emails: &[String],
+) {
+    let Some((to, cc)) = owner_emails.split_first() else {
+        return;
+    };
+    let subject = if status.state == STATE_OVERDUE {
+        format!("{} is overdue for {}", asset.name, status.plan_name)
+    } else {
+        format!("{} is due for {}", asset.name, status.plan_name)
+    };
+    let serial = if asset.serial_number.trim().is_empty() {
+        String::new()
+    } else {
+        format!(" (serial {})", escape_html(&asset.serial_number))
+    };
+    let mut details = Vec::new();
+    if let Some(next_due_on) = &status.next_due_on {
+        details.push(format!("due by {}", escape_html(next_due_on)));
+    }
+    if let Some(hours_remaining) = status.hours_remaining {
+        details.push(format!(
+            "{} usage hours logged, {} remaining",
+            format_hours(status.usage_hours),
+            format_hours(hours_remaining)
+        ));
+    }
+    let consequence = if status.state == STATE_OVERDUE {
+        "It cannot be checked out until the service is logged."
+    } else {
+        "Once overdue it can no longer be checked out."
+    };
+    let body = format!(
+        "<p><strong>{}</strong>{serial} needs <strong>{}</strong> ({}). Counting since {}: {}.</p><p>{consequence}</p>",
+        escape_html(&asset.name),
+        escape_html(&status.plan_name),
+        escape_html(&status.interval_label.to_lowercase()),
+        escape_html(&status.since),
+        details.join("; "),
+    );
+    let _ = email_service::queue_email(
+        db,
+        tenant_id,
+        None,
+        None,
+        to.clone(),
+        cc.to_vec(),
+        subject,
+        body,
+    )
+    .await;
+}
+
+fn format_hours(hours: f64) -> String {
+    if hours.fract() == 0.0 {
+        format!("{hours:.0}")
+    } else {
+        format!("{hours:.1}")
+    }
+}
+
+fn now_local() -> NaiveDateTime {
+    let now = chrono::Local::now().naive_local();
+    parse_datetime(&now.format("%Y-%m-%d %H:%M").to_string()).unwrap_or(now)
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worked(started_at: &str, hours_worked: f64) -> maintenance_repo::UsageWindow {
+        maintenance_repo::UsageWindow {
+            asset_id: 1,
+            checked_out_at: "2026-03-01 07:00".to_string(),
+            checked_in_at: None,
+            work_started_at: started_at.to_string(),
+            hours_worked,
+        }
+    }
+
+    fn at(value: &str) -> NaiveDateTime {
+        parse_datetime(value).unwrap()
+    }
+
+    #[test]
+    fn multi_day_job_counts_logged_hours_not_nights() {
+        let windows = [worked("2026-03-02 08:00", 8.0), worked("2026-03-03 08:00", 8.0)];
+        let usage = usage_hours(&windows, at("2026-03-01 00:00"), at("2026-03-10 00:00"));
+        assert_eq!(usage, 16.0);
+    }
+
+    #[test]
+    fn overlapping_logs_count_once() {
+        let windows = [
+            worked("2026-03-02 08:00", 8.0),
+            worked("2026-03-02 08:00", 8.0),
+            worked("2026-03-02 09:00", 8.0),
+        ];
+        let usage = usage_hours(&windows, at("2026-03-01 00:00"), at("2026-03-10 00:00"));
+        assert_eq!(usage, 9.0);
+    }
+
+    #[test]
+    fn usage_is_clipped_to_last_service_and_check_in() {
+        let mut returned = worked("2026-03-04 08:00", 8.0);
+        returned.checked_in_at = Some("2026-03-04 12:00".to_string());
+        let windows = [worked("2026-03-02 08:00", 8.0), returned];
+        let usage = usage_hours(&windows, at("2026-03-03 00:00"), at("2026-03-10 00:00"));
+        assert_eq!(usage, 4.0);
+    }
+}
@@ -1,16 +1,40 @@
 pub mod admin_service;
 pub mod access_service;
 pub mod auth_service;
+pub mod availability_service;
+pub mod certification_service;
 pub mod client_service;
 pub mod crew_service;
+pub mod custom_field_service;
 pub mod crew_discussion_service;
 pub mod appointment_service;
+pub mod asset_assignment_service;
+pub mod asset_service;
+pub mod attachment_service;
 pub mod discussion_service;
 pub mod deployment_service;
 pub mod deployment_discussion_service;
+pub mod deployment_task_service;
+pub mod deployment_template_service;
 pub mod email_service;
+pub mod export_service;
+pub mod import_service;
+pub mod expense_service;
+pub mod gear_service;
+pub mod incident_service;
 pub mod invoice_service;
+pub mod label_service;
+pub mod maintenance_service;
+pub mod payroll_service;
+pub mod profitability_service;
+pub mod recommendation_service;
+pub mod scheduler_service;
 pub mod schema_service;
+pub mod timesheet_service;
+pub mod timer_policy_service;
 pub mod tracking_service;
+pub mod tracking_sync_service;
 pub mod utils;
+pub mod week_grid_service;
+pub mod workspace_export_service;
 pub mod workspace_service;
@@ -0,0 +1,275 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use rocket_db_pools::sqlx;
+
+use crate::models::{PayrollDeployment, PayrollEntry, PayrollLine, PayrollPolicy, PayrollPolicyForm};
+use crate::repositories::payroll_repo;
+use crate::services::timesheet_service;
+use crate::services::utils::csv_field;
+use crate::Db;
+
+pub const DEFAULT_DAILY_OVERTIME_HOURS: f64 = 8.0;
+pub const DEFAULT_WEEKLY_OVERTIME_HOURS: f64 = 40.0;
+const MAX_PERIOD_DAYS: i64 = 93;
+
+pub struct PayrollPolicyError {
+    pub message: String,
+    pub form: PayrollPolicy,
+}
+
+pub struct PayrollTotals {
+    pub regular_hours: f64,
+    pub overtime_hours: f64,
+    pub total_hours: f64,
+}
+
+pub fn default_policy() -> PayrollPolicy {
+    PayrollPolicy {
+        daily_overtime_hours: Some(DEFAULT_DAILY_OVERTIME_HOURS),
+        weekly_overtime_hours: Some(DEFAULT_WEEKLY_OVERTIME_HOURS),
+    }
+}
+
+pub async fn policy_for_tenant(db: &Db, tenant_id: i64) -> PayrollPolicy {
+    payroll_repo::find_policy(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(default_policy)
+}
+
+pub async fn update_policy(
+    db: &Db,
+    tenant_id: i64,
+    form: &PayrollPolicyForm,
+) -> Result<(), PayrollPolicyError> {
+    let policy = PayrollPolicy {
+        daily_overtime_hours: form.daily_overtime_hours,
+        weekly_overtime_hours: form.weekly_overtime_hours,
+    };
+    let invalid = |message: &str| PayrollPolicyError {
+        message: message.to_string(),
+        form: policy.clone(),
+    };
+    if policy
+        .daily_overtime_hours
+        .is_some_and(|hours| !(hours > 0.0 && hours <= 24.0))
+    {
+        return Err(invalid("Daily overtime threshold must be between 0 and 24 hours."));
+    }
+    if policy
+        .weekly_overtime_hours
+        .is_some_and(|hours| !(hours > 0.0 && hours <= 168.0))
+    {
+        return Err(invalid("Weekly overtime threshold must be between 0 and 168 hours."));
+    }
+
+    payroll_repo::save_policy(db, tenant_id, &policy)
+        .await
+        .map_err(|err| PayrollPolicyError {
+            message: format!("Unable to save payroll settings: {err}"),
+            form: policy.clone(),
+        })
+}
+
+pub fn period_bounds(
+    start: Option<&str>,
+    end: Option<&str>,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    let parse = |value: Option<&str>| {
+        value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map_err(|_| "Pay period dates must be valid dates.".to_string())
+            })
+            .transpose()
+    };
+    let (week_start, week_end) = timesheet_service::week_bounds(None);
+    let start = parse(start)?;
+    let end = parse(end)?;
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) => (start, end),
+        (Some(start), None) => (start, start + Duration::days(6)),
+        (None, Some(end)) => (end - Duration::days(6), end),
+        (None, None) => (week_start, week_end),
+    };
+    if end < start {
+        return Err("The pay period must end on or after its start date.".to_string());
+    }
+    if (end - start).num_days() >= MAX_PERIOD_DAYS {
+        return Err(format!(
+            "Pay periods can cover at most {MAX_PERIOD_DAYS} days."
+        ));
+    }
+    Ok((start, end))
+}
+
+pub async fn list_payroll(
+    db: &Db,
+    tenant_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+    policy: &PayrollPolicy,
+) -> Result<Vec<PayrollLine>, sqlx::Error> {
+    let entries = payroll_repo::list_approved_entries(
+        db,
+        tenant_id,
+        &start.format("%Y-%m-%d").to_string(),
+        &end.format("%Y-%m-%d").to_string(),
+    )
+    .await?;
+    Ok(build_lines(&entries, policy))
+}
+
+pub async fn count_unapproved_entries(
+    db: &Db,
+    tenant_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<i64, sqlx::Error> {
+    payroll_repo::count_unapproved_entries(
+        db,
+        tenant_id,
+        &start.format("%Y-%m-%d").to_string(),
+        &end.format("%Y-%m-%d").to_string(),
+    )
+    .await
+}
+
+/// Splits each member's approved hours into regular and overtime. Hours over
+/// the daily threshold count as overtime first; the weekly threshold then
+/// applies to the remaining hours of each Monday-based week, so no hour is
+/// counted as overtime twice. Weeks cut by the period edges only see the hours
+/// inside the period.
+pub fn build_lines(entries: &[PayrollEntry], policy: &PayrollPolicy) -> Vec<PayrollLine> {
+    let mut members: BTreeMap<i64, Vec<&PayrollEntry>> = BTreeMap::new();
+    for entry in entries {
+        members.entry(entry.user_id).or_default().push(entry);
+    }
+
+    let mut lines = members
+        .into_values()
+        .filter_map(|entries| {
+            let first = entries.first()?;
+            let mut days: BTreeMap<&str, f64> = BTreeMap::new();
+            let mut deployments: Vec<PayrollDeployment> = Vec::new();
+            for entry in &entries {
+                *days.entry(entry.work_date.as_str()).or_default() += entry.hours_worked;
+                match deployments
+                    .iter_mut()
+                    .find(|deployment| deployment.deployment_id == entry.deployment_id)
+                {
+                    Some(deployment) => deployment.hours += entry.hours_worked,
+                    None => deployments.push(PayrollDeployment {
+                        deployment_id: entry.deployment_id,
+                        label: format!("{} - {}", entry.client_name, entry.crew_name),
+                        hours: entry.hours_worked,
+                    }),
+                }
+            }
+
+            let mut daily_overtime = 0.0;
+            let mut weeks: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+            for (date, hours) in &days {
+                let day_overtime = policy
+                    .daily_overtime_hours
+                    .map(|threshold| (hours - threshold).max(0.0))
+                    .unwrap_or(0.0);
+                daily_overtime += day_overtime;
+                if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                    let week_start =
+                        date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                    *weeks.entry(week_start).or_default() += hours - day_overtime;
+                }
+            }
+            let weekly_overtime = policy
+                .weekly_overtime_hours
+                .map(|threshold| {
+                    weeks
+                        .values()
+                        .map(|hours| (hours - threshold).max(0.0))
+                        .sum::<f64>()
+                })
+                .unwrap_or(0.0);
+
+            let total_hours = days.values().sum::<f64>();
+            let overtime_hours = daily_overtime + weekly_overtime;
+            for deployment in &mut deployments {
+                deployment.hours = round_hours(deployment.hours);
+            }
+            Some(PayrollLine {
+                user_id: first.user_id,
+                member_name: first
+                    .member_name
+                    .clone()
+                    .filter(|name| !name.trim().is_empty())
+                    .unwrap_or_else(|| first.user_email.clone()),
+                user_email: first.user_email.clone(),
+                regular_hours: round_hours(total_hours - overtime_hours),
+                daily_overtime_hours: round_hours(daily_overtime),
+                weekly_overtime_hours: round_hours(weekly_overtime),
+                overtime_hours: round_hours(overtime_hours),
+                total_hours: round_hours(total_hours),
+                days_worked: days.len() as i64,
+                deployments,
+            })
+        })
+        .collect::<Vec<_>>();
+    lines.sort_by(|a, b| {
+        a.member_name
+            .to_lowercase()
+            .cmp(&b.member_name.to_lowercase())
+    });
+    lines
+}
+
+pub fn totals(lines: &[PayrollLine]) -> PayrollTotals {
+    PayrollTotals {
+        regular_hours: round_hours(lines.iter().map(|line| line.regular_hours).sum()),
+        overtime_hours: round_hours(lines.iter().map(|line| line.overtime_hours).sum()),
+        total_hours: round_hours(lines.iter().map(|line| line.total_hours).sum()),
+    }
+}
+
+pub fn export_csv(lines: &[PayrollLine], start: NaiveDate, end: NaiveDate) -> String {
+    let mut csv = String::from(
+        "Period start,Period end,Member,Email,Regular hours,Overtime hours,Daily overtime hours,Weekly overtime hours,Total hours,Days worked,Deployments\n",
+    );
+    for line in lines {
+        let deployments = line
+            .deployments
+            .iter()
+            .map(|deployment| format!("{} ({}h)", deployment.label, deployment.hours))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let fields = [
+            start.format("%Y-%m-%d").to_string(),
+            end.format("%Y-%m-%d").to_string(),
+            line.member_name.clone(),
+            line.user_email.clone(),
+            line.regular_hours.to_string(),
+            line.overtime_hours.to_string(),
+            line.daily_overtime_hours.to_string(),
+            line.weekly_overtime_hours.to_string(),
+            line.total_hours.to_string(),
+            line.days_worked.to_string(),
+            deployments,
+        ];
+        csv.push_str(
+            &fields
+                .iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
+
+fn round_hours(hours: f64) -> f64 {
+    (hours * 100.0).round() / 100.0
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+
+use rocket_db_pools::sqlx;
+
+use crate::models::{DeploymentProfitability, LabourCostLine, ProfitabilityLine};
+use crate::repositories::profitability_repo;
+use crate::Db;
+
+pub const GROUP_DEPLOYMENT: &str = "deployment";
+pub const GROUP_CLIENT: &str = "client";
+pub const GROUP_CREW: &str = "crew";
+pub const GROUP_TYPE: &str = "type";
+pub const GROUP_MONTH: &str = "month";
+
+pub fn group_options() -> [(&'static str, &'static str); 5] {
+    [
+        (GROUP_DEPLOYMENT, "Deployment"),
+        (GROUP_CLIENT, "Client"),
+        (GROUP_CREW, "Crew"),
+        (GROUP_TYPE, "Deployment type"),
+        (GROUP_MONTH, "Month"),
+    ]
+}
+
+pub fn normalize_group(group: Option<&str>) -> &'static str {
+    let group = group.unwrap_or_default().trim();
+    group_options()
+        .into_iter()
+        .map(|(key, _)| key)
+        .find(|key| key.eq_ignore_ascii_case(group))
+        .unwrap_or(GROUP_DEPLOYMENT)
+}
+
+pub async fn list_deployment_profitability(
+    db: &Db,
+    tenant_id: i64,
+    start_from: Option<&str>,
+    start_to: Option<&str>,
+) -> Result<Vec<DeploymentProfitability>, sqlx::Error> {
+    profitability_repo::list_deployment_profitability(db, tenant_id, None, start_from, start_to)
+        .await
+}
+
+pub async fn find_deployment_profitability(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Option<(DeploymentProfitability, Vec<LabourCostLine>)>, sqlx::Error> {
+    let row = profitability_repo::list_deployment_profitability(
+        db,
+        tenant_id,
+        Some(deployment_id),
+        None,
+        None,
+    )
+    .await?
+    .into_iter()
+    .next();
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let lines = profitability_repo::list_labour_cost_lines(db, tenant_id, deployment_id).await?;
+    Ok(Some((row, lines)))
+}
+
+pub fn roll_up(rows: &[DeploymentProfitability], group: &str) -> Vec<ProfitabilityLine> {
+    if group == GROUP_DEPLOYMENT {
+        return rows
+            .iter()
+            .map(|row| {
+                summarize(
+                    row.deployment_id.to_string(),
+                    row.client_name.clone(),
+                    format!(
+                        "{} · {} · {}",
+                        row.crew_name,
+                        row.deployment_type,
+                        date_part(&row.start_at)
+                    ),
+                    &[row],
+                )
+            })
+            .collect();
+    }
+
+    let mut groups: BTreeMap<(String, String), Vec<&DeploymentProfitability>> = BTreeMap::new();
+    for row in rows {
+        let key = match group {
+            GROUP_CLIENT => row.client_name.clone(),
+            GROUP_CREW => row.crew_name.clone(),
+            GROUP_TYPE => row.deployment_type.clone(),
+            _ => row.start_at.chars().take(7).collect(),
+        };
+        groups
+            .entry((key, row.currency.clone()))
+            .or_default()
+            .push(row);
+    }
+    let mut lines = groups
+        .into_iter()
+        .map(|((key, _), items)| summarize(key.clone(), key, String::new(), &items))
+        .collect::<Vec<_>>();
+    if group == GROUP_MONTH {
+        lines.reverse();
+    }
+    lines
+}
+
+pub fn totals_by_currency(rows: &[DeploymentProfitability]) -> Vec<ProfitabilityLine> {
+    let mut groups: BTreeMap<String, Vec<&DeploymentProfitability>> = BTreeMap::new();
+    for row in rows {
+        groups.entry(row.currency.clone()).or_default().push(row);
+    }
+    groups
+        .into_iter()
+        .map(|(currency, items)| summarize(currency.clone(), "Total".to_string(), currency, &items))
+        .collect()
+}
+
+fn summarize(
+    key: String,
+    label: String,
+    detail: String,
+    rows: &[&DeploymentProfitability],
+) -> ProfitabilityLine {
+    let currency = rows
+        .first()
+        .map(|row| row.currency.clone())
+        .unwrap_or_default();
+    let hours = rows.iter().fold(0.0, |sum, row| sum + row.total_hours);
+    let hours_revenue = rows
+        .iter()
+        .fold(0.0, |sum, row| sum + row.total_hours * row.fee_per_hour);
+    let expense_revenue = rows.iter().fold(0.0, |sum, row| sum + row.billable_expenses);
+    let labour_cost = rows.iter().fold(0.0, |sum, row| sum + row.labour_cost);
+    let expense_cost = rows.iter().fold(0.0, |sum, row| sum + row.expense_cost);
+    let uncosted_hours = rows.iter().fold(0.0, |sum, row| sum + row.uncosted_hours);
+    let revenue = hours_revenue + expense_revenue;
+    let margin = revenue - labour_cost - expense_cost;
+    let margin_percent = if revenue > 0.0 {
+        Some(((margin / revenue) * 1000.0).round() / 10.0)
+    } else {
+        None
+    };
+    ProfitabilityLine {
+        key,
+        label,
+        detail,
+        currency,
+        deployment_count: rows.len() as i64,
+        hours: round_money(hours),
+        hours_revenue: round_money(hours_revenue),
+        expense_revenue: round_money(expense_revenue),
+        revenue: round_money(revenue),
+        labour_cost: round_money(labour_cost),
+        expense_cost: round_money(expense_cost),
+        margin: round_money(margin),
+        margin_percent,
+        uncosted_hours: round_money(uncosted_hours),
+    }
+}
+
+fn round_money(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn date_part(value: &str) -> String {
+    value.chars().take(10).collect()
+}
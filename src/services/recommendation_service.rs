@@ -0,0 +1,554 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Local;
+
+use crate::models::{Crew, CrewRecommendation, RecommendationFactor, RecommendationWeight};
+use crate::repositories::recommendation_repo::{self, ClientHistoryRow};
+use crate::services::certification_service::{self, CertifiedSkills};
+use crate::services::utils::distance_meters;
+use crate::services::{availability_service, client_service, crew_service};
+use crate::Db;
+
+pub const MAX_WEIGHT: i64 = 10;
+const DISTANCE_RANGE_KM: f64 = 200.0;
+const NEUTRAL_SCORE: i64 = 50;
+const INCIDENT_PENALTY: i64 = 10;
+
+pub struct RecommendationRequest<'a> {
+    pub required_skills: &'a str,
+    pub compatibility_pref: &'a str,
+    pub client_id: i64,
+    pub start_at: &'a str,
+    pub end_at: &'a str,
+    pub deployment_id: Option<i64>,
+}
+
+pub struct CrewCandidate<'a> {
+    pub crew: &'a Crew,
+    pub skills: HashSet<String>,
+    pub compatibility: HashSet<String>,
+    pub availability_score: i64,
+    pub booked_on: Option<i64>,
+    pub distance_km: Option<f64>,
+    pub history: Option<&'a ClientHistoryRow>,
+}
+
+pub struct RequestTags {
+    pub required: HashSet<String>,
+    pub compatibility: HashSet<String>,
+}
+
+pub struct FactorScore {
+    pub score: i64,
+    pub detail: String,
+}
+
+pub struct ScoringFactor {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+    pub default_weight: i64,
+    pub score: fn(&CrewCandidate, &RequestTags) -> FactorScore,
+}
+
+pub const FACTORS: [ScoringFactor; 7] = [
+    ScoringFactor {
+        key: "skills",
+        label: "Skills",
+        description: "Share of the required skills the crew has, counting current certifications.",
+        default_weight: 4,
+        score: skills_factor,
+    },
+    ScoringFactor {
+        key: "compatibility",
+        label: "Compatibility",
+        description: "Share of the compatibility preferences the crew matches.",
+        default_weight: 2,
+        score: compatibility_factor,
+    },
+    ScoringFactor {
+        key: "availability",
+        label: "Availability",
+        description: "Member-days free during the deployment, after leave, holidays and recurring days off. Crews already booked on another deployment then score 0.",
+        default_weight: 3,
+        score: availability_factor,
+    },
+    ScoringFactor {
+        key: "distance",
+        label: "Distance",
+        description: "How close the crew's previous job is to the client.",
+        default_weight: 2,
+        score: distance_factor,
+    },
+    ScoringFactor {
+        key: "history",
+        label: "Client history",
+        description: "Completed versus cancelled jobs for this client, less incidents.",
+        default_weight: 2,
+        score: history_factor,
+    },
+    ScoringFactor {
+        key: "gear",
+        label: "Gear",
+        description: "The crew's gear score.",
+        default_weight: 1,
+        score: gear_factor,
+    },
+    ScoringFactor {
+        key: "status",
+        label: "Crew status",
+        description: "Active crews rank above idle ones; crews on leave rank last.",
+        default_weight: 1,
+        score: status_factor,
+    },
+];
+
+pub fn default_weights() -> HashMap<String, i64> {
+    FACTORS
+        .iter()
+        .map(|factor| (factor.key.to_string(), factor.default_weight))
+        .collect()
+}
+
+pub async fn weights_for_tenant(db: &Db, tenant_id: i64) -> HashMap<String, i64> {
+    let mut weights = default_weights();
+    for (key, weight) in recommendation_repo::list_weights(db, tenant_id)
+        .await
+        .unwrap_or_default()
+    {
+        if let Some(entry) = weights.get_mut(&key) {
+            *entry = weight.clamp(0, MAX_WEIGHT);
+        }
+    }
+    weights
+}
+
+pub fn weight_views(weights: &HashMap<String, i64>) -> Vec<RecommendationWeight> {
+    FACTORS
+        .iter()
+        .map(|factor| RecommendationWeight {
+            key: factor.key.to_string(),
+            label: factor.label.to_string(),
+            description: factor.description.to_string(),
+            weight: weights
+                .get(factor.key)
+                .copied()
+                .unwrap_or(factor.default_weight),
+        })
+        .collect()
+}
+
+pub async fn update_weights(
+    db: &Db,
+    tenant_id: i64,
+    submitted: &HashMap<String, i64>,
+) -> Result<(), String> {
+    let mut weights = Vec::with_capacity(FACTORS.len());
+    for factor in &FACTORS {
+        let weight = submitted
+            .get(factor.key)
+            .copied()
+            .unwrap_or(factor.default_weight);
+        if !(0..=MAX_WEIGHT).contains(&weight) {
+            return Err(format!(
+                "{} weight must be between 0 and {MAX_WEIGHT}.",
+                factor.label
+            ));
+        }
+        weights.push((factor.key.to_string(), weight));
+    }
+    if weights.iter().all(|(_, weight)| *weight == 0) {
+        return Err("At least one factor needs a weight above 0.".to_string());
+    }
+    recommendation_repo::save_weights(db, tenant_id, &weights)
+        .await
+        .map_err(|err| format!("Unable to save recommendation weights: {err}"))
+}
+
+pub async fn recommend_crews(
+    db: &Db,
+    tenant_id: i64,
+    crews: &[Crew],
+    request: &RecommendationRequest<'_>,
+) -> Vec<CrewRecommendation> {
+    let (start, end) = availability_service::deployment_dates(request.start_at, request.end_at)
+        .unwrap_or_else(|| {
+            let today = Local::now().date_naive();
+            (today, today)
+        });
+    let crew_ids = crews.iter().map(|crew| crew.id).collect::<Vec<_>>();
+    let availability = crew_service::availability_scores(db, tenant_id, &crew_ids, start, end).await;
+    let certified_skills = certification_service::certified_skills_by_crew(db, tenant_id).await;
+    let exclude_id = request.deployment_id.unwrap_or(0);
+
+    let client_location = client_service::find_client_by_id(db, tenant_id, request.client_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|client| parse_coordinates(&client.latitude, &client.longitude));
+    let before = if request.start_at.trim().is_empty() {
+        Local::now().format("%Y-%m-%d %H:%M").to_string()
+    } else {
+        request.start_at.trim().replace('T', " ")
+    };
+    let mut crew_locations: HashMap<i64, (f64, f64)> = HashMap::new();
+    for (crew_id, latitude, longitude) in
+        recommendation_repo::list_previous_locations(db, tenant_id, &before, exclude_id)
+            .await
+            .unwrap_or_default()
+    {
+        if crew_locations.contains_key(&crew_id) {
+            continue;
+        }
+        if let Some(location) = parse_coordinates(&latitude, &longitude) {
+            crew_locations.insert(crew_id, location);
+        }
+    }
+    let mut bookings: HashMap<i64, i64> = HashMap::new();
+    let window = (
+        request.start_at.trim().replace('T', " "),
+        request.end_at.trim().replace('T', " "),
+    );
+    if !window.0.is_empty() && !window.1.is_empty() {
+        for (crew_id, deployment_id) in
+            recommendation_repo::list_overlapping_bookings(db, tenant_id, &window.0, &window.1, exclude_id)
+                .await
+                .unwrap_or_default()
+        {
+            bookings.entry(crew_id).or_insert(deployment_id);
+        }
+    }
+    let history = recommendation_repo::list_client_history(db, tenant_id, request.client_id, exclude_id)
+        .await
+        .unwrap_or_default();
+
+    let candidates = crews
+        .iter()
+        .map(|crew| CrewCandidate {
+            crew,
+            skills: effective_skills(crew, certified_skills.get(&crew.id)),
+            compatibility: tag_set(&crew.compatibility_tags),
+            availability_score: availability.get(&crew.id).copied().unwrap_or(0),
+            booked_on: bookings.get(&crew.id).copied(),
+            distance_km: client_location.zip(crew_locations.get(&crew.id).copied()).map(
+                |(client, crew_location)| distance_meters(crew_location, client) / 1000.0,
+            ),
+            history: history.iter().find(|row| row.crew_id == crew.id),
+        })
+        .collect::<Vec<_>>();
+    let tags = RequestTags {
+        required: tag_set(request.required_skills),
+        compatibility: tag_set(request.compatibility_pref),
+    };
+    let weights = weights_for_tenant(db, tenant_id).await;
+    rank_crews(&candidates, &tags, &weights)
+}
+
+pub fn rank_crews(
+    candidates: &[CrewCandidate],
+    tags: &RequestTags,
+    weights: &HashMap<String, i64>,
+) -> Vec<CrewRecommendation> {
+    let total_weight: i64 = FACTORS
+        .iter()
+        .map(|factor| weights.get(factor.key).copied().unwrap_or(0).max(0))
+        .sum();
+    let mut recommendations = candidates
+        .iter()
+        .map(|candidate| {
+            let factors = FACTORS
+                .iter()
+                .map(|factor| {
+                    let weight = weights.get(factor.key).copied().unwrap_or(0).max(0);
+                    let result = (factor.score)(candidate, tags);
+                    let score = result.score.clamp(0, 100);
+                    let points = if total_weight == 0 {
+                        0.0
+                    } else {
+                        ((score * weight) as f64 / total_weight as f64 * 10.0).round() / 10.0
+                    };
+                    RecommendationFactor {
+                        key: factor.key.to_string(),
+                        label: factor.label.to_string(),
+                        score,
+                        weight,
+                        points,
+                        detail: result.detail,
+                    }
+                })
+                .collect::<Vec<_>>();
+            let score = factors.iter().map(|factor| factor.points).sum::<f64>().round() as i64;
+            CrewRecommendation {
+                id: candidate.crew.id,
+                name: candidate.crew.name.clone(),
+                status: candidate.crew.status.clone(),
+                score,
+                factors,
+            }
+        })
+        .collect::<Vec<_>>();
+    recommendations.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    recommendations
+}
+
+pub fn effective_skills(crew: &Crew, certified: Option<&CertifiedSkills>) -> HashSet<String> {
+    let mut skills = tag_set(&crew.skill_tags);
+    if let Some(certified) = certified {
+        skills.retain(|skill| !certified.lapsed.contains(skill));
+        skills.extend(certified.valid.iter().cloned());
+    }
+    skills
+}
+
+pub fn tag_set(input: &str) -> HashSet<String> {
+    input
+        .split(',')
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+pub fn parse_coordinates(latitude: &str, longitude: &str) -> Option<(f64, f64)> {
+    let latitude = latitude.trim().parse::<f64>().ok()?;
+    let longitude = longitude.trim().parse::<f64>().ok()?;
+    ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude))
+        .then_some((latitude, longitude))
+}
+
+fn match_share(source: &HashSet<String>, target: &HashSet<String>, noun: &str) -> FactorScore {
+    if target.is_empty() {
+        return FactorScore {
+            score: 100,
+            detail: format!("No {noun} requested"),
+        };
+    }
+    let matches = target.iter().filter(|tag| source.contains(*tag)).count();
+    FactorScore {
+        score: (matches * 100 / target.len()) as i64,
+        detail: format!("{matches} of {} {noun}", target.len()),
+    }
+}
+
+fn skills_factor(candidate: &CrewCandidate, tags: &RequestTags) -> FactorScore {
+    match_share(&candidate.skills, &tags.required, "skills")
+}
+
+fn compatibility_factor(candidate: &CrewCandidate, tags: &RequestTags) -> FactorScore {
+    match_share(&candidate.compatibility, &tags.compatibility, "preferences")
+}
+
+fn availability_factor(candidate: &CrewCandidate, _tags: &RequestTags) -> FactorScore {
+    if let Some(deployment_id) = candidate.booked_on {
+        return FactorScore {
+            score: 0,
+            detail: format!("Booked on deployment #{deployment_id}"),
+        };
+    }
+    FactorScore {
+        score: candidate.availability_score,
+        detail: if candidate.crew.members_count == 0 {
+            "No members".to_string()
+        } else {
+            format!("{}% available", candidate.availability_score)
+        },
+    }
+}
+
+fn distance_factor(candidate: &CrewCandidate, _tags: &RequestTags) -> FactorScore {
+    match candidate.distance_km {
+        Some(km) => FactorScore {
+            score: ((1.0 - km / DISTANCE_RANGE_KM).max(0.0) * 100.0).round() as i64,
+            detail: format!("{km:.0} km from previous job"),
+        },
+        None => FactorScore {
+            score: NEUTRAL_SCORE,
+            detail: "Distance unknown".to_string(),
+        },
+    }
+}
+
+fn history_factor(candidate: &CrewCandidate, _tags: &RequestTags) -> FactorScore {
+    let Some(history) = candidate.history.filter(|row| row.completed + row.cancelled > 0) else {
+        return FactorScore {
+            score: NEUTRAL_SCORE,
+            detail: "No previous jobs for this client".to_string(),
+        };
+    };
+    let finished = history.completed + history.cancelled;
+    let score = history.completed * 100 / finished - history.incidents * INCIDENT_PENALTY;
+    FactorScore {
+        score,
+        detail: format!(
+            "{} completed, {} cancelled, {} incident{}",
+            history.completed,
+            history.cancelled,
+            history.incidents,
+            if history.incidents == 1 { "" } else { "s" }
+        ),
+    }
+}
+
+fn gear_factor(candidate: &CrewCandidate, _tags: &RequestTags) -> FactorScore {
+    FactorScore {
+        score: candidate.crew.gear_score,
+        detail: format!("Gear score {}", candidate.crew.gear_score),
+    }
+}
+
+fn status_factor(candidate: &CrewCandidate, _tags: &RequestTags) -> FactorScore {
+    let score = match candidate.crew.status.as_str() {
+        "Active" => 100,
+        "Idle" => 80,
+        "On Leave" => 0,
+        _ => NEUTRAL_SCORE,
+    };
+    FactorScore {
+        score,
+        detail: candidate.crew.status.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crew(id: i64, name: &str, skills: &str) -> Crew {
+        Crew {
+            id,
+            tenant_id: 1,
+            name: name.to_string(),
+            members_count: 3,
+            status: "Active".to_string(),
+            gear_score: 50,
+            skill_tags: skills.to_string(),
+            compatibility_tags: String::new(),
+        }
+    }
+
+    fn candidate<'a>(crew: &'a Crew, availability_score: i64) -> CrewCandidate<'a> {
+        CrewCandidate {
+            crew,
+            skills: tag_set(&crew.skill_tags),
+            compatibility: tag_set(&crew.compatibility_tags),
+            availability_score,
+            booked_on: None,
+            distance_km: None,
+            history: None,
+        }
+    }
+
+    fn tags(required: &str) -> RequestTags {
+        RequestTags {
+            required: tag_set(required),
+            compatibility: HashSet::new(),
+        }
+    }
+
+    fn only(weights: &[(&str, i64)]) -> HashMap<String, i64> {
+        FACTORS
+            .iter()
+            .map(|factor| {
+                let weight = weights
+                    .iter()
+                    .find(|(key, _)| *key == factor.key)
+                    .map(|(_, weight)| *weight)
+                    .unwrap_or(0);
+                (factor.key.to_string(), weight)
+            })
+            .collect()
+    }
+
+    fn factor<'a>(recommendation: &'a CrewRecommendation, key: &str) -> &'a RecommendationFactor {
+        recommendation.factors.iter().find(|factor| factor.key == key).unwrap()
+    }
+
+    #[test]
+    fn weights_decide_which_factor_wins() {
+        let skilled = crew(1, "Skilled", "rigging, welding");
+        let free = crew(2, "Free", "rigging");
+        let candidates = [candidate(&skilled, 40), candidate(&free, 100)];
+        let tags = tags("rigging, welding");
+
+        let by_skills = rank_crews(&candidates, &tags, &only(&[("skills", 5), ("availability", 1)]));
+        assert_eq!(by_skills[0].name, "Skilled");
+
+        let by_availability = rank_crews(&candidates, &tags, &only(&[("skills", 1), ("availability", 5)]));
+        assert_eq!(by_availability[0].name, "Free");
+    }
+
+    #[test]
+    fn equal_scores_fall_back_to_name_order() {
+        let bravo = crew(1, "Bravo", "");
+        let alpha = crew(2, "Alpha", "");
+        let ranked = rank_crews(
+            &[candidate(&bravo, 80), candidate(&alpha, 80)],
+            &tags(""),
+            &default_weights(),
+        );
+        assert_eq!(ranked[0].name, "Alpha");
+        assert_eq!(ranked[0].score, ranked[1].score);
+    }
+
+    #[test]
+    fn unknown_distance_and_history_score_neutral() {
+        let crew = crew(1, "Alpha", "");
+        let ranked = rank_crews(&[candidate(&crew, 100)], &tags(""), &default_weights());
+
+        let distance = factor(&ranked[0], "distance");
+        assert_eq!(distance.score, NEUTRAL_SCORE);
+        assert_eq!(distance.detail, "Distance unknown");
+        let history = factor(&ranked[0], "history");
+        assert_eq!(history.score, NEUTRAL_SCORE);
+        assert_eq!(factor(&ranked[0], "skills").score, 100);
+    }
+
+    #[test]
+    fn history_score_drops_for_cancellations_and_incidents() {
+        let crew = crew(1, "Alpha", "");
+        let clean = ClientHistoryRow { crew_id: 1, completed: 4, cancelled: 0, incidents: 0 };
+        let troubled = ClientHistoryRow { crew_id: 1, completed: 3, cancelled: 1, incidents: 2 };
+        let mut with_clean = candidate(&crew, 100);
+        with_clean.history = Some(&clean);
+        let mut with_troubled = candidate(&crew, 100);
+        with_troubled.history = Some(&troubled);
+        let weights = only(&[("history", 1)]);
+
+        let clean_score = factor(&rank_crews(&[with_clean], &tags(""), &weights)[0], "history").score;
+        let troubled_score = factor(&rank_crews(&[with_troubled], &tags(""), &weights)[0], "history").score;
+
+        assert_eq!(clean_score, 100);
+        assert_eq!(troubled_score, 75 - 2 * INCIDENT_PENALTY);
+    }
+
+    #[test]
+    fn incident_penalty_never_goes_below_zero() {
+        let crew = crew(1, "Alpha", "");
+        let history = ClientHistoryRow { crew_id: 1, completed: 0, cancelled: 1, incidents: 5 };
+        let mut candidate = candidate(&crew, 100);
+        candidate.history = Some(&history);
+
+        let ranked = rank_crews(&[candidate], &tags(""), &only(&[("history", 1)]));
+
+        assert_eq!(factor(&ranked[0], "history").score, 0);
+        assert_eq!(ranked[0].score, 0);
+    }
+
+    #[test]
+    fn crew_booked_in_the_window_scores_unavailable() {
+        let booked = crew(1, "Booked", "");
+        let free = crew(2, "Free", "");
+        let mut booked_candidate = candidate(&booked, 100);
+        booked_candidate.booked_on = Some(42);
+
+        let ranked = rank_crews(
+            &[booked_candidate, candidate(&free, 100)],
+            &tags(""),
+            &only(&[("availability", 1)]),
+        );
+
+        assert_eq!(ranked[0].name, "Free");
+        let availability = factor(&ranked[1], "availability");
+        assert_eq!(availability.score, 0);
+        assert_eq!(availability.detail, "Booked on deployment #42");
+    }
+}
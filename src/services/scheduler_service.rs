@@ -0,0 +1,671 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+
+use crate::models::{
+    Crew,
+    CrewMember,
+    ScheduleCrewOption,
+    ScheduleDeployment,
+    ScheduleProposal,
+};
+use crate::repositories::{crew_member_repo, recommendation_repo, scheduler_repo};
+use crate::services::availability_service::{self, AvailabilityCalendar};
+use crate::services::deployment_service::{STATUS_SCHEDULED, STATUS_TENTATIVE};
+use crate::services::utils::distance_meters;
+use crate::services::{certification_service, crew_service, recommendation_service};
+use crate::Db;
+
+const DEFAULT_RANGE_DAYS: i64 = 13;
+const MAX_RANGE_DAYS: i64 = 31;
+const TRAVEL_SPEED_KMH: f64 = 60.0;
+const CREW_ON_LEAVE: &str = "On Leave";
+
+pub struct SchedulePlan {
+    pub proposals: Vec<ScheduleProposal>,
+    pub assigned: usize,
+    pub travel_km: f64,
+}
+
+#[derive(Clone)]
+struct Job {
+    deployment_id: i64,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    location: Option<(f64, f64)>,
+}
+
+struct Candidate {
+    deployment: ScheduleDeployment,
+    job: Job,
+    eligible: Vec<(i64, i64)>,
+    unmet: String,
+}
+
+struct Workspace<'a> {
+    crews: &'a [Crew],
+    skills: HashMap<i64, HashSet<String>>,
+    members: Vec<CrewMember>,
+    calendar: AvailabilityCalendar,
+    home: HashMap<i64, (f64, f64)>,
+    booked: CrewJobs,
+}
+
+type CrewJobs = HashMap<i64, Vec<Job>>;
+
+pub fn range_bounds(from: Option<&str>, until: Option<&str>) -> (NaiveDate, NaiveDate) {
+    let today = Local::now().date_naive();
+    let from = from
+        .and_then(|value| NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok())
+        .unwrap_or(today);
+    let until = until
+        .and_then(|value| NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok())
+        .unwrap_or(from + Duration::days(DEFAULT_RANGE_DAYS));
+    let until = until.clamp(from, from + Duration::days(MAX_RANGE_DAYS - 1));
+    (from, until)
+}
+
+pub async fn propose_plan(
+    db: &Db,
+    tenant_id: i64,
+    from: NaiveDate,
+    until: NaiveDate,
+) -> Result<SchedulePlan, String> {
+    let crews = crew_service::list_crews(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load crews: {err}"))?;
+    let (workspace, candidates) = load(db, tenant_id, &crews, from, until).await?;
+
+    let mut planned: HashMap<i64, Vec<Job>> = HashMap::new();
+    let mut assignment: HashMap<i64, i64> = HashMap::new();
+    let mut order = (0..candidates.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| {
+        let (a, b) = (&candidates[*a], &candidates[*b]);
+        a.eligible
+            .len()
+            .cmp(&b.eligible.len())
+            .then_with(|| a.job.start.cmp(&b.job.start))
+    });
+    for index in &order {
+        let candidate = &candidates[*index];
+        if let Some((crew_id, _)) = best_crew(&workspace, &planned, candidate) {
+            planned.entry(crew_id).or_default().push(candidate.job.clone());
+            assignment.insert(candidate.deployment.id, crew_id);
+        }
+    }
+    for index in &order {
+        let candidate = &candidates[*index];
+        if !assignment.contains_key(&candidate.deployment.id) {
+            repair(&workspace, &candidates, &mut planned, &mut assignment, candidate);
+        }
+    }
+
+    let mut proposals = Vec::with_capacity(candidates.len());
+    let mut travel_km = 0.0;
+    for candidate in &candidates {
+        let proposed = assignment.get(&candidate.deployment.id).copied();
+        let travel = proposed.and_then(|crew_id| {
+            let others = planned
+                .get(&crew_id)
+                .map(|jobs| {
+                    jobs.iter()
+                        .filter(|job| job.deployment_id != candidate.deployment.id)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            fits(&workspace, crew_id, &others, &candidate.job).ok()
+        });
+        travel_km += travel.unwrap_or(0.0);
+        let note = match (proposed, travel) {
+            (Some(_), _) if candidate.job.location.is_none() => {
+                "Client has no coordinates; travel not counted".to_string()
+            }
+            (Some(_), Some(km)) => format!("Adds {km:.0} km of travel"),
+            (Some(_), None) => String::new(),
+            (None, _) => candidate.unmet.clone(),
+        };
+        proposals.push(proposal_view(
+            &workspace,
+            candidate,
+            proposed,
+            travel.map(|km| (km * 10.0).round() / 10.0),
+            note,
+        ));
+    }
+    Ok(SchedulePlan {
+        assigned: assignment.len(),
+        travel_km: (travel_km * 10.0).round() / 10.0,
+        proposals,
+    })
+}
+
+pub async fn commit_plan(
+    db: &Db,
+    tenant_id: i64,
+    from: NaiveDate,
+    until: NaiveDate,
+    choices: &HashMap<i64, i64>,
+) -> Result<usize, String> {
+    let crews = crew_service::list_crews(db, tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load crews: {err}"))?;
+    let (workspace, candidates) = load(db, tenant_id, &crews, from, until).await?;
+
+    let mut chosen = Vec::new();
+    for (deployment_id, crew_id) in choices {
+        if *crew_id <= 0 {
+            continue;
+        }
+        let Some(candidate) = candidates
+            .iter()
+            .find(|candidate| candidate.deployment.id == *deployment_id)
+        else {
+            return Err(format!(
+                "Deployment #{deployment_id} is no longer tentative in this range. Review the plan again."
+            ));
+        };
+        let Some(crew) = crews.iter().find(|crew| crew.id == *crew_id) else {
+            return Err(format!("Deployment #{deployment_id}: crew not found."));
+        };
+        if let Err(reason) = check_crew(&workspace, crew, &candidate.deployment, &candidate.job) {
+            return Err(format!(
+                "Deployment #{} ({}): {} {}.",
+                deployment_id, candidate.deployment.client_name, crew.name, reason
+            ));
+        }
+        chosen.push((candidate, crew));
+    }
+    if chosen.is_empty() {
+        return Err("Choose a crew for at least one deployment.".to_string());
+    }
+    chosen.sort_by_key(|(candidate, _)| candidate.job.start);
+
+    let mut planned: HashMap<i64, Vec<Job>> = HashMap::new();
+    for (candidate, crew) in &chosen {
+        let jobs = planned.entry(crew.id).or_default();
+        if let Err(reason) = fits(&workspace, crew.id, jobs, &candidate.job) {
+            return Err(format!(
+                "Deployment #{} ({}): {} {}.",
+                candidate.deployment.id, candidate.deployment.client_name, crew.name, reason
+            ));
+        }
+        jobs.push(candidate.job.clone());
+    }
+
+    let assignments = chosen
+        .iter()
+        .map(|(candidate, crew)| (candidate.deployment.id, crew.id))
+        .collect::<Vec<_>>();
+    let updated = scheduler_repo::assign_crews(
+        db,
+        tenant_id,
+        &assignments,
+        STATUS_TENTATIVE,
+        STATUS_SCHEDULED,
+    )
+    .await
+    .map_err(|err| format!("Unable to save the schedule: {err}"))?;
+    if updated as usize != assignments.len() {
+        return Err(
+            "Some deployments changed while you were planning. Nothing was saved; review the plan again."
+                .to_string(),
+        );
+    }
+    Ok(assignments.len())
+}
+
+async fn load<'a>(
+    db: &Db,
+    tenant_id: i64,
+    crews: &'a [Crew],
+    from: NaiveDate,
+    until: NaiveDate,
+) -> Result<(Workspace<'a>, Vec<Candidate>), String> {
+    // A day either side so travel from the previous evening's job counts.
+    let mut deployments = load_deployments(db, tenant_id, from, until).await?;
+    // Tentative jobs that run past the range must still fit around whatever
+    // the crew is booked on afterwards, so load bookings up to their end.
+    let booked_until = bookings_until(&deployments, from, until);
+    if booked_until > until {
+        deployments = load_deployments(db, tenant_id, from, booked_until).await?;
+    }
+    let crew_ids = crews.iter().map(|crew| crew.id).collect::<Vec<_>>();
+    let certified = certification_service::certified_skills_by_crew(db, tenant_id).await;
+    let members = crew_member_repo::list_members_for_crews(db, tenant_id, &crew_ids)
+        .await
+        .unwrap_or_default();
+
+    let (tentative, booked) = split_jobs(deployments, from, until);
+    let latest_end = tentative
+        .iter()
+        .map(|(_, job)| job.end.date())
+        .fold(until, NaiveDate::max);
+
+    let before = from.format("%Y-%m-%d 00:00").to_string();
+    let mut home = HashMap::new();
+    for (crew_id, latitude, longitude) in
+        recommendation_repo::list_previous_locations(db, tenant_id, &before, 0)
+            .await
+            .unwrap_or_default()
+    {
+        if home.contains_key(&crew_id) {
+            continue;
+        }
+        if let Some(location) = recommendation_service::parse_coordinates(&latitude, &longitude) {
+            home.insert(crew_id, location);
+        }
+    }
+
+    let workspace = Workspace {
+        crews,
+        skills: crews
+            .iter()
+            .map(|crew| {
+                (
+                    crew.id,
+                    recommendation_service::effective_skills(crew, certified.get(&crew.id)),
+                )
+            })
+            .collect(),
+        members,
+        calendar: availability_service::load_calendar(db, tenant_id, from, latest_end).await,
+        home,
+        booked,
+    };
+    let candidates = tentative
+        .into_iter()
+        .map(|(deployment, job)| {
+            let mut eligible = Vec::new();
+            let mut reasons = Vec::new();
+            for crew in crews {
+                match check_crew(&workspace, crew, &deployment, &job) {
+                    Ok(availability) => eligible.push((crew.id, availability)),
+                    Err(reason) => reasons.push(reason),
+                }
+            }
+            let unmet = unmet_reason(&workspace, &deployment, &eligible, &reasons);
+            Candidate {
+                deployment,
+                job,
+                eligible,
+                unmet,
+            }
+        })
+        .collect();
+    Ok((workspace, candidates))
+}
+
+async fn load_deployments(
+    db: &Db,
+    tenant_id: i64,
+    from: NaiveDate,
+    until: NaiveDate,
+) -> Result<Vec<ScheduleDeployment>, String> {
+    scheduler_repo::list_deployments_between(
+        db,
+        tenant_id,
+        &(from - Duration::days(1)).format("%Y-%m-%d").to_string(),
+        &(until + Duration::days(1)).format("%Y-%m-%d").to_string(),
+    )
+    .await
+    .map_err(|err| format!("Unable to load deployments: {err}"))
+}
+
+fn bookings_until(deployments: &[ScheduleDeployment], from: NaiveDate, until: NaiveDate) -> NaiveDate {
+    deployments
+        .iter()
+        .filter(|deployment| deployment.status == STATUS_TENTATIVE)
+        .filter_map(job_for)
+        .filter(|job| job.start.date() >= from && job.start.date() <= until)
+        .map(|job| job.end.date())
+        .fold(until, NaiveDate::max)
+}
+
+fn split_jobs(
+    deployments: Vec<ScheduleDeployment>,
+    from: NaiveDate,
+    until: NaiveDate,
+) -> (Vec<(ScheduleDeployment, Job)>, CrewJobs) {
+    let mut booked: CrewJobs = HashMap::new();
+    let mut tentative = Vec::new();
+    for deployment in deployments {
+        let Some(job) = job_for(&deployment) else {
+            continue;
+        };
+        let in_range = job.start.date() >= from && job.start.date() <= until;
+        if deployment.status == STATUS_TENTATIVE && in_range {
+            tentative.push((deployment, job));
+        } else if deployment.status != STATUS_TENTATIVE {
+            booked.entry(deployment.crew_id).or_default().push(job);
+        }
+    }
+    (tentative, booked)
+}
+
+fn job_for(deployment: &ScheduleDeployment) -> Option<Job> {
+    let start = parse_datetime(&deployment.start_at)?;
+    let end = parse_datetime(&deployment.end_at).unwrap_or(start).max(start);
+    Some(Job {
+        deployment_id: deployment.id,
+        start,
+        end,
+        location: recommendation_service::parse_coordinates(
+            &deployment.latitude,
+            &deployment.longitude,
+        ),
+    })
+}
+
+fn check_crew(
+    workspace: &Workspace,
+    crew: &Crew,
+    deployment: &ScheduleDeployment,
+    job: &Job,
+) -> Result<i64, String> {
+    if crew.status == CREW_ON_LEAVE {
+        return Err("is on leave".to_string());
+    }
+    let required = recommendation_service::tag_set(&deployment.required_skills);
+    let missing = workspace
+        .skills
+        .get(&crew.id)
+        .map(|skills| {
+            let mut missing = required
+                .iter()
+                .filter(|skill| !skills.contains(*skill))
+                .cloned()
+                .collect::<Vec<_>>();
+            missing.sort();
+            missing
+        })
+        .unwrap_or_default();
+    if !missing.is_empty() {
+        return Err(format!("is missing {}", missing.join(", ")));
+    }
+    let members = workspace
+        .members
+        .iter()
+        .filter(|member| member.crew_id == crew.id)
+        .collect::<Vec<_>>();
+    let availability = crew_service::availability_score(
+        &members,
+        &workspace.calendar,
+        job.start.date(),
+        job.end.date(),
+    );
+    if availability == 0 {
+        return Err("has no members available".to_string());
+    }
+    Ok(availability)
+}
+
+fn unmet_reason(
+    workspace: &Workspace,
+    deployment: &ScheduleDeployment,
+    eligible: &[(i64, i64)],
+    reasons: &[String],
+) -> String {
+    if !eligible.is_empty() {
+        return "Every qualified crew is booked or cannot travel there in time".to_string();
+    }
+    if workspace.crews.is_empty() {
+        return "No crews in this workspace".to_string();
+    }
+    if reasons.iter().all(|reason| reason.starts_with("is missing")) {
+        return format!("No crew has {}", deployment.required_skills.trim());
+    }
+    "No qualified crew is available on these dates".to_string()
+}
+
+fn fits(workspace: &Workspace, crew_id: i64, planned: &[Job], job: &Job) -> Result<f64, String> {
+    let booked = workspace.booked.get(&crew_id).map(Vec::as_slice).unwrap_or(&[]);
+    let mut previous: Option<&Job> = None;
+    let mut next: Option<&Job> = None;
+    for other in booked.iter().chain(planned.iter()) {
+        if other.start <= job.start {
+            if other.end + travel_time(other.location, job.location) > job.start {
+                return Err(clash(other, job));
+            }
+            if previous.is_none_or(|previous| other.start > previous.start) {
+                previous = Some(other);
+            }
+        } else {
+            if job.end + travel_time(job.location, other.location) > other.start {
+                return Err(clash(other, job));
+            }
+            if next.is_none_or(|next| other.start < next.start) {
+                next = Some(other);
+            }
+        }
+    }
+    let origin = previous
+        .and_then(|job| job.location)
+        .or_else(|| workspace.home.get(&crew_id).copied());
+    let onward = next.and_then(|job| job.location);
+    let added = distance_km(origin, job.location) + distance_km(job.location, onward)
+        - distance_km(origin, onward);
+    Ok(added.max(0.0))
+}
+
+fn clash(other: &Job, job: &Job) -> String {
+    if other.start < job.end && job.start < other.end {
+        format!("is already on deployment #{}", other.deployment_id)
+    } else {
+        format!(
+            "cannot travel between this job and deployment #{} in time",
+            other.deployment_id
+        )
+    }
+}
+
+fn best_crew(
+    workspace: &Workspace,
+    planned: &HashMap<i64, Vec<Job>>,
+    candidate: &Candidate,
+) -> Option<(i64, f64)> {
+    candidate
+        .eligible
+        .iter()
+        .filter_map(|(crew_id, availability)| {
+            let jobs = planned.get(crew_id).map(Vec::as_slice).unwrap_or(&[]);
+            fits(workspace, *crew_id, jobs, &candidate.job)
+                .ok()
+                .map(|km| (*crew_id, *availability, km))
+        })
+        .min_by(|a, b| {
+            a.2.partial_cmp(&b.2)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| {
+                    let current = candidate.deployment.crew_id;
+                    (b.0 == current).cmp(&(a.0 == current))
+                })
+                .then_with(|| b.1.cmp(&a.1))
+        })
+        .map(|(crew_id, _, km)| (crew_id, km))
+}
+
+fn repair(
+    workspace: &Workspace,
+    candidates: &[Candidate],
+    planned: &mut HashMap<i64, Vec<Job>>,
+    assignment: &mut HashMap<i64, i64>,
+    candidate: &Candidate,
+) {
+    for (crew_id, _) in &candidate.eligible {
+        let jobs = planned.get(crew_id).cloned().unwrap_or_default();
+        if fits(workspace, *crew_id, &[], &candidate.job).is_err() {
+            continue;
+        }
+        let blocking = jobs
+            .iter()
+            .filter(|job| fits(workspace, *crew_id, &[(*job).clone()], &candidate.job).is_err())
+            .collect::<Vec<_>>();
+        let [blocking] = blocking.as_slice() else {
+            continue;
+        };
+        let Some(moved) = candidates
+            .iter()
+            .find(|other| other.deployment.id == blocking.deployment_id)
+        else {
+            continue;
+        };
+        let remaining = jobs
+            .iter()
+            .filter(|job| job.deployment_id != moved.deployment.id)
+            .cloned()
+            .collect::<Vec<_>>();
+        if fits(workspace, *crew_id, &remaining, &candidate.job).is_err() {
+            continue;
+        }
+        let target = moved.eligible.iter().find(|(other_id, _)| {
+            other_id != crew_id
+                && fits(
+                    workspace,
+                    *other_id,
+                    planned.get(other_id).map(Vec::as_slice).unwrap_or(&[]),
+                    &moved.job,
+                )
+                .is_ok()
+        });
+        if let Some((target_id, _)) = target {
+            let mut remaining = remaining;
+            remaining.push(candidate.job.clone());
+            planned.insert(*crew_id, remaining);
+            planned.entry(*target_id).or_default().push(moved.job.clone());
+            assignment.insert(moved.deployment.id, *target_id);
+            assignment.insert(candidate.deployment.id, *crew_id);
+            return;
+        }
+    }
+}
+
+fn proposal_view(
+    workspace: &Workspace,
+    candidate: &Candidate,
+    proposed: Option<i64>,
+    travel_km: Option<f64>,
+    note: String,
+) -> ScheduleProposal {
+    let crew_name = |crew_id: i64| {
+        workspace
+            .crews
+            .iter()
+            .find(|crew| crew.id == crew_id)
+            .map(|crew| crew.name.clone())
+            .unwrap_or_default()
+    };
+    let options = candidate
+        .eligible
+        .iter()
+        .map(|(crew_id, availability)| ScheduleCrewOption {
+            id: *crew_id,
+            name: crew_name(*crew_id),
+            note: match fits(workspace, *crew_id, &[], &candidate.job) {
+                Ok(_) => format!("{availability}% available"),
+                Err(_) => "booked".to_string(),
+            },
+        })
+        .collect();
+    ScheduleProposal {
+        deployment_id: candidate.deployment.id,
+        client_name: candidate.deployment.client_name.clone(),
+        start_at: candidate.deployment.start_at.clone(),
+        end_at: candidate.deployment.end_at.clone(),
+        required_skills: candidate.deployment.required_skills.clone(),
+        current_crew_id: candidate.deployment.crew_id,
+        current_crew_name: candidate.deployment.crew_name.clone(),
+        proposed_crew_id: proposed.unwrap_or(0),
+        proposed_crew_name: proposed.map(crew_name).unwrap_or_default(),
+        travel_km,
+        note,
+        options,
+    }
+}
+
+fn distance_km(from: Option<(f64, f64)>, to: Option<(f64, f64)>) -> f64 {
+    match (from, to) {
+        (Some(from), Some(to)) => distance_meters(from, to) / 1000.0,
+        _ => 0.0,
+    }
+}
+
+fn travel_time(from: Option<(f64, f64)>, to: Option<(f64, f64)>) -> Duration {
+    let minutes = distance_km(from, to) / TRAVEL_SPEED_KMH * 60.0;
+    Duration::minutes(minutes.ceil() as i64)
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(id: i64, crew_id: i64, status: &str, start_at: &str, end_at: &str) -> ScheduleDeployment {
+        ScheduleDeployment {
+            id,
+            client_id: 1,
+            client_name: "Globex".to_string(),
+            crew_id,
+            crew_name: String::new(),
+            start_at: start_at.to_string(),
+            end_at: end_at.to_string(),
+            status: status.to_string(),
+            required_skills: String::new(),
+            latitude: String::new(),
+            longitude: String::new(),
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn bookings_cover_tentative_jobs_running_past_the_range() {
+        let (from, until) = (date("2026-03-02"), date("2026-03-08"));
+        let deployments = vec![
+            deployment(1, 0, STATUS_TENTATIVE, "2026-03-08 08:00", "2026-03-11 17:00"),
+            deployment(2, 0, STATUS_TENTATIVE, "2026-03-03 08:00", "2026-03-03 17:00"),
+            deployment(3, 0, STATUS_TENTATIVE, "2026-03-12 08:00", "2026-03-20 17:00"),
+        ];
+
+        assert_eq!(bookings_until(&deployments, from, until), date("2026-03-11"));
+        assert_eq!(bookings_until(&deployments[1..], from, until), until);
+    }
+
+    #[test]
+    fn job_crossing_the_range_end_clashes_with_a_later_booking() {
+        let (from, until) = (date("2026-03-02"), date("2026-03-08"));
+        let deployments = vec![
+            deployment(1, 0, STATUS_TENTATIVE, "2026-03-08 08:00", "2026-03-11 17:00"),
+            deployment(2, 7, STATUS_SCHEDULED, "2026-03-10 08:00", "2026-03-10 17:00"),
+        ];
+        let (tentative, booked) = split_jobs(deployments, from, until);
+        assert_eq!(tentative.len(), 1);
+        assert_eq!(booked.get(&7).map(Vec::len), Some(1));
+
+        let workspace = Workspace {
+            crews: &[],
+            skills: HashMap::new(),
+            members: Vec::new(),
+            calendar: AvailabilityCalendar::default(),
+            home: HashMap::new(),
+            booked,
+        };
+        let job = &tentative[0].1;
+        assert!(fits(&workspace, 7, &[], job).is_err());
+        assert!(fits(&workspace, 8, &[], job).is_ok());
+    }
+}
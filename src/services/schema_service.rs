@@ -460,6 +460,11 @@ pub async fn ensure_schema(db: &Db) -> Result<(), sqlx::Error> {
             .execute(&db.0)
             .await,
     );
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE tenants ADD COLUMN label_secret TEXT NOT NULL DEFAULT ''")
+            .execute(&db.0)
+            .await,
+    );
     ignore_duplicate_column(
         sqlx::query("ALTER TABLE client_contacts ADD COLUMN is_rogue INTEGER NOT NULL DEFAULT 0")
             .execute(&db.0)
@@ -504,6 +509,24 @@ pub async fn ensure_schema(db: &Db) -> Result<(), sqlx::Error> {
             .execute(&db.0)
             .await,
     );
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE crews ADD COLUMN gear_override_reason TEXT NOT NULL DEFAULT ''")
+            .execute(&db.0)
+            .await,
+    );
+    if sqlx::query("ALTER TABLE crews ADD COLUMN gear_override_score INTEGER")
+        .execute(&db.0)
+        .await
+        .is_ok()
+    {
+        // Scores typed in before gear tracking stay in place until someone clears them.
+        sqlx::query(
+            "UPDATE crews SET gear_override_score = gear_score, gear_override_reason = 'Entered manually before equipment tracking' WHERE gear_score != 100",
+        )
+        .execute(&db.0)
+        .await
+        .ok();
+    }
     ensure_deployment_updates_user_id(db).await?;
     ignore_duplicate_column(
         sqlx::query(
@@ -590,14 +613,44 @@ pub async fn ensure_schema(db: &Db) -> Result<(), sqlx::Error> {
         .await,
     );
 
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE plan_limits ADD COLUMN storage_mb INTEGER NOT NULL DEFAULT 0")
+            .execute(&db.0)
+            .await,
+    );
+
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE plan_limits ADD COLUMN assets INTEGER NOT NULL DEFAULT 0")
+            .execute(&db.0)
+            .await,
+    );
+
     sqlx::query(
         r#"
         INSERT OR IGNORE INTO plan_limits
-            (plan_key, clients, contacts_per_client, appointments_per_client, deployments_per_client, crews, members_per_crew, users, expires_after_days)
+            (plan_key, clients, contacts_per_client, appointments_per_client, deployments_per_client, crews, members_per_crew, users, storage_mb, assets, expires_after_days)
         VALUES
-            ('free', 5, 5, 20, 1, 2, 5, 11, 30),
-            ('pro', 20, 5, 40, 5, 5, 10, 51, 180),
-            ('enterprise', 0, 0, 0, 0, 0, 0, 0, 365)
+            ('free', 5, 5, 20, 1, 2, 5, 11, 250, 25, 30),
+            ('pro', 20, 5, 40, 5, 5, 10, 51, 5120, 250, 180),
+            ('enterprise', 0, 0, 0, 0, 0, 0, 0, 0, 0, 365)
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+    sqlx::query(
+        r#"
+        UPDATE plan_limits
+        SET storage_mb = CASE plan_key WHEN 'free' THEN 250 WHEN 'pro' THEN 5120 ELSE 0 END
+        WHERE storage_mb = 0 AND plan_key IN ('free', 'pro')
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+    sqlx::query(
+        r#"
+        UPDATE plan_limits
+        SET assets = CASE plan_key WHEN 'free' THEN 25 WHEN 'pro' THEN 250 ELSE 0 END
+        WHERE assets = 0 AND plan_key IN ('free', 'pro')
         "#,
     )
     .execute(&db.0)
@@ -663,6 +716,645 @@ pub async fn ensure_schema(db: &Db) -> Result<(), sqlx::Error> {
     .execute(&db.0)
     .await?;
 
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS deployment_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            deployment_type TEXT NOT NULL DEFAULT 'Onsite',
+            required_skills TEXT NOT NULL DEFAULT '',
+            compatibility_pref TEXT NOT NULL DEFAULT '',
+            fee_per_hour REAL NOT NULL DEFAULT 0,
+            default_duration_hours REAL NOT NULL DEFAULT 8,
+            checklist TEXT NOT NULL DEFAULT '',
+            info TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(tenant_id, name),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE deployments ADD COLUMN template_id INTEGER")
+            .execute(&db.0)
+            .await,
+    );
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS deployment_tasks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            deployment_id INTEGER NOT NULL,
+            position INTEGER NOT NULL DEFAULT 0,
+            title TEXT NOT NULL,
+            is_required INTEGER NOT NULL DEFAULT 1,
+            completed_at TEXT,
+            completed_by_user_id INTEGER,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(deployment_id) REFERENCES deployments(id),
+            FOREIGN KEY(completed_by_user_id) REFERENCES users(id),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS deployment_expenses (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            deployment_id INTEGER NOT NULL,
+            submitted_by_user_id INTEGER NOT NULL,
+            category TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            amount REAL NOT NULL,
+            currency TEXT NOT NULL DEFAULT 'USD',
+            incurred_on TEXT NOT NULL,
+            receipt_path TEXT,
+            is_billable INTEGER NOT NULL DEFAULT 0,
+            markup_percent REAL NOT NULL DEFAULT 0,
+            status TEXT NOT NULL DEFAULT 'Submitted',
+            reviewed_by_user_id INTEGER,
+            reviewed_at TEXT,
+            review_note TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(deployment_id) REFERENCES deployments(id),
+            FOREIGN KEY(submitted_by_user_id) REFERENCES users(id),
+            FOREIGN KEY(reviewed_by_user_id) REFERENCES users(id),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE crew_members ADD COLUMN hourly_cost REAL NOT NULL DEFAULT 0")
+            .execute(&db.0)
+            .await,
+    );
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE clients ADD COLUMN geofence_mode TEXT NOT NULL DEFAULT 'Off'")
+            .execute(&db.0)
+            .await,
+    );
+    ignore_duplicate_column(
+        sqlx::query(
+            "ALTER TABLE clients ADD COLUMN geofence_radius_m INTEGER NOT NULL DEFAULT 250",
+        )
+        .execute(&db.0)
+        .await,
+    );
+    for column in [
+        "start_latitude REAL",
+        "start_longitude REAL",
+        "start_distance_m REAL",
+        "stop_latitude REAL",
+        "stop_longitude REAL",
+        "stop_distance_m REAL",
+        "geofence_flag TEXT NOT NULL DEFAULT ''",
+    ] {
+        ignore_duplicate_column(
+            sqlx::query(&format!("ALTER TABLE work_timers ADD COLUMN {column}"))
+                .execute(&db.0)
+                .await,
+        );
+    }
+    ensure_deployment_updates_approval(db).await?;
+    for column in [
+        "paid_break_minutes INTEGER NOT NULL DEFAULT 0",
+        "unpaid_break_minutes INTEGER NOT NULL DEFAULT 0",
+    ] {
+        ignore_duplicate_column(
+            sqlx::query(&format!("ALTER TABLE deployment_updates ADD COLUMN {column}"))
+                .execute(&db.0)
+                .await,
+        );
+    }
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS deployment_update_segments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            update_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            start_time TEXT NOT NULL,
+            end_time TEXT NOT NULL,
+            minutes INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY(update_id) REFERENCES deployment_updates(id) ON DELETE CASCADE,
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS work_timer_breaks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            timer_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            start_at TEXT NOT NULL,
+            end_at TEXT,
+            FOREIGN KEY(timer_id) REFERENCES work_timers(id) ON DELETE CASCADE,
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS timer_policies (
+            tenant_id INTEGER PRIMARY KEY,
+            max_shift_hours REAL NOT NULL DEFAULT 9,
+            cap_at_deployment_end INTEGER NOT NULL DEFAULT 0,
+            reminder_minutes_before INTEGER NOT NULL DEFAULT 60,
+            notify_after_close INTEGER NOT NULL DEFAULT 1,
+            notify_lead INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE work_timers ADD COLUMN reminder_sent_at TEXT")
+            .execute(&db.0)
+            .await,
+    );
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS timer_auto_closures (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            timer_id INTEGER NOT NULL,
+            deployment_id INTEGER NOT NULL,
+            user_id INTEGER NOT NULL,
+            start_at TEXT NOT NULL,
+            closed_at TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            max_shift_hours REAL NOT NULL,
+            notified_emails TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(timer_id) REFERENCES work_timers(id) ON DELETE CASCADE,
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS deployment_update_attachments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            update_id INTEGER NOT NULL,
+            deployment_id INTEGER NOT NULL,
+            user_id INTEGER,
+            original_name TEXT NOT NULL,
+            stored_name TEXT NOT NULL,
+            thumbnail_name TEXT,
+            content_type TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL DEFAULT 0,
+            is_client_visible INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(update_id) REFERENCES deployment_updates(id) ON DELETE CASCADE,
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS deployment_incidents (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            deployment_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            severity TEXT NOT NULL,
+            category TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'Open',
+            reported_by_user_id INTEGER,
+            assignee_user_id INTEGER,
+            resolution_notes TEXT NOT NULL DEFAULT '',
+            occurred_at TEXT NOT NULL,
+            resolved_at TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(deployment_id) REFERENCES deployments(id) ON DELETE CASCADE,
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS tracking_sync_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            user_id INTEGER NOT NULL,
+            client_uuid TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            deployment_id INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            message TEXT NOT NULL DEFAULT '',
+            record_id INTEGER,
+            recorded_at TEXT NOT NULL,
+            synced_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(tenant_id, user_id, client_uuid),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS crew_member_certifications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            crew_id INTEGER NOT NULL,
+            member_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            skill_tag TEXT NOT NULL,
+            issuing_body TEXT NOT NULL DEFAULT '',
+            certificate_number TEXT NOT NULL DEFAULT '',
+            issued_on TEXT,
+            expires_on TEXT,
+            document_name TEXT,
+            document_path TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id),
+            FOREIGN KEY(crew_id) REFERENCES crews(id) ON DELETE CASCADE,
+            FOREIGN KEY(member_id) REFERENCES crew_members(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_crew_member_certifications_expiry ON crew_member_certifications (tenant_id, expires_on)",
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS certification_reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            certification_id INTEGER NOT NULL,
+            threshold_days INTEGER NOT NULL,
+            expires_on TEXT NOT NULL,
+            sent_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(certification_id, threshold_days, expires_on),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id),
+            FOREIGN KEY(certification_id) REFERENCES crew_member_certifications(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS member_leave_requests (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            crew_id INTEGER NOT NULL,
+            member_id INTEGER NOT NULL,
+            requested_by_user_id INTEGER NOT NULL,
+            start_date TEXT NOT NULL,
+            end_date TEXT NOT NULL,
+            leave_type TEXT NOT NULL,
+            reason TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL DEFAULT 'Pending',
+            review_note TEXT NOT NULL DEFAULT '',
+            reviewed_by_user_id INTEGER,
+            reviewed_at TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id),
+            FOREIGN KEY(crew_id) REFERENCES crews(id) ON DELETE CASCADE,
+            FOREIGN KEY(member_id) REFERENCES crew_members(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_member_leave_requests_dates ON member_leave_requests (tenant_id, start_date, end_date)",
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS workspace_holidays (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            holiday_date TEXT NOT NULL,
+            name TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(tenant_id, holiday_date),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS member_recurring_unavailability (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            crew_id INTEGER NOT NULL,
+            member_id INTEGER NOT NULL,
+            weekday INTEGER NOT NULL,
+            note TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(member_id, weekday),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id),
+            FOREIGN KEY(crew_id) REFERENCES crews(id) ON DELETE CASCADE,
+            FOREIGN KEY(member_id) REFERENCES crew_members(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS recommendation_weights (
+            tenant_id INTEGER NOT NULL,
+            factor_key TEXT NOT NULL,
+            weight INTEGER NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY(tenant_id, factor_key),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS assets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            asset_type TEXT NOT NULL,
+            serial_number TEXT NOT NULL DEFAULT '',
+            purchase_date TEXT,
+            purchase_cost REAL NOT NULL DEFAULT 0,
+            condition TEXT NOT NULL DEFAULT 'Good',
+            location TEXT NOT NULL DEFAULT '',
+            notes TEXT NOT NULL DEFAULT '',
+            custom_fields TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_assets_serial ON assets (tenant_id, serial_number) WHERE serial_number != ''",
+    )
+    .execute(&db.0)
+    .await?;
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS asset_assignments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            asset_id INTEGER NOT NULL,
+            crew_id INTEGER,
+            deployment_id INTEGER,
+            checked_out_at TEXT NOT NULL,
+            checked_out_by INTEGER NOT NULL,
+            checkout_condition TEXT NOT NULL,
+            checkout_notes TEXT NOT NULL DEFAULT '',
+            checked_in_at TEXT,
+            checked_in_by INTEGER,
+            checkin_condition TEXT NOT NULL DEFAULT '',
+            checkin_notes TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(asset_id) REFERENCES assets(id),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_asset_assignments_asset ON asset_assignments (tenant_id, asset_id, checked_in_at)",
+    )
+    .execute(&db.0)
+    .await?;
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS required_kit (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            skill_tag TEXT NOT NULL,
+            asset_type TEXT NOT NULL,
+            quantity INTEGER NOT NULL DEFAULT 1,
+            UNIQUE(tenant_id, skill_tag, asset_type),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS maintenance_plans (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            asset_type TEXT NOT NULL,
+            name TEXT NOT NULL,
+            interval_days INTEGER,
+            interval_hours REAL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS maintenance_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            asset_id INTEGER NOT NULL,
+            plan_id INTEGER,
+            performed_at TEXT NOT NULL,
+            performed_by INTEGER NOT NULL,
+            cost REAL NOT NULL DEFAULT 0,
+            notes TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id),
+            FOREIGN KEY(asset_id) REFERENCES assets(id),
+            FOREIGN KEY(plan_id) REFERENCES maintenance_plans(id),
+            FOREIGN KEY(performed_by) REFERENCES users(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_maintenance_logs_asset ON maintenance_logs (tenant_id, asset_id, performed_at)",
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS maintenance_reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            asset_id INTEGER NOT NULL,
+            plan_id INTEGER NOT NULL,
+            state TEXT NOT NULL,
+            cycle_start TEXT NOT NULL,
+            sent_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(asset_id, plan_id, state, cycle_start),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS custom_field_definitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            entity TEXT NOT NULL,
+            label TEXT NOT NULL,
+            field_type TEXT NOT NULL,
+            options TEXT NOT NULL DEFAULT '',
+            is_required INTEGER NOT NULL DEFAULT 0,
+            position INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS custom_field_values (
+            tenant_id INTEGER NOT NULL,
+            definition_id INTEGER NOT NULL,
+            record_id INTEGER NOT NULL,
+            value TEXT NOT NULL DEFAULT '',
+            PRIMARY KEY(definition_id, record_id),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id),
+            FOREIGN KEY(definition_id) REFERENCES custom_field_definitions(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS data_imports (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            user_id INTEGER NOT NULL,
+            entity TEXT NOT NULL,
+            file_name TEXT NOT NULL,
+            headers TEXT NOT NULL,
+            rows TEXT NOT NULL,
+            row_count INTEGER NOT NULL DEFAULT 0,
+            mapping TEXT NOT NULL DEFAULT '{}',
+            status TEXT NOT NULL DEFAULT 'Draft',
+            imported_count INTEGER NOT NULL DEFAULT 0,
+            skipped_count INTEGER NOT NULL DEFAULT 0,
+            error_report TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            completed_at TEXT,
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    ignore_duplicate_column(
+        sqlx::query("ALTER TABLE data_imports ADD COLUMN header_line INTEGER NOT NULL DEFAULT 1")
+            .execute(&db.0)
+            .await,
+    );
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS workspace_exports (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tenant_id INTEGER NOT NULL,
+            requested_by INTEGER NOT NULL,
+            origin TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL DEFAULT 'Queued',
+            token TEXT NOT NULL DEFAULT '',
+            file_name TEXT NOT NULL DEFAULT '',
+            file_size INTEGER NOT NULL DEFAULT 0,
+            error TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            started_at TEXT,
+            completed_at TEXT,
+            expires_at TEXT,
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS payroll_policies (
+            tenant_id INTEGER PRIMARY KEY,
+            daily_overtime_hours REAL,
+            weekly_overtime_hours REAL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+        )
+        "#,
+    )
+    .execute(&db.0)
+    .await?;
+
     seed_admin(db).await?;
     seed_client_data(db).await?;
 
@@ -710,6 +1402,38 @@ async fn ensure_deployment_updates_user_id(db: &Db) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
+async fn ensure_deployment_updates_approval(db: &Db) -> Result<(), sqlx::Error> {
+    let columns = sqlx::query("PRAGMA table_info(deployment_updates)")
+        .fetch_all(&db.0)
+        .await?;
+    let has_approval_status = columns
+        .iter()
+        .any(|row| row.get::<String, _>("name") == "approval_status");
+    if !has_approval_status {
+        sqlx::query(
+            "ALTER TABLE deployment_updates ADD COLUMN approval_status TEXT NOT NULL DEFAULT 'Submitted'",
+        )
+        .execute(&db.0)
+        .await?;
+        // Hours logged before the review workflow existed were already invoiced as-is.
+        sqlx::query("UPDATE deployment_updates SET approval_status = 'Approved'")
+            .execute(&db.0)
+            .await?;
+    }
+    for column in [
+        "reviewed_by_user_id INTEGER",
+        "reviewed_at TEXT",
+        "review_note TEXT NOT NULL DEFAULT ''",
+    ] {
+        ignore_duplicate_column(
+            sqlx::query(&format!("ALTER TABLE deployment_updates ADD COLUMN {column}"))
+                .execute(&db.0)
+                .await,
+        );
+    }
+    Ok(())
+}
+
 async fn ensure_deployment_updates_unique_per_user(db: &Db) -> Result<(), sqlx::Error> {
     let index_rows = sqlx::query("PRAGMA index_list('deployment_updates')")
         .fetch_all(&db.0)
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDateTime};
+use rocket_db_pools::sqlx;
+
+use crate::models::{OpenTimer, TimerAutoClosure, TimerPolicy, TimerPolicyForm};
+use crate::repositories::{crew_member_repo, timer_policy_repo, work_timer_repo};
+use crate::services::{email_service, tracking_service};
+use crate::services::utils::escape_html;
+use crate::Db;
+
+pub const DEFAULT_MAX_SHIFT_HOURS: f64 = 9.0;
+const AUDIT_LIMIT: i64 = 50;
+
+pub struct TimerPolicyError {
+    pub message: String,
+    pub form: TimerPolicy,
+}
+
+pub fn default_policy() -> TimerPolicy {
+    TimerPolicy {
+        max_shift_hours: DEFAULT_MAX_SHIFT_HOURS,
+        cap_at_deployment_end: false,
+        reminder_minutes_before: 60,
+        notify_after_close: true,
+        notify_lead: false,
+    }
+}
+
+pub async fn policy_for_tenant(db: &Db, tenant_id: i64) -> TimerPolicy {
+    timer_policy_repo::find_policy(db, tenant_id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(default_policy)
+}
+
+pub async fn update_policy(
+    db: &Db,
+    tenant_id: i64,
+    form: TimerPolicyForm,
+) -> Result<(), TimerPolicyError> {
+    let policy = TimerPolicy {
+        max_shift_hours: form.max_shift_hours.unwrap_or(DEFAULT_MAX_SHIFT_HOURS),
+        cap_at_deployment_end: form.cap_at_deployment_end,
+        reminder_minutes_before: form.reminder_minutes_before.unwrap_or(0),
+        notify_after_close: form.notify_after_close,
+        notify_lead: form.notify_lead,
+    };
+    let invalid = |message: &str| TimerPolicyError {
+        message: message.to_string(),
+        form: policy.clone(),
+    };
+    if form.max_shift_hours.is_none() {
+        return Err(invalid("Maximum shift length is required."));
+    }
+    if !(1.0..=24.0).contains(&policy.max_shift_hours) {
+        return Err(invalid("Maximum shift length must be between 1 and 24 hours."));
+    }
+    if policy.reminder_minutes_before < 0 {
+        return Err(invalid("Reminder lead time cannot be negative."));
+    }
+    if policy.reminder_minutes_before as f64 >= policy.max_shift_hours * 60.0 {
+        return Err(invalid("The reminder must be sent before the maximum shift length is reached."));
+    }
+
+    timer_policy_repo::save_policy(db, tenant_id, &policy)
+        .await
+        .map_err(|err| TimerPolicyError {
+            message: format!("Unable to save timer policy: {err}"),
+            form: policy.clone(),
+        })
+}
+
+pub async fn list_auto_closures(
+    db: &Db,
+    tenant_id: i64,
+) -> Result<Vec<TimerAutoClosure>, sqlx::Error> {
+    timer_policy_repo::list_auto_closures(db, tenant_id, AUDIT_LIMIT).await
+}
+
+pub async fn process_open_timers(db: &Db) -> Result<(), sqlx::Error> {
+    let timers = work_timer_repo::list_open_timers(db).await?;
+    let now = chrono::Local::now().naive_local();
+    let mut policies: HashMap<i64, TimerPolicy> = HashMap::new();
+
+    for timer in timers {
+        let policy = match policies.get(&timer.tenant_id) {
+            Some(policy) => policy.clone(),
+            None => {
+                let policy = policy_for_tenant(db, timer.tenant_id).await;
+                policies.insert(timer.tenant_id, policy.clone());
+                policy
+            }
+        };
+        let Some((close_at, reason)) = close_deadline(&timer, &policy) else {
+            continue;
+        };
+
+        if now >= close_at {
+            let closed_at = close_at.format("%Y-%m-%d %H:%M").to_string();
+            if let Err(err) =
+                tracking_service::auto_close_timer(db, timer.tenant_id, &timer, &closed_at).await
+            {
+                eprintln!("Timer auto-close error for timer {}: {err}", timer.id);
+                continue;
+            }
+            let notified = if policy.notify_after_close {
+                notify(db, &timer, &policy, Notice::Closed { closed_at: &closed_at, reason: &reason })
+                    .await
+            } else {
+                Vec::new()
+            };
+            timer_policy_repo::create_auto_closure(
+                db,
+                timer.tenant_id,
+                timer.id,
+                timer.deployment_id,
+                timer.user_id,
+                &timer.start_at,
+                &closed_at,
+                &reason,
+                policy.max_shift_hours,
+                &notified.join(", "),
+            )
+            .await?;
+        } else if policy.reminder_minutes_before > 0
+            && timer.reminder_sent_at.is_none()
+            && now >= close_at - Duration::minutes(policy.reminder_minutes_before)
+        {
+            let close_at = close_at.format("%Y-%m-%d %H:%M").to_string();
+            notify(db, &timer, &policy, Notice::Reminder { close_at: &close_at }).await;
+            work_timer_repo::mark_reminder_sent(db, timer.tenant_id, timer.id).await?;
+        }
+    }
+    Ok(())
+}
+
+fn close_deadline(timer: &OpenTimer, policy: &TimerPolicy) -> Option<(NaiveDateTime, String)> {
+    let start = NaiveDateTime::parse_from_str(&timer.start_at, "%Y-%m-%d %H:%M").ok()?;
+    let max_end = start + Duration::minutes((policy.max_shift_hours * 60.0).round() as i64);
+    if policy.cap_at_deployment_end
+        && let Ok(deployment_end) =
+            NaiveDateTime::parse_from_str(&timer.deployment_end_at, "%Y-%m-%d %H:%M")
+        && deployment_end > start
+        && deployment_end < max_end
+    {
+        return Some((deployment_end, "Scheduled deployment end".to_string()));
+    }
+    Some((
+        max_end,
+        format!("Maximum shift length ({}h)", policy.max_shift_hours),
+    ))
+}
+
+enum Notice<'a> {
+    Reminder { close_at: &'a str },
+    Closed { closed_at: &'a str, reason: &'a str },
+}
+
+async fn notify(db: &Db, timer: &OpenTimer, policy: &TimerPolicy, notice: Notice<'_>) -> Vec<String> {
+    let mut recipients = Vec::new();
+    if !timer.user_email.trim().is_empty() {
+        recipients.push(timer.user_email.clone());
+    }
+    if policy.notify_lead {
+        let leads = crew_member_repo::list_lead_emails_for_deployment(
+            db,
+            timer.tenant_id,
+            timer.deployment_id,
+            timer.user_id,
+        )
+        .await
+        .unwrap_or_default();
+        for lead in leads {
+            if !recipients.iter().any(|email| email.eq_ignore_ascii_case(&lead)) {
+                recipients.push(lead);
+            }
+        }
+    }
+    let Some((to, cc)) = recipients.split_first() else {
+        return Vec::new();
+    };
+
+    let member = if timer.user_email.trim().is_empty() {
+        "A crew member".to_string()
+    } else {
+        escape_html(&timer.user_email)
+    };
+    let deployment = format!("#{} {}", timer.deployment_id, escape_html(&timer.client_name));
+    let (subject, body) = match notice {
+        Notice::Reminder { close_at } => (
+            format!("Timer still running on deployment #{}", timer.deployment_id),
+            format!(
+                "<p>{member}'s timer on deployment {deployment} has been running since {}.</p>\
+                 <p>It will be closed automatically at <strong>{}</strong>. Stop the timer and submit the day's update if the shift is over.</p>",
+                escape_html(&timer.start_at),
+                escape_html(close_at)
+            ),
+        ),
+        Notice::Closed { closed_at, reason } => (
+            format!("Timer closed automatically on deployment #{}", timer.deployment_id),
+            format!(
+                "<p>{member}'s timer on deployment {deployment} was closed automatically at <strong>{}</strong>.</p>\
+                 <p><strong>Reason:</strong> {}</p>\
+                 <p>The hours were recorded as a placeholder. Open Tracking to correct the times and add the day's notes.</p>",
+                escape_html(closed_at),
+                escape_html(reason)
+            ),
+        ),
+    };
+
+    let _ = email_service::queue_email(
+        db,
+        timer.tenant_id,
+        None,
+        None,
+        to.clone(),
+        cc.to_vec(),
+        subject,
+        body,
+    )
+    .await;
+    recipients
+}
@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use rocket_db_pools::sqlx;
+
+use crate::models::{DeploymentUpdate, TimesheetEntry, TimesheetMember};
+use crate::repositories::timesheet_repo;
+use crate::services::email_service;
+use crate::services::utils::escape_html;
+use crate::Db;
+
+pub const STATUS_SUBMITTED: &str = "Submitted";
+pub const STATUS_APPROVED: &str = "Approved";
+pub const STATUS_REJECTED: &str = "Rejected";
+
+pub fn week_bounds(week: Option<&str>) -> (NaiveDate, NaiveDate) {
+    let day = week
+        .and_then(|value| NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok())
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    let start = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+    (start, start + Duration::days(6))
+}
+
+pub fn is_locked(update: &DeploymentUpdate) -> bool {
+    update.approval_status == STATUS_APPROVED
+}
+
+pub async fn list_members_for_week(
+    db: &Db,
+    tenant_id: i64,
+    week_start: NaiveDate,
+) -> Result<Vec<TimesheetMember>, sqlx::Error> {
+    let week_end = week_start + Duration::days(6);
+    timesheet_repo::list_members_for_week(
+        db,
+        tenant_id,
+        &week_start.format("%Y-%m-%d").to_string(),
+        &week_end.format("%Y-%m-%d").to_string(),
+    )
+    .await
+}
+
+pub async fn list_entries_for_week(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    week_start: NaiveDate,
+) -> Result<Vec<TimesheetEntry>, sqlx::Error> {
+    let week_end = week_start + Duration::days(6);
+    timesheet_repo::list_entries_for_week(
+        db,
+        tenant_id,
+        user_id,
+        &week_start.format("%Y-%m-%d").to_string(),
+        &week_end.format("%Y-%m-%d").to_string(),
+    )
+    .await
+}
+
+pub async fn count_pending_entries(db: &Db, tenant_id: i64) -> Result<i64, sqlx::Error> {
+    timesheet_repo::count_entries_by_status(db, tenant_id, STATUS_SUBMITTED).await
+}
+
+pub async fn approve_entries(
+    db: &Db,
+    tenant_id: i64,
+    reviewer_id: i64,
+    update_ids: &[i64],
+) -> Result<u64, String> {
+    if update_ids.is_empty() {
+        return Err("Select at least one entry to approve.".to_string());
+    }
+    timesheet_repo::set_entries_status(
+        db,
+        tenant_id,
+        update_ids,
+        STATUS_SUBMITTED,
+        STATUS_APPROVED,
+        reviewer_id,
+        "",
+    )
+    .await
+    .map_err(|err| format!("Unable to approve entries: {err}"))
+}
+
+pub async fn reject_entries(
+    db: &Db,
+    tenant_id: i64,
+    reviewer_id: i64,
+    update_ids: &[i64],
+    review_note: Option<String>,
+) -> Result<u64, String> {
+    if update_ids.is_empty() {
+        return Err("Select at least one entry to reject.".to_string());
+    }
+    let review_note = review_note.unwrap_or_default().trim().to_string();
+    if review_note.is_empty() {
+        return Err("Add a comment explaining why the entries were rejected.".to_string());
+    }
+    let rejected = timesheet_repo::set_entries_status(
+        db,
+        tenant_id,
+        update_ids,
+        STATUS_SUBMITTED,
+        STATUS_REJECTED,
+        reviewer_id,
+        &review_note,
+    )
+    .await
+    .map_err(|err| format!("Unable to reject entries: {err}"))?;
+
+    if rejected > 0 {
+        let entries = timesheet_repo::list_entries_by_ids(db, tenant_id, update_ids)
+            .await
+            .unwrap_or_default();
+        notify_rejected(db, tenant_id, &entries, &review_note).await;
+    }
+    Ok(rejected)
+}
+
+pub async fn reopen_entry(
+    db: &Db,
+    tenant_id: i64,
+    reviewer_id: i64,
+    update: &DeploymentUpdate,
+) -> Result<(), String> {
+    if update.approval_status != STATUS_APPROVED {
+        return Err("Only approved entries can be reopened.".to_string());
+    }
+    timesheet_repo::set_entries_status(
+        db,
+        tenant_id,
+        &[update.id],
+        STATUS_APPROVED,
+        STATUS_SUBMITTED,
+        reviewer_id,
+        "",
+    )
+    .await
+    .map_err(|err| format!("Unable to reopen entry: {err}"))?;
+    Ok(())
+}
+
+async fn notify_rejected(db: &Db, tenant_id: i64, entries: &[TimesheetEntry], review_note: &str) {
+    let mut by_submitter: BTreeMap<&str, Vec<&TimesheetEntry>> = BTreeMap::new();
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.approval_status == STATUS_REJECTED)
+        .filter(|entry| !entry.user_email.trim().is_empty())
+    {
+        by_submitter
+            .entry(entry.user_email.as_str())
+            .or_default()
+            .push(entry);
+    }
+
+    for (email, entries) in by_submitter {
+        let mut body = String::new();
+        body.push_str("<p>Some of your timesheet entries were returned for changes.</p>");
+        body.push_str(&format!(
+            "<p><strong>Reviewer comment:</strong> {}</p>",
+            escape_html(review_note)
+        ));
+        body.push_str("<table cellpadding=\"6\" cellspacing=\"0\" border=\"1\" style=\"border-collapse: collapse;\">");
+        body.push_str("<thead><tr><th>Date</th><th>Deployment</th><th>Start</th><th>Finish</th><th>Hours</th></tr></thead><tbody>");
+        for entry in &entries {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>#{} {}</td><td>{}</td><td>{}</td><td>{:.2}</td></tr>",
+                escape_html(&entry.work_date),
+                entry.deployment_id,
+                escape_html(&entry.client_name),
+                escape_html(&entry.start_time),
+                escape_html(&entry.end_time),
+                entry.hours_worked
+            ));
+        }
+        body.push_str("</tbody></table>");
+        body.push_str("<p>Update the entries in Tracking to resubmit them for approval.</p>");
+
+        let _ = email_service::queue_email(
+            db,
+            tenant_id,
+            None,
+            None,
+            email.to_string(),
+            Vec::new(),
+            format!("Timesheet entries returned ({})", entries.len()),
+            body,
+        )
+        .await;
+    }
+}
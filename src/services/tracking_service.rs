@@ -1,14 +1,59 @@
 use rocket_db_pools::sqlx;
 
-use crate::models::{DeploymentUpdate, DeploymentUpdateForm, DeploymentUpdateFormView, WorkTimer};
-use crate::repositories::{deployment_update_repo, work_timer_repo};
+use crate::models::{
+    DeploymentUpdate,
+    DeploymentUpdateForm,
+    DeploymentUpdateFormView,
+    OpenTimer,
+    UpdateBreakForm,
+    UpdateSegment,
+    WorkTimer,
+    WorkTimerBreak,
+    WorkTimerLog,
+};
+use crate::repositories::{client_repo, deployment_repo, deployment_update_repo, work_timer_repo};
+use crate::services::{attachment_service, client_service, timesheet_service, utils};
 use crate::Db;
 
+const TIMER_LOG_LIMIT: i64 = 50;
+
+pub const SEGMENT_WORK: &str = "Work";
+pub const BREAK_UNPAID: &str = "Unpaid break";
+pub const BREAK_PAID: &str = "Paid break";
+pub const NO_REPORT_NOTES: &str = "NO REPORT SUBMITTED";
+const LOCKED_ENTRY_MESSAGE: &str = "This entry has been approved and is locked. Ask a reviewer to reopen it.";
+
 pub struct TrackingError {
     pub message: String,
     pub form: DeploymentUpdateFormView,
 }
 
+impl TrackingError {
+    fn with_breaks(self, breaks: Vec<UpdateSegment>) -> Self {
+        TrackingError {
+            message: self.message,
+            form: self.form.with_breaks(breaks),
+        }
+    }
+}
+
+struct Shift {
+    segments: Vec<UpdateSegment>,
+    span_minutes: i64,
+    paid_break_minutes: i64,
+    unpaid_break_minutes: i64,
+}
+
+impl Shift {
+    fn hours_worked(&self) -> f64 {
+        ((self.span_minutes - self.unpaid_break_minutes) as f64 / 60.0 * 100.0).round() / 100.0
+    }
+}
+
+pub fn break_kind_options() -> [&'static str; 2] {
+    [BREAK_UNPAID, BREAK_PAID]
+}
+
 pub async fn list_updates(
     db: &Db,
     tenant_id: i64,
@@ -26,6 +71,32 @@ pub async fn list_updates_for_user(
     deployment_update_repo::list_updates_for_user(db, tenant_id, deployment_id, user_id).await
 }
 
+pub async fn list_approved_updates(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<DeploymentUpdate>, sqlx::Error> {
+    Ok(deployment_update_repo::list_updates(db, tenant_id, deployment_id)
+        .await?
+        .into_iter()
+        .filter(|update| {
+            !update.is_placeholder && update.approval_status == timesheet_service::STATUS_APPROVED
+        })
+        .collect())
+}
+
+pub async fn list_update_breaks(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+) -> Result<Vec<UpdateSegment>, sqlx::Error> {
+    Ok(deployment_update_repo::list_segments(db, tenant_id, update_id)
+        .await?
+        .into_iter()
+        .filter(|segment| segment.kind != SEGMENT_WORK)
+        .collect())
+}
+
 pub async fn find_update_by_id(
     db: &Db,
     tenant_id: i64,
@@ -54,8 +125,37 @@ pub async fn create_update(
     db: &Db,
     tenant_id: i64,
     user_id: i64,
-    form: DeploymentUpdateForm,
-) -> Result<(), TrackingError> {
+    mut form: DeploymentUpdateForm<'_>,
+) -> Result<(i64, Option<String>), TrackingError> {
+    let breaks = break_views(&form.breaks);
+    let deployment_id = form.deployment_id;
+    let attachments = std::mem::take(&mut form.attachments);
+    let saved = match attachment_service::validate_uploads(db, tenant_id, &attachments).await {
+        Ok(()) => create_update_entry(db, tenant_id, user_id, form).await,
+        Err(message) => Err(TrackingError {
+            message,
+            form: DeploymentUpdateFormView::new(
+                form.deployment_id,
+                form.work_date,
+                form.start_time,
+                form.end_time,
+                form.notes,
+            ),
+        }),
+    };
+    let update_id = saved.map_err(|err| err.with_breaks(breaks))?;
+    let warning = attachment_service::store_attachments(db, tenant_id, update_id, deployment_id, user_id, attachments)
+        .await
+        .err();
+    Ok((update_id, warning))
+}
+
+async fn create_update_entry(
+    db: &Db,
+    tenant_id: i64,
+    user_id: i64,
+    form: DeploymentUpdateForm<'_>,
+) -> Result<i64, TrackingError> {
     if form.deployment_id <= 0 {
         return Err(TrackingError {
             message: "Deployment is required.".to_string(),
@@ -133,7 +233,22 @@ pub async fn create_update(
             ),
         });
     }
-    let hours_worked = (minutes as f64 / 60.0 * 100.0).round() / 100.0;
+    let shift = match build_shift(start, end, &form.breaks) {
+        Ok(shift) => shift,
+        Err(message) => {
+            return Err(TrackingError {
+                message,
+                form: DeploymentUpdateFormView::new(
+                    form.deployment_id,
+                    work_date,
+                    start_time,
+                    end_time,
+                    form.notes,
+                ),
+            })
+        }
+    };
+    let hours_worked = shift.hours_worked();
 
     if let Ok(Some(existing)) = deployment_update_repo::find_update_by_date_for_user(
         db,
@@ -184,6 +299,21 @@ pub async fn create_update(
             )
             .await
             {
+                return Err(TrackingError {
+                    message: match err {
+                        sqlx::Error::RowNotFound => LOCKED_ENTRY_MESSAGE.to_string(),
+                        err => format!("Unable to save update: {err}"),
+                    },
+                    form: DeploymentUpdateFormView::new(
+                        form.deployment_id,
+                        work_date,
+                        start_time,
+                        end_time,
+                        notes,
+                    ),
+                });
+            }
+            if let Err(err) = save_shift(db, tenant_id, existing.id, &shift).await {
                 return Err(TrackingError {
                     message: format!("Unable to save update: {err}"),
                     form: DeploymentUpdateFormView::new(
@@ -195,7 +325,7 @@ pub async fn create_update(
                     ),
                 });
             }
-            return Ok(());
+            return Ok(existing.id);
         }
         return Err(TrackingError {
             message: "An update for this work day already exists.".to_string(),
@@ -222,7 +352,7 @@ pub async fn create_update(
             ),
         });
     }
-    if notes == "NO REPORT SUBMITTED" {
+    if notes == NO_REPORT_NOTES {
         return Err(TrackingError {
             message: "Please enter a report before saving.".to_string(),
             form: DeploymentUpdateFormView::new(
@@ -235,7 +365,7 @@ pub async fn create_update(
         });
     }
 
-    if let Err(err) = deployment_update_repo::create_update(
+    let saved = match deployment_update_repo::create_update(
         db,
         tenant_id,
         form.deployment_id,
@@ -248,26 +378,57 @@ pub async fn create_update(
     )
     .await
     {
-        return Err(TrackingError {
-            message: format!("Unable to save update: {err}"),
+        Ok(update_id) => save_shift(db, tenant_id, update_id, &shift)
+            .await
+            .map(|_| update_id),
+        Err(err) => Err(err),
+    };
+    saved.map_err(|err| TrackingError {
+        message: format!("Unable to save update: {err}"),
+        form: DeploymentUpdateFormView::new(
+            form.deployment_id,
+            work_date,
+            start_time,
+            end_time,
+            notes,
+        ),
+    })
+}
+
+pub async fn update_update(
+    db: &Db,
+    tenant_id: i64,
+    update_id: i64,
+    user_id: i64,
+    mut form: DeploymentUpdateForm<'_>,
+) -> Result<(i64, Option<String>), TrackingError> {
+    let breaks = break_views(&form.breaks);
+    let attachments = std::mem::take(&mut form.attachments);
+    let saved = match attachment_service::validate_uploads(db, tenant_id, &attachments).await {
+        Ok(()) => update_update_entry(db, tenant_id, update_id, form).await,
+        Err(message) => Err(TrackingError {
+            message,
             form: DeploymentUpdateFormView::new(
                 form.deployment_id,
-                work_date,
-                start_time,
-                end_time,
-                notes,
+                form.work_date,
+                form.start_time,
+                form.end_time,
+                form.notes,
             ),
-        });
-    }
-
-    Ok(())
+        }),
+    };
+    let deployment_id = saved.map_err(|err| err.with_breaks(breaks))?;
+    let warning = attachment_service::store_attachments(db, tenant_id, update_id, deployment_id, user_id, attachments)
+        .await
+        .err();
+    Ok((deployment_id, warning))
 }
 
-pub async fn update_update(
+async fn update_update_entry(
     db: &Db,
     tenant_id: i64,
     update_id: i64,
-    form: DeploymentUpdateForm,
+    form: DeploymentUpdateForm<'_>,
 ) -> Result<i64, TrackingError> {
     let existing = match deployment_update_repo::find_update_by_id(db, tenant_id, update_id).await {
         Ok(Some(update)) => update,
@@ -296,6 +457,18 @@ pub async fn update_update(
             })
         }
     };
+    if timesheet_service::is_locked(&existing) {
+        return Err(TrackingError {
+            message: LOCKED_ENTRY_MESSAGE.to_string(),
+            form: DeploymentUpdateFormView::new(
+                existing.deployment_id,
+                existing.work_date,
+                existing.start_time,
+                existing.end_time,
+                existing.notes,
+            ),
+        });
+    }
 
     let deployment_id = existing.deployment_id;
     let work_date = form.work_date.trim().to_string();
@@ -362,7 +535,22 @@ pub async fn update_update(
             ),
         });
     }
-    let hours_worked = (minutes as f64 / 60.0 * 100.0).round() / 100.0;
+    let shift = match build_shift(start, end, &form.breaks) {
+        Ok(shift) => shift,
+        Err(message) => {
+            return Err(TrackingError {
+                message,
+                form: DeploymentUpdateFormView::new(
+                    deployment_id,
+                    work_date,
+                    start_time,
+                    end_time,
+                    form.notes,
+                ),
+            })
+        }
+    };
+    let hours_worked = shift.hours_worked();
 
     if let Ok(Some(other)) = match existing.user_id {
         Some(user_id) => {
@@ -406,7 +594,7 @@ pub async fn update_update(
         });
     }
 
-    if let Err(err) = deployment_update_repo::update_update(
+    let saved = match deployment_update_repo::update_update(
         db,
         tenant_id,
         deployment_id,
@@ -416,7 +604,7 @@ pub async fn update_update(
         &end_time,
         hours_worked,
         &notes,
-        if existing.is_placeholder && notes != "NO REPORT SUBMITTED" {
+        if existing.is_placeholder && notes != NO_REPORT_NOTES {
             false
         } else {
             existing.is_placeholder
@@ -424,8 +612,15 @@ pub async fn update_update(
     )
     .await
     {
+        Ok(_) => save_shift(db, tenant_id, update_id, &shift).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = saved {
         return Err(TrackingError {
-            message: format!("Unable to update: {err}"),
+            message: match err {
+                sqlx::Error::RowNotFound => LOCKED_ENTRY_MESSAGE.to_string(),
+                err => format!("Unable to update: {err}"),
+            },
             form: DeploymentUpdateFormView::new(
                 deployment_id,
                 work_date,
@@ -448,26 +643,138 @@ pub async fn delete_update(
         .await
         .map_err(|err| format!("Unable to load update: {err}"))?
         .ok_or_else(|| "Update not found.".to_string())?;
+    if timesheet_service::is_locked(&existing) {
+        return Err("This entry has been approved and is locked. Reopen it before deleting.".to_string());
+    }
+    attachment_service::delete_attachments_for_update(db, tenant_id, update_id)
+        .await
+        .map_err(|err| format!("Unable to delete update attachments: {err}"))?;
     deployment_update_repo::delete_update(db, tenant_id, existing.deployment_id, update_id)
         .await
         .map_err(|err| format!("Unable to delete update: {err}"))?;
     Ok(existing.deployment_id)
 }
 
+struct SiteCheck {
+    distance_m: Option<f64>,
+    radius_m: i64,
+    enforced: bool,
+    outside: bool,
+}
+
+impl SiteCheck {
+    fn flag(&self, action: &str) -> String {
+        if !self.outside {
+            return String::new();
+        }
+        match self.distance_m {
+            Some(distance) => format!("{action} {} from site", format_distance(distance)),
+            None => format!("{action} without location"),
+        }
+    }
+}
+
+pub fn device_location(latitude: Option<f64>, longitude: Option<f64>) -> Option<(f64, f64)> {
+    match (latitude, longitude) {
+        (Some(latitude), Some(longitude))
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+        {
+            Some((latitude, longitude))
+        }
+        _ => None,
+    }
+}
+
+pub fn format_distance(distance_m: f64) -> String {
+    if distance_m >= 1000.0 {
+        format!("{:.1} km", distance_m / 1000.0)
+    } else {
+        format!("{} m", distance_m.round() as i64)
+    }
+}
+
+async fn check_site(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    location: Option<(f64, f64)>,
+) -> SiteCheck {
+    let client = match deployment_repo::find_deployment_by_id(db, tenant_id, deployment_id).await {
+        Ok(Some(deployment)) => client_repo::find_client_by_id(db, tenant_id, deployment.client_id)
+            .await
+            .ok()
+            .flatten(),
+        _ => None,
+    };
+    let site = client.as_ref().and_then(client_service::site_coordinates);
+    let distance_m = match (location, site) {
+        (Some(location), Some(site)) => Some(utils::distance_meters(location, site)),
+        _ => None,
+    };
+    let (mode, radius_m) = client
+        .map(|client| (client.geofence_mode, client.geofence_radius_m))
+        .unwrap_or_else(|| (client_service::GEOFENCE_OFF.to_string(), 0));
+    let active = mode != client_service::GEOFENCE_OFF && site.is_some();
+    let outside = active
+        && match distance_m {
+            Some(distance) => distance > radius_m as f64,
+            None => true,
+        };
+    SiteCheck {
+        distance_m,
+        radius_m,
+        enforced: active && mode == client_service::GEOFENCE_ENFORCE,
+        outside,
+    }
+}
+
 pub async fn start_timer(
     db: &Db,
     tenant_id: i64,
     deployment_id: i64,
     user_id: i64,
+    location: Option<(f64, f64)>,
+) -> Result<(), String> {
+    let now = chrono::Local::now().naive_local();
+    start_timer_at(db, tenant_id, deployment_id, user_id, location, now).await
+}
+
+pub async fn start_timer_at(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    location: Option<(f64, f64)>,
+    started_at: chrono::NaiveDateTime,
 ) -> Result<(), String> {
     if let Ok(Some(_)) = work_timer_repo::find_active_timer(db, tenant_id, user_id).await {
         return Err("You already have an active timer.".to_string());
     }
-    let now = chrono::Local::now().naive_local();
-    let start_at = now.format("%Y-%m-%d %H:%M").to_string();
-    work_timer_repo::create_timer(db, tenant_id, deployment_id, user_id, &start_at)
-        .await
-        .map_err(|err| format!("Unable to start timer: {err}"))?;
+    let site = check_site(db, tenant_id, deployment_id, location).await;
+    if site.enforced && site.outside {
+        return Err(match site.distance_m {
+            Some(distance) => format!(
+                "You are {} from the client site. Work can only be started within {} m.",
+                format_distance(distance),
+                site.radius_m
+            ),
+            None => "Location is required to start work at this site. Allow location access and try again."
+                .to_string(),
+        });
+    }
+    let start_at = started_at.format("%Y-%m-%d %H:%M").to_string();
+    work_timer_repo::create_timer(
+        db,
+        tenant_id,
+        deployment_id,
+        user_id,
+        &start_at,
+        location,
+        site.distance_m,
+        &site.flag("Started"),
+    )
+    .await
+    .map_err(|err| format!("Unable to start timer: {err}"))?;
     Ok(())
 }
 
@@ -476,6 +783,19 @@ pub async fn stop_timer(
     tenant_id: i64,
     deployment_id: i64,
     user_id: i64,
+    location: Option<(f64, f64)>,
+) -> Result<(), String> {
+    let now = chrono::Local::now().naive_local();
+    stop_timer_at(db, tenant_id, deployment_id, user_id, location, now).await
+}
+
+pub async fn stop_timer_at(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    location: Option<(f64, f64)>,
+    stopped_at: chrono::NaiveDateTime,
 ) -> Result<(), String> {
     let timer = work_timer_repo::find_active_timer(db, tenant_id, user_id)
         .await
@@ -484,14 +804,114 @@ pub async fn stop_timer(
     if timer.deployment_id != deployment_id {
         return Err("Active timer belongs to another deployment.".to_string());
     }
-    let now = chrono::Local::now().naive_local();
-    let mut end_at = now.format("%Y-%m-%d %H:%M").to_string();
+    let mut end_at = stopped_at.format("%Y-%m-%d %H:%M").to_string();
     end_at = ensure_end_after_start(&timer.start_at, &end_at).unwrap_or(end_at);
     work_timer_repo::stop_timer(db, tenant_id, timer.id, &end_at)
         .await
         .map_err(|err| format!("Unable to stop timer: {err}"))?;
-    maybe_create_placeholder_update(db, tenant_id, &timer.start_at, &end_at, timer.deployment_id, user_id)
-        .await?;
+    // Stops are never blocked so a timer cannot get stuck; off-site stops are flagged.
+    let site = check_site(db, tenant_id, timer.deployment_id, location).await;
+    let _ = work_timer_repo::record_stop_location(
+        db,
+        tenant_id,
+        timer.id,
+        location,
+        site.distance_m,
+        &site.flag("Stopped"),
+    )
+    .await;
+    let _ = work_timer_repo::close_open_breaks(db, tenant_id, timer.id, &end_at).await;
+    let breaks = work_timer_repo::list_breaks(db, tenant_id, timer.id)
+        .await
+        .unwrap_or_default();
+    maybe_create_placeholder_update(
+        db,
+        tenant_id,
+        &timer.start_at,
+        &end_at,
+        timer.deployment_id,
+        user_id,
+        &breaks,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn pause_timer(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    kind: &str,
+) -> Result<(), String> {
+    let now = chrono::Local::now().naive_local();
+    pause_timer_at(db, tenant_id, deployment_id, user_id, kind, now).await
+}
+
+pub async fn pause_timer_at(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    kind: &str,
+    paused_at: chrono::NaiveDateTime,
+) -> Result<(), String> {
+    let timer = work_timer_repo::find_active_timer(db, tenant_id, user_id)
+        .await
+        .map_err(|err| format!("Unable to load timer: {err}"))?
+        .ok_or_else(|| "No active timer found.".to_string())?;
+    if timer.deployment_id != deployment_id {
+        return Err("Active timer belongs to another deployment.".to_string());
+    }
+    if timer.paused_at.is_some() {
+        return Err("The timer is already paused.".to_string());
+    }
+    let kind = break_kind_options()
+        .into_iter()
+        .find(|option| option.eq_ignore_ascii_case(kind.trim()))
+        .ok_or_else(|| "Select a break type.".to_string())?;
+    let paused_at = paused_at.format("%Y-%m-%d %H:%M").to_string();
+    if paused_at < timer.start_at {
+        return Err("A break cannot start before the timer.".to_string());
+    }
+    work_timer_repo::create_break(db, tenant_id, timer.id, kind, &paused_at)
+    .await
+    .map_err(|err| format!("Unable to pause timer: {err}"))
+}
+
+pub async fn resume_timer(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+) -> Result<(), String> {
+    let now = chrono::Local::now().naive_local();
+    resume_timer_at(db, tenant_id, deployment_id, user_id, now).await
+}
+
+pub async fn resume_timer_at(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+    user_id: i64,
+    resumed_at: chrono::NaiveDateTime,
+) -> Result<(), String> {
+    let timer = work_timer_repo::find_active_timer(db, tenant_id, user_id)
+        .await
+        .map_err(|err| format!("Unable to load timer: {err}"))?
+        .ok_or_else(|| "No active timer found.".to_string())?;
+    if timer.deployment_id != deployment_id {
+        return Err("Active timer belongs to another deployment.".to_string());
+    }
+    let Some(paused_at) = timer.paused_at.as_deref() else {
+        return Err("The timer is not paused.".to_string());
+    };
+    let resumed_at = resumed_at.format("%Y-%m-%d %H:%M").to_string();
+    // A device clock behind the server's must not produce a negative break.
+    let resumed_at = resumed_at.max(paused_at.to_string());
+    work_timer_repo::close_open_breaks(db, tenant_id, timer.id, &resumed_at)
+    .await
+    .map_err(|err| format!("Unable to resume timer: {err}"))?;
     Ok(())
 }
 
@@ -503,28 +923,34 @@ pub async fn active_timer(
     work_timer_repo::find_active_timer(db, tenant_id, user_id).await
 }
 
-pub async fn close_stale_timers(db: &Db, tenant_id: i64) -> Result<(), sqlx::Error> {
-    let cutoff = chrono::Local::now()
-        .naive_local()
-        .checked_sub_signed(chrono::Duration::hours(9))
-        .unwrap_or_else(|| chrono::Local::now().naive_local())
-        .format("%Y-%m-%d %H:%M")
-        .to_string();
-    let timers = work_timer_repo::list_stale_timers(db, tenant_id, &cutoff).await?;
-    for timer in timers {
-        if let Some(end_at) = add_hours(&timer.start_at, 9) {
-            work_timer_repo::stop_timer(db, tenant_id, timer.id, &end_at).await?;
-            let _ = maybe_create_placeholder_update(
-                db,
-                tenant_id,
-                &timer.start_at,
-                &end_at,
-                timer.deployment_id,
-                timer.user_id,
-            )
-            .await;
-        }
-    }
+pub async fn list_timer_log(
+    db: &Db,
+    tenant_id: i64,
+    deployment_id: i64,
+) -> Result<Vec<WorkTimerLog>, sqlx::Error> {
+    work_timer_repo::list_timers_by_deployment(db, tenant_id, deployment_id, TIMER_LOG_LIMIT).await
+}
+
+pub async fn auto_close_timer(
+    db: &Db,
+    tenant_id: i64,
+    timer: &OpenTimer,
+    end_at: &str,
+) -> Result<(), sqlx::Error> {
+    let end_at = ensure_end_after_start(&timer.start_at, end_at).unwrap_or_else(|| end_at.to_string());
+    work_timer_repo::stop_timer(db, tenant_id, timer.id, &end_at).await?;
+    work_timer_repo::close_open_breaks(db, tenant_id, timer.id, &end_at).await?;
+    let breaks = work_timer_repo::list_breaks(db, tenant_id, timer.id).await?;
+    let _ = maybe_create_placeholder_update(
+        db,
+        tenant_id,
+        &timer.start_at,
+        &end_at,
+        timer.deployment_id,
+        timer.user_id,
+        &breaks,
+    )
+    .await;
     Ok(())
 }
 
@@ -535,6 +961,7 @@ async fn maybe_create_placeholder_update(
     end_at: &str,
     deployment_id: i64,
     user_id: i64,
+    timer_breaks: &[WorkTimerBreak],
 ) -> Result<(), String> {
     let (work_date, start_time) = split_datetime(start_at)
         .ok_or_else(|| "Invalid start time.".to_string())?;
@@ -542,12 +969,7 @@ async fn maybe_create_placeholder_update(
         split_datetime(end_at).ok_or_else(|| "Invalid finish time.".to_string())?;
     let start = parse_time(&start_time).ok_or_else(|| "Start time format is invalid.".to_string())?;
     let end = parse_time(&end_time).ok_or_else(|| "Finish time format is invalid.".to_string())?;
-    let mut minutes = end.signed_duration_since(start).num_minutes();
-    if minutes <= 0 {
-        minutes = 1;
-    }
-    let hours_worked = (minutes as f64 / 60.0 * 100.0).round() / 100.0;
-    if let Ok(Some(existing)) = deployment_update_repo::find_update_by_date_for_user(
+    let existing = deployment_update_repo::find_update_by_date_for_user(
         db,
         tenant_id,
         deployment_id,
@@ -555,8 +977,61 @@ async fn maybe_create_placeholder_update(
         &work_date,
     )
     .await
+    .ok()
+    .flatten();
+    if existing.as_ref().is_some_and(|existing| !existing.is_placeholder) {
+        return Ok(());
+    }
+
+    let mut breaks = timer_break_forms(timer_breaks, start_at, end_at);
+    let (mut span_start, mut span_end) = (start, end);
+    if let Some(existing) = existing.as_ref()
+        && let (Some(previous_start), Some(previous_end)) =
+            (parse_time(&existing.start_time), parse_time(&existing.end_time))
+        && (previous_end <= start || end <= previous_start)
     {
-        if existing.is_placeholder {
+        let (gap_start, gap_end) = if previous_end <= start {
+            span_start = previous_start;
+            (previous_end, start)
+        } else {
+            span_end = previous_end;
+            (end, previous_start)
+        };
+        if gap_end > gap_start {
+            breaks.push(UpdateBreakForm {
+                kind: BREAK_UNPAID.to_string(),
+                start_time: gap_start.format("%H:%M").to_string(),
+                end_time: gap_end.format("%H:%M").to_string(),
+            });
+        }
+        let previous_breaks = deployment_update_repo::list_segments(db, tenant_id, existing.id)
+            .await
+            .unwrap_or_default();
+        breaks.extend(
+            previous_breaks
+                .into_iter()
+                .filter(|segment| segment.kind != SEGMENT_WORK)
+                .map(|segment| UpdateBreakForm {
+                    kind: segment.kind,
+                    start_time: segment.start_time,
+                    end_time: segment.end_time,
+                }),
+        );
+    }
+    let shift = build_shift(span_start, span_end, &breaks)
+        .or_else(|_| build_shift(span_start, span_end, &[]))
+        .unwrap_or(Shift {
+            segments: Vec::new(),
+            span_minutes: 1,
+            paid_break_minutes: 0,
+            unpaid_break_minutes: 0,
+        });
+    let start_time = span_start.format("%H:%M").to_string();
+    let end_time = span_end.format("%H:%M").to_string();
+    let hours_worked = shift.hours_worked();
+
+    let update_id = match existing {
+        Some(existing) => {
             deployment_update_repo::update_update(
                 db,
                 tenant_id,
@@ -566,28 +1041,157 @@ async fn maybe_create_placeholder_update(
                 &start_time,
                 &end_time,
                 hours_worked,
-                "NO REPORT SUBMITTED",
+                NO_REPORT_NOTES,
                 true,
             )
             .await
             .map_err(|err| format!("Unable to update placeholder: {err}"))?;
+            existing.id
         }
-        return Ok(());
+        None => deployment_update_repo::create_placeholder_update(
+            db,
+            tenant_id,
+            deployment_id,
+            user_id,
+            &work_date,
+            &start_time,
+            &end_time,
+            hours_worked,
+            NO_REPORT_NOTES,
+        )
+        .await
+        .map_err(|err| format!("Unable to create placeholder: {err}"))?,
+    };
+    save_shift(db, tenant_id, update_id, &shift)
+        .await
+        .map_err(|err| format!("Unable to save placeholder segments: {err}"))?;
+    Ok(())
+}
+
+fn timer_break_forms(breaks: &[WorkTimerBreak], start_at: &str, end_at: &str) -> Vec<UpdateBreakForm> {
+    let work_date = split_datetime(start_at).map(|(date, _)| date);
+    breaks
+        .iter()
+        .filter_map(|item| {
+            let from = item.start_at.as_str().max(start_at);
+            let to = item.end_at.as_deref()?.min(end_at);
+            if to <= from {
+                return None;
+            }
+            let (from_date, start_time) = split_datetime(from)?;
+            let (to_date, end_time) = split_datetime(to)?;
+            if from_date != to_date || Some(&from_date) != work_date.as_ref() {
+                return None;
+            }
+            Some(UpdateBreakForm {
+                kind: item.kind.clone(),
+                start_time,
+                end_time,
+            })
+        })
+        .collect()
+}
+
+fn build_shift(
+    start: chrono::NaiveTime,
+    end: chrono::NaiveTime,
+    breaks: &[UpdateBreakForm],
+) -> Result<Shift, String> {
+    let span_minutes = end.signed_duration_since(start).num_minutes();
+    if span_minutes <= 0 {
+        return Err("Finish time must be after start time.".to_string());
+    }
+    let mut parsed = Vec::new();
+    for item in breaks {
+        let start_text = item.start_time.trim();
+        let end_text = item.end_time.trim();
+        if start_text.is_empty() && end_text.is_empty() {
+            continue;
+        }
+        let kind = break_kind_options()
+            .into_iter()
+            .find(|option| option.eq_ignore_ascii_case(item.kind.trim()))
+            .ok_or_else(|| "Select a break type.".to_string())?;
+        let (Some(break_start), Some(break_end)) = (parse_time(start_text), parse_time(end_text))
+        else {
+            return Err("Break start and finish times are required.".to_string());
+        };
+        if break_end <= break_start {
+            return Err("Each break must finish after it starts.".to_string());
+        }
+        if break_start < start || break_end > end {
+            return Err("Breaks must fall between the start and finish times.".to_string());
+        }
+        parsed.push((break_start, break_end, kind));
+    }
+    parsed.sort_by_key(|(break_start, _, _)| *break_start);
+    if parsed.windows(2).any(|pair| pair[1].0 < pair[0].1) {
+        return Err("Breaks cannot overlap.".to_string());
+    }
+
+    let mut segments = Vec::new();
+    let mut cursor = start;
+    let mut paid_break_minutes = 0;
+    let mut unpaid_break_minutes = 0;
+    for (break_start, break_end, kind) in parsed {
+        if break_start > cursor {
+            segments.push(time_segment(SEGMENT_WORK, cursor, break_start));
+        }
+        let segment = time_segment(kind, break_start, break_end);
+        if kind == BREAK_PAID {
+            paid_break_minutes += segment.minutes;
+        } else {
+            unpaid_break_minutes += segment.minutes;
+        }
+        segments.push(segment);
+        cursor = break_end;
+    }
+    if end > cursor {
+        segments.push(time_segment(SEGMENT_WORK, cursor, end));
     }
-    deployment_update_repo::create_placeholder_update(
+    if unpaid_break_minutes >= span_minutes {
+        return Err("Unpaid breaks cannot cover the whole shift.".to_string());
+    }
+    Ok(Shift {
+        segments,
+        span_minutes,
+        paid_break_minutes,
+        unpaid_break_minutes,
+    })
+}
+
+fn time_segment(kind: &str, start: chrono::NaiveTime, end: chrono::NaiveTime) -> UpdateSegment {
+    UpdateSegment {
+        kind: kind.to_string(),
+        start_time: start.format("%H:%M").to_string(),
+        end_time: end.format("%H:%M").to_string(),
+        minutes: end.signed_duration_since(start).num_minutes(),
+    }
+}
+
+fn break_views(breaks: &[UpdateBreakForm]) -> Vec<UpdateSegment> {
+    breaks
+        .iter()
+        .filter(|item| !item.start_time.trim().is_empty() || !item.end_time.trim().is_empty())
+        .map(|item| UpdateSegment {
+            kind: item.kind.trim().to_string(),
+            start_time: item.start_time.trim().to_string(),
+            end_time: item.end_time.trim().to_string(),
+            minutes: 0,
+        })
+        .collect()
+}
+
+async fn save_shift(db: &Db, tenant_id: i64, update_id: i64, shift: &Shift) -> Result<(), sqlx::Error> {
+    deployment_update_repo::save_segments(
         db,
         tenant_id,
-        deployment_id,
-        user_id,
-        &work_date,
-        &start_time,
-        &end_time,
-        hours_worked,
-        "NO REPORT SUBMITTED",
+        update_id,
+        &shift.segments,
+        shift.paid_break_minutes,
+        shift.unpaid_break_minutes,
     )
     .await
-    .map_err(|err| format!("Unable to create placeholder: {err}"))?;
-    Ok(())
 }
 
 fn parse_time(value: &str) -> Option<chrono::NaiveTime> {
@@ -599,12 +1203,6 @@ fn split_datetime(value: &str) -> Option<(String, String)> {
     Some((parts.0.to_string(), parts.1.to_string()))
 }
 
-fn add_hours(value: &str, hours: i64) -> Option<String> {
-    let parsed = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").ok()?;
-    let updated = parsed + chrono::Duration::hours(hours);
-    Some(updated.format("%Y-%m-%d %H:%M").to_string())
-}
-
 fn ensure_end_after_start(start_at: &str, end_at: &str) -> Option<String> {
     let start = chrono::NaiveDateTime::parse_from_str(start_at, "%Y-%m-%d %H:%M").ok()?;
     let end = chrono::NaiveDateTime::parse_from_str(end_at, "%Y-%m-%d %H:%M").ok()?;
@@ -0,0 +1,355 @@
+use chrono::{Duration, NaiveDateTime};
+
+use crate::models::{DeploymentUpdateForm, TrackingSyncItem, TrackingSyncResult, User};
+use crate::repositories::{crew_member_repo, deployment_update_repo, tracking_sync_repo};
+use crate::services::{access_service, deployment_service, timesheet_service, tracking_service};
+use crate::Db;
+
+pub const KIND_TIMER_START: &str = "timer_start";
+pub const KIND_TIMER_STOP: &str = "timer_stop";
+pub const KIND_TIMER_PAUSE: &str = "timer_pause";
+pub const KIND_TIMER_RESUME: &str = "timer_resume";
+pub const KIND_UPDATE: &str = "update";
+
+pub const STATUS_APPLIED: &str = "applied";
+pub const STATUS_SKIPPED: &str = "skipped";
+pub const STATUS_CONFLICT: &str = "conflict";
+pub const STATUS_REJECTED: &str = "rejected";
+
+pub const ON_CONFLICT_SKIP: &str = "skip";
+pub const ON_CONFLICT_OVERWRITE: &str = "overwrite";
+
+pub const MAX_BATCH_ITEMS: usize = 100;
+const MAX_UUID_LENGTH: usize = 64;
+const MAX_ITEM_AGE_DAYS: i64 = 30;
+const MAX_CLOCK_SKEW_MINUTES: i64 = 10;
+
+struct Outcome {
+    status: &'static str,
+    message: String,
+    record_id: Option<i64>,
+}
+
+impl Outcome {
+    fn new(status: &'static str, message: impl Into<String>, record_id: Option<i64>) -> Self {
+        Outcome {
+            status,
+            message: message.into(),
+            record_id,
+        }
+    }
+}
+
+pub async fn sync_items(db: &Db, user: &User, items: Vec<TrackingSyncItem>) -> Vec<TrackingSyncResult> {
+    let allowed_deployment_ids = allowed_deployment_ids(db, user).await;
+    let mut results = Vec::with_capacity(items.len());
+    for item in items {
+        let client_uuid = item.client_uuid.trim().to_string();
+        let kind = item.kind.trim().to_lowercase();
+        let result = |outcome: Outcome, duplicate: bool| TrackingSyncResult {
+            client_uuid: client_uuid.clone(),
+            kind: kind.clone(),
+            status: outcome.status.to_string(),
+            message: outcome.message,
+            record_id: outcome.record_id,
+            duplicate,
+        };
+
+        let recorded_at = match validate_item(&item, &client_uuid, &kind, &allowed_deployment_ids) {
+            Ok(recorded_at) => recorded_at,
+            Err(message) => {
+                results.push(result(Outcome::new(STATUS_REJECTED, message, None), false));
+                continue;
+            }
+        };
+
+        let reserved = tracking_sync_repo::reserve_item(
+            db,
+            user.tenant_id,
+            user.id,
+            &client_uuid,
+            &kind,
+            item.deployment_id,
+            &recorded_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        )
+        .await;
+        match reserved {
+            Ok(true) => {}
+            Ok(false) => {
+                let previous =
+                    tracking_sync_repo::find_item(db, user.tenant_id, user.id, &client_uuid)
+                        .await
+                        .ok()
+                        .flatten();
+                results.push(previous.unwrap_or_else(|| {
+                    result(
+                        Outcome::new(
+                            tracking_sync_repo::STATUS_PROCESSING,
+                            "This item is already being synced.",
+                            None,
+                        ),
+                        true,
+                    )
+                }));
+                continue;
+            }
+            Err(err) => {
+                results.push(result(
+                    Outcome::new(STATUS_REJECTED, format!("Unable to sync item: {err}"), None),
+                    false,
+                ));
+                continue;
+            }
+        }
+
+        let outcome = apply_item(db, user, &kind, item, recorded_at).await;
+        if outcome.status == STATUS_APPLIED || outcome.status == STATUS_SKIPPED {
+            let _ = tracking_sync_repo::complete_item(
+                db,
+                user.tenant_id,
+                user.id,
+                &client_uuid,
+                outcome.status,
+                &outcome.message,
+                outcome.record_id,
+            )
+            .await;
+        } else {
+            let _ = tracking_sync_repo::release_item(db, user.tenant_id, user.id, &client_uuid).await;
+        }
+        results.push(result(outcome, false));
+    }
+    results
+}
+
+async fn allowed_deployment_ids(db: &Db, user: &User) -> Vec<i64> {
+    let deployments = if access_service::is_employee(&user.role) {
+        let crew_ids =
+            crew_member_repo::list_crew_ids_for_user(db, user.tenant_id, user.id, &user.email)
+                .await
+                .unwrap_or_default();
+        deployment_service::list_deployments_for_select_for_crews(db, user.tenant_id, &crew_ids)
+            .await
+            .unwrap_or_default()
+    } else {
+        deployment_service::list_deployments_for_select(db, user.tenant_id)
+            .await
+            .unwrap_or_default()
+    };
+    deployments.into_iter().map(|deployment| deployment.id).collect()
+}
+
+fn validate_item(
+    item: &TrackingSyncItem,
+    client_uuid: &str,
+    kind: &str,
+    allowed_deployment_ids: &[i64],
+) -> Result<NaiveDateTime, String> {
+    if client_uuid.is_empty()
+        || client_uuid.len() > MAX_UUID_LENGTH
+        || !client_uuid.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+    {
+        return Err("Each item needs a client UUID.".to_string());
+    }
+    if ![
+        KIND_TIMER_START,
+        KIND_TIMER_STOP,
+        KIND_TIMER_PAUSE,
+        KIND_TIMER_RESUME,
+        KIND_UPDATE,
+    ]
+    .contains(&kind)
+    {
+        return Err("Unknown item kind.".to_string());
+    }
+    if !allowed_deployment_ids.contains(&item.deployment_id) {
+        return Err("You do not have access to that deployment.".to_string());
+    }
+    let recorded_at = parse_device_time(&item.recorded_at)
+        .ok_or_else(|| "Device timestamp is missing or invalid.".to_string())?;
+    let now = chrono::Local::now().naive_local();
+    if recorded_at > now + Duration::minutes(MAX_CLOCK_SKEW_MINUTES) {
+        return Err("Device timestamp is in the future. Check the device clock.".to_string());
+    }
+    if recorded_at < now - Duration::days(MAX_ITEM_AGE_DAYS) {
+        return Err(format!(
+            "Items older than {MAX_ITEM_AGE_DAYS} days cannot be synced. Enter them manually."
+        ));
+    }
+    Ok(recorded_at)
+}
+
+fn parse_device_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Ok(parsed) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(parsed.with_timezone(&chrono::Local).naive_local());
+    }
+    [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+async fn apply_item(
+    db: &Db,
+    user: &User,
+    kind: &str,
+    item: TrackingSyncItem,
+    recorded_at: NaiveDateTime,
+) -> Outcome {
+    let location = tracking_service::device_location(item.latitude, item.longitude);
+    let active_timer = tracking_service::active_timer(db, user.tenant_id, user.id)
+        .await
+        .ok()
+        .flatten();
+    let result = match kind {
+        KIND_TIMER_START => {
+            if let Some(timer) = active_timer {
+                return Outcome::new(
+                    STATUS_CONFLICT,
+                    format!("A timer started at {} is already running.", timer.start_at),
+                    Some(timer.id),
+                );
+            }
+            tracking_service::start_timer_at(
+                db,
+                user.tenant_id,
+                item.deployment_id,
+                user.id,
+                location,
+                recorded_at,
+            )
+            .await
+            .map(|_| "Timer started.")
+        }
+        KIND_TIMER_STOP | KIND_TIMER_PAUSE | KIND_TIMER_RESUME => {
+            let Some(timer) = active_timer.filter(|timer| timer.deployment_id == item.deployment_id)
+            else {
+                return Outcome::new(
+                    STATUS_CONFLICT,
+                    "No timer is running for this deployment.",
+                    None,
+                );
+            };
+            let applied = match kind {
+                KIND_TIMER_STOP => tracking_service::stop_timer_at(
+                    db,
+                    user.tenant_id,
+                    item.deployment_id,
+                    user.id,
+                    location,
+                    recorded_at,
+                )
+                .await
+                .map(|_| "Timer stopped."),
+                KIND_TIMER_PAUSE => tracking_service::pause_timer_at(
+                    db,
+                    user.tenant_id,
+                    item.deployment_id,
+                    user.id,
+                    &item.break_kind,
+                    recorded_at,
+                )
+                .await
+                .map(|_| "Timer paused."),
+                _ => tracking_service::resume_timer_at(
+                    db,
+                    user.tenant_id,
+                    item.deployment_id,
+                    user.id,
+                    recorded_at,
+                )
+                .await
+                .map(|_| "Timer resumed."),
+            };
+            return match applied {
+                Ok(message) => Outcome::new(STATUS_APPLIED, message, Some(timer.id)),
+                Err(message) => Outcome::new(STATUS_REJECTED, message, Some(timer.id)),
+            };
+        }
+        _ => return apply_update(db, user, item).await,
+    };
+    match result {
+        Ok(message) => {
+            let timer_id = tracking_service::active_timer(db, user.tenant_id, user.id)
+                .await
+                .ok()
+                .flatten()
+                .map(|timer| timer.id);
+            Outcome::new(STATUS_APPLIED, message, timer_id)
+        }
+        Err(message) => Outcome::new(STATUS_REJECTED, message, None),
+    }
+}
+
+async fn apply_update(db: &Db, user: &User, item: TrackingSyncItem) -> Outcome {
+    let on_conflict = item.on_conflict.trim().to_lowercase();
+    let form = DeploymentUpdateForm {
+        deployment_id: item.deployment_id,
+        work_date: item.work_date,
+        start_time: item.start_time,
+        end_time: item.end_time,
+        notes: item.notes,
+        breaks: item.breaks,
+        attachments: Vec::new(),
+    };
+    let existing = deployment_update_repo::find_update_by_date_for_user(
+        db,
+        user.tenant_id,
+        form.deployment_id,
+        user.id,
+        form.work_date.trim(),
+    )
+    .await
+    .ok()
+    .flatten()
+    .filter(|existing| !existing.is_placeholder);
+
+    let Some(existing) = existing else {
+        return match tracking_service::create_update(db, user.tenant_id, user.id, form).await {
+            Ok((update_id, _)) => Outcome::new(STATUS_APPLIED, "Update saved.", Some(update_id)),
+            Err(err) => Outcome::new(STATUS_REJECTED, err.message, None),
+        };
+    };
+
+    match on_conflict.as_str() {
+        ON_CONFLICT_SKIP => Outcome::new(
+            STATUS_SKIPPED,
+            "Kept the update already saved for this work day.",
+            Some(existing.id),
+        ),
+        ON_CONFLICT_OVERWRITE => {
+            let can_replace = access_service::is_owner(&user.role)
+                || existing.approval_status == timesheet_service::STATUS_REJECTED;
+            if !can_replace {
+                return Outcome::new(
+                    STATUS_CONFLICT,
+                    "The saved update is awaiting review and cannot be replaced. Ask an owner to edit it.",
+                    Some(existing.id),
+                );
+            }
+            match tracking_service::update_update(db, user.tenant_id, existing.id, user.id, form)
+                .await
+            {
+                Ok(_) => Outcome::new(
+                    STATUS_APPLIED,
+                    "Replaced the update saved for this work day.",
+                    Some(existing.id),
+                ),
+                Err(err) => Outcome::new(STATUS_REJECTED, err.message, Some(existing.id)),
+            }
+        }
+        _ => Outcome::new(
+            STATUS_CONFLICT,
+            format!(
+                "An update for {} already exists ({}–{}). Resend with on_conflict \"skip\" or \"overwrite\".",
+                existing.work_date, existing.start_time, existing.end_time
+            ),
+            Some(existing.id),
+        ),
+    }
+}
@@ -32,3 +32,40 @@ pub fn verify_password(password: &str, hash: &str) -> Result<(), String> {
         .verify_password(password.as_bytes(), &parsed)
         .map_err(|_| "Invalid credentials.".to_string())
 }
+
+pub fn distance_meters(from: (f64, f64), to: (f64, f64)) -> f64 {
+    const EARTH_RADIUS_M: f64 = 6_371_000.0;
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = lon2 - lon1;
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Quotes a CSV value and neutralises leading formula characters so names
+/// cannot run as spreadsheet formulas.
+pub fn csv_field(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@']) {
+        csv_quote(&format!("'{value}"))
+    } else {
+        csv_quote(value)
+    }
+}
+
+pub fn csv_quote(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
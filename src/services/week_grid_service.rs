@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, NaiveTime};
+
+use crate::Db;
+use crate::models::{
+    DeploymentUpdate, DeploymentUpdateForm, UpdateBreakForm, User, WeekGridCell, WeekGridForm,
+    WeekGridRow,
+};
+use crate::repositories::{crew_member_repo, deployment_repo, deployment_update_repo};
+use crate::services::{access_service, deployment_service, timesheet_service, tracking_service};
+
+const DEFAULT_START_TIME: &str = "08:00";
+
+pub struct WeekGrid {
+    pub rows: Vec<WeekGridRow>,
+    pub day_totals: Vec<f64>,
+    pub week_total: f64,
+}
+
+pub type GridInputs = HashMap<(i64, String), String>;
+
+struct GridDeployment {
+    id: i64,
+    label: String,
+    start_date: String,
+    end_date: String,
+    start_time: String,
+    is_cancelled: bool,
+}
+
+pub async fn build_week_grid(
+    db: &Db,
+    user: &User,
+    week_start: NaiveDate,
+    inputs: &GridInputs,
+    notes: &HashMap<i64, String>,
+) -> WeekGrid {
+    let dates = week_dates(week_start);
+    let week_end = dates[6].clone();
+    let updates = week_updates(db, user, &dates[0], &week_end).await;
+    let today = chrono::Local::now()
+        .date_naive()
+        .format("%Y-%m-%d")
+        .to_string();
+    let deployments = grid_deployments(db, user)
+        .await
+        .into_iter()
+        .filter(|deployment| {
+            let overlaps = !deployment.is_cancelled
+                && !deployment.start_date.is_empty()
+                && deployment.start_date <= week_end
+                && deployment.end_date >= dates[0];
+            overlaps
+                || updates
+                    .iter()
+                    .any(|update| update.deployment_id == deployment.id)
+        })
+        .collect::<Vec<_>>();
+
+    let mut day_totals = vec![0.0; 7];
+    let rows = deployments
+        .into_iter()
+        .map(|deployment| {
+            let cells = dates
+                .iter()
+                .enumerate()
+                .map(|(index, date)| {
+                    let update = updates.iter().find(|update| {
+                        update.deployment_id == deployment.id && &update.work_date == date
+                    });
+                    let hours = update.map(|update| update.hours_worked);
+                    day_totals[index] += hours.unwrap_or(0.0);
+                    let in_range = deployment.start_date <= *date && deployment.end_date >= *date;
+                    WeekGridCell {
+                        work_date: date.clone(),
+                        input: inputs
+                            .get(&(deployment.id, date.clone()))
+                            .cloned()
+                            .unwrap_or_else(|| hours.map(format_hours).unwrap_or_default()),
+                        hours,
+                        update_id: update.map(|update| update.id),
+                        approval_status: update.map(|update| update.approval_status.clone()),
+                        is_placeholder: update.is_some_and(|update| update.is_placeholder),
+                        is_missing: update.is_none() && in_range && *date < today,
+                        is_editable: update.is_none_or(|update| can_change_hours(user, update)),
+                        in_range,
+                    }
+                })
+                .collect::<Vec<_>>();
+            let total_hours = cells.iter().filter_map(|cell| cell.hours).sum::<f64>();
+            WeekGridRow {
+                deployment_id: deployment.id,
+                label: deployment.label,
+                notes: notes.get(&deployment.id).cloned().unwrap_or_default(),
+                cells,
+                total_hours: round_hours(total_hours),
+            }
+        })
+        .collect::<Vec<_>>();
+    let week_total = round_hours(day_totals.iter().sum());
+    WeekGrid {
+        rows,
+        day_totals: day_totals.into_iter().map(round_hours).collect(),
+        week_total,
+    }
+}
+
+pub async fn save_week_grid(db: &Db, user: &User, form: &WeekGridForm) -> Result<(), Vec<String>> {
+    let (week_start, _) = timesheet_service::week_bounds(Some(&form.week));
+    let dates = week_dates(week_start);
+    let updates = week_updates(db, user, &dates[0], &dates[6]).await;
+    let deployments = grid_deployments(db, user).await;
+    let notes = form
+        .rows
+        .iter()
+        .map(|row| (row.deployment_id, row.notes.trim()))
+        .collect::<HashMap<_, _>>();
+
+    let mut errors = Vec::new();
+    for cell in &form.cells {
+        let input = cell.hours.trim();
+        if input.is_empty() || !dates.contains(&cell.work_date) {
+            continue;
+        }
+        let Some(deployment) = deployments
+            .iter()
+            .find(|deployment| deployment.id == cell.deployment_id)
+        else {
+            errors.push("You do not have access to that deployment.".to_string());
+            continue;
+        };
+        let day_label = NaiveDate::parse_from_str(&cell.work_date, "%Y-%m-%d")
+            .map(|date| date.format("%a %d %b").to_string())
+            .unwrap_or_else(|_| cell.work_date.clone());
+        let existing = updates.iter().find(|update| {
+            update.deployment_id == deployment.id && update.work_date == cell.work_date
+        });
+        let note = notes.get(&deployment.id).copied().unwrap_or_default();
+        let result = match input.parse::<f64>() {
+            Ok(hours) if (0.0..=24.0).contains(&hours) => match existing {
+                None if hours == 0.0 => Ok(()),
+                None => create_entry(db, user, deployment, &cell.work_date, hours, note).await,
+                Some(update) if (update.hours_worked - hours).abs() < 0.005 => Ok(()),
+                Some(update) => update_entry(db, user, update, hours, note).await,
+            },
+            _ => Err("Enter hours between 0 and 24.".to_string()),
+        };
+        if let Err(message) = result {
+            errors.push(format!("{}, {day_label}: {message}", deployment.label));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+async fn create_entry(
+    db: &Db,
+    user: &User,
+    deployment: &GridDeployment,
+    work_date: &str,
+    hours: f64,
+    notes: &str,
+) -> Result<(), String> {
+    if notes.is_empty() {
+        return Err("Add a note for this deployment to log new hours.".to_string());
+    }
+    let start = NaiveTime::parse_from_str(&deployment.start_time, "%H:%M").unwrap_or_else(|_| {
+        NaiveTime::parse_from_str(DEFAULT_START_TIME, "%H:%M").unwrap_or_default()
+    });
+    let end_time = shift_end(start, hours, 0)?;
+    let form = DeploymentUpdateForm {
+        deployment_id: deployment.id,
+        work_date: work_date.to_string(),
+        start_time: start.format("%H:%M").to_string(),
+        end_time,
+        notes: notes.to_string(),
+        breaks: Vec::new(),
+        attachments: Vec::new(),
+    };
+    tracking_service::create_update(db, user.tenant_id, user.id, form)
+        .await
+        .map(|_| ())
+        .map_err(|err| err.message)
+}
+
+async fn update_entry(
+    db: &Db,
+    user: &User,
+    update: &DeploymentUpdate,
+    hours: f64,
+    notes: &str,
+) -> Result<(), String> {
+    if timesheet_service::is_locked(update) {
+        return Err("This entry has been approved and is locked.".to_string());
+    }
+    if !can_change_hours(user, update) {
+        return Err(if update.is_placeholder {
+            "Timer hours can only be changed by an owner. Add your report on the tracking page."
+                .to_string()
+        } else {
+            "Submitted entries can only be changed by an owner.".to_string()
+        });
+    }
+    let notes = if update.is_placeholder || update.notes == tracking_service::NO_REPORT_NOTES {
+        if notes.is_empty() {
+            return Err("Add a note for this deployment to report the day.".to_string());
+        }
+        notes.to_string()
+    } else {
+        update.notes.clone()
+    };
+    let start = NaiveTime::parse_from_str(&update.start_time, "%H:%M")
+        .map_err(|_| "Start time format is invalid.".to_string())?;
+    let end_time = shift_end(start, hours, update.unpaid_break_minutes)?;
+    let breaks = tracking_service::list_update_breaks(db, user.tenant_id, update.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|segment| UpdateBreakForm {
+            kind: segment.kind,
+            start_time: segment.start_time,
+            end_time: segment.end_time,
+        })
+        .collect();
+    let form = DeploymentUpdateForm {
+        deployment_id: update.deployment_id,
+        work_date: update.work_date.clone(),
+        start_time: update.start_time.clone(),
+        end_time,
+        notes,
+        breaks,
+        attachments: Vec::new(),
+    };
+    tracking_service::update_update(db, user.tenant_id, update.id, user.id, form)
+        .await
+        .map(|_| ())
+        .map_err(|err| err.message)
+}
+
+fn can_change_hours(user: &User, update: &DeploymentUpdate) -> bool {
+    !timesheet_service::is_locked(update)
+        && (access_service::is_owner(&user.role)
+            || (update.user_id == Some(user.id)
+                && update.approval_status == timesheet_service::STATUS_REJECTED))
+}
+
+fn shift_end(start: NaiveTime, hours: f64, unpaid_break_minutes: i64) -> Result<String, String> {
+    let minutes = (hours * 60.0).round() as i64 + unpaid_break_minutes;
+    let start_minutes = (start - NaiveTime::MIN).num_minutes();
+    if start_minutes + minutes >= 24 * 60 {
+        return Err(format!(
+            "{} hours from {} runs past midnight. Adjust the times on the tracking page.",
+            format_hours(hours),
+            start.format("%H:%M")
+        ));
+    }
+    Ok((start + Duration::minutes(minutes))
+        .format("%H:%M")
+        .to_string())
+}
+
+async fn week_updates(
+    db: &Db,
+    user: &User,
+    start_date: &str,
+    end_date: &str,
+) -> Vec<DeploymentUpdate> {
+    deployment_update_repo::list_updates_for_user_between(
+        db,
+        user.tenant_id,
+        user.id,
+        start_date,
+        end_date,
+    )
+    .await
+    .unwrap_or_default()
+}
+
+async fn grid_deployments(db: &Db, user: &User) -> Vec<GridDeployment> {
+    let rows = if access_service::is_employee(&user.role) {
+        let crew_ids =
+            crew_member_repo::list_crew_ids_for_user(db, user.tenant_id, user.id, &user.email)
+                .await
+                .unwrap_or_default();
+        deployment_repo::list_deployments_with_names_for_crews(db, user.tenant_id, &crew_ids).await
+    } else {
+        deployment_repo::list_deployments_with_names(db, user.tenant_id).await
+    };
+    rows.unwrap_or_default()
+        .into_iter()
+        .map(|row| GridDeployment {
+            id: row.id,
+            label: format!("{} - {}", row.client_name, row.crew_name),
+            start_date: row.start_at.get(..10).unwrap_or_default().to_string(),
+            end_date: row.end_at.get(..10).unwrap_or_default().to_string(),
+            start_time: row.start_at.get(11..16).unwrap_or_default().to_string(),
+            is_cancelled: row.status == deployment_service::STATUS_CANCELLED,
+        })
+        .collect()
+}
+
+fn week_dates(week_start: NaiveDate) -> Vec<String> {
+    (0..7)
+        .map(|offset| {
+            (week_start + Duration::days(offset))
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .collect()
+}
+
+fn format_hours(hours: f64) -> String {
+    let rounded = round_hours(hours);
+    if rounded.fract() == 0.0 {
+        format!("{rounded:.0}")
+    } else {
+        format!("{rounded}")
+    }
+}
+
+fn round_hours(hours: f64) -> f64 {
+    (hours * 100.0).round() / 100.0
+}
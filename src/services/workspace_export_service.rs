@@ -0,0 +1,319 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::models::{User, WorkspaceExport};
+use crate::repositories::{user_repo, workspace_export_repo};
+use crate::services::utils::{csv_quote, escape_html};
+use crate::services::{
+    attachment_service,
+    certification_service,
+    client_service,
+    email_service,
+    expense_service,
+    workspace_service,
+};
+use crate::Db;
+
+pub const STATUS_READY: &str = "Ready";
+pub const LINK_VALID_HOURS: i64 = 72;
+/// The export history itself holds live download tokens, so it stays out.
+const EXCLUDED_TABLES: [&str; 1] = ["workspace_exports"];
+/// Credentials and access tokens are blanked rather than handed out in a file
+/// that may be stored or shared outside the app.
+const REDACTED_COLUMNS: [&str; 9] = [
+    "password_hash",
+    "portal_token",
+    "smtp_password",
+    "mailgun_api_key",
+    "postmark_server_token",
+    "resend_api_key",
+    "ses_access_key",
+    "ses_secret_key",
+    "label_secret",
+];
+
+struct TableDump {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+pub fn export_dir(tenant_id: i64) -> PathBuf {
+    Path::new("uploads")
+        .join("exports")
+        .join(format!("tenant-{tenant_id}"))
+}
+
+pub fn export_file_path(export: &WorkspaceExport) -> PathBuf {
+    export_dir(export.tenant_id).join(&export.file_name)
+}
+
+pub fn download_name(slug: &str, export: &WorkspaceExport) -> String {
+    let date = export
+        .completed_at
+        .as_deref()
+        .and_then(|completed_at| completed_at.get(..10))
+        .unwrap_or("export");
+    format!("{slug}-data-{date}.zip")
+}
+
+pub async fn request_export(db: &Db, user: &User, origin: &str) -> Result<(), String> {
+    let pending = workspace_export_repo::has_pending_export(db, user.tenant_id)
+        .await
+        .map_err(|err| format!("Unable to check existing exports: {err}"))?;
+    if pending {
+        return Err("An export is already being prepared. You will get an email when it is ready.".to_string());
+    }
+    workspace_export_repo::create_export(db, user.tenant_id, user.id, origin)
+        .await
+        .map_err(|err| format!("Unable to queue export: {err}"))?;
+    Ok(())
+}
+
+pub async fn list_exports(db: &Db, tenant_id: i64) -> Vec<WorkspaceExport> {
+    workspace_export_repo::list_exports(db, tenant_id, 10)
+        .await
+        .unwrap_or_default()
+}
+
+pub async fn find_ready_export(db: &Db, tenant_id: i64, token: &str) -> Option<WorkspaceExport> {
+    if token.trim().is_empty() {
+        return None;
+    }
+    workspace_export_repo::find_ready_export(db, tenant_id, token)
+        .await
+        .ok()
+        .flatten()
+        .filter(|export| export_file_path(export).is_file())
+}
+
+pub async fn process_exports(db: &Db) -> Result<(), String> {
+    let expired = workspace_export_repo::list_expired_exports(db)
+        .await
+        .map_err(|err| format!("Unable to load expired exports: {err}"))?;
+    for export in expired {
+        let _ = std::fs::remove_file(export_file_path(&export));
+        workspace_export_repo::mark_expired(db, export.id)
+            .await
+            .map_err(|err| format!("Unable to expire export: {err}"))?;
+    }
+
+    while let Some(export) = workspace_export_repo::claim_next_export(db)
+        .await
+        .map_err(|err| format!("Unable to load queued exports: {err}"))?
+    {
+        match build_archive(db, &export).await {
+            Ok((file_name, file_size)) => {
+                let token = client_service::generate_portal_token();
+                workspace_export_repo::mark_ready(db, export.id, &file_name, file_size, &token, LINK_VALID_HOURS)
+                    .await
+                    .map_err(|err| format!("Unable to save export: {err}"))?;
+                notify(db, &export, &token).await;
+            }
+            Err(message) => {
+                let _ = std::fs::remove_file(export_dir(export.tenant_id).join(archive_name(&export)));
+                workspace_export_repo::mark_failed(db, export.id, &message)
+                    .await
+                    .map_err(|err| format!("Unable to save export: {err}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn archive_name(export: &WorkspaceExport) -> String {
+    format!("export-{}.zip", export.id)
+}
+
+async fn build_archive(db: &Db, export: &WorkspaceExport) -> Result<(String, i64), String> {
+    let workspace = workspace_service::find_workspace_by_id(db, export.tenant_id)
+        .await
+        .map_err(|err| format!("Unable to load workspace: {err}"))?
+        .ok_or_else(|| "Workspace not found.".to_string())?;
+
+    let mut tables = Vec::new();
+    let (columns, rows) = workspace_export_repo::fetch_table_rows(db, "tenants", "id", export.tenant_id)
+        .await
+        .map_err(|err| format!("Unable to export workspace settings: {err}"))?;
+    tables.push(redact(TableDump { name: "workspace".to_string(), columns, rows }));
+    let names = workspace_export_repo::list_tenant_tables(db)
+        .await
+        .map_err(|err| format!("Unable to list workspace tables: {err}"))?;
+    for name in names {
+        if EXCLUDED_TABLES.contains(&name.as_str()) {
+            continue;
+        }
+        let (columns, rows) = workspace_export_repo::fetch_table_rows(db, &name, "tenant_id", export.tenant_id)
+            .await
+            .map_err(|err| format!("Unable to export {name}: {err}"))?;
+        tables.push(redact(TableDump { name, columns, rows }));
+    }
+
+    let files = uploaded_files(export.tenant_id, &workspace.logo_path);
+    let manifest = json!({
+        "workspace": workspace.name,
+        "slug": workspace.slug,
+        "generated_at": chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        "tables": tables
+            .iter()
+            .map(|table| (table.name.clone(), Value::from(table.rows.len())))
+            .collect::<Map<_, _>>(),
+        "files": files.len(),
+        "redacted_columns": REDACTED_COLUMNS,
+    });
+
+    let file_name = archive_name(export);
+    let path = export_dir(export.tenant_id).join(&file_name);
+    let file_size = rocket::tokio::task::spawn_blocking(move || write_archive(&path, &manifest, &tables, &files))
+        .await
+        .map_err(|err| format!("Unable to build export: {err}"))??;
+    Ok((file_name, file_size))
+}
+
+fn redact(mut table: TableDump) -> TableDump {
+    let redacted = table
+        .columns
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| REDACTED_COLUMNS.contains(&column.as_str()))
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    for row in &mut table.rows {
+        for index in &redacted {
+            if let Some(value) = row.get_mut(*index) {
+                *value = Value::Null;
+            }
+        }
+    }
+    table
+}
+
+fn uploaded_files(tenant_id: i64, logo_path: &str) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    for (folder, dir) in [
+        ("receipts", expense_service::receipt_dir(tenant_id)),
+        ("attachments", attachment_service::attachment_dir(tenant_id)),
+        ("certifications", certification_service::document_dir(tenant_id)),
+    ] {
+        collect_files(&dir, &format!("files/{folder}"), &mut files);
+    }
+    if let Some(name) = logo_path.strip_prefix("/static/uploads/") {
+        let path = Path::new("static/uploads").join(name);
+        if !name.contains(['/', '\\']) && path.is_file() {
+            files.push((format!("files/logo/{name}"), path));
+        }
+    }
+    files
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries = entries.filter_map(Result::ok).collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let name = format!("{prefix}/{}", entry.file_name().to_string_lossy());
+        if path.is_dir() {
+            collect_files(&path, &name, files);
+        } else if path.is_file() {
+            files.push((name, path));
+        }
+    }
+}
+
+fn write_archive(
+    path: &Path,
+    manifest: &Value,
+    tables: &[TableDump],
+    files: &[(String, PathBuf)],
+) -> Result<i64, String> {
+    let write_error = |err: std::io::Error| format!("Unable to write export: {err}");
+    let zip_error = |err: zip::result::ZipError| format!("Unable to write export: {err}");
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    let file = File::create(path).map_err(write_error)?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file("manifest.json", options).map_err(zip_error)?;
+    serde_json::to_writer_pretty(&mut zip, manifest).map_err(|err| format!("Unable to write export: {err}"))?;
+    for table in tables {
+        zip.start_file(format!("json/{}.json", table.name), options).map_err(zip_error)?;
+        let records = table
+            .rows
+            .iter()
+            .map(|row| {
+                table
+                    .columns
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().cloned())
+                    .collect::<Map<_, _>>()
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_writer_pretty(&mut zip, &records).map_err(|err| format!("Unable to write export: {err}"))?;
+
+        zip.start_file(format!("csv/{}.csv", table.name), options).map_err(zip_error)?;
+        zip.write_all(table_csv(table).as_bytes()).map_err(write_error)?;
+    }
+    for (name, source) in files {
+        let mut source = File::open(source).map_err(write_error)?;
+        zip.start_file(name.as_str(), options).map_err(zip_error)?;
+        std::io::copy(&mut source, &mut zip).map_err(write_error)?;
+    }
+    let mut writer = zip.finish().map_err(zip_error)?;
+    writer.flush().map_err(write_error)?;
+    let size = std::fs::metadata(path).map_err(write_error)?.len();
+    Ok(size as i64)
+}
+
+fn table_csv(table: &TableDump) -> String {
+    let mut csv = table.columns.iter().map(|column| csv_quote(column)).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+    for row in &table.rows {
+        let fields = row
+            .iter()
+            .map(|value| match value {
+                Value::String(text) => csv_quote(text),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+async fn notify(db: &Db, export: &WorkspaceExport, token: &str) {
+    let Some(user) = user_repo::find_user_by_id(db, export.tenant_id, export.requested_by)
+        .await
+        .ok()
+        .flatten()
+    else {
+        return;
+    };
+    let Some(workspace) = workspace_service::find_workspace_by_id(db, export.tenant_id)
+        .await
+        .ok()
+        .flatten()
+    else {
+        return;
+    };
+    let link = format!("{}/{}/data-export/{token}", export.origin, workspace.slug);
+    let subject = format!("Your {} data export is ready", workspace.name);
+    let body = format!(
+        "<p>The export of <strong>{}</strong> you requested is ready. It holds every workspace table as JSON and CSV, plus uploaded files.</p><p><a href=\"{link}\">Download the export</a></p><p>The link works for signed-in workspace owners and expires in {LINK_VALID_HOURS} hours. Passwords and API keys are not included.</p>",
+        escape_html(&workspace.name),
+        link = escape_html(&link),
+    );
+    let _ = email_service::queue_email(db, export.tenant_id, None, None, user.email, Vec::new(), subject, body).await;
+}
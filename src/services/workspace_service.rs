@@ -12,6 +12,7 @@ use crate::models::{
     WorkspaceThemeView,
 };
 use crate::repositories::tenant_repo;
+use crate::services::{attachment_service, certification_service, expense_service, workspace_export_service};
 use crate::services::utils::normalize_slug;
 use crate::Db;
 use chrono::{Duration, NaiveDateTime, Utc};
@@ -40,6 +41,8 @@ pub struct PlanLimits {
     pub crews: Option<i64>,
     pub members_per_crew: Option<i64>,
     pub users: Option<i64>,
+    pub storage_mb: Option<i64>,
+    pub assets: Option<i64>,
     pub expires_after_days: Option<i64>,
 }
 
@@ -176,6 +179,8 @@ pub fn plan_definitions() -> Vec<PlanDefinition> {
                 crews: Some(2),
                 members_per_crew: Some(5),
                 users: Some(11),
+                storage_mb: Some(250),
+                assets: Some(25),
                 expires_after_days: Some(30),
             },
             payment_url: "https://wise.com/pay/kinetic/free",
@@ -195,6 +200,8 @@ pub fn plan_definitions() -> Vec<PlanDefinition> {
                 crews: Some(5),
                 members_per_crew: Some(10),
                 users: Some(51),
+                storage_mb: Some(5120),
+                assets: Some(250),
                 expires_after_days: None,
             },
             payment_url: "https://wise.com/pay/kinetic/pro",
@@ -214,6 +221,8 @@ pub fn plan_definitions() -> Vec<PlanDefinition> {
                 crews: None,
                 members_per_crew: None,
                 users: None,
+                storage_mb: None,
+                assets: None,
                 expires_after_days: None,
             },
             payment_url: "https://wise.com/pay/kinetic/enterprise",
@@ -245,6 +254,8 @@ fn default_plan_limits(plan_key: &str) -> PlanLimits {
             crews: None,
             members_per_crew: None,
             users: None,
+            storage_mb: None,
+            assets: None,
             expires_after_days: None,
         })
 }
@@ -259,6 +270,8 @@ pub async fn plan_limits_for(db: &Db, plan_key: &str) -> PlanLimits {
                crews,
                members_per_crew,
                users,
+               storage_mb,
+               assets,
                expires_after_days
         FROM plan_limits
         WHERE plan_key = ?
@@ -277,6 +290,8 @@ pub async fn plan_limits_for(db: &Db, plan_key: &str) -> PlanLimits {
             crews: None,
             members_per_crew: None,
             users: None,
+            storage_mb: None,
+            assets: None,
             expires_after_days: Some(row.get("expires_after_days")),
         },
         Ok(Some(row)) => PlanLimits {
@@ -287,6 +302,8 @@ pub async fn plan_limits_for(db: &Db, plan_key: &str) -> PlanLimits {
             crews: Some(row.get("crews")),
             members_per_crew: Some(row.get("members_per_crew")),
             users: Some(row.get("users")),
+            storage_mb: Some(row.get("storage_mb")),
+            assets: Some(row.get("assets")),
             expires_after_days: Some(row.get("expires_after_days")),
         },
         _ => default_plan_limits(plan_key),
@@ -328,6 +345,8 @@ pub async fn update_plan_limits(
         || limits.crews <= 0
         || limits.members_per_crew <= 0
         || limits.users <= 0
+        || limits.storage_mb <= 0
+        || limits.assets <= 0
         || limits.expires_after_days < 0
     {
         return Err("All limit values must be greater than 0.".to_string());
@@ -344,8 +363,10 @@ pub async fn update_plan_limits(
             crews,
             members_per_crew,
             users,
+            storage_mb,
+            assets,
             expires_after_days
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         ON CONFLICT(plan_key) DO UPDATE SET
             clients = excluded.clients,
             contacts_per_client = excluded.contacts_per_client,
@@ -354,6 +375,8 @@ pub async fn update_plan_limits(
             crews = excluded.crews,
             members_per_crew = excluded.members_per_crew,
             users = excluded.users,
+            storage_mb = excluded.storage_mb,
+            assets = excluded.assets,
             expires_after_days = excluded.expires_after_days
         "#,
     )
@@ -365,6 +388,8 @@ pub async fn update_plan_limits(
     .bind(limits.crews)
     .bind(limits.members_per_crew)
     .bind(limits.users)
+    .bind(limits.storage_mb)
+    .bind(limits.assets)
     .bind(limits.expires_after_days)
     .execute(&db.0)
     .await
@@ -413,8 +438,10 @@ pub async fn update_enterprise_plan_expiry(
             crews,
             members_per_crew,
             users,
+            storage_mb,
+            assets,
             expires_after_days
-        ) VALUES (?, 0, 0, 0, 0, 0, 0, 0, ?)
+        ) VALUES (?, 0, 0, 0, 0, 0, 0, 0, 0, 0, ?)
         ON CONFLICT(plan_key) DO UPDATE SET
             expires_after_days = excluded.expires_after_days
         "#,
@@ -1176,6 +1203,46 @@ pub async fn delete_workspace(db: &Db, id: i64) -> Result<(), String> {
         .execute(&mut *tx)
         .await
         .map_err(|err| format!("Unable to delete workspace appointments: {err}"))?;
+    sqlx::query("DELETE FROM deployment_update_segments WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace time segments: {err}"))?;
+    sqlx::query("DELETE FROM work_timer_breaks WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace timer breaks: {err}"))?;
+    sqlx::query("DELETE FROM timer_auto_closures WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace timer audit: {err}"))?;
+    sqlx::query("DELETE FROM timer_policies WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace timer policy: {err}"))?;
+    sqlx::query("DELETE FROM payroll_policies WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace payroll policy: {err}"))?;
+    sqlx::query("DELETE FROM tracking_sync_items WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace sync log: {err}"))?;
+    sqlx::query("DELETE FROM deployment_incidents WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace incidents: {err}"))?;
+    sqlx::query("DELETE FROM deployment_update_attachments WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace update attachments: {err}"))?;
     sqlx::query("DELETE FROM deployment_updates WHERE tenant_id = ?")
         .bind(id)
         .execute(&mut *tx)
@@ -1186,6 +1253,21 @@ pub async fn delete_workspace(db: &Db, id: i64) -> Result<(), String> {
         .execute(&mut *tx)
         .await
         .map_err(|err| format!("Unable to delete workspace invoices: {err}"))?;
+    sqlx::query("DELETE FROM deployment_expenses WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace expenses: {err}"))?;
+    sqlx::query("DELETE FROM deployment_tasks WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace deployment tasks: {err}"))?;
+    sqlx::query("DELETE FROM deployment_templates WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace deployment templates: {err}"))?;
     sqlx::query("DELETE FROM deployments WHERE tenant_id = ?")
         .bind(id)
         .execute(&mut *tx)
@@ -1201,6 +1283,86 @@ pub async fn delete_workspace(db: &Db, id: i64) -> Result<(), String> {
         .execute(&mut *tx)
         .await
         .map_err(|err| format!("Unable to delete workspace clients: {err}"))?;
+    sqlx::query("DELETE FROM required_kit WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace required kit: {err}"))?;
+    sqlx::query("DELETE FROM maintenance_reminders WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace maintenance reminders: {err}"))?;
+    sqlx::query("DELETE FROM maintenance_logs WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace maintenance logs: {err}"))?;
+    sqlx::query("DELETE FROM maintenance_plans WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace maintenance plans: {err}"))?;
+    sqlx::query("DELETE FROM custom_field_values WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace custom field values: {err}"))?;
+    sqlx::query("DELETE FROM custom_field_definitions WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace custom fields: {err}"))?;
+    sqlx::query("DELETE FROM data_imports WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace imports: {err}"))?;
+    sqlx::query("DELETE FROM workspace_exports WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace exports: {err}"))?;
+    sqlx::query("DELETE FROM asset_assignments WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace asset assignments: {err}"))?;
+    sqlx::query("DELETE FROM assets WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace assets: {err}"))?;
+    sqlx::query("DELETE FROM recommendation_weights WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace recommendation weights: {err}"))?;
+    sqlx::query("DELETE FROM member_leave_requests WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace leave requests: {err}"))?;
+    sqlx::query("DELETE FROM member_recurring_unavailability WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace recurring unavailability: {err}"))?;
+    sqlx::query("DELETE FROM workspace_holidays WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace holidays: {err}"))?;
+    sqlx::query("DELETE FROM certification_reminders WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace certification reminders: {err}"))?;
+    sqlx::query("DELETE FROM crew_member_certifications WHERE tenant_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| format!("Unable to delete workspace certifications: {err}"))?;
     sqlx::query("DELETE FROM crew_members WHERE tenant_id = ?")
         .bind(id)
         .execute(&mut *tx)
@@ -1236,6 +1398,10 @@ pub async fn delete_workspace(db: &Db, id: i64) -> Result<(), String> {
     tx.commit()
         .await
         .map_err(|err| format!("Unable to finalize workspace delete: {err}"))?;
+    let _ = std::fs::remove_dir_all(expense_service::receipt_dir(id));
+    let _ = std::fs::remove_dir_all(attachment_service::attachment_dir(id));
+    let _ = std::fs::remove_dir_all(certification_service::document_dir(id));
+    let _ = std::fs::remove_dir_all(workspace_export_service::export_dir(id));
     Ok(())
 }
 